@@ -970,6 +970,9 @@ impl Database {
                         current_merge_conflicts,
                         branch_summary,
                         head_commit_details,
+                        // The paused operation is only sent along with the host's
+                        // updates, and isn't persisted.
+                        sequencer_operation: None,
                         scan_id: db_repository_entry.scan_id as u64,
                         is_last_update: true,
                     });
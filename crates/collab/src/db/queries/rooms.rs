@@ -786,6 +786,9 @@ impl Database {
                             current_merge_conflicts,
                             branch_summary,
                             head_commit_details,
+                            // The paused operation is only sent along with the host's
+                            // updates, and isn't persisted.
+                            sequencer_operation: None,
                             project_id: project_id.to_proto(),
                             id: db_repository.id as u64,
                             abs_path: db_repository.abs_path,
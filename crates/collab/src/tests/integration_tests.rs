@@ -3665,6 +3665,7 @@ async fn test_buffer_reloading(
             path!("/dir/a.txt").as_ref(),
             &new_contents,
             LineEnding::Windows,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -4379,6 +4380,103 @@ async fn test_collaborating_with_lsp_progress_updates_and_diagnostics_ordering(
     assert!(disk_based_diagnostics_finished.load(SeqCst));
 }
 
+#[gpui::test]
+async fn test_spellchecking_is_not_shared(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(path!("/dir"), json!({ "a.txt": "the quick fox" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project(path!("/dir"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+
+    let dictionary = Arc::new(spellcheck::Dictionary::new("", "3\nthe\nquick\nfox\n").unwrap());
+    let diagnostic_ops = Rc::new(Cell::new(0));
+    let mut subscriptions = Vec::new();
+    for (cx, buffer) in [(&mut *cx_a, &buffer_a), (&mut *cx_b, &buffer_b)] {
+        let diagnostic_ops = diagnostic_ops.clone();
+        subscriptions.push(cx.update(|cx| {
+            cx.subscribe(buffer, move |_, event, _| {
+                if let language::BufferEvent::Operation {
+                    operation: language::Operation::UpdateDiagnostics { .. },
+                    ..
+                } = event
+                {
+                    diagnostic_ops.set(diagnostic_ops.get() + 1);
+                }
+            })
+        }));
+        cx.update(|cx| {
+            spellcheck::init(cx);
+            spellcheck::Spellchecker::global(cx)
+                .unwrap()
+                .update(cx, |spellchecker, cx| {
+                    spellchecker.set_dictionary(dictionary.clone(), cx);
+                    spellchecker.register_buffer(buffer.clone(), cx);
+                });
+        });
+    }
+
+    // The guest edits the buffer, and both collaborators check their own replica.
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(13..13, " jumpz")], None, cx)
+    });
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+
+    for (cx, buffer) in [(&*cx_a, &buffer_a), (&*cx_b, &buffer_b)] {
+        buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            let misspellings = buffer
+                .get_diagnostics(spellcheck::SPELLCHECK_SERVER_ID)
+                .unwrap()
+                .iter()
+                .map(|entry| {
+                    snapshot
+                        .text_for_range(entry.range.to_offset(&snapshot))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(misspellings, ["jumpz"]);
+        });
+    }
+    assert_eq!(diagnostic_ops.get(), 0);
+
+    // Spellcheck results are left out when the buffer is replicated to a new guest.
+    let operations = buffer_a
+        .read_with(cx_a, |buffer, cx| buffer.serialize_ops(None, cx))
+        .await;
+    assert!(!operations.iter().any(|operation| matches!(
+        operation.variant,
+        Some(rpc::proto::operation::Variant::UpdateDiagnostics(_))
+    )));
+    drop(subscriptions);
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_reloading_buffer_manually(
     executor: BackgroundExecutor,
@@ -4428,6 +4526,7 @@ async fn test_reloading_buffer_manually(
             path!("/a/a.rs").as_ref(),
             &Rope::from("let seven = 7;"),
             LineEnding::Unix,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -925,7 +925,12 @@ impl RandomizedTest for ProjectCollaborationTest {
 
                     client
                         .fs()
-                        .save(&path, &content.as_str().into(), text::LineEnding::Unix)
+                        .save(
+                            &path,
+                            &content.as_str().into(),
+                            text::LineEnding::Unix,
+                            Default::default(),
+                        )
                         .await
                         .unwrap();
                 }
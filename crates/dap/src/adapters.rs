@@ -14,8 +14,14 @@ use serde::{Deserialize, Serialize};
 use settings::WorktreeId;
 use smol::{self, fs::File, lock::Mutex};
 use std::{
-    borrow::Borrow, collections::HashSet, ffi::OsStr, fmt::Debug, net::Ipv4Addr, ops::Deref,
-    path::PathBuf, sync::Arc,
+    borrow::Borrow,
+    collections::HashSet,
+    ffi::OsStr,
+    fmt::Debug,
+    net::Ipv4Addr,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 use task::{DebugTaskDefinition, TcpArgumentsTemplate};
 use util::ResultExt;
@@ -31,6 +37,7 @@ pub enum DapStatus {
 #[async_trait(?Send)]
 pub trait DapDelegate {
     fn worktree_id(&self) -> WorktreeId;
+    fn worktree_root_path(&self) -> &Path;
     fn http_client(&self) -> Arc<dyn HttpClient>;
     fn node_runtime(&self) -> NodeRuntime;
     fn toolchain_store(&self) -> Arc<dyn LanguageToolchainStore>;
@@ -288,6 +295,12 @@ pub async fn fetch_latest_adapter_version_from_github(
 pub trait DebugAdapter: 'static + Send + Sync {
     fn name(&self) -> DebugAdapterName;
 
+    /// Returns the JSON schema of the adapter-specific configuration that
+    /// debug tasks using this adapter can pass in `initialize_args`, if known.
+    fn dap_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
     async fn get_binary(
         &self,
         delegate: &dyn DapDelegate,
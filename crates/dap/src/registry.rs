@@ -1,12 +1,27 @@
+use anyhow::{Result, bail};
+use collections::HashMap;
 use gpui::{App, Global};
 use parking_lot::RwLock;
+use postage::watch;
 
 use crate::adapters::{DebugAdapter, DebugAdapterName};
 use std::{collections::BTreeMap, sync::Arc};
 
-#[derive(Default)]
 struct DapRegistryState {
     adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
+    /// The IDs of the extensions that provide each of the adapters registered by extensions.
+    extension_adapters: HashMap<DebugAdapterName, Arc<str>>,
+    subscription: (watch::Sender<()>, watch::Receiver<()>),
+}
+
+impl Default for DapRegistryState {
+    fn default() -> Self {
+        Self {
+            adapters: BTreeMap::default(),
+            extension_adapters: HashMap::default(),
+            subscription: watch::channel(),
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -28,11 +43,51 @@ impl DapRegistry {
 
     pub fn add_adapter(&self, adapter: Arc<dyn DebugAdapter>) {
         let name = adapter.name();
-        let _previous_value = self.0.write().adapters.insert(name, adapter);
+        let mut state = self.0.write();
+        let _previous_value = state.adapters.insert(name, adapter);
         debug_assert!(
             _previous_value.is_none(),
             "Attempted to insert a new debug adapter when one is already registered"
         );
+        *state.subscription.0.borrow_mut() = ();
+    }
+
+    /// Registers an adapter provided by the given extension, unless an adapter
+    /// with the same name is already registered.
+    pub fn add_extension_adapter(
+        &self,
+        extension_id: Arc<str>,
+        adapter: Arc<dyn DebugAdapter>,
+    ) -> Result<()> {
+        let name = adapter.name();
+        let mut state = self.0.write();
+        if state.adapters.contains_key(&name) {
+            bail!("a debug adapter named {name} is already registered");
+        }
+        state.extension_adapters.insert(name.clone(), extension_id);
+        state.adapters.insert(name, adapter);
+        *state.subscription.0.borrow_mut() = ();
+        Ok(())
+    }
+
+    /// Removes the adapter with the given name if it was registered by the given extension.
+    pub fn remove_extension_adapter(&self, extension_id: &str, name: &str) {
+        let mut state = self.0.write();
+        if state
+            .extension_adapters
+            .get(name)
+            .is_none_or(|owner| owner.as_ref() != extension_id)
+        {
+            return;
+        }
+        state.extension_adapters.remove(name);
+        state.adapters.remove(name);
+        *state.subscription.0.borrow_mut() = ();
+    }
+
+    /// Returns a receiver that is notified whenever adapters are added or removed.
+    pub fn subscribe(&self) -> watch::Receiver<()> {
+        self.0.read().subscription.1.clone()
     }
 
     pub fn adapter(&self, name: &str) -> Option<Arc<dyn DebugAdapter>> {
@@ -42,4 +97,50 @@ impl DapRegistry {
     pub fn enumerate_adapters(&self) -> Vec<DebugAdapterName> {
         self.0.read().adapters.keys().cloned().collect()
     }
+
+    /// Returns the JSON schemas of the adapter-specific configurations of the
+    /// adapters that provide one.
+    pub fn adapter_schemas(&self) -> Vec<(DebugAdapterName, serde_json::Value)> {
+        self.0
+            .read()
+            .adapters
+            .iter()
+            .filter_map(|(name, adapter)| Some((name.clone(), adapter.dap_schema()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeAdapter;
+
+    #[test]
+    fn test_extension_adapters_do_not_replace_other_adapters() {
+        let name = FakeAdapter::ADAPTER_NAME;
+
+        let registry = DapRegistry::default();
+        registry.add_adapter(Arc::new(FakeAdapter::new()));
+        assert!(
+            registry
+                .add_extension_adapter("extension".into(), Arc::new(FakeAdapter::new()))
+                .is_err()
+        );
+        registry.remove_extension_adapter("extension", name);
+        assert!(registry.adapter(name).is_some());
+
+        let registry = DapRegistry::default();
+        registry
+            .add_extension_adapter("extension-a".into(), Arc::new(FakeAdapter::new()))
+            .unwrap();
+        assert!(
+            registry
+                .add_extension_adapter("extension-b".into(), Arc::new(FakeAdapter::new()))
+                .is_err()
+        );
+        registry.remove_extension_adapter("extension-b", name);
+        assert!(registry.adapter(name).is_some());
+        registry.remove_extension_adapter("extension-a", name);
+        assert!(registry.adapter(name).is_none());
+    }
 }
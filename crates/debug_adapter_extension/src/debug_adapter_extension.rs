@@ -0,0 +1,55 @@
+mod extension_dap_adapter;
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use dap::DapRegistry;
+use extension::{Extension, ExtensionDebugAdapterProviderProxy, ExtensionHostProxy};
+use extension_dap_adapter::ExtensionDapAdapter;
+use gpui::App;
+use util::ResultExt as _;
+
+pub fn init(extension_host_proxy: Arc<ExtensionHostProxy>, cx: &mut App) {
+    let debug_adapter_registry_proxy = DebugAdapterRegistryProxy::new(cx);
+    extension_host_proxy.register_debug_adapter_proxy(debug_adapter_registry_proxy);
+}
+
+#[derive(Clone)]
+struct DebugAdapterRegistryProxy {
+    debug_adapter_registry: DapRegistry,
+}
+
+impl DebugAdapterRegistryProxy {
+    fn new(cx: &mut App) -> Self {
+        Self {
+            debug_adapter_registry: DapRegistry::global(cx).clone(),
+        }
+    }
+}
+
+impl ExtensionDebugAdapterProviderProxy for DebugAdapterRegistryProxy {
+    fn register_debug_adapter(
+        &self,
+        extension: Arc<dyn Extension>,
+        debug_adapter_name: Arc<str>,
+        schema: Option<serde_json::Value>,
+    ) {
+        let extension_id = extension.manifest().id.clone();
+        self.debug_adapter_registry
+            .add_extension_adapter(
+                extension_id.clone(),
+                Arc::new(ExtensionDapAdapter::new(
+                    extension,
+                    debug_adapter_name,
+                    schema,
+                )),
+            )
+            .with_context(|| format!("registering debug adapter of extension {extension_id}"))
+            .log_err();
+    }
+
+    fn unregister_debug_adapter(&self, extension_id: Arc<str>, debug_adapter_name: Arc<str>) {
+        self.debug_adapter_registry
+            .remove_extension_adapter(&extension_id, &debug_adapter_name);
+    }
+}
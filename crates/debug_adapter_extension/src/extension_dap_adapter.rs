@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use dap::adapters::{
+    AdapterVersion, DapDelegate, DebugAdapter, DebugAdapterBinary, DebugAdapterName,
+};
+use extension::{Extension, WorktreeDelegate};
+use fs::Fs;
+use gpui::AsyncApp;
+use task::DebugTaskDefinition;
+
+/// A debug adapter provided by an extension.
+pub(crate) struct ExtensionDapAdapter {
+    extension: Arc<dyn Extension>,
+    debug_adapter_name: Arc<str>,
+    schema: Option<serde_json::Value>,
+}
+
+impl ExtensionDapAdapter {
+    pub(crate) fn new(
+        extension: Arc<dyn Extension>,
+        debug_adapter_name: Arc<str>,
+        schema: Option<serde_json::Value>,
+    ) -> Self {
+        Self {
+            extension,
+            debug_adapter_name,
+            schema,
+        }
+    }
+}
+
+/// A [`WorktreeDelegate`] for the worktree of a [`DapDelegate`].
+///
+/// The [`DapDelegate`] can't be handed to the extension itself, as it isn't
+/// `Send`, so the parts of it that extensions need are captured up front.
+struct WorktreeDelegateAdapter {
+    worktree_id: u64,
+    root_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    shell_env: HashMap<String, String>,
+}
+
+impl WorktreeDelegateAdapter {
+    async fn new(delegate: &dyn DapDelegate) -> Self {
+        Self {
+            worktree_id: delegate.worktree_id().to_proto(),
+            root_path: delegate.worktree_root_path().into(),
+            fs: delegate.fs(),
+            shell_env: delegate.shell_env().await,
+        }
+    }
+}
+
+#[async_trait]
+impl WorktreeDelegate for WorktreeDelegateAdapter {
+    fn id(&self) -> u64 {
+        self.worktree_id
+    }
+
+    fn root_path(&self) -> String {
+        self.root_path.to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.fs.load(&self.root_path.join(path)).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        which::which_in(
+            binary_name,
+            self.shell_env.get("PATH"),
+            self.root_path.as_ref(),
+        )
+        .ok()
+        .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.shell_env.clone().into_iter().collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for ExtensionDapAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(self.debug_adapter_name.clone().into())
+    }
+
+    fn dap_schema(&self) -> Option<serde_json::Value> {
+        self.schema.clone()
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &dyn DapDelegate,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let worktree = Arc::new(WorktreeDelegateAdapter::new(delegate).await);
+        self.extension
+            .get_dap_binary(
+                self.debug_adapter_name.clone(),
+                config.clone(),
+                user_installed_path,
+                worktree,
+            )
+            .await
+    }
+
+    async fn fetch_latest_adapter_version(
+        &self,
+        _delegate: &dyn DapDelegate,
+    ) -> Result<AdapterVersion> {
+        bail!("debug adapters provided by extensions are installed by their extension")
+    }
+
+    async fn install_binary(
+        &self,
+        _version: AdapterVersion,
+        _delegate: &dyn DapDelegate,
+    ) -> Result<()> {
+        bail!("debug adapters provided by extensions are installed by their extension")
+    }
+
+    async fn get_installed_binary(
+        &self,
+        _: &dyn DapDelegate,
+        _: &DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        bail!("debug adapters provided by extensions are installed by their extension")
+    }
+}
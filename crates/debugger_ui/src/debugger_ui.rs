@@ -31,6 +31,8 @@ actions!(
         StepOver,
         StepOut,
         StepBack,
+        StepOverInstruction,
+        StepIntoInstruction,
         Stop,
         ToggleIgnoreBreakpoints,
         ClearAllBreakpoints,
@@ -107,6 +109,28 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &StepOverInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .and_then(|session| session.read(cx).mode().as_running().cloned())
+                        }) {
+                            active_item.update(cx, |item, cx| item.step_over_instruction(cx))
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepIntoInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .and_then(|session| session.read(cx).mode().as_running().cloned())
+                        }) {
+                            active_item.update(cx, |item, cx| item.step_in_instruction(cx))
+                        }
+                    }
+                })
                 .register_action(|workspace, _: &Stop, _, cx| {
                     if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
                         if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
@@ -247,7 +271,7 @@ pub fn init(cx: &mut App) {
                                     let stack_id = state.selected_stack_frame_id(cx);
 
                                     state.session().update(cx, |session, cx| {
-                                        session.evaluate(text, None, stack_id, None, cx);
+                                        session.evaluate(text, None, stack_id, None, cx).detach();
                                     });
                                 });
                             Some(())
@@ -10,8 +10,9 @@ use workspace::{Member, Pane, PaneAxis, Workspace};
 
 use crate::session::running::{
     self, RunningState, SubView, breakpoint_list::BreakpointList, console::Console,
-    loaded_source_list::LoadedSourceList, module_list::ModuleList,
-    stack_frame_list::StackFrameList, variable_list::VariableList,
+    disassembly::Disassembly, loaded_source_list::LoadedSourceList, memory_view::MemoryView,
+    module_list::ModuleList, stack_frame_list::StackFrameList, variable_list::VariableList,
+    watch_list::WatchList,
 };
 
 #[derive(Clone, Hash, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -22,6 +23,9 @@ pub(crate) enum DebuggerPaneItem {
     Frames,
     Modules,
     LoadedSources,
+    Disassembly,
+    Memory,
+    WatchList,
 }
 
 impl DebuggerPaneItem {
@@ -33,6 +37,9 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::Frames,
             DebuggerPaneItem::Modules,
             DebuggerPaneItem::LoadedSources,
+            DebuggerPaneItem::Disassembly,
+            DebuggerPaneItem::Memory,
+            DebuggerPaneItem::WatchList,
         ];
         VARIANTS
     }
@@ -43,6 +50,12 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources => capabilities
                 .supports_loaded_sources_request
                 .unwrap_or_default(),
+            DebuggerPaneItem::Disassembly => capabilities
+                .supports_disassemble_request
+                .unwrap_or_default(),
+            DebuggerPaneItem::Memory => capabilities
+                .supports_read_memory_request
+                .unwrap_or_default(),
             _ => true,
         }
     }
@@ -55,6 +68,9 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::Frames => SharedString::new_static("Frames"),
             DebuggerPaneItem::Modules => SharedString::new_static("Modules"),
             DebuggerPaneItem::LoadedSources => SharedString::new_static("Sources"),
+            DebuggerPaneItem::Disassembly => SharedString::new_static("Disassembly"),
+            DebuggerPaneItem::Memory => SharedString::new_static("Memory"),
+            DebuggerPaneItem::WatchList => SharedString::new_static("Watch"),
         }
     }
 }
@@ -85,6 +101,7 @@ pub(crate) struct SerializedPane {
 }
 
 const DEBUGGER_PANEL_PREFIX: &str = "debugger_panel_";
+const DEBUGGER_WATCH_EXPRESSIONS_PREFIX: &str = "debugger_watch_expressions_";
 
 pub(crate) async fn serialize_pane_layout(
     adapter_name: SharedString,
@@ -159,6 +176,43 @@ pub(crate) async fn get_serialized_pane_layout(
         .and_then(|value| serde_json::from_str::<SerializedPaneLayout>(&value).ok())
 }
 
+/// Watch expressions are shared by every debug session of a project, so they're keyed by the
+/// project's visible worktree roots.
+pub(crate) fn watch_expressions_key(project: &Entity<Project>, cx: &App) -> Option<String> {
+    let mut roots = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        return None;
+    }
+    roots.sort();
+
+    Some(format!(
+        "{DEBUGGER_WATCH_EXPRESSIONS_PREFIX}{}",
+        roots.join(",")
+    ))
+}
+
+pub(crate) async fn serialize_watch_expressions(
+    key: String,
+    expressions: Vec<String>,
+) -> anyhow::Result<()> {
+    KEY_VALUE_STORE
+        .write_kvp(key, serde_json::to_string(&expressions)?)
+        .await
+}
+
+pub(crate) async fn get_serialized_watch_expressions(key: &str) -> Vec<String> {
+    KEY_VALUE_STORE
+        .read_kvp(key)
+        .log_err()
+        .flatten()
+        .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+        .unwrap_or_default()
+}
+
 pub(crate) fn deserialize_pane_layout(
     serialized: SerializedPaneLayout,
     workspace: &WeakEntity<Workspace>,
@@ -169,6 +223,9 @@ pub(crate) fn deserialize_pane_layout(
     console: &Entity<Console>,
     breakpoint_list: &Entity<BreakpointList>,
     loaded_sources: &Entity<LoadedSourceList>,
+    disassembly: &Entity<Disassembly>,
+    memory_view: &Entity<MemoryView>,
+    watch_list: &Entity<WatchList>,
     subscriptions: &mut HashMap<EntityId, Subscription>,
     window: &mut Window,
     cx: &mut Context<RunningState>,
@@ -191,6 +248,9 @@ pub(crate) fn deserialize_pane_layout(
                     console,
                     breakpoint_list,
                     loaded_sources,
+                    disassembly,
+                    memory_view,
+                    watch_list,
                     subscriptions,
                     window,
                     cx,
@@ -259,6 +319,27 @@ pub(crate) fn deserialize_pane_layout(
                         None,
                         cx,
                     )),
+                    DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                        disassembly.focus_handle(cx),
+                        disassembly.clone().into(),
+                        DebuggerPaneItem::Disassembly,
+                        None,
+                        cx,
+                    )),
+                    DebuggerPaneItem::Memory => Box::new(SubView::new(
+                        memory_view.focus_handle(cx),
+                        memory_view.clone().into(),
+                        DebuggerPaneItem::Memory,
+                        None,
+                        cx,
+                    )),
+                    DebuggerPaneItem::WatchList => Box::new(SubView::new(
+                        watch_list.focus_handle(cx),
+                        watch_list.clone().into(),
+                        DebuggerPaneItem::WatchList,
+                        None,
+                        cx,
+                    )),
                     DebuggerPaneItem::Console => Box::new(SubView::new(
                         pane.focus_handle(cx),
                         console.clone().into(),
@@ -1,28 +1,40 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly;
 pub(crate) mod loaded_source_list;
+pub(crate) mod memory_view;
 pub(crate) mod module_list;
 pub mod stack_frame_list;
 pub mod variable_list;
+pub(crate) mod watch_list;
 
 use std::{any::Any, ops::ControlFlow, sync::Arc, time::Duration};
 
 use crate::persistence::{self, DebuggerPaneItem, SerializedPaneLayout};
 
 use super::DebugPanelItemEvent;
+use anyhow::anyhow;
 use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
-use dap::{Capabilities, Thread, client::SessionId, debugger_settings::DebuggerSettings};
+use dap::{
+    Capabilities, SteppingGranularity, Thread, VariableReference, client::SessionId,
+    debugger_settings::DebuggerSettings,
+};
+use disassembly::Disassembly;
 use gpui::{
     Action as _, AnyView, AppContext, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
     NoAction, Pixels, Point, Subscription, Task, WeakEntity,
 };
 use loaded_source_list::LoadedSourceList;
+use memory_view::MemoryView;
 use module_list::ModuleList;
 use project::{
     Project,
-    debugger::session::{Session, SessionEvent, ThreadId, ThreadStatus},
+    debugger::{
+        breakpoint_store::{BreakpointState, DataBreakpoint, DataBreakpointAccessType},
+        session::{Session, SessionEvent, ThreadId, ThreadStatus},
+    },
 };
 use rpc::proto::ViewId;
 use settings::Settings;
@@ -33,7 +45,8 @@ use ui::{
     StatefulInteractiveElement, Styled, Tab, Window, div, h_flex, v_flex,
 };
 use util::ResultExt;
-use variable_list::VariableList;
+use variable_list::{VariableList, VariableListEvent};
+use watch_list::WatchList;
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, Member, Pane, PaneGroup, Workspace,
     item::TabContentParams, move_item, pane::Event,
@@ -51,6 +64,9 @@ pub struct RunningState {
     stack_frame_list: Entity<stack_frame_list::StackFrameList>,
     loaded_sources_list: Entity<LoadedSourceList>,
     module_list: Entity<module_list::ModuleList>,
+    disassembly: Entity<Disassembly>,
+    memory_view: Entity<MemoryView>,
+    watch_list: Entity<WatchList>,
     _console: Entity<Console>,
     breakpoint_list: Entity<BreakpointList>,
     panes: PaneGroup,
@@ -377,7 +393,13 @@ impl RunningState {
         let session_id = session.read(cx).session_id();
         let weak_state = cx.weak_entity();
         let stack_frame_list = cx.new(|cx| {
-            StackFrameList::new(workspace.clone(), session.clone(), weak_state, window, cx)
+            StackFrameList::new(
+                workspace.clone(),
+                session.clone(),
+                weak_state.clone(),
+                window,
+                cx,
+            )
         });
 
         let variable_list =
@@ -387,6 +409,28 @@ impl RunningState {
 
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
 
+        let disassembly = cx.new(|cx| {
+            Disassembly::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                workspace.clone(),
+                weak_state.clone(),
+                cx,
+            )
+        });
+
+        let memory_view = cx.new(|cx| MemoryView::new(session.clone(), window, cx));
+
+        let watch_list = cx.new(|cx| {
+            WatchList::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                &project,
+                window,
+                cx,
+            )
+        });
+
         let console = cx.new(|cx| {
             Console::new(
                 session.clone(),
@@ -401,6 +445,24 @@ impl RunningState {
 
         let _subscriptions = vec![
             cx.observe(&module_list, |_, _, cx| cx.notify()),
+            cx.subscribe_in(
+                &variable_list,
+                window,
+                |this, _, event, window, cx| match event {
+                    VariableListEvent::ViewMemory(memory_reference) => {
+                        this.view_memory(memory_reference.clone(), window, cx);
+                    }
+                    VariableListEvent::AddWatchExpression(expression) => {
+                        this.add_watch_expression(expression.clone(), window, cx);
+                    }
+                    VariableListEvent::AddDataBreakpoint {
+                        variables_reference,
+                        name,
+                    } => {
+                        this.add_data_breakpoint(*variables_reference, name.clone(), cx);
+                    }
+                },
+            ),
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
@@ -439,6 +501,9 @@ impl RunningState {
                 &console,
                 &breakpoint_list,
                 &loaded_source_list,
+                &disassembly,
+                &memory_view,
+                &watch_list,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -469,6 +534,28 @@ impl RunningState {
                 None
             };
 
+            let disassembly = if session
+                .read(cx)
+                .capabilities()
+                .supports_disassemble_request
+                .unwrap_or(false)
+            {
+                Some(&disassembly)
+            } else {
+                None
+            };
+
+            let memory_view = if session
+                .read(cx)
+                .capabilities()
+                .supports_read_memory_request
+                .unwrap_or(false)
+            {
+                Some(&memory_view)
+            } else {
+                None
+            };
+
             let root = Self::default_pane_layout(
                 project,
                 &workspace,
@@ -476,6 +563,9 @@ impl RunningState {
                 &variable_list,
                 module_list,
                 loaded_source_list,
+                disassembly,
+                memory_view,
+                &watch_list,
                 &console,
                 &breakpoint_list,
                 &mut pane_close_subscriptions,
@@ -498,6 +588,9 @@ impl RunningState {
             session_id,
             panes,
             module_list,
+            disassembly,
+            memory_view,
+            watch_list,
             _console: console,
             breakpoint_list,
             loaded_sources_list: loaded_source_list,
@@ -551,62 +644,212 @@ impl RunningState {
         );
 
         if let Some(pane) = self.panes.pane_at_pixel_position(position) {
-            let sub_view = match item_kind {
-                DebuggerPaneItem::Console => {
-                    let weak_console = self._console.clone().downgrade();
+            let sub_view = self.create_sub_view(item_kind, pane, cx);
+            pane.update(cx, |pane, cx| {
+                pane.add_item(sub_view, false, false, None, window, cx);
+            })
+        }
+    }
 
-                    Box::new(SubView::new(
-                        pane.focus_handle(cx),
-                        self._console.clone().into(),
-                        item_kind,
-                        Some(Box::new(move |cx| {
-                            weak_console
-                                .read_with(cx, |console, cx| console.show_indicator(cx))
-                                .unwrap_or_default()
-                        })),
-                        cx,
-                    ))
-                }
-                DebuggerPaneItem::Variables => Box::new(SubView::new(
-                    self.variable_list.focus_handle(cx),
-                    self.variable_list.clone().into(),
-                    item_kind,
-                    None,
-                    cx,
-                )),
-                DebuggerPaneItem::BreakpointList => Box::new(SubView::new(
-                    self.breakpoint_list.focus_handle(cx),
-                    self.breakpoint_list.clone().into(),
-                    item_kind,
-                    None,
-                    cx,
-                )),
-                DebuggerPaneItem::Frames => Box::new(SubView::new(
-                    self.stack_frame_list.focus_handle(cx),
-                    self.stack_frame_list.clone().into(),
-                    item_kind,
-                    None,
-                    cx,
-                )),
-                DebuggerPaneItem::Modules => Box::new(SubView::new(
-                    self.module_list.focus_handle(cx),
-                    self.module_list.clone().into(),
+    fn create_sub_view(
+        &self,
+        item_kind: DebuggerPaneItem,
+        pane: &Entity<Pane>,
+        cx: &mut App,
+    ) -> Box<Entity<SubView>> {
+        match item_kind {
+            DebuggerPaneItem::Console => {
+                let weak_console = self._console.clone().downgrade();
+
+                Box::new(SubView::new(
+                    pane.focus_handle(cx),
+                    self._console.clone().into(),
                     item_kind,
-                    None,
+                    Some(Box::new(move |cx| {
+                        weak_console
+                            .read_with(cx, |console, cx| console.show_indicator(cx))
+                            .unwrap_or_default()
+                    })),
                     cx,
-                )),
-                DebuggerPaneItem::LoadedSources => Box::new(SubView::new(
-                    self.loaded_sources_list.focus_handle(cx),
-                    self.loaded_sources_list.clone().into(),
-                    item_kind,
-                    None,
+                ))
+            }
+            DebuggerPaneItem::Variables => Box::new(SubView::new(
+                self.variable_list.focus_handle(cx),
+                self.variable_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::BreakpointList => Box::new(SubView::new(
+                self.breakpoint_list.focus_handle(cx),
+                self.breakpoint_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Frames => Box::new(SubView::new(
+                self.stack_frame_list.focus_handle(cx),
+                self.stack_frame_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Modules => Box::new(SubView::new(
+                self.module_list.focus_handle(cx),
+                self.module_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::LoadedSources => Box::new(SubView::new(
+                self.loaded_sources_list.focus_handle(cx),
+                self.loaded_sources_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly.focus_handle(cx),
+                self.disassembly.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Memory => Box::new(SubView::new(
+                self.memory_view.focus_handle(cx),
+                self.memory_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::WatchList => Box::new(SubView::new(
+                self.watch_list.focus_handle(cx),
+                self.watch_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+        }
+    }
+
+    /// Shows the memory at the given reference in the memory view, adding the view to the pane
+    /// that contains the variable list if it isn't part of the layout.
+    pub(crate) fn view_memory(
+        &mut self,
+        memory_reference: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.memory_view.update(cx, |memory_view, cx| {
+            memory_view.open(memory_reference, window, cx);
+        });
+        self.activate_or_add_pane_item(DebuggerPaneItem::Memory, window, cx);
+    }
+
+    /// Adds an expression to the watch list, adding the list to the pane that contains the
+    /// variable list if it isn't part of the layout.
+    pub(crate) fn add_watch_expression(
+        &mut self,
+        expression: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.watch_list.update(cx, |watch_list, cx| {
+            watch_list.add_expression(expression, cx);
+        });
+        self.activate_or_add_pane_item(DebuggerPaneItem::WatchList, window, cx);
+    }
+
+    /// Asks the adapter for the data backing the named child of a variables container, and
+    /// adds a breakpoint that stops when that data changes.
+    pub(crate) fn add_data_breakpoint(
+        &mut self,
+        variables_reference: VariableReference,
+        name: String,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(breakpoint_store) = self
+            .workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.project().read(cx).breakpoint_store()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let info = self.session.update(cx, |session, cx| {
+            session.data_breakpoint_info(variables_reference, name.clone(), cx)
+        });
+
+        cx.spawn(async move |_, cx| {
+            let info = info.await?;
+            let data_id = info.data_id.ok_or_else(|| {
+                anyhow!(
+                    "Can't set a data breakpoint on {name}: {}",
+                    info.description
+                )
+            })?;
+            let access_types = info.access_types.unwrap_or_default();
+            let access_type = access_types
+                .iter()
+                .any(|access_type| matches!(access_type, dap::DataBreakpointAccessType::Write))
+                .then_some(DataBreakpointAccessType::Write);
+
+            breakpoint_store.update(cx, |store, cx| {
+                store.add_data_breakpoint(
+                    DataBreakpoint {
+                        data_id: data_id.into(),
+                        description: info.description.into(),
+                        access_type,
+                        condition: None,
+                        hit_condition: None,
+                        state: BreakpointState::Enabled,
+                        can_persist: info.can_persist.unwrap_or_default(),
+                    },
                     cx,
-                )),
-            };
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
 
+    fn activate_or_add_pane_item(
+        &mut self,
+        item_kind: DebuggerPaneItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let existing_item = self.panes.panes().into_iter().find_map(|pane| {
+            let ix = pane.read(cx).items().position(|item| {
+                item.act_as::<SubView>(cx)
+                    .is_some_and(|view| view.read(cx).kind == item_kind)
+            })?;
+            Some((pane.clone(), ix))
+        });
+        if let Some((pane, ix)) = existing_item {
             pane.update(cx, |pane, cx| {
-                pane.add_item(sub_view, false, false, None, window, cx);
+                pane.activate_item(ix, true, true, window, cx);
+            });
+            return;
+        }
+
+        let pane = self
+            .panes
+            .panes()
+            .into_iter()
+            .find(|pane| {
+                pane.read(cx).items().any(|item| {
+                    item.act_as::<SubView>(cx)
+                        .is_some_and(|view| view.read(cx).kind == DebuggerPaneItem::Variables)
+                })
             })
+            .or_else(|| self.panes.panes().into_iter().next())
+            .cloned();
+        if let Some(pane) = pane {
+            let sub_view = self.create_sub_view(item_kind, &pane, cx);
+            pane.update(cx, |pane, cx| {
+                pane.add_item(sub_view, true, true, None, window, cx);
+            });
         }
     }
 
@@ -732,6 +975,21 @@ impl RunningState {
             this.activate_item(variable_list_position, true, true, window, cx);
         })
     }
+    #[cfg(test)]
+    pub(crate) fn disassembly(&self) -> &Entity<Disassembly> {
+        &self.disassembly
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory_view(&self) -> &Entity<MemoryView> {
+        &self.memory_view
+    }
+
+    #[cfg(test)]
+    pub(crate) fn watch_list(&self) -> &Entity<WatchList> {
+        &self.watch_list
+    }
+
     #[cfg(test)]
     pub(crate) fn variable_list(&self) -> &Entity<VariableList> {
         &self.variable_list
@@ -839,6 +1097,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub fn restart_session(&self, cx: &mut Context<Self>) {
         self.session().update(cx, |state, cx| {
             state.restart(None, cx);
@@ -948,6 +1226,9 @@ impl RunningState {
         variable_list: &Entity<VariableList>,
         module_list: Option<&Entity<ModuleList>>,
         loaded_source_list: Option<&Entity<LoadedSourceList>>,
+        disassembly: Option<&Entity<Disassembly>>,
+        memory_view: Option<&Entity<MemoryView>>,
+        watch_list: &Entity<WatchList>,
         console: &Entity<Console>,
         breakpoints: &Entity<BreakpointList>,
         subscriptions: &mut HashMap<EntityId, Subscription>,
@@ -1038,6 +1319,55 @@ impl RunningState {
                 );
                 this.activate_item(1, false, false, window, cx);
             }
+
+            this.add_item(
+                Box::new(SubView::new(
+                    watch_list.focus_handle(cx),
+                    watch_list.clone().into(),
+                    DebuggerPaneItem::WatchList,
+                    None,
+                    cx,
+                )),
+                false,
+                false,
+                None,
+                window,
+                cx,
+            );
+
+            if let Some(disassembly) = disassembly {
+                this.add_item(
+                    Box::new(SubView::new(
+                        disassembly.focus_handle(cx),
+                        disassembly.clone().into(),
+                        DebuggerPaneItem::Disassembly,
+                        None,
+                        cx,
+                    )),
+                    false,
+                    false,
+                    None,
+                    window,
+                    cx,
+                );
+            }
+
+            if let Some(memory_view) = memory_view {
+                this.add_item(
+                    Box::new(SubView::new(
+                        memory_view.focus_handle(cx),
+                        memory_view.clone().into(),
+                        DebuggerPaneItem::Memory,
+                        None,
+                        cx,
+                    )),
+                    false,
+                    false,
+                    None,
+                    window,
+                    cx,
+                );
+            }
         });
 
         let rightmost_pane = new_debugger_pane(workspace.clone(), project.clone(), window, cx);
@@ -1,5 +1,6 @@
 use std::{
     path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
@@ -13,7 +14,10 @@ use language::Point;
 use project::{
     Project,
     debugger::{
-        breakpoint_store::{BreakpointEditAction, BreakpointStore, SourceBreakpoint},
+        breakpoint_store::{
+            BreakpointEditAction, BreakpointStore, DataBreakpoint, DataBreakpointAccessType,
+            FunctionBreakpoint, SourceBreakpoint,
+        },
         session::Session,
     },
     worktree_store::WorktreeStore,
@@ -21,8 +25,8 @@ use project::{
 use ui::{
     App, Clickable, Color, Context, Div, Icon, IconButton, IconName, Indicator, InteractiveElement,
     IntoElement, Label, LabelCommon, LabelSize, ListItem, ParentElement, Render, RenderOnce,
-    Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement, Styled, Window, div,
-    h_flex, px, v_flex,
+    Scrollbar, ScrollbarState, SharedString, StatefulInteractiveElement, Styled, Tooltip, Window,
+    div, h_flex, px, v_flex,
 };
 use util::{ResultExt, maybe};
 use workspace::Workspace;
@@ -38,6 +42,14 @@ pub(crate) struct BreakpointList {
     hide_scrollbar_task: Option<Task<()>>,
     show_scrollbar: bool,
     focus_handle: FocusHandle,
+    editor: Option<(EditorTarget, Entity<Editor>)>,
+}
+
+/// What the text typed into the breakpoint list's inline editor is used for.
+#[derive(Clone, Debug, PartialEq)]
+enum EditorTarget {
+    NewFunctionBreakpoint,
+    ExceptionCondition(String),
 }
 
 impl Focusable for BreakpointList {
@@ -57,6 +69,8 @@ impl BreakpointList {
         let worktree_store = project.worktree_store();
 
         cx.new(|cx| {
+            cx.observe(&breakpoint_store, |_, _, cx| cx.notify())
+                .detach();
             let weak: gpui::WeakEntity<Self> = cx.weak_entity();
             let list_state = ListState::new(
                 0,
@@ -83,10 +97,103 @@ impl BreakpointList {
                 workspace,
                 session,
                 focus_handle: cx.focus_handle(),
+                editor: None,
             }
         })
     }
 
+    fn edit(
+        &mut self,
+        target: EditorTarget,
+        text: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                match target {
+                    EditorTarget::NewFunctionBreakpoint => "Function name",
+                    EditorTarget::ExceptionCondition(_) => "Condition",
+                },
+                cx,
+            );
+            if let Some(text) = text {
+                editor.set_text(text, window, cx);
+                editor.select_all(&editor::actions::SelectAll, window, cx);
+            }
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.editor = Some((target, editor));
+        self.list_state.reset(self.breakpoints.len());
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((target, editor)) = self.editor.take() else {
+            return;
+        };
+        let text = editor.read(cx).text(cx).trim().to_string();
+        match target {
+            EditorTarget::NewFunctionBreakpoint => {
+                self.breakpoint_store.update(cx, |store, cx| {
+                    store.add_function_breakpoint(text.into(), cx);
+                });
+            }
+            EditorTarget::ExceptionCondition(id) => {
+                self.session.update(cx, |session, cx| {
+                    session.set_exception_breakpoint_condition(&id, Some(text.into()), cx);
+                });
+            }
+        }
+        self.focus_handle.focus(window);
+        self.list_state.reset(self.breakpoints.len());
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editor.take().is_some() {
+            self.focus_handle.focus(window);
+            self.list_state.reset(self.breakpoints.len());
+            cx.notify();
+        }
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if let Some((EditorTarget::NewFunctionBreakpoint, editor)) = &self.editor {
+            return Some(
+                h_flex()
+                    .w_full()
+                    .px_1()
+                    .child(div().flex_1().child(editor.clone()))
+                    .into_any_element(),
+            );
+        }
+
+        let supports_function_breakpoints = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_function_breakpoints
+            .unwrap_or_default();
+        supports_function_breakpoints.then(|| {
+            h_flex()
+                .w_full()
+                .px_1()
+                .justify_end()
+                .child(
+                    IconButton::new("breakpoint-list-add-function-breakpoint", IconName::Plus)
+                        .icon_size(ui::IconSize::XSmall)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.edit(EditorTarget::NewFunctionBreakpoint, None, window, cx);
+                        }))
+                        .tooltip(Tooltip::text("Add Function Breakpoint")),
+                )
+                .into_any_element()
+        })
+    }
+
     fn hide_scrollbar(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
         self.hide_scrollbar_task = Some(cx.spawn_in(window, async move |panel, cx| {
@@ -146,6 +253,26 @@ impl Render for BreakpointList {
         _window: &mut ui::Window,
         cx: &mut ui::Context<Self>,
     ) -> impl ui::IntoElement {
+        let store = self.breakpoint_store.read(cx);
+        let function_breakpoints = store
+            .function_breakpoints()
+            .iter()
+            .cloned()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::FunctionBreakpoint(breakpoint),
+                weak: cx.weak_entity(),
+            })
+            .collect::<Vec<_>>();
+        let data_breakpoints = store
+            .data_breakpoints()
+            .iter()
+            .cloned()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::DataBreakpoint(breakpoint),
+                weak: cx.weak_entity(),
+            })
+            .collect::<Vec<_>>();
+
         let old_len = self.breakpoints.len();
         let breakpoints = self.breakpoint_store.read(cx).all_breakpoints(cx);
         self.breakpoints.clear();
@@ -194,25 +321,40 @@ impl Render for BreakpointList {
                 })
             })
         });
+        let session = self.session.read(cx);
         let exception_breakpoints =
-            self.session
-                .read(cx)
+            session
                 .exception_breakpoints()
                 .map(|(data, is_enabled)| BreakpointEntry {
                     kind: BreakpointEntryKind::ExceptionBreakpoint(ExceptionBreakpoint {
                         id: data.filter.clone(),
                         data: data.clone(),
                         is_enabled: *is_enabled,
+                        condition: session
+                            .exception_breakpoint_condition(&data.filter)
+                            .cloned(),
+                        editor: self.editor.as_ref().and_then(|(target, editor)| {
+                            (*target == EditorTarget::ExceptionCondition(data.filter.clone()))
+                                .then(|| editor.clone())
+                        }),
                     }),
                     weak: weak.clone(),
                 });
-        self.breakpoints
-            .extend(breakpoints.chain(exception_breakpoints));
+        self.breakpoints.extend(
+            breakpoints
+                .chain(function_breakpoints)
+                .chain(data_breakpoints)
+                .chain(exception_breakpoints),
+        );
         if self.breakpoints.len() != old_len {
             self.list_state.reset(self.breakpoints.len());
         }
         v_flex()
             .id("breakpoint-list")
+            .key_context("BreakpointList")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
             .on_hover(cx.listener(|this, hovered, window, cx| {
                 if *hovered {
                     this.show_scrollbar = true;
@@ -224,6 +366,7 @@ impl Render for BreakpointList {
             }))
             .size_full()
             .m_0p5()
+            .children(self.render_header(cx))
             .child(list(self.list_state.clone()).flex_grow())
             .children(self.render_vertical_scrollbar(cx))
     }
@@ -401,11 +544,158 @@ impl LineBreakpoint {
         )
     }
 }
+fn render_function_breakpoint(
+    breakpoint: FunctionBreakpoint,
+    weak: WeakEntity<BreakpointList>,
+) -> ListItem {
+    let icon_name = if breakpoint.state.is_enabled() {
+        IconName::DebugBreakpoint
+    } else {
+        IconName::DebugDisabledBreakpoint
+    };
+    let name = breakpoint.name.clone();
+    ListItem::new(SharedString::from(format!(
+        "function-breakpoint-ui-item-{}",
+        breakpoint.name
+    )))
+    .rounded()
+    .start_slot(
+        div()
+            .id(SharedString::from(format!(
+                "function-breakpoint-ui-toggle-{}",
+                breakpoint.name
+            )))
+            .cursor_pointer()
+            .on_click({
+                let weak = weak.clone();
+                let name = name.clone();
+                move |_, _, cx| {
+                    weak.update(cx, |this, cx| {
+                        this.breakpoint_store.update(cx, |store, cx| {
+                            store.toggle_function_breakpoint_state(&name, cx);
+                        })
+                    })
+                    .ok();
+                }
+            })
+            .child(Indicator::icon(Icon::new(icon_name)).color(Color::Debugger)),
+    )
+    .end_hover_slot(
+        IconButton::new(
+            SharedString::from(format!("function-breakpoint-ui-remove-{}", breakpoint.name)),
+            IconName::Close,
+        )
+        .on_click(move |_, _, cx| {
+            weak.update(cx, |this, cx| {
+                this.breakpoint_store.update(cx, |store, cx| {
+                    store.remove_function_breakpoint(&name, cx);
+                })
+            })
+            .ok();
+        })
+        .icon_size(ui::IconSize::XSmall),
+    )
+    .child(
+        v_flex()
+            .py_1()
+            .child(
+                Label::new(SharedString::from(breakpoint.name.to_string()))
+                    .size(LabelSize::Small)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            )
+            .child(
+                Label::new(match &breakpoint.condition {
+                    Some(condition) => SharedString::from(format!("Function, when {condition}")),
+                    None => SharedString::from("Function"),
+                })
+                .size(LabelSize::XSmall)
+                .color(Color::Muted)
+                .line_height_style(ui::LineHeightStyle::UiLabel),
+            ),
+    )
+}
+
+fn render_data_breakpoint(
+    breakpoint: DataBreakpoint,
+    weak: WeakEntity<BreakpointList>,
+) -> ListItem {
+    let icon_name = if breakpoint.state.is_enabled() {
+        IconName::DebugBreakpoint
+    } else {
+        IconName::DebugDisabledBreakpoint
+    };
+    let data_id = breakpoint.data_id.clone();
+    let access = match breakpoint.access_type {
+        Some(DataBreakpointAccessType::Read) => "Break on read",
+        Some(DataBreakpointAccessType::ReadWrite) => "Break on access",
+        Some(DataBreakpointAccessType::Write) | None => "Break on value change",
+    };
+    ListItem::new(SharedString::from(format!(
+        "data-breakpoint-ui-item-{}",
+        breakpoint.data_id
+    )))
+    .rounded()
+    .start_slot(
+        div()
+            .id(SharedString::from(format!(
+                "data-breakpoint-ui-toggle-{}",
+                breakpoint.data_id
+            )))
+            .cursor_pointer()
+            .on_click({
+                let weak = weak.clone();
+                let data_id = data_id.clone();
+                move |_, _, cx| {
+                    weak.update(cx, |this, cx| {
+                        this.breakpoint_store.update(cx, |store, cx| {
+                            store.toggle_data_breakpoint_state(&data_id, cx);
+                        })
+                    })
+                    .ok();
+                }
+            })
+            .child(Indicator::icon(Icon::new(icon_name)).color(Color::Debugger)),
+    )
+    .end_hover_slot(
+        IconButton::new(
+            SharedString::from(format!("data-breakpoint-ui-remove-{}", breakpoint.data_id)),
+            IconName::Close,
+        )
+        .on_click(move |_, _, cx| {
+            weak.update(cx, |this, cx| {
+                this.breakpoint_store.update(cx, |store, cx| {
+                    store.remove_data_breakpoint(&data_id, cx);
+                })
+            })
+            .ok();
+        })
+        .icon_size(ui::IconSize::XSmall),
+    )
+    .child(
+        v_flex()
+            .py_1()
+            .child(
+                Label::new(SharedString::from(breakpoint.description.to_string()))
+                    .size(LabelSize::Small)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            )
+            .child(
+                Label::new(access)
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .line_height_style(ui::LineHeightStyle::UiLabel),
+            ),
+    )
+}
+
 #[derive(Clone, Debug)]
 struct ExceptionBreakpoint {
     id: String,
     data: ExceptionBreakpointsFilter,
     is_enabled: bool,
+    condition: Option<Arc<str>>,
+    /// Set while this filter's condition is being edited.
+    editor: Option<Entity<Editor>>,
 }
 
 impl ExceptionBreakpoint {
@@ -416,11 +706,38 @@ impl ExceptionBreakpoint {
             Color::Muted
         };
         let id = SharedString::from(&self.id);
+        let supports_condition = self.data.supports_condition.unwrap_or_default();
+        let edit_condition = supports_condition.then(|| {
+            let list = list.clone();
+            let id = self.id.clone();
+            let condition = self.condition.clone();
+            IconButton::new(
+                SharedString::from(format!(
+                    "exception-breakpoint-ui-edit-condition-{}",
+                    self.id
+                )),
+                IconName::Pencil,
+            )
+            .on_click(move |_, window, cx| {
+                list.update(cx, |this, cx| {
+                    this.edit(
+                        EditorTarget::ExceptionCondition(id.clone()),
+                        condition.as_deref(),
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+            })
+            .icon_size(ui::IconSize::XSmall)
+            .tooltip(Tooltip::text("Edit Condition"))
+        });
         ListItem::new(SharedString::from(format!(
             "exception-breakpoint-ui-item-{}",
             self.id
         )))
         .rounded()
+        .end_hover_slot(edit_condition)
         .start_slot(
             div()
                 .id(SharedString::from(format!(
@@ -453,13 +770,26 @@ impl ExceptionBreakpoint {
                         .size(LabelSize::XSmall)
                         .line_height_style(ui::LineHeightStyle::UiLabel)
                         .color(Color::Muted)
-                })),
+                }))
+                .children(
+                    self.condition
+                        .filter(|_| self.editor.is_none())
+                        .map(|condition| {
+                            Label::new(SharedString::from(format!("When {condition}")))
+                                .size(LabelSize::XSmall)
+                                .line_height_style(ui::LineHeightStyle::UiLabel)
+                                .color(Color::Muted)
+                        }),
+                )
+                .children(self.editor),
         )
     }
 }
 #[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
+    FunctionBreakpoint(FunctionBreakpoint),
+    DataBreakpoint(DataBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
 }
 
@@ -474,6 +804,12 @@ impl RenderOnce for BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.render(self.weak)
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                render_function_breakpoint(function_breakpoint, self.weak)
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                render_data_breakpoint(data_breakpoint, self.weak)
+            }
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 exception_breakpoint.render(self.weak)
             }
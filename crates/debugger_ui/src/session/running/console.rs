@@ -142,13 +142,15 @@ impl Console {
         });
 
         self.session.update(cx, |state, cx| {
-            state.evaluate(
-                expression,
-                Some(dap::EvaluateArgumentsContext::Variables),
-                self.stack_frame_list.read(cx).selected_stack_frame_id(),
-                None,
-                cx,
-            );
+            state
+                .evaluate(
+                    expression,
+                    Some(dap::EvaluateArgumentsContext::Variables),
+                    self.stack_frame_list.read(cx).selected_stack_frame_id(),
+                    None,
+                    cx,
+                )
+                .detach();
         });
     }
 
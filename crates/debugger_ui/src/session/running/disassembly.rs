@@ -0,0 +1,453 @@
+use std::{path::Path, sync::Arc};
+
+use collections::HashMap;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy, Stateful,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{Buffer, Point};
+use project::debugger::session::{Session, SessionEvent, ThreadStatus, parse_memory_address};
+use ui::{Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::Workspace;
+
+use super::{
+    RunningState,
+    stack_frame_list::{StackFrameList, StackFrameListEvent},
+};
+
+/// How many instructions are disassembled before the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 50;
+/// How many instructions are disassembled starting at the instruction pointer.
+const INSTRUCTIONS_AFTER: u64 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DisassemblyEntry {
+    /// A line of source code that the instructions following it were generated from.
+    Source {
+        path: Arc<Path>,
+        line: u64,
+        text: Option<SharedString>,
+    },
+    Instruction {
+        instruction: dap::DisassembledInstruction,
+        is_current: bool,
+    },
+}
+
+pub(crate) struct Disassembly {
+    session: Entity<Session>,
+    state: WeakEntity<RunningState>,
+    workspace: WeakEntity<Workspace>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    list_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    entries: Vec<DisassemblyEntry>,
+    instruction_pointer: Option<String>,
+    invalidate: bool,
+    _fetch_instructions: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Disassembly {
+    pub fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        workspace: WeakEntity<Workspace>,
+        state: WeakEntity<RunningState>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_) => this.refresh(cx),
+            }),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Memory => this.refresh(cx),
+                _ => {}
+            }),
+        ];
+
+        let list_handle = UniformListScrollHandle::default();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(list_handle.clone()),
+            list_handle,
+            session,
+            state,
+            workspace,
+            stack_frame_list,
+            focus_handle: cx.focus_handle(),
+            entries: Vec::new(),
+            instruction_pointer: None,
+            invalidate: true,
+            _fetch_instructions: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> &[DisassemblyEntry] {
+        &self.entries
+    }
+
+    /// Disassembles the instructions surrounding the instruction pointer of the selected stack frame.
+    pub(crate) fn refresh(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .read(cx)
+            .selected_stack_frame()
+            .and_then(|frame| frame.instruction_pointer_reference.clone());
+        self.instruction_pointer = instruction_pointer.clone();
+
+        let Some(instruction_pointer) = instruction_pointer else {
+            self.entries.clear();
+            self._fetch_instructions = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        let instructions = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                instruction_pointer.clone(),
+                -(INSTRUCTIONS_BEFORE as i64),
+                INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                cx,
+            )
+        });
+
+        self._fetch_instructions = cx.spawn(async move |this, cx| {
+            let Some(instructions) = instructions.await.log_err() else {
+                return;
+            };
+
+            let mut source_buffers = HashMap::default();
+            for path in instructions
+                .iter()
+                .filter_map(|instruction| instruction.location.as_ref()?.path.as_deref())
+            {
+                let path = Arc::<Path>::from(Path::new(path));
+                if source_buffers.contains_key(&path) {
+                    continue;
+                }
+                let buffer = this
+                    .update(cx, |this, cx| {
+                        this.workspace.update(cx, |workspace, cx| {
+                            workspace
+                                .project()
+                                .update(cx, |project, cx| project.open_local_buffer(&path, cx))
+                        })
+                    })
+                    .ok()
+                    .and_then(|buffer| buffer.ok());
+                let buffer = match buffer {
+                    Some(buffer) => buffer.await.ok(),
+                    None => None,
+                };
+                source_buffers.insert(path, buffer);
+            }
+
+            this.update(cx, |this, cx| {
+                let current_address = parse_memory_address(&instruction_pointer);
+                this.entries = build_entries(instructions, current_address, |path, line| {
+                    let buffer = source_buffers.get(path)?.as_ref()?;
+                    source_line(buffer, line, cx)
+                });
+                if let Some(ix) = this.entries.iter().position(|entry| {
+                    matches!(entry, DisassemblyEntry::Instruction { is_current, .. } if *is_current)
+                }) {
+                    this.list_handle.scroll_to_item(ix, ScrollStrategy::Center);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn render_entries(
+        &mut self,
+        range: std::ops::Range<usize>,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        self.entries[range]
+            .iter()
+            .map(|entry| match entry {
+                DisassemblyEntry::Source { path, line, text } => {
+                    self.render_source_line(path, *line, text.clone(), cx)
+                }
+                DisassemblyEntry::Instruction {
+                    instruction,
+                    is_current,
+                } => self.render_instruction(instruction, *is_current, cx),
+            })
+            .collect()
+    }
+
+    fn render_source_line(
+        &self,
+        path: &Path,
+        line: u64,
+        text: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_2()
+            .child(
+                Label::new(format!("{file_name}:{line}"))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .when_some(text, |this, text| {
+                this.child(
+                    Label::new(text)
+                        .size(LabelSize::Small)
+                        .buffer_font(cx)
+                        .truncate(),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_instruction(
+        &self,
+        instruction: &dap::DisassembledInstruction,
+        is_current: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_2()
+            .when(is_current, |this| {
+                this.bg(cx.theme().colors().editor_debugger_active_line_background)
+            })
+            .child(div().w_4().when(is_current, |this| {
+                this.child(
+                    Icon::new(IconName::ArrowRight)
+                        .size(IconSize::XSmall)
+                        .color(Color::Accent),
+                )
+            }))
+            .child(
+                Label::new(instruction.address.clone())
+                    .size(LabelSize::Small)
+                    .buffer_font(cx)
+                    .color(Color::Muted),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    div().w_32().flex_none().child(
+                        Label::new(bytes)
+                            .size(LabelSize::Small)
+                            .buffer_font(cx)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+                )
+            })
+            .child(
+                Label::new(instruction.instruction.clone())
+                    .size(LabelSize::Small)
+                    .buffer_font(cx),
+            )
+            .when_some(instruction.symbol.clone(), |this, symbol| {
+                this.child(
+                    Label::new(format!("<{symbol}>"))
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_stopped = self
+            .state
+            .read_with(cx, |state, cx| state.thread_status(cx))
+            .ok()
+            .flatten()
+            .is_some_and(|status| status == ThreadStatus::Stopped);
+        let supports_stepping_granularity = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let enabled = is_stopped && supports_stepping_granularity;
+
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                IconButton::new("disassembly-step-over", IconName::ArrowRight)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!enabled)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.state
+                            .update(cx, |state, cx| state.step_over_instruction(cx))
+                            .ok();
+                    }))
+                    .tooltip(Tooltip::text("Step over instruction")),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::ArrowDownRight)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!enabled)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.state
+                            .update(cx, |state, cx| state.step_in_instruction(cx))
+                            .ok();
+                    }))
+                    .tooltip(Tooltip::text("Step into instruction")),
+            )
+            .when_some(self.instruction_pointer.clone(), |this, address| {
+                this.child(
+                    Label::new(address)
+                        .size(LabelSize::XSmall)
+                        .buffer_font(cx)
+                        .color(Color::Muted),
+                )
+            })
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("disassembly-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+/// Interleaves the source lines that instructions were generated from with the instructions.
+///
+/// Adapters may omit an instruction's source file when it's the same as the previous instruction's,
+/// so the last reported file is used for instructions without one, and a source line is inserted
+/// whenever the file or the line changes.
+pub(crate) fn build_entries(
+    instructions: Vec<dap::DisassembledInstruction>,
+    current_address: Option<u64>,
+    mut source_line: impl FnMut(&Path, u64) -> Option<SharedString>,
+) -> Vec<DisassemblyEntry> {
+    let mut entries = Vec::with_capacity(instructions.len());
+    let mut current_path: Option<Arc<Path>> = None;
+    let mut last_location: Option<(Arc<Path>, u64)> = None;
+
+    for instruction in instructions {
+        if let Some(source) = &instruction.location {
+            let path = source.path.as_deref().map(Path::new);
+            if current_path.as_deref() != path {
+                current_path = path.map(Arc::from);
+            }
+        }
+        if let Some((path, line)) = current_path.clone().zip(instruction.line) {
+            let is_new_location = last_location
+                .as_ref()
+                .is_none_or(|(last_path, last_line)| *last_path != path || *last_line != line);
+            if is_new_location {
+                entries.push(DisassemblyEntry::Source {
+                    text: source_line(&path, line),
+                    path: path.clone(),
+                    line,
+                });
+                last_location = Some((path, line));
+            }
+        }
+
+        let is_current = current_address.is_some()
+            && parse_memory_address(&instruction.address) == current_address;
+        entries.push(DisassemblyEntry::Instruction {
+            instruction,
+            is_current,
+        });
+    }
+
+    entries
+}
+
+fn source_line(buffer: &Entity<Buffer>, line: u64, cx: &App) -> Option<SharedString> {
+    let snapshot = buffer.read(cx).snapshot();
+    let row = u32::try_from(line.checked_sub(1)?).ok()?;
+    if row > snapshot.max_point().row {
+        return None;
+    }
+    let text = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    Some(text.trim_end().to_string().into())
+}
+
+impl Focusable for Disassembly {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for Disassembly {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.invalidate {
+            self.invalidate = false;
+            self.refresh(cx);
+        }
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .relative()
+                    .size_full()
+                    .p_1()
+                    .child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "disassembly",
+                            self.entries.len(),
+                            |this, range, _window, cx| this.render_entries(range, cx),
+                        )
+                        .track_scroll(self.list_handle.clone())
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+    }
+}
@@ -0,0 +1,308 @@
+use editor::Editor;
+use gpui::{AnyElement, Entity, FocusHandle, Focusable, Subscription, Task, TextStyleRefinement};
+use project::debugger::session::{MemoryChunk, Session, SessionEvent};
+use ui::{Tooltip, prelude::*};
+use util::ResultExt;
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 16;
+const PAGE_SIZE: usize = BYTES_PER_ROW * ROWS_PER_PAGE;
+
+/// A hex view of the debuggee's memory, starting at a memory reference reported by the adapter.
+pub(crate) struct MemoryView {
+    session: Entity<Session>,
+    focus_handle: FocusHandle,
+    address_editor: Entity<Editor>,
+    memory_reference: Option<String>,
+    /// The offset of the first displayed byte from `memory_reference`.
+    offset: i64,
+    memory: Option<MemoryChunk>,
+    edited_byte: Option<(usize, Entity<Editor>)>,
+    _fetch_memory: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MemoryView {
+    pub fn new(session: Entity<Session>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let address_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Memory reference or address", cx);
+            editor
+        });
+
+        let _subscriptions = vec![cx.subscribe(&session, |this, _, event, cx| match event {
+            SessionEvent::Stopped(_) | SessionEvent::Memory => this.fetch(cx),
+            _ => {}
+        })];
+
+        Self {
+            session,
+            focus_handle: cx.focus_handle(),
+            address_editor,
+            memory_reference: None,
+            offset: 0,
+            memory: None,
+            edited_byte: None,
+            _fetch_memory: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory(&self) -> Option<&MemoryChunk> {
+        self.memory.as_ref()
+    }
+
+    /// Shows the memory at the given memory reference, such as one reported for a variable.
+    pub(crate) fn open(
+        &mut self,
+        memory_reference: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.address_editor.update(cx, |editor, cx| {
+            editor.set_text(memory_reference.as_str(), window, cx);
+        });
+        self.memory_reference = Some(memory_reference);
+        self.offset = 0;
+        self.memory = None;
+        self.edited_byte = None;
+        self.fetch(cx);
+    }
+
+    fn fetch(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.memory_reference.clone() else {
+            return;
+        };
+
+        let memory = self.session.update(cx, |session, cx| {
+            session.read_memory(memory_reference, self.offset, PAGE_SIZE as u64, cx)
+        });
+        self._fetch_memory = cx.spawn(async move |this, cx| {
+            let memory = memory.await.log_err();
+            this.update(cx, |this, cx| {
+                this.memory = memory;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn scroll_by(&mut self, delta: i64, cx: &mut Context<Self>) {
+        self.offset += delta;
+        self.edited_byte = None;
+        self.fetch(cx);
+    }
+
+    fn edit_byte(&mut self, ix: usize, value: u8, window: &mut Window, cx: &mut Context<Self>) {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text_style_refinement(TextStyleRefinement {
+                font_size: Some(
+                    TextSize::XSmall
+                        .rems(cx)
+                        .to_pixels(window.rem_size())
+                        .into(),
+                ),
+                ..Default::default()
+            });
+            editor.set_text(format!("{value:02X}"), window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.edited_byte = Some((ix, editor));
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((ix, editor)) = self.edited_byte.take() {
+            let text = editor.read(cx).text(cx);
+            let Some(memory_reference) = self.memory_reference.clone() else {
+                return;
+            };
+            match u8::from_str_radix(text.trim().trim_start_matches("0x"), 16) {
+                Ok(value) => self
+                    .session
+                    .update(cx, |session, cx| {
+                        session.write_memory(
+                            memory_reference,
+                            self.offset + ix as i64,
+                            vec![value],
+                            cx,
+                        )
+                    })
+                    .detach_and_log_err(cx),
+                Err(_) => log::error!("Invalid byte value: {text}"),
+            }
+            self.focus_handle.focus(window);
+            cx.notify();
+        } else {
+            let memory_reference = self.address_editor.read(cx).text(cx).trim().to_string();
+            if !memory_reference.is_empty() {
+                self.open(memory_reference, window, cx);
+            }
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.edited_byte.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_reference = self.memory_reference.is_some();
+
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(div().flex_1().child(self.address_editor.clone()))
+            .child(
+                IconButton::new("memory-previous-page", IconName::ChevronUp)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!has_reference)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.scroll_by(-(PAGE_SIZE as i64), cx);
+                    }))
+                    .tooltip(Tooltip::text("Previous page")),
+            )
+            .child(
+                IconButton::new("memory-next-page", IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!has_reference)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.scroll_by(PAGE_SIZE as i64, cx);
+                    }))
+                    .tooltip(Tooltip::text("Next page")),
+            )
+    }
+
+    fn render_row(&self, row: usize, memory: &MemoryChunk, cx: &mut Context<Self>) -> AnyElement {
+        let supports_write = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_write_memory_request
+            .unwrap_or_default();
+        let start = row * BYTES_PER_ROW;
+        let bytes = (start..start + BYTES_PER_ROW)
+            .map(|ix| memory.data.get(ix).copied())
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .w_full()
+            .gap_3()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .child(
+                div()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(format!("{:016X}", memory.address + start as u64)),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(bytes.iter().enumerate().map(|(column, byte)| {
+                        let ix = start + column;
+                        if let Some((_, editor)) = self
+                            .edited_byte
+                            .as_ref()
+                            .filter(|(edited, _)| *edited == ix)
+                        {
+                            return div().w_6().child(editor.clone()).into_any_element();
+                        }
+
+                        let Some(byte) = *byte else {
+                            return div()
+                                .w_6()
+                                .text_color(cx.theme().colors().text_disabled)
+                                .child("??")
+                                .into_any_element();
+                        };
+
+                        div()
+                            .id(("memory-byte", ix))
+                            .w_6()
+                            .when(byte == 0, |this| {
+                                this.text_color(cx.theme().colors().text_muted)
+                            })
+                            .when(supports_write, |this| {
+                                this.cursor_pointer()
+                                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.edit_byte(ix, byte, window, cx);
+                                    }))
+                            })
+                            .child(format!("{byte:02X}"))
+                            .into_any_element()
+                    })),
+            )
+            .child(
+                div().child(
+                    bytes
+                        .iter()
+                        .map(|byte| match byte {
+                            Some(byte) if byte.is_ascii_graphic() || *byte == b' ' => *byte as char,
+                            _ => '.',
+                        })
+                        .collect::<String>(),
+                ),
+            )
+            .into_any_element()
+    }
+}
+
+impl Focusable for MemoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MemoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match self.memory.clone() {
+            Some(memory) => v_flex()
+                .id("memory-view-rows")
+                .size_full()
+                .p_1()
+                .overflow_y_scroll()
+                .children(
+                    (0..ROWS_PER_PAGE)
+                        .filter(|row| {
+                            row * BYTES_PER_ROW
+                                < memory.data.len() + memory.unreadable_bytes as usize
+                        })
+                        .map(|row| self.render_row(row, &memory, cx)),
+                )
+                .into_any_element(),
+            None => div()
+                .p_2()
+                .child(
+                    Label::new(if self.memory_reference.is_some() {
+                        "Memory could not be read"
+                    } else {
+                        "Enter an address, or view a variable's memory from its context menu"
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .key_context("MemoryView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(body)
+    }
+}
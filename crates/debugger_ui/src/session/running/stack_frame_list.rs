@@ -9,6 +9,7 @@ use gpui::{
 };
 
 use language::PointUtf16;
+use project::debugger::breakpoint_store::ActiveStackFrame;
 use project::debugger::session::{Session, SessionEvent, StackFrame};
 use project::{ProjectItem, ProjectPath};
 use ui::{Scrollbar, ScrollbarState, Tooltip, prelude::*};
@@ -187,29 +188,26 @@ impl StackFrameList {
         cx.notify();
     }
 
-    pub fn go_to_selected_stack_frame(&mut self, window: &Window, cx: &mut Context<Self>) {
-        if let Some(selected_stack_frame_id) = self.selected_stack_frame_id {
-            let frame = self
-                .entries
-                .iter()
-                .find_map(|entry| match entry {
-                    StackFrameEntry::Normal(dap) => {
-                        if dap.id == selected_stack_frame_id {
-                            Some(dap)
-                        } else {
-                            None
-                        }
-                    }
-                    StackFrameEntry::Collapsed(daps) => {
-                        daps.iter().find(|dap| dap.id == selected_stack_frame_id)
-                    }
-                })
-                .cloned();
-
-            if let Some(frame) = frame.as_ref() {
-                self.select_stack_frame(frame, true, window, cx)
-                    .detach_and_log_err(cx);
+    pub(crate) fn selected_stack_frame(&self) -> Option<&dap::StackFrame> {
+        let selected_stack_frame_id = self.selected_stack_frame_id?;
+        self.entries.iter().find_map(|entry| match entry {
+            StackFrameEntry::Normal(dap) => {
+                if dap.id == selected_stack_frame_id {
+                    Some(dap)
+                } else {
+                    None
+                }
             }
+            StackFrameEntry::Collapsed(daps) => {
+                daps.iter().find(|dap| dap.id == selected_stack_frame_id)
+            }
+        })
+    }
+
+    pub fn go_to_selected_stack_frame(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if let Some(frame) = self.selected_stack_frame().cloned() {
+            self.select_stack_frame(&frame, true, window, cx)
+                .detach_and_log_err(cx);
         }
     }
 
@@ -232,6 +230,7 @@ impl StackFrameList {
         };
 
         let row = (stack_frame.line.saturating_sub(1)) as u32;
+        let stack_frame_id = stack_frame.id;
 
         let Some(abs_path) = self.abs_path_from_stack_frame(&stack_frame) else {
             return Task::ready(Err(anyhow!("Project path not found")));
@@ -290,7 +289,12 @@ impl StackFrameList {
 
                     breakpoint_store.update(cx, |store, cx| {
                         store.set_active_position(
-                            (this.session.read(cx).session_id(), abs_path, position),
+                            ActiveStackFrame {
+                                session_id: this.session.read(cx).session_id(),
+                                stack_frame_id,
+                                path: abs_path,
+                                position,
+                            },
                             cx,
                         );
                     })
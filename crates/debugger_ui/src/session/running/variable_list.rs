@@ -2,9 +2,9 @@ use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
 use dap::{ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference};
 use editor::Editor;
 use gpui::{
-    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle, Focusable,
-    Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, TextStyleRefinement,
-    UniformListScrollHandle, actions, anchored, deferred, uniform_list,
+    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription,
+    TextStyleRefinement, UniformListScrollHandle, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use project::debugger::session::{Session, SessionEvent};
@@ -14,6 +14,19 @@ use util::{debug_panic, maybe};
 
 actions!(variable_list, [ExpandSelectedEntry, CollapseSelectedEntry]);
 
+#[derive(Debug)]
+pub enum VariableListEvent {
+    /// The user asked to view the memory backing a variable.
+    ViewMemory(String),
+    /// The user asked to add a variable's evaluatable name to the watch list.
+    AddWatchExpression(String),
+    /// The user asked to break when the named child of a variables container changes.
+    AddDataBreakpoint {
+        variables_reference: VariableReference,
+        name: String,
+    },
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) struct EntryState {
     depth: usize,
@@ -503,6 +516,25 @@ impl VariableList {
 
         let variable_value = dap_var.value.clone();
         let variable_name = dap_var.name.clone();
+        let evaluate_name = dap_var.evaluate_name.clone();
+        let memory_reference = dap_var.memory_reference.clone().filter(|_| {
+            self.session
+                .read(cx)
+                .capabilities()
+                .supports_read_memory_request
+                .unwrap_or_default()
+        });
+        let data_breakpoint_target = self
+            .entry_states
+            .get(&variable.path)
+            .filter(|_| {
+                self.session
+                    .read(cx)
+                    .capabilities()
+                    .supports_data_breakpoints
+                    .unwrap_or_default()
+            })
+            .map(|state| (state.parent_reference, dap_var.name.clone()));
         let this = cx.entity().clone();
 
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
@@ -515,13 +547,45 @@ impl VariableList {
                     cx.write_to_clipboard(ClipboardItem::new_string(variable_value.clone()))
                 }
             })
-            .entry("Set value", None, move |window, cx| {
-                this.update(cx, |variable_list, cx| {
-                    let editor = Self::create_variable_editor(&variable_value, window, cx);
-                    variable_list.edited_path = Some((variable.path.clone(), editor));
-
-                    cx.notify();
-                });
+            .entry("Set value", None, {
+                let this = this.clone();
+                move |window, cx| {
+                    this.update(cx, |variable_list, cx| {
+                        let editor = Self::create_variable_editor(&variable_value, window, cx);
+                        variable_list.edited_path = Some((variable.path.clone(), editor));
+
+                        cx.notify();
+                    });
+                }
+            })
+            .when_some(evaluate_name, |menu, evaluate_name| {
+                let this = this.clone();
+                menu.entry("Add to watch", None, move |_, cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(VariableListEvent::AddWatchExpression(evaluate_name.clone()));
+                    });
+                })
+            })
+            .when_some(
+                data_breakpoint_target,
+                |menu, (variables_reference, name)| {
+                    let this = this.clone();
+                    menu.entry("Break on value change", None, move |_, cx| {
+                        this.update(cx, |_, cx| {
+                            cx.emit(VariableListEvent::AddDataBreakpoint {
+                                variables_reference,
+                                name: name.clone(),
+                            });
+                        });
+                    })
+                },
+            )
+            .when_some(memory_reference, |menu, memory_reference| {
+                menu.separator().entry("View memory", None, move |_, cx| {
+                    this.update(cx, |_, cx| {
+                        cx.emit(VariableListEvent::ViewMemory(memory_reference.clone()));
+                    });
+                })
             })
         });
 
@@ -918,6 +982,8 @@ impl VariableList {
     }
 }
 
+impl EventEmitter<VariableListEvent> for VariableList {}
+
 impl Focusable for VariableList {
     fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
         self.focus_handle.clone()
@@ -0,0 +1,664 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashSet;
+use dap::EvaluateArgumentsContext;
+use editor::Editor;
+use gpui::{
+    AnyElement, ClickEvent, Entity, FocusHandle, Focusable, MouseButton, Stateful, Subscription,
+    Task, TextStyleRefinement, UniformListScrollHandle, uniform_list,
+};
+use project::{
+    Project,
+    debugger::session::{Session, SessionEvent},
+};
+use ui::{ListItem, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use util::ResultExt;
+
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use crate::persistence;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct WatchId(usize);
+
+/// The result of evaluating a watch expression in the selected stack frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WatchValue {
+    Value {
+        result: SharedString,
+        variables_reference: u64,
+    },
+    /// The adapter failed to evaluate the expression, e.g. because a name isn't in scope.
+    Unavailable,
+}
+
+struct WatchExpression {
+    id: WatchId,
+    expression: SharedString,
+    /// `None` until the expression has been evaluated in a stack frame.
+    value: Option<WatchValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EntryPath {
+    watch: WatchId,
+    names: Arc<[SharedString]>,
+}
+
+impl EntryPath {
+    fn with_child(&self, name: SharedString) -> Self {
+        Self {
+            watch: self.watch,
+            names: self
+                .names
+                .iter()
+                .cloned()
+                .chain(std::iter::once(name))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum EntryKind {
+    Expression(WatchId),
+    Variable(dap::Variable),
+}
+
+#[derive(Debug, Clone)]
+struct WatchListEntry {
+    path: EntryPath,
+    kind: EntryKind,
+}
+
+/// User-defined expressions that are re-evaluated whenever the selected stack frame changes.
+pub(crate) struct WatchList {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    list_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    watches: Vec<WatchExpression>,
+    next_watch_id: usize,
+    entries: Vec<WatchListEntry>,
+    expanded: HashSet<EntryPath>,
+    /// The watch being edited, or `None` for a watch that's being added.
+    edited_watch: Option<(Option<WatchId>, Entity<Editor>)>,
+    serialization_key: Option<String>,
+    _evaluate: Task<()>,
+    _serialize: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl WatchList {
+    pub fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_) => this.evaluate(cx),
+            }),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Memory => this.evaluate(cx),
+                _ => {}
+            }),
+            cx.observe(&session, |_, _, cx| cx.notify()),
+            cx.on_focus_out(&focus_handle, window, |this, _, _, cx| {
+                if this.edited_watch.take().is_some() {
+                    cx.notify();
+                }
+            }),
+        ];
+
+        let serialization_key = persistence::watch_expressions_key(project, cx);
+        if let Some(key) = serialization_key.clone() {
+            cx.spawn(async move |this, cx| {
+                let expressions = persistence::get_serialized_watch_expressions(&key).await;
+                this.update(cx, |this, cx| {
+                    let watches = expressions
+                        .into_iter()
+                        .map(|expression| this.new_watch(expression.into()))
+                        .collect::<Vec<_>>();
+                    this.watches.splice(0..0, watches);
+                    this.evaluate(cx);
+                })
+                .ok();
+            })
+            .detach();
+        }
+
+        let list_handle = UniformListScrollHandle::default();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(list_handle.clone()),
+            list_handle,
+            session,
+            stack_frame_list,
+            focus_handle,
+            watches: Vec::new(),
+            next_watch_id: 0,
+            entries: Vec::new(),
+            expanded: HashSet::default(),
+            edited_watch: None,
+            serialization_key,
+            _evaluate: Task::ready(()),
+            _serialize: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn watches(&self) -> Vec<(SharedString, Option<WatchValue>)> {
+        self.watches
+            .iter()
+            .map(|watch| (watch.expression.clone(), watch.value.clone()))
+            .collect()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn toggle_watch(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let path = EntryPath {
+            watch: self.watches[ix].id,
+            names: Arc::new([]),
+        };
+        self.toggle_entry(&path, cx);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn visible_variables(&mut self, cx: &mut Context<Self>) -> Vec<String> {
+        self.build_entries(cx);
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.kind {
+                EntryKind::Variable(variable) => Some(variable.name.clone()),
+                EntryKind::Expression(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_watch(&mut self, expression: SharedString) -> WatchExpression {
+        let id = WatchId(self.next_watch_id);
+        self.next_watch_id += 1;
+        WatchExpression {
+            id,
+            expression,
+            value: None,
+        }
+    }
+
+    pub(crate) fn add_expression(&mut self, expression: String, cx: &mut Context<Self>) {
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return;
+        }
+
+        let watch = self.new_watch(expression.to_string().into());
+        self.watches.push(watch);
+        self.serialize(cx);
+        self.evaluate(cx);
+    }
+
+    pub(crate) fn remove_expression(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.watches.len() {
+            return;
+        }
+
+        let watch = self.watches.remove(ix);
+        self.expanded.retain(|path| path.watch != watch.id);
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_expression(&mut self, id: WatchId, expression: String, cx: &mut Context<Self>) {
+        let Some(ix) = self.watches.iter().position(|watch| watch.id == id) else {
+            return;
+        };
+
+        let expression = expression.trim();
+        if expression.is_empty() {
+            self.remove_expression(ix, cx);
+            return;
+        }
+
+        let watch = &mut self.watches[ix];
+        if watch.expression.as_ref() != expression {
+            watch.expression = expression.to_string().into();
+            watch.value = None;
+            self.expanded.retain(|path| path.watch != id);
+            self.serialize(cx);
+            self.evaluate(cx);
+        }
+    }
+
+    /// Evaluates every watch expression in the selected stack frame.
+    pub(crate) fn evaluate(&mut self, cx: &mut Context<Self>) {
+        let Some(frame_id) = self.stack_frame_list.read(cx).selected_stack_frame_id() else {
+            for watch in &mut self.watches {
+                watch.value = None;
+            }
+            self._evaluate = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        let tasks = self
+            .watches
+            .iter()
+            .map(|watch| {
+                let task = self.session.update(cx, |session, cx| {
+                    session.evaluate(
+                        watch.expression.to_string(),
+                        Some(EvaluateArgumentsContext::Watch),
+                        Some(frame_id),
+                        None,
+                        cx,
+                    )
+                });
+                (watch.id, task)
+            })
+            .collect::<Vec<_>>();
+
+        self._evaluate = cx.spawn(async move |this, cx| {
+            for (id, task) in tasks {
+                let value = match task.await {
+                    Some(response) => WatchValue::Value {
+                        result: response.result.into(),
+                        variables_reference: response.variables_reference,
+                    },
+                    None => WatchValue::Unavailable,
+                };
+
+                let updated = this.update(cx, |this, cx| {
+                    if let Some(watch) = this.watches.iter_mut().find(|watch| watch.id == id) {
+                        watch.value = Some(value);
+                        cx.notify();
+                    }
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(key) = self.serialization_key.clone() else {
+            return;
+        };
+
+        let expressions = self
+            .watches
+            .iter()
+            .map(|watch| watch.expression.to_string())
+            .collect();
+        self._serialize = cx.background_spawn(async move {
+            persistence::serialize_watch_expressions(key, expressions)
+                .await
+                .log_err();
+        });
+    }
+
+    fn build_entries(&mut self, cx: &mut Context<Self>) {
+        let mut entries = Vec::new();
+
+        for watch in &self.watches {
+            let path = EntryPath {
+                watch: watch.id,
+                names: Arc::new([]),
+            };
+            entries.push(WatchListEntry {
+                path: path.clone(),
+                kind: EntryKind::Expression(watch.id),
+            });
+
+            let Some(WatchValue::Value {
+                variables_reference,
+                ..
+            }) = watch.value
+            else {
+                continue;
+            };
+            if variables_reference == 0 || !self.expanded.contains(&path) {
+                continue;
+            }
+
+            let mut stack = self
+                .session
+                .update(cx, |session, cx| session.variables(variables_reference, cx))
+                .into_iter()
+                .rev()
+                .map(|child| (path.with_child(child.name.clone().into()), child))
+                .collect::<Vec<_>>();
+            while let Some((path, variable)) = stack.pop() {
+                let variables_reference = variable.variables_reference;
+                entries.push(WatchListEntry {
+                    path: path.clone(),
+                    kind: EntryKind::Variable(variable),
+                });
+
+                if variables_reference != 0 && self.expanded.contains(&path) {
+                    let children = self
+                        .session
+                        .update(cx, |session, cx| session.variables(variables_reference, cx));
+                    stack.extend(
+                        children
+                            .into_iter()
+                            .rev()
+                            .map(|child| (path.with_child(child.name.clone().into()), child)),
+                    );
+                }
+            }
+        }
+
+        self.entries = entries;
+    }
+
+    fn toggle_entry(&mut self, path: &EntryPath, cx: &mut Context<Self>) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.clone());
+        }
+        cx.notify();
+    }
+
+    fn create_editor(default: &str, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text_style_refinement(TextStyleRefinement {
+                font_size: Some(
+                    TextSize::XSmall
+                        .rems(cx)
+                        .to_pixels(window.rem_size())
+                        .into(),
+                ),
+                ..Default::default()
+            });
+            editor.set_placeholder_text("Expression to watch", cx);
+            editor.set_text(default, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        editor
+    }
+
+    fn start_adding(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.edited_watch = Some((None, Self::create_editor("", window, cx)));
+        cx.notify();
+    }
+
+    fn start_editing(&mut self, id: WatchId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(watch) = self.watches.iter().find(|watch| watch.id == id) else {
+            return;
+        };
+
+        let editor = Self::create_editor(&watch.expression, window, cx);
+        self.edited_watch = Some((Some(id), editor));
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((id, editor)) = self.edited_watch.take() else {
+            return;
+        };
+
+        let expression = editor.read(cx).text(cx);
+        match id {
+            Some(id) => self.set_expression(id, expression, cx),
+            None => self.add_expression(expression, cx),
+        }
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.edited_watch.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn render_entries(&mut self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let entry = self.entries.get(ix)?.clone();
+                Some(match &entry.kind {
+                    EntryKind::Expression(id) => self.render_expression(&entry, *id, cx),
+                    EntryKind::Variable(variable) => self.render_variable(&entry, variable, cx),
+                })
+            })
+            .collect()
+    }
+
+    fn render_expression(
+        &self,
+        entry: &WatchListEntry,
+        id: WatchId,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(ix) = self.watches.iter().position(|watch| watch.id == id) else {
+            return div().into_any_element();
+        };
+        let watch = &self.watches[ix];
+        let variables_reference = match watch.value {
+            Some(WatchValue::Value {
+                variables_reference,
+                ..
+            }) => variables_reference,
+            _ => 0,
+        };
+        let edited_editor = self
+            .edited_watch
+            .as_ref()
+            .filter(|(edited, _)| *edited == Some(id))
+            .map(|(_, editor)| editor.clone());
+
+        ListItem::new(("watch-expression", id.0))
+            .selectable(false)
+            .indent_level(1)
+            .indent_step_size(px(20.))
+            .always_show_disclosure_icon(true)
+            .when(variables_reference > 0, |this| {
+                let path = entry.path.clone();
+                this.toggle(self.expanded.contains(&path))
+                    .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_entry(&path, cx)))
+            })
+            .end_hover_slot(
+                IconButton::new(("remove-watch-expression", id.0), IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(ix) = this.watches.iter().position(|watch| watch.id == id) {
+                            this.remove_expression(ix, cx);
+                        }
+                    }))
+                    .tooltip(Tooltip::text("Remove Expression")),
+            )
+            .child(match edited_editor {
+                Some(editor) => div().w_full().child(editor).into_any_element(),
+                None => h_flex()
+                    .id(("watch-expression-label", id.0))
+                    .gap_1()
+                    .w_full()
+                    .text_ui_sm(cx)
+                    .on_click(cx.listener(move |this, click: &ClickEvent, window, cx| {
+                        if click.down.click_count > 1 {
+                            this.start_editing(id, window, cx);
+                        }
+                    }))
+                    .child(
+                        Label::new(watch.expression.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Default),
+                    )
+                    .child(
+                        match &watch.value {
+                            Some(WatchValue::Value { result, .. }) => {
+                                Label::new(format!("=  {result}")).color(Color::Muted)
+                            }
+                            Some(WatchValue::Unavailable) => {
+                                Label::new("not available").color(Color::Error)
+                            }
+                            None => Label::new("not evaluated").color(Color::Disabled),
+                        }
+                        .size(LabelSize::Small)
+                        .single_line()
+                        .truncate(),
+                    )
+                    .into_any_element(),
+            })
+            .into_any_element()
+    }
+
+    fn render_variable(
+        &self,
+        entry: &WatchListEntry,
+        variable: &dap::Variable,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let path = entry.path.clone();
+        let is_expanded = self.expanded.contains(&path);
+        let mut id = format!("watch-variable-{}", path.watch.0);
+        for name in path.names.iter() {
+            id.push('-');
+            id.push_str(name);
+        }
+
+        ListItem::new(SharedString::from(id))
+            .selectable(false)
+            .indent_level(path.names.len() + 1)
+            .indent_step_size(px(20.))
+            .always_show_disclosure_icon(true)
+            .when(variable.variables_reference > 0, |this| {
+                this.toggle(is_expanded)
+                    .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_entry(&path, cx)))
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm(cx)
+                    .w_full()
+                    .child(
+                        Label::new(variable.name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Default),
+                    )
+                    .when(!variable.value.is_empty(), |this| {
+                        this.child(
+                            Label::new(format!("=  {}", variable.value))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line()
+                                .truncate(),
+                        )
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn render_new_expression(&self) -> Option<AnyElement> {
+        let (_, editor) = self
+            .edited_watch
+            .as_ref()
+            .filter(|(edited, _)| edited.is_none())?;
+
+        Some(
+            div()
+                .w_full()
+                .pl_6()
+                .pr_2()
+                .child(editor.clone())
+                .into_any_element(),
+        )
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("watch-list-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+impl Focusable for WatchList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for WatchList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.build_entries(cx);
+
+        v_flex()
+            .key_context("WatchList")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .child(
+                h_flex()
+                    .w_full()
+                    .px_1()
+                    .justify_end()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        IconButton::new("add-watch-expression", IconName::Plus)
+                            .icon_size(IconSize::XSmall)
+                            .shape(ui::IconButtonShape::Square)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.start_adding(window, cx);
+                            }))
+                            .tooltip(Tooltip::text("Add Expression")),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .relative()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "watch-list",
+                            self.entries.len(),
+                            |this, range, _, cx| this.render_entries(range, cx),
+                        )
+                        .track_scroll(self.list_handle.clone())
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+            .children(self.render_new_expression())
+    }
+}
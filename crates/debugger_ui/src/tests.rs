@@ -7,11 +7,14 @@ use workspace::Workspace;
 use crate::{debugger_panel::DebugPanel, session::DebugSession};
 
 mod attach_modal;
+mod breakpoints;
 mod console;
 mod debugger_panel;
+mod memory;
 mod module_list;
 mod stack_frame_list;
 mod variable_list;
+mod watch_list;
 
 pub fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
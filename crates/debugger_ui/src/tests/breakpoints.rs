@@ -0,0 +1,167 @@
+use crate::tests::{active_debug_session_panel, init_test, init_test_workspace};
+use dap::requests::{DataBreakpointInfo, Initialize, SetDataBreakpoints, SetFunctionBreakpoints};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{
+    FakeFs, Project,
+    debugger::{
+        self,
+        breakpoint_store::{BreakpointState, DataBreakpointAccessType},
+    },
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use util::path;
+
+#[gpui::test]
+async fn test_function_and_data_breakpoints(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "main.rs": "fn main() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let function_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    let data_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    let session = debugger::test::start_debug_session(&project, cx, {
+        let function_breakpoints = function_breakpoints.clone();
+        let data_breakpoints = data_breakpoints.clone();
+        move |client| {
+            client.on_request::<Initialize, _>(move |_, _| {
+                Ok(dap::Capabilities {
+                    supports_function_breakpoints: Some(true),
+                    supports_data_breakpoints: Some(true),
+                    ..Default::default()
+                })
+            });
+
+            client.on_request::<SetFunctionBreakpoints, _>({
+                let function_breakpoints = function_breakpoints.clone();
+                move |_, args| {
+                    *function_breakpoints.lock().unwrap() = args
+                        .breakpoints
+                        .into_iter()
+                        .map(|breakpoint| breakpoint.name)
+                        .collect::<Vec<_>>();
+                    Ok(dap::SetFunctionBreakpointsResponse {
+                        breakpoints: Vec::new(),
+                    })
+                }
+            });
+
+            client.on_request::<SetDataBreakpoints, _>({
+                let data_breakpoints = data_breakpoints.clone();
+                move |_, args| {
+                    *data_breakpoints.lock().unwrap() = args
+                        .breakpoints
+                        .into_iter()
+                        .map(|breakpoint| breakpoint.data_id)
+                        .collect::<Vec<_>>();
+                    Ok(dap::SetDataBreakpointsResponse {
+                        breakpoints: Vec::new(),
+                    })
+                }
+            });
+
+            client.on_request::<DataBreakpointInfo, _>(move |_, args| {
+                assert_eq!(Some(2), args.variables_reference);
+                assert_eq!("counter", args.name);
+
+                Ok(dap::DataBreakpointInfoResponse {
+                    data_id: Some("0x1000".into()),
+                    description: "counter (4 bytes)".into(),
+                    access_types: Some(vec![
+                        dap::DataBreakpointAccessType::Read,
+                        dap::DataBreakpointAccessType::Write,
+                    ]),
+                    can_persist: Some(false),
+                })
+            });
+        }
+    })
+    .await
+    .unwrap();
+
+    cx.run_until_parked();
+
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+    breakpoint_store.update(cx, |store, cx| {
+        store.add_function_breakpoint("main".into(), cx);
+        store.add_function_breakpoint("main".into(), cx);
+        store.add_function_breakpoint("helper".into(), cx);
+    });
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        vec!["main".to_string(), "helper".to_string()],
+        *function_breakpoints.lock().unwrap(),
+    );
+
+    breakpoint_store.update(cx, |store, cx| {
+        store.toggle_function_breakpoint_state("main", cx);
+    });
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        vec!["helper".to_string()],
+        *function_breakpoints.lock().unwrap(),
+        "Disabled function breakpoints shouldn't be sent to the adapter"
+    );
+
+    let running_state = active_debug_session_panel(workspace, cx).update(cx, |item, _| {
+        item.mode()
+            .as_running()
+            .expect("Session should be running by this point")
+            .clone()
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.add_data_breakpoint(2, "counter".into(), cx);
+    });
+
+    cx.run_until_parked();
+
+    breakpoint_store.read_with(cx, |store, _| {
+        let data_breakpoints = store.data_breakpoints();
+        assert_eq!(1, data_breakpoints.len());
+        assert_eq!("0x1000", data_breakpoints[0].data_id.as_ref());
+        assert_eq!(
+            Some(DataBreakpointAccessType::Write),
+            data_breakpoints[0].access_type
+        );
+        assert_eq!(BreakpointState::Enabled, data_breakpoints[0].state);
+        assert!(!data_breakpoints[0].can_persist);
+    });
+    assert_eq!(
+        vec!["0x1000".to_string()],
+        *data_breakpoints.lock().unwrap()
+    );
+
+    breakpoint_store.update(cx, |store, cx| {
+        store.clear_breakpoints(cx);
+    });
+
+    cx.run_until_parked();
+
+    assert!(function_breakpoints.lock().unwrap().is_empty());
+    assert!(data_breakpoints.lock().unwrap().is_empty());
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
@@ -0,0 +1,323 @@
+use crate::{
+    session::running::disassembly::DisassemblyEntry,
+    tests::{active_debug_session_panel, init_test, init_test_workspace},
+};
+use dap::{
+    StackFrame,
+    requests::{Disassemble, Initialize, ReadMemory, Scopes, StackTrace, Threads, WriteMemory},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{
+    FakeFs, Project,
+    debugger::{self, session::MemoryChunk},
+};
+use serde_json::json;
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use unindent::Unindent as _;
+use util::path;
+
+fn instruction(
+    address: &str,
+    instruction: &str,
+    path: Option<&str>,
+    line: Option<u64>,
+) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: instruction.into(),
+        symbol: None,
+        location: path.map(|path| dap::Source {
+            name: None,
+            path: Some(path.into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line,
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_of_selected_stack_frame(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let main_file_content = r#"
+        int main() {
+            foo();
+        }
+    "#
+    .unindent();
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "main.c": main_file_content,
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = debugger::test::start_debug_session(&project, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .await
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(dap::Source {
+                    name: Some("main.c".into()),
+                    path: Some(path!("/project/src/main.c").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 2,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1000".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    // The adapter omits the location of instructions in the same file as the previous one.
+    let instructions = vec![
+        instruction(
+            "0x0ff8",
+            "push rbp",
+            Some(path!("/project/src/main.c")),
+            Some(1),
+        ),
+        instruction("0x0ffc", "mov rbp, rsp", None, None),
+        instruction("0x1000", "call foo", None, Some(2)),
+    ];
+
+    client.on_request::<Disassemble, _>({
+        let instructions = instructions.clone();
+        move |_, args| {
+            assert_eq!("0x1000", args.memory_reference);
+            assert_eq!(Some(-50), args.instruction_offset);
+
+            Ok(dap::DisassembleResponse {
+                instructions: instructions.clone(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx).update(cx, |item, _| {
+        item.mode()
+            .as_running()
+            .expect("Session should be running by this point")
+            .clone()
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.select_current_thread(
+            &running_state
+                .session()
+                .update(cx, |session, cx| session.threads(cx)),
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        let main_path = Arc::<Path>::from(Path::new(path!("/project/src/main.c")));
+        assert_eq!(
+            vec![
+                DisassemblyEntry::Source {
+                    path: main_path.clone(),
+                    line: 1,
+                    text: Some("int main() {".into()),
+                },
+                DisassemblyEntry::Instruction {
+                    instruction: instructions[0].clone(),
+                    is_current: false,
+                },
+                DisassemblyEntry::Instruction {
+                    instruction: instructions[1].clone(),
+                    is_current: false,
+                },
+                DisassemblyEntry::Source {
+                    path: main_path,
+                    line: 2,
+                    text: Some("    foo();".into()),
+                },
+                DisassemblyEntry::Instruction {
+                    instruction: instructions[2].clone(),
+                    is_current: true,
+                },
+            ],
+            running_state.disassembly().read(cx).entries()
+        );
+    });
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
+
+#[gpui::test]
+async fn test_memory_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = debugger::test::start_debug_session(&project, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_read_memory_request: Some(true),
+                supports_write_memory_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .await
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<ReadMemory, _>(move |_, args| {
+        assert_eq!("0x2000", args.memory_reference);
+        assert_eq!(Some(0), args.offset);
+
+        Ok(dap::ReadMemoryResponse {
+            address: "0x2000".into(),
+            unreadable_bytes: Some(12),
+            // [0x48, 0x69, 0x00, 0xFF]
+            data: Some("SGkA/w==".into()),
+        })
+    });
+
+    let wrote_memory = Arc::new(AtomicBool::new(false));
+    client.on_request::<WriteMemory, _>({
+        let wrote_memory = wrote_memory.clone();
+        move |_, args| {
+            assert_eq!("0x2000", args.memory_reference);
+            assert_eq!(Some(3), args.offset);
+            // [0x7F]
+            assert_eq!("fw==", args.data);
+            wrote_memory.store(true, Ordering::SeqCst);
+
+            Ok(dap::WriteMemoryResponse {
+                offset: None,
+                bytes_written: Some(1),
+            })
+        }
+    });
+
+    let running_state = active_debug_session_panel(workspace, cx).update(cx, |item, _| {
+        item.mode()
+            .as_running()
+            .expect("Session should be running by this point")
+            .clone()
+    });
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.view_memory("0x2000".into(), window, cx);
+    });
+
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        assert_eq!(
+            Some(&MemoryChunk {
+                address: 0x2000,
+                data: vec![0x48, 0x69, 0x00, 0xFF],
+                unreadable_bytes: 12,
+            }),
+            running_state.memory_view().read(cx).memory()
+        );
+    });
+
+    session
+        .update(cx, |session, cx| {
+            session.write_memory("0x2000".into(), 3, vec![0x7F], cx)
+        })
+        .await
+        .unwrap();
+
+    assert!(
+        wrote_memory.load(Ordering::SeqCst),
+        "Request WriteMemory should be sent to the adapter"
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
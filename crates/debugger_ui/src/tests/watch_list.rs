@@ -0,0 +1,216 @@
+use crate::{
+    session::running::watch_list::WatchValue,
+    tests::{active_debug_session_panel, init_test, init_test_workspace},
+};
+use dap::{
+    ErrorResponse, StackFrame, Variable,
+    requests::{Evaluate, Scopes, StackTrace, Threads, Variables},
+};
+use gpui::{BackgroundExecutor, SharedString, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project, debugger};
+use serde_json::json;
+use util::path;
+
+#[gpui::test]
+async fn test_watch_expressions_are_evaluated_on_stop(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/watch_project"),
+        json!({
+           "src": {
+               "main.rs": "fn main() {}",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/watch_project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = debugger::test::start_debug_session(&project, cx, |_| {})
+        .await
+        .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    client.on_request::<Evaluate, _>(move |_, args| {
+        assert!(matches!(
+            args.context,
+            Some(dap::EvaluateArgumentsContext::Watch)
+        ));
+        assert_eq!(Some(1), args.frame_id);
+
+        match args.expression.as_str() {
+            "point" => Ok(dap::EvaluateResponse {
+                result: "Point { x: 1, y: 2 }".into(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 5,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            }),
+            _ => Err(ErrorResponse {
+                error: Some(dap::Message {
+                    id: 1,
+                    format: "not in scope".into(),
+                    variables: None,
+                    send_telemetry: None,
+                    show_user: None,
+                    url: None,
+                    url_label: None,
+                }),
+            }),
+        }
+    });
+
+    client.on_request::<Variables, _>(move |_, args| {
+        assert_eq!(5, args.variables_reference);
+
+        Ok(dap::VariablesResponse {
+            variables: ["x", "y"]
+                .into_iter()
+                .enumerate()
+                .map(|(ix, name)| Variable {
+                    name: name.into(),
+                    value: (ix + 1).to_string(),
+                    type_: None,
+                    presentation_hint: None,
+                    evaluate_name: Some(format!("point.{name}")),
+                    variables_reference: 0,
+                    named_variables: None,
+                    indexed_variables: None,
+                    memory_reference: None,
+                    declaration_location_reference: None,
+                    value_location_reference: None,
+                })
+                .collect(),
+        })
+    });
+
+    let running_state = active_debug_session_panel(workspace, cx).update(cx, |item, _| {
+        item.mode()
+            .as_running()
+            .expect("Session should be running by this point")
+            .clone()
+    });
+    let watch_list =
+        running_state.update(cx, |running_state, _| running_state.watch_list().clone());
+
+    watch_list.update(cx, |watch_list, cx| {
+        watch_list.add_expression("point".into(), cx);
+        watch_list.add_expression("  ".into(), cx);
+        watch_list.add_expression("missing".into(), cx);
+    });
+
+    cx.run_until_parked();
+
+    watch_list.update(cx, |watch_list, _| {
+        assert_eq!(
+            vec![
+                (SharedString::from("point"), None),
+                (SharedString::from("missing"), None)
+            ],
+            watch_list.watches(),
+            "Watch expressions aren't evaluated until a stack frame is selected"
+        );
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.select_current_thread(
+            &running_state
+                .session()
+                .update(cx, |session, cx| session.threads(cx)),
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    watch_list.update(cx, |watch_list, cx| {
+        assert_eq!(
+            vec![
+                (
+                    SharedString::from("point"),
+                    Some(WatchValue::Value {
+                        result: "Point { x: 1, y: 2 }".into(),
+                        variables_reference: 5,
+                    })
+                ),
+                (SharedString::from("missing"), Some(WatchValue::Unavailable)),
+            ],
+            watch_list.watches()
+        );
+
+        assert!(watch_list.visible_variables(cx).is_empty());
+        watch_list.toggle_watch(0, cx);
+        watch_list.visible_variables(cx);
+    });
+
+    cx.run_until_parked();
+
+    watch_list.update(cx, |watch_list, cx| {
+        assert_eq!(vec!["x", "y"], watch_list.visible_variables(cx));
+
+        watch_list.remove_expression(1, cx);
+        assert_eq!(1, watch_list.watches().len());
+    });
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
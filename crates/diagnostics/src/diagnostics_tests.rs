@@ -849,6 +849,7 @@ async fn test_random_diagnostics_with_inlays(cx: &mut TestAppContext, mut rng: S
                                 id: InlayId::InlineCompletion(post_inc(&mut next_inlay_id)),
                                 position: snapshot.buffer_snapshot.anchor_before(position),
                                 text: Rope::from(format!("Test inlay {next_inlay_id}")),
+                                color: None,
                             }],
                             cx,
                         );
@@ -236,6 +236,9 @@ impl_actions!(
 actions!(
     editor,
     [
+        AcceptConflictBoth,
+        AcceptConflictOurs,
+        AcceptConflictTheirs,
         AcceptEditPrediction,
         AcceptPartialCopilotSuggestion,
         AcceptPartialEditPrediction,
@@ -281,6 +284,7 @@ actions!(
         DuplicateLineDown,
         DuplicateLineUp,
         DuplicateSelection,
+        EnableLargeFileEditing,
         ExpandMacroRecursively,
         FindAllReferences,
         FindNextMatch,
@@ -294,6 +298,7 @@ actions!(
         ToggleFoldRecursive,
         Format,
         FormatSelections,
+        GoToConflict,
         GoToDeclaration,
         GoToDeclarationSplit,
         GoToDefinition,
@@ -305,6 +310,7 @@ actions!(
         GoToImplementationSplit,
         GoToNextChange,
         GoToPreviousChange,
+        GoToPreviousConflict,
         GoToPreviousDiagnostic,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
@@ -395,9 +401,11 @@ actions!(
         SelectToPreviousWordStart,
         SelectToStartOfParagraph,
         SelectUp,
+        ShowCallHierarchy,
         ShowCharacterPalette,
         ShowEditPrediction,
         ShowSignatureHelp,
+        ShowTypeHierarchy,
         ShowWordCompletions,
         ShuffleLines,
         SortLinesCaseInsensitive,
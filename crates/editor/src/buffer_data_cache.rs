@@ -0,0 +1,251 @@
+//! Stores data fetched for each buffer of an editor, such as the semantic tokens or the folding ranges
+//! of language servers, and keeps it up to date as the buffers are edited and the excerpts change.
+//!
+//! Each kind of data is described by a [`BufferData`] implementation, which fetches the data of a buffer
+//! and shows the cached data in the editor; the cache only tracks what was fetched for which buffer.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, Context, Entity, Task};
+use language::Buffer;
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    Editor, document_colors::DocumentColors, document_links::DocumentLinks,
+    folding_ranges::FoldingRanges, merge_conflicts::MergeConflicts,
+    semantic_tokens::SemanticTokens,
+};
+
+/// How long to wait after an edit before fetching the data of the edited buffer again.
+const FETCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A kind of data fetched for each buffer of an editor.
+pub(super) trait BufferData: 'static {
+    type Data: 'static;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data>;
+
+    /// Whether the data should be fetched for the buffer, according to its settings.
+    fn enabled(_buffer: &Buffer, _cx: &App) -> bool {
+        true
+    }
+
+    /// Starts fetching the data of the buffer, returning `None` if it can't be fetched.
+    ///
+    /// The task resolves to `None` when the buffer has no such data, e.g. when none of
+    /// its language servers provide it.
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<Result<Option<Self::Data>>>>;
+
+    /// Shows the cached data of the buffer in all of its excerpts, or clears it from them
+    /// if there is none.
+    fn update(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>);
+
+    /// Shows the cached data of all buffers in the editor.
+    fn update_all(editor: &mut Editor, cx: &mut Context<Editor>) {
+        let buffer_ids = editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .map(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+        for buffer_id in buffer_ids {
+            Self::update(editor, buffer_id, cx);
+        }
+    }
+}
+
+/// The data of one kind last fetched for each buffer of the editor.
+pub(super) struct BufferDataCache<T> {
+    data: HashMap<BufferId, T>,
+    fetch_tasks: HashMap<BufferId, Task<()>>,
+}
+
+impl<T> Default for BufferDataCache<T> {
+    fn default() -> Self {
+        Self {
+            data: HashMap::default(),
+            fetch_tasks: HashMap::default(),
+        }
+    }
+}
+
+impl<T> BufferDataCache<T> {
+    pub(super) fn get(&self, buffer_id: &BufferId) -> Option<&T> {
+        self.data.get(buffer_id)
+    }
+
+    pub(super) fn get_mut(&mut self, buffer_id: &BufferId) -> Option<&mut T> {
+        self.data.get_mut(buffer_id)
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&BufferId, &T)> {
+        self.data.iter()
+    }
+
+    fn is_fetched(&self, buffer_id: &BufferId) -> bool {
+        self.data.contains_key(buffer_id)
+    }
+
+    /// Whether the data of the buffer was fetched or is being fetched.
+    fn is_requested(&self, buffer_id: &BufferId) -> bool {
+        self.data.contains_key(buffer_id) || self.fetch_tasks.contains_key(buffer_id)
+    }
+
+    fn remove(&mut self, buffer_id: &BufferId) -> Option<T> {
+        self.fetch_tasks.remove(buffer_id);
+        self.data.remove(buffer_id)
+    }
+
+    fn retain_buffers(&mut self, mut is_in_editor: impl FnMut(BufferId) -> bool) {
+        self.data.retain(|buffer_id, _| is_in_editor(*buffer_id));
+        self.fetch_tasks
+            .retain(|buffer_id, _| is_in_editor(*buffer_id));
+    }
+}
+
+impl Editor {
+    /// Fetches all kinds of buffer data for the given buffer, or for all buffers in the
+    /// editor if none is given.
+    pub(super) fn refresh_buffer_data(
+        &mut self,
+        for_buffer: Option<BufferId>,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.refresh_buffer_data_of::<SemanticTokens>(for_buffer, debounce, cx);
+        self.refresh_buffer_data_of::<FoldingRanges>(for_buffer, debounce, cx);
+        self.refresh_buffer_data_of::<DocumentLinks>(for_buffer, debounce, cx);
+        self.refresh_buffer_data_of::<DocumentColors>(for_buffer, debounce, cx);
+        self.refresh_buffer_data_of::<MergeConflicts>(for_buffer, debounce, cx);
+    }
+
+    /// Shows the cached buffer data in the editor's excerpts again, after they have changed.
+    pub(super) fn update_buffer_data(&mut self, cx: &mut Context<Self>) {
+        self.update_buffer_data_of::<SemanticTokens>(cx);
+        self.update_buffer_data_of::<FoldingRanges>(cx);
+        self.update_buffer_data_of::<DocumentLinks>(cx);
+        self.update_buffer_data_of::<DocumentColors>(cx);
+        self.update_buffer_data_of::<MergeConflicts>(cx);
+    }
+
+    /// Shows the cached data of a buffer whose excerpts were added to the editor, or
+    /// fetches it if it isn't cached yet.
+    pub(super) fn buffer_data_excerpts_added(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut Context<Self>,
+    ) {
+        self.buffer_data_excerpts_added_of::<SemanticTokens>(buffer_id, cx);
+        self.buffer_data_excerpts_added_of::<FoldingRanges>(buffer_id, cx);
+        self.buffer_data_excerpts_added_of::<DocumentLinks>(buffer_id, cx);
+        self.buffer_data_excerpts_added_of::<DocumentColors>(buffer_id, cx);
+        self.buffer_data_excerpts_added_of::<MergeConflicts>(buffer_id, cx);
+    }
+
+    /// Fetches the buffer data that was enabled by the new settings, clears the data that
+    /// was disabled, and restyles the rest.
+    pub(super) fn buffer_data_settings_changed(&mut self, cx: &mut Context<Self>) {
+        self.buffer_data_settings_changed_of::<SemanticTokens>(cx);
+        self.buffer_data_settings_changed_of::<FoldingRanges>(cx);
+        self.buffer_data_settings_changed_of::<DocumentLinks>(cx);
+        self.buffer_data_settings_changed_of::<DocumentColors>(cx);
+        self.buffer_data_settings_changed_of::<MergeConflicts>(cx);
+    }
+
+    /// Fetches the data of the given buffer, or of all buffers in the editor if none is
+    /// given, replacing any fetch of it that is still in progress.
+    pub(super) fn refresh_buffer_data_of<D: BufferData>(
+        &mut self,
+        for_buffer: Option<BufferId>,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_full() {
+            return;
+        }
+
+        let buffers = match for_buffer {
+            Some(buffer_id) => self.buffer.read(cx).buffer(buffer_id).into_iter().collect(),
+            None => self.buffer.read(cx).all_buffers(),
+        };
+        for buffer in buffers {
+            let buffer_id = buffer.read(cx).remote_id();
+            if !D::enabled(buffer.read(cx), cx) {
+                if D::cache(self).remove(&buffer_id).is_some() {
+                    D::update(self, buffer_id, cx);
+                }
+                continue;
+            }
+
+            let task = cx.spawn(async move |editor, cx| {
+                if debounce {
+                    cx.background_executor().timer(FETCH_DEBOUNCE).await;
+                }
+                let Some(fetch_task) = editor
+                    .update(cx, |editor, cx| D::fetch(editor, &buffer, cx))
+                    .ok()
+                    .flatten()
+                else {
+                    return;
+                };
+                let Some(data) = fetch_task.await.log_err() else {
+                    return;
+                };
+                editor
+                    .update(cx, |editor, cx| {
+                        let cache = D::cache(editor);
+                        match data {
+                            Some(data) => {
+                                cache.data.insert(buffer_id, data);
+                            }
+                            None => {
+                                cache.data.remove(&buffer_id);
+                            }
+                        }
+                        D::update(editor, buffer_id, cx);
+                    })
+                    .ok();
+            });
+            D::cache(self).fetch_tasks.insert(buffer_id, task);
+        }
+    }
+
+    fn update_buffer_data_of<D: BufferData>(&mut self, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer.clone();
+        D::cache(self)
+            .retain_buffers(|buffer_id| multi_buffer.read(cx).buffer(buffer_id).is_some());
+        D::update_all(self, cx);
+    }
+
+    fn buffer_data_excerpts_added_of<D: BufferData>(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut Context<Self>,
+    ) {
+        if D::cache(self).is_fetched(&buffer_id) {
+            D::update(self, buffer_id, cx);
+        } else {
+            self.refresh_buffer_data_of::<D>(Some(buffer_id), false, cx);
+        }
+    }
+
+    fn buffer_data_settings_changed_of<D: BufferData>(&mut self, cx: &mut Context<Self>) {
+        let buffers = self.buffer.read(cx).all_buffers();
+        for buffer in buffers {
+            let buffer_id = buffer.read(cx).remote_id();
+            let enabled = D::enabled(buffer.read(cx), cx);
+            if enabled != D::cache(self).is_requested(&buffer_id) {
+                self.refresh_buffer_data_of::<D>(Some(buffer_id), false, cx);
+            }
+        }
+        self.update_buffer_data_of::<D>(cx);
+    }
+}
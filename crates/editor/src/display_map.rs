@@ -81,6 +81,8 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[(Range<Anchor>, HighlightStyle)]>>;
+type FoldingRanges = TreeMap<BufferId, Arc<[Range<Anchor>]>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -104,6 +106,11 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Styles of the semantic tokens reported by language servers, by buffer.
+    semantic_token_highlights: SemanticTokenHighlights,
+    /// Regions that language servers reported as foldable, by buffer. Buffers
+    /// without an entry are folded by indentation.
+    folding_ranges: FoldingRanges,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -147,6 +154,8 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
+            folding_ranges: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -174,6 +183,8 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
+            folding_ranges: self.folding_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -504,6 +515,31 @@ impl DisplayMap {
         cleared
     }
 
+    /// Replaces the semantic token styles of a buffer. The ranges must be
+    /// sorted and must not overlap.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Arc<[(Range<Anchor>, HighlightStyle)]>,
+    ) {
+        self.semantic_token_highlights.insert(buffer_id, highlights);
+    }
+
+    pub(crate) fn clear_semantic_token_highlights(&mut self, buffer_id: BufferId) -> bool {
+        self.semantic_token_highlights.remove(&buffer_id).is_some()
+    }
+
+    /// Replaces the folding ranges of a buffer. The ranges must be sorted by
+    /// their start, with the largest of the ranges starting at the same
+    /// position first.
+    pub(crate) fn set_folding_ranges(&mut self, buffer_id: BufferId, ranges: Arc<[Range<Anchor>]>) {
+        self.folding_ranges.insert(buffer_id, ranges);
+    }
+
+    pub(crate) fn clear_folding_ranges(&mut self, buffer_id: BufferId) -> bool {
+        self.folding_ranges.remove(&buffer_id).is_some()
+    }
+
     pub fn set_font(&self, font: Font, font_size: Pixels, cx: &mut Context<Self>) -> bool {
         self.wrap_map
             .update(cx, |map, cx| map.set_font_with_size(font, font_size, cx))
@@ -608,6 +644,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -742,6 +779,8 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
+    folding_ranges: FoldingRanges,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -908,6 +947,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -1223,6 +1263,43 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether a fold can start at the given row, either because a language
+    /// server reported a folding range there or because it starts an indent.
+    pub fn starts_fold(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.folding_ranges_for_row(buffer_row) {
+            Some(ranges) => self.folding_range_end(ranges, buffer_row).is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// The folding ranges of the buffer at the given row, if a language server
+    /// reported them.
+    fn folding_ranges_for_row(&self, buffer_row: MultiBufferRow) -> Option<&[Range<Anchor>]> {
+        if self.folding_ranges.is_empty() {
+            return None;
+        }
+        let (buffer, _) = self.buffer_snapshot.buffer_line_for_row(buffer_row)?;
+        self.folding_ranges
+            .get(&buffer.remote_id())
+            .map(|ranges| &ranges[..])
+    }
+
+    /// The end of the largest of the folding ranges that start at the given row.
+    fn folding_range_end(
+        &self,
+        ranges: &[Range<Anchor>],
+        buffer_row: MultiBufferRow,
+    ) -> Option<Point> {
+        let start_ix = ranges.partition_point(|range| {
+            range.start.to_point(&self.buffer_snapshot).row < buffer_row.0
+        });
+        ranges[start_ix..]
+            .iter()
+            .take_while(|range| range.start.to_point(&self.buffer_snapshot).row == buffer_row.0)
+            .map(|range| range.end.to_point(&self.buffer_snapshot))
+            .find(|end| end.row > buffer_row.0)
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -1259,6 +1336,18 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(ranges) = self.folding_ranges_for_row(buffer_row) {
+            if self.is_line_folded(buffer_row) {
+                return None;
+            }
+            let end = self.folding_range_end(ranges, buffer_row)?;
+            Some(Crease::Inline {
+                range: start..end,
+                placeholder: self.fold_placeholder.clone(),
+                render_toggle: None,
+                render_trailer: None,
+                metadata: None,
+            })
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -1984,6 +2073,7 @@ pub mod tests {
                     id: InlayId::InlineCompletion(0),
                     position: buffer_snapshot.anchor_after(0),
                     text: "\n".into(),
+                    color: None,
                 }],
                 cx,
             );
@@ -11,6 +11,9 @@ use std::{
     vec,
 };
 use sum_tree::TreeMap;
+use text::BufferId;
+
+use super::SemanticTokenHighlights;
 
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
@@ -19,15 +22,24 @@ pub struct CustomHighlightsChunks<'a> {
     multibuffer_snapshot: &'a MultiBufferSnapshot,
 
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<TypeId, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightTag, HighlightStyle>,
     text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
+}
+
+/// Identifies the source of an active highlight. Semantic tokens sort before
+/// text highlights, so that text highlights are applied on top of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightTag {
+    SemanticToken(BufferId, usize),
+    Text(TypeId),
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct HighlightEndpoint {
     offset: usize,
     is_start: bool,
-    tag: TypeId,
+    tag: HighlightTag,
     style: HighlightStyle,
 }
 
@@ -36,6 +48,7 @@ impl<'a> CustomHighlightsChunks<'a> {
         range: Range<usize>,
         language_aware: bool,
         text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -44,9 +57,11 @@ impl<'a> CustomHighlightsChunks<'a> {
             offset: range.start,
 
             text_highlights,
+            semantic_token_highlights,
             highlight_endpoints: create_highlight_endpoints(
                 &range,
                 text_highlights,
+                semantic_token_highlights,
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
@@ -55,8 +70,12 @@ impl<'a> CustomHighlightsChunks<'a> {
     }
 
     pub fn seek(&mut self, new_range: Range<usize>) {
-        self.highlight_endpoints =
-            create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.highlight_endpoints = create_highlight_endpoints(
+            &new_range,
+            self.text_highlights,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -67,51 +86,88 @@ impl<'a> CustomHighlightsChunks<'a> {
 fn create_highlight_endpoints(
     range: &Range<usize>,
     text_highlights: Option<&TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
     buffer: &MultiBufferSnapshot,
 ) -> iter::Peekable<vec::IntoIter<HighlightEndpoint>> {
     let mut highlight_endpoints = Vec::new();
+    let start = buffer.anchor_after(range.start);
+    let end = buffer.anchor_after(range.end);
     if let Some(text_highlights) = text_highlights {
-        let start = buffer.anchor_after(range.start);
-        let end = buffer.anchor_after(range.end);
         for (&tag, text_highlights) in text_highlights.iter() {
             let style = text_highlights.0;
             let ranges = &text_highlights.1;
 
-            let start_ix = match ranges.binary_search_by(|probe| {
-                let cmp = probe.end.cmp(&start, &buffer);
-                if cmp.is_gt() {
-                    cmp::Ordering::Greater
-                } else {
-                    cmp::Ordering::Less
+            let start_ix = first_overlapping_range(ranges, |range| &range.end, &start, buffer);
+            for range in &ranges[start_ix..] {
+                if range.start.cmp(&end, &buffer).is_ge() {
+                    break;
                 }
-            }) {
-                Ok(i) | Err(i) => i,
-            };
 
-            for range in &ranges[start_ix..] {
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: range.start.to_offset(&buffer),
+                    is_start: true,
+                    tag: HighlightTag::Text(tag),
+                    style,
+                });
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: range.end.to_offset(&buffer),
+                    is_start: false,
+                    tag: HighlightTag::Text(tag),
+                    style,
+                });
+            }
+        }
+    }
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        for (&buffer_id, tokens) in semantic_token_highlights.iter() {
+            let start_ix = first_overlapping_range(tokens, |(range, _)| &range.end, &start, buffer);
+            for (token_ix, (range, style)) in tokens.iter().enumerate().skip(start_ix) {
                 if range.start.cmp(&end, &buffer).is_ge() {
                     break;
                 }
 
+                // Adjacent tokens share an offset, so each token gets its own
+                // tag to keep the end of one from clearing the start of the next.
+                let tag = HighlightTag::SemanticToken(buffer_id, token_ix);
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.start.to_offset(&buffer),
                     is_start: true,
                     tag,
-                    style,
+                    style: *style,
                 });
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.end.to_offset(&buffer),
                     is_start: false,
                     tag,
-                    style,
+                    style: *style,
                 });
             }
         }
-        highlight_endpoints.sort();
     }
+    highlight_endpoints.sort();
     highlight_endpoints.into_iter().peekable()
 }
 
+/// Returns the index of the first of the sorted items whose range ends after
+/// the given anchor.
+fn first_overlapping_range<T>(
+    items: &[T],
+    range_end: impl Fn(&T) -> &Anchor,
+    start: &Anchor,
+    buffer: &MultiBufferSnapshot,
+) -> usize {
+    match items.binary_search_by(|probe| {
+        let cmp = range_end(probe).cmp(start, buffer);
+        if cmp.is_gt() {
+            cmp::Ordering::Greater
+        } else {
+            cmp::Ordering::Less
+        }
+    }) {
+        Ok(i) | Err(i) => i,
+    }
+}
+
 impl<'a> Iterator for CustomHighlightsChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -0,0 +1,449 @@
+use gpui::{Context, Entity, Hsla, Pixels, Point, Rgba, Task, WeakEntity, Window};
+use language::{Bias, Buffer, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::{ColorPresentation, DocumentColor};
+use text::BufferId;
+use ui::{ContextMenu, prelude::*};
+use util::{ResultExt as _, post_inc};
+
+use crate::{
+    Editor, EditorSnapshot, InlayId, PointForPosition,
+    buffer_data_cache::{BufferData, BufferDataCache},
+    display_map::Inlay,
+    mouse_context_menu::MouseContextMenu,
+};
+
+/// The document colors last fetched for each buffer of the editor, and the
+/// swatches showing them.
+#[derive(Default)]
+pub(super) struct DocumentColorsState {
+    colors: BufferDataCache<Vec<DocumentColor>>,
+    inlays: Vec<ColorInlay>,
+    picker_task: Option<Task<()>>,
+}
+
+/// Shows swatches before the document colors of language servers, and opens
+/// a color picker changing them.
+pub(super) enum DocumentColors {}
+
+impl BufferData for DocumentColors {
+    type Data = Vec<DocumentColor>;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data> {
+        &mut editor.document_colors.colors
+    }
+
+    fn enabled(buffer: &Buffer, cx: &App) -> bool {
+        language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).document_colors
+    }
+
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<anyhow::Result<Option<Self::Data>>>> {
+        let colors = editor
+            .semantics_provider
+            .as_ref()?
+            .document_colors(buffer, cx)?;
+        Some(cx.background_spawn(async move { colors.await.map(Some) }))
+    }
+
+    fn update(editor: &mut Editor, _: BufferId, cx: &mut Context<Editor>) {
+        editor.update_document_color_inlays(cx);
+    }
+
+    fn update_all(editor: &mut Editor, cx: &mut Context<Editor>) {
+        editor.update_document_color_inlays(cx);
+    }
+}
+
+struct ColorInlay {
+    id: InlayId,
+    position: Anchor,
+    buffer_id: BufferId,
+    color: DocumentColor,
+}
+
+impl Editor {
+    /// Shows a swatch before each fetched document color in all excerpts of
+    /// the editor.
+    pub(super) fn update_document_color_inlays(&mut self, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut new_inlays = Vec::new();
+        for (buffer_id, colors) in self.document_colors.colors.iter() {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                let context = excerpt_range.context;
+                for color in colors {
+                    if color
+                        .range
+                        .start
+                        .cmp(&context.start, &buffer_snapshot)
+                        .is_lt()
+                    {
+                        continue;
+                    }
+                    if color
+                        .range
+                        .start
+                        .cmp(&context.end, &buffer_snapshot)
+                        .is_ge()
+                    {
+                        break;
+                    }
+                    let Some(position) =
+                        multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, color.range.start)
+                    else {
+                        continue;
+                    };
+                    new_inlays.push((position, *buffer_id, color.clone()));
+                }
+            }
+        }
+
+        let old_inlay_ids = self
+            .document_colors
+            .inlays
+            .drain(..)
+            .map(|inlay| inlay.id)
+            .collect::<Vec<_>>();
+        let mut to_insert = Vec::with_capacity(new_inlays.len());
+        for (position, buffer_id, color) in new_inlays {
+            let inlay = Inlay::color(
+                post_inc(&mut self.next_inlay_id),
+                position,
+                hsla_from_lsp(color.color),
+            );
+            self.document_colors.inlays.push(ColorInlay {
+                id: inlay.id,
+                position,
+                buffer_id,
+                color,
+            });
+            to_insert.push(inlay);
+        }
+        if !old_inlay_ids.is_empty() || !to_insert.is_empty() {
+            self.splice_inlays(&old_inlay_ids, to_insert, cx);
+        }
+    }
+
+    /// Opens the color picker when a color swatch was clicked, returning
+    /// whether it was.
+    pub(crate) fn click_document_color(
+        &mut self,
+        point_for_position: &PointForPosition,
+        snapshot: &EditorSnapshot,
+        mouse_position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.document_colors.inlays.is_empty()
+            || point_for_position.as_valid().is_some()
+            || point_for_position.column_overshoot_after_line_end != 0
+        {
+            return false;
+        }
+
+        let buffer_snapshot = &snapshot.buffer_snapshot;
+        let previous_valid_anchor = buffer_snapshot.anchor_at(
+            point_for_position.previous_valid.to_point(snapshot),
+            Bias::Left,
+        );
+        let next_valid_anchor = buffer_snapshot.anchor_at(
+            point_for_position.next_valid.to_point(snapshot),
+            Bias::Right,
+        );
+        let Some(inlay) = self.document_colors.inlays.iter().find(|inlay| {
+            inlay
+                .position
+                .cmp(&previous_valid_anchor, buffer_snapshot)
+                .is_ge()
+                && inlay
+                    .position
+                    .cmp(&next_valid_anchor, buffer_snapshot)
+                    .is_le()
+        }) else {
+            return false;
+        };
+        let (position, buffer_id, color) = (inlay.position, inlay.buffer_id, inlay.color.clone());
+        self.open_color_picker(position, buffer_id, color, mouse_position, window, cx);
+        true
+    }
+
+    fn open_color_picker(
+        &mut self,
+        position: Anchor,
+        buffer_id: BufferId,
+        color: DocumentColor,
+        mouse_position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(semantics_provider) = self.semantics_provider.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let Some(presentations) =
+            semantics_provider.color_presentations(&buffer, color.range.clone(), color.color, cx)
+        else {
+            return;
+        };
+
+        self.document_colors.picker_task = Some(cx.spawn_in(window, async move |editor, cx| {
+            let Some(presentations) = presentations.await.log_err() else {
+                return;
+            };
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    let context_menu =
+                        editor.color_picker_menu(buffer, color, presentations, window, cx);
+                    editor.mouse_context_menu = MouseContextMenu::pinned_to_editor(
+                        editor,
+                        position,
+                        mouse_position,
+                        context_menu,
+                        window,
+                        cx,
+                    );
+                    cx.notify();
+                })
+                .ok();
+        }));
+    }
+
+    /// Builds a menu that rewrites the color in the notations the language
+    /// server offers, or replaces it with an adjusted color.
+    fn color_picker_menu(
+        &self,
+        buffer: Entity<Buffer>,
+        color: DocumentColor,
+        presentations: Vec<ColorPresentation>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        let editor = cx.weak_entity();
+        let current_text = buffer
+            .read(cx)
+            .text_for_range(color.range.clone())
+            .collect::<String>();
+        let current_color = hsla_from_lsp(color.color);
+
+        ContextMenu::build(window, cx, move |menu, _, _| {
+            let menu = menu.custom_row(move |_, cx| {
+                h_flex()
+                    .gap_2()
+                    .child(color_swatch(current_color, cx))
+                    .child(Label::new(hex_label(current_color)))
+                    .into_any_element()
+            });
+
+            let menu = menu.when(!presentations.is_empty(), |mut menu| {
+                menu = menu.separator().header("Format");
+                for presentation in presentations {
+                    let editor = editor.clone();
+                    let buffer = buffer.clone();
+                    menu = menu.entry(presentation.label.clone(), None, move |_, cx| {
+                        editor
+                            .update(cx, |_, cx| {
+                                apply_color_presentation(&buffer, &presentation, cx)
+                            })
+                            .ok();
+                    });
+                }
+                menu
+            });
+
+            let mut menu = menu.separator().header("Adjust");
+            for (label, adjusted_color) in color_adjustments(current_color) {
+                let editor = editor.clone();
+                let buffer = buffer.clone();
+                let color = color.clone();
+                let current_text = current_text.clone();
+                menu = menu.custom_entry(
+                    move |_, cx| {
+                        h_flex()
+                            .gap_2()
+                            .child(color_swatch(adjusted_color, cx))
+                            .child(Label::new(label))
+                            .into_any_element()
+                    },
+                    move |_, cx| {
+                        change_document_color(
+                            editor.clone(),
+                            buffer.clone(),
+                            &color,
+                            adjusted_color,
+                            current_text.clone(),
+                            cx,
+                        );
+                    },
+                );
+            }
+            menu
+        })
+    }
+}
+
+/// Replaces a document color with another one, written in the same notation
+/// if the language server offers it.
+fn change_document_color(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    color: &DocumentColor,
+    new_color: Hsla,
+    current_text: String,
+    cx: &mut App,
+) {
+    editor
+        .update(cx, |editor, cx| {
+            let Some(presentations) = editor.semantics_provider.as_ref().and_then(|provider| {
+                provider.color_presentations(
+                    &buffer,
+                    color.range.clone(),
+                    lsp_from_hsla(new_color),
+                    cx,
+                )
+            }) else {
+                return;
+            };
+            editor.document_colors.picker_task = Some(cx.spawn(async move |editor, cx| {
+                let Some(presentations) = presentations.await.log_err() else {
+                    return;
+                };
+                let Some(presentation) = presentations
+                    .iter()
+                    .find(|presentation| same_notation(&presentation.label, &current_text))
+                    .or_else(|| presentations.first())
+                else {
+                    return;
+                };
+                editor
+                    .update(cx, |_, cx| {
+                        apply_color_presentation(&buffer, presentation, cx)
+                    })
+                    .ok();
+            }));
+        })
+        .ok();
+}
+
+fn apply_color_presentation(
+    buffer: &Entity<Buffer>,
+    presentation: &ColorPresentation,
+    cx: &mut App,
+) {
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(presentation.edits.iter().cloned(), None, cx);
+    });
+}
+
+/// Whether two ways of writing a color use the same notation, such as
+/// `#rrggbb` or `rgb(…)`.
+fn same_notation(a: &str, b: &str) -> bool {
+    fn notation(text: &str) -> &str {
+        if text.starts_with('#') {
+            "#"
+        } else {
+            text.split('(').next().unwrap_or(text).trim()
+        }
+    }
+    notation(a) == notation(b)
+}
+
+fn color_adjustments(color: Hsla) -> Vec<(&'static str, Hsla)> {
+    const STEP: f32 = 0.1;
+    [
+        (
+            "Lighter",
+            Hsla {
+                l: (color.l + STEP).min(1.),
+                ..color
+            },
+        ),
+        (
+            "Darker",
+            Hsla {
+                l: (color.l - STEP).max(0.),
+                ..color
+            },
+        ),
+        (
+            "More Saturated",
+            Hsla {
+                s: (color.s + STEP).min(1.),
+                ..color
+            },
+        ),
+        (
+            "Less Saturated",
+            Hsla {
+                s: (color.s - STEP).max(0.),
+                ..color
+            },
+        ),
+        (
+            "More Opaque",
+            Hsla {
+                a: (color.a + STEP).min(1.),
+                ..color
+            },
+        ),
+        (
+            "More Transparent",
+            Hsla {
+                a: (color.a - STEP).max(0.),
+                ..color
+            },
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, adjusted)| *adjusted != color)
+    .collect()
+}
+
+fn color_swatch(color: Hsla, cx: &App) -> impl IntoElement {
+    div()
+        .size_3()
+        .rounded_xs()
+        .border_1()
+        .border_color(cx.theme().colors().border)
+        .bg(color)
+}
+
+fn hex_label(color: Hsla) -> String {
+    let Rgba { r, g, b, a } = color.to_rgb();
+    let channel = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+    let mut label = format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b));
+    if a < 1. {
+        label.push_str(&format!("{:02x}", channel(a)));
+    }
+    label
+}
+
+fn hsla_from_lsp(color: lsp::Color) -> Hsla {
+    Rgba {
+        r: color.red,
+        g: color.green,
+        b: color.blue,
+        a: color.alpha,
+    }
+    .into()
+}
+
+fn lsp_from_hsla(color: Hsla) -> lsp::Color {
+    let Rgba { r, g, b, a } = color.to_rgb();
+    lsp::Color {
+        red: r,
+        green: g,
+        blue: b,
+        alpha: a,
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use gpui::{Context, Entity, HighlightStyle, Task, UnderlineStyle, px};
+use language::Buffer;
+use project::{DocumentLink, ResolveState};
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    Editor,
+    buffer_data_cache::{BufferData, BufferDataCache},
+};
+
+/// Underlines the document links of language servers, and opens them.
+pub(super) enum DocumentLinks {}
+
+impl BufferData for DocumentLinks {
+    type Data = Vec<DocumentLink>;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data> {
+        &mut editor.document_links
+    }
+
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<Result<Option<Self::Data>>>> {
+        let links = editor
+            .semantics_provider
+            .as_ref()?
+            .document_links(buffer, cx)?;
+        Some(cx.background_spawn(async move { links.await.map(Some) }))
+    }
+
+    fn update(editor: &mut Editor, _: BufferId, cx: &mut Context<Editor>) {
+        editor.update_document_link_highlights(cx);
+    }
+
+    fn update_all(editor: &mut Editor, cx: &mut Context<Editor>) {
+        editor.update_document_link_highlights(cx);
+    }
+}
+
+impl Editor {
+    /// Underlines the fetched document links in all excerpts of the editor.
+    pub(super) fn update_document_link_highlights(&mut self, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut ranges = Vec::new();
+        for (buffer_id, links) in self.document_links.iter() {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                let context = excerpt_range.context;
+                for link in links {
+                    if link.range.end.cmp(&context.start, &buffer_snapshot).is_le() {
+                        continue;
+                    }
+                    if link.range.start.cmp(&context.end, &buffer_snapshot).is_ge() {
+                        break;
+                    }
+                    let (Some(start), Some(end)) = (
+                        multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, link.range.start),
+                        multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, link.range.end),
+                    ) else {
+                        continue;
+                    };
+                    ranges.push(start..end);
+                }
+            }
+        }
+
+        if ranges.is_empty() {
+            self.clear_highlights::<DocumentLinks>(cx);
+        } else {
+            let style = HighlightStyle {
+                underline: Some(UnderlineStyle {
+                    thickness: px(1.),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            self.highlight_text::<DocumentLinks>(ranges, style, cx);
+        }
+    }
+
+    /// Returns the document link at the given position of a buffer, resolving
+    /// its target first if the server left it out.
+    pub(super) fn document_link_at(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Option<DocumentLink>>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let snapshot = buffer.read(cx).snapshot();
+        let link = self
+            .document_links
+            .get(&buffer_id)?
+            .iter()
+            .find(|link| {
+                link.range.start.cmp(&position, &snapshot).is_le()
+                    && link.range.end.cmp(&position, &snapshot).is_ge()
+            })?
+            .clone();
+        if !matches!(link.resolve_state, ResolveState::CanResolve(..)) {
+            return Some(Task::ready(Some(link)));
+        }
+
+        let resolve_task =
+            self.semantics_provider
+                .as_ref()?
+                .resolve_document_link(link.clone(), buffer, cx)?;
+        Some(cx.spawn(async move |editor, cx| {
+            let resolved_link = resolve_task.await.log_err()?;
+            editor
+                .update(cx, |editor, _| {
+                    let links = editor.document_links.get_mut(&buffer_id)?;
+                    let cached_link = links.iter_mut().find(|cached| **cached == link)?;
+                    *cached_link = resolved_link.clone();
+                    Some(())
+                })
+                .ok();
+            Some(resolved_link)
+        }))
+    }
+}
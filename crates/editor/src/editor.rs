@@ -14,29 +14,38 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 mod blink_manager;
+mod buffer_data_cache;
 mod clangd_ext;
 mod code_context_menus;
 pub mod display_map;
+mod document_colors;
+mod document_links;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 pub mod hover_popover;
 mod indent_guides;
 mod inlay_hint_cache;
+mod inline_values;
 pub mod items;
 mod jsx_tag_auto_close;
 mod linked_editing_ranges;
 mod lsp_ext;
+mod merge_conflicts;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -52,6 +61,7 @@ pub use actions::{AcceptEditPrediction, OpenExcerpts, OpenExcerptsSplit};
 use aho_corasick::AhoCorasick;
 use anyhow::{Context as _, Result, anyhow};
 use blink_manager::BlinkManager;
+use buffer_data_cache::BufferDataCache;
 use buffer_diff::DiffHunkStatus;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
@@ -62,7 +72,7 @@ pub use display_map::{ChunkRenderer, ChunkRendererContext, DisplayPoint, FoldPla
 use editor_settings::GoToDefinitionFallback;
 pub use editor_settings::{
     CurrentLineHighlight, EditorSettings, HideMouseMode, ScrollBeyondLastLine, SearchSettings,
-    ShowScrollbar,
+    ShowMinimap, ShowScrollbar,
 };
 pub use editor_settings_controls::*;
 use element::{AcceptEditPredictionBinding, LineWithInvisibles, PositionMap, layout_line};
@@ -115,6 +125,7 @@ use language::{
 };
 use language::{BufferRow, CharClassifier, Runnable, RunnableRange, point_to_lsp};
 use linked_editing_ranges::refresh_linked_ranges;
+use merge_conflicts::MergeConflicts;
 use mouse_context_menu::MouseContextMenu;
 use persistence::DB;
 use project::{
@@ -151,10 +162,12 @@ use multi_buffer::{
 };
 use parking_lot::Mutex;
 use project::{
-    CodeAction, Completion, CompletionIntent, CompletionSource, DocumentHighlight, InlayHint,
-    Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectTransaction,
-    TaskSourceKind,
+    BufferSemanticTokens, CodeAction, ColorPresentation, Completion, CompletionIntent,
+    CompletionSource, DocumentColor, DocumentHighlight, DocumentLink, FoldingRange, InlayHint,
+    InlineValue, Location, LocationLink, PrepareRenameResponse, Project, ProjectItem,
+    ProjectTransaction, TaskSourceKind,
     debugger::breakpoint_store::Breakpoint,
+    git_store::GitStoreEvent,
     lsp_store::{CompletionDocumentation, FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
 };
@@ -164,6 +177,7 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAu
 use selections_collection::{
     MutableSelectionsCollection, SelectionsCollection, resolve_selections,
 };
+use semantic_tokens::SemanticTokens;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::SmallVec;
@@ -250,6 +264,8 @@ const COLUMNAR_SELECTION_MODIFIERS: Modifiers = Modifiers {
 pub enum InlayId {
     InlineCompletion(usize),
     Hint(usize),
+    Color(usize),
+    DebuggerValue(usize),
 }
 
 impl InlayId {
@@ -257,6 +273,8 @@ impl InlayId {
         match self {
             Self::InlineCompletion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
+            Self::DebuggerValue(id) => *id,
         }
     }
 }
@@ -764,6 +782,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_syntax_node_history: SelectSyntaxNodeHistory,
+    select_selection_ranges_task: Option<Task<()>>,
     ime_transaction: Option<TransactionId>,
     active_diagnostics: ActiveDiagnostic,
     show_inline_diagnostics: bool,
@@ -846,6 +865,12 @@ pub struct Editor {
     edit_prediction_indent_conflict: bool,
     edit_prediction_requires_modifier_in_indent_conflict: bool,
     inlay_hint_cache: InlayHintCache,
+    semantic_tokens: BufferDataCache<BufferSemanticTokens>,
+    folding_ranges: BufferDataCache<Vec<FoldingRange>>,
+    document_colors: document_colors::DocumentColorsState,
+    inline_values: inline_values::InlineValuesState,
+    document_links: BufferDataCache<Vec<DocumentLink>>,
+    merge_conflicts: merge_conflicts::MergeConflictsState,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -1420,7 +1445,13 @@ impl Editor {
 
         let blink_manager = cx.new(|cx| BlinkManager::new(CURSOR_BLINK_INTERVAL, cx));
 
-        let soft_wrap_mode_override = matches!(mode, EditorMode::SingleLine { .. })
+        // Soft wrapping has to lay out every line of the buffer, so it's off for large files.
+        let is_large_file = buffer
+            .read(cx)
+            .as_singleton()
+            .is_some_and(|buffer| buffer.read(cx).is_large_file());
+        let soft_wrap_mode_override = (matches!(mode, EditorMode::SingleLine { .. })
+            || is_large_file)
             .then(|| language_settings::SoftWrap::None);
 
         let mut project_subscriptions = Vec::new();
@@ -1437,6 +1468,12 @@ impl Editor {
                             editor
                                 .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                         }
+                        project::Event::RefreshSemanticTokens => {
+                            editor.refresh_buffer_data_of::<SemanticTokens>(None, false, cx);
+                        }
+                        project::Event::LanguageServerAdded(..) => {
+                            editor.refresh_buffer_data(None, false, cx);
+                        }
                         project::Event::SnippetEdit(id, snippet_edits) => {
                             if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                                 let focus_handle = editor.focus_handle(cx);
@@ -1481,6 +1518,7 @@ impl Editor {
                     window,
                     |editor, _, event, window, cx| match event {
                         BreakpointStoreEvent::ActiveDebugLineChanged => {
+                            editor.refresh_inline_values(cx);
                             if editor.go_to_active_debug_line(window, cx) {
                                 cx.stop_propagation();
                             }
@@ -1488,6 +1526,14 @@ impl Editor {
                         _ => {}
                     },
                 ));
+                project_subscriptions.push(cx.subscribe(
+                    &project.read(cx).git_store().clone(),
+                    |editor, _, event, cx| {
+                        if let GitStoreEvent::RepositoryUpdated(..) = event {
+                            editor.refresh_buffer_data_of::<MergeConflicts>(None, false, cx);
+                        }
+                    },
+                ));
             }
         }
 
@@ -1547,6 +1593,7 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_syntax_node_history: SelectSyntaxNodeHistory::default(),
+            select_selection_ranges_task: None,
             ime_transaction: Default::default(),
             active_diagnostics: ActiveDiagnostic::None,
             show_inline_diagnostics: ProjectSettings::get_global(cx).diagnostics.inline.enabled,
@@ -1625,6 +1672,12 @@ impl Editor {
             },
             inline_diagnostics_enabled: mode.is_full(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
+            semantic_tokens: Default::default(),
+            folding_ranges: Default::default(),
+            document_colors: Default::default(),
+            inline_values: Default::default(),
+            document_links: Default::default(),
+            merge_conflicts: Default::default(),
 
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
@@ -1707,6 +1760,7 @@ impl Editor {
                 }));
         }
         this.tasks_update_task = Some(this.refresh_runnables(window, cx));
+        this.refresh_buffer_data(None, false, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this._subscriptions.push(cx.subscribe_in(
@@ -2273,6 +2327,29 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Makes buffers that were opened read-only in large-file mode editable.
+    pub fn enable_large_file_editing(
+        &mut self,
+        _: &EnableLargeFileEditing,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .project
+            .as_ref()
+            .is_some_and(|project| project.read(cx).is_read_only(cx))
+        {
+            return;
+        }
+        for buffer in self.buffer.read(cx).all_buffers() {
+            buffer.update(cx, |buffer, cx| {
+                if buffer.is_large_file() && buffer.read_only() {
+                    buffer.set_capability(Capability::ReadWrite, cx);
+                }
+            });
+        }
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -12585,26 +12662,64 @@ impl Editor {
         _: &SelectLargerSyntaxNode,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
+        if self.selection_ranges_supported(cx) {
+            self.select_larger_selection_ranges(window, cx);
+        } else {
+            let old_selections = self.selections.all::<usize>(cx).into();
+            self.select_larger_ranges(old_selections, &[], window, cx);
+        }
+    }
+
+    /// Expands each selection to the smallest of its `selection_ranges` that
+    /// contains it, or to its syntax node's parent when there is none.
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        selection_ranges: &[Option<Vec<Range<usize>>>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         let Some(visible_row_count) = self.visible_row_count() else {
             return;
         };
-        let old_selections: Box<[_]> = self.selections.all::<usize>(cx).into();
         if old_selections.is_empty() {
             return;
         }
 
-        self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
-
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
         let mut selected_larger_node = false;
         let mut new_selections = old_selections
             .iter()
-            .map(|selection| {
+            .enumerate()
+            .map(|(ix, selection)| {
                 let old_range = selection.start..selection.end;
 
+                if let Some(new_range) =
+                    selection_ranges
+                        .get(ix)
+                        .and_then(Option::as_ref)
+                        .and_then(|ranges| {
+                            ranges.iter().find(|range| {
+                                range.start <= old_range.start
+                                    && range.end >= old_range.end
+                                    && **range != old_range
+                            })
+                        })
+                {
+                    selected_larger_node = true;
+                    return Selection {
+                        id: selection.id,
+                        start: new_range.start,
+                        end: new_range.end,
+                        goal: SelectionGoal::None,
+                        reversed: selection.reversed,
+                    };
+                }
+
                 if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
                     // manually select word at selection
                     if ["string_content", "inline"].contains(&node.kind()) {
@@ -12738,7 +12853,20 @@ impl Editor {
         cx: &mut Context<Self>,
     ) {
         self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
+        if self.selection_ranges_supported(cx) {
+            self.select_smaller_selection_ranges(window, cx);
+        } else {
+            self.pop_select_syntax_node_history(window, cx);
+        }
+    }
 
+    /// Restores the selections from before the last expansion to a larger
+    /// syntax node, returning whether there was one.
+    fn pop_select_syntax_node_history(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
         if let Some((mut selections, scroll_behavior, is_selection_reversed)) =
             self.select_syntax_node_history.pop()
         {
@@ -12763,6 +12891,9 @@ impl Editor {
                     self.scroll_cursor_bottom(&ScrollCursorBottom, window, cx);
                 }
             }
+            true
+        } else {
+            false
         }
     }
 
@@ -16284,8 +16415,10 @@ impl Editor {
         maybe!({
             let breakpoint_store = self.breakpoint_store.as_ref()?;
 
-            let Some((_, _, active_position)) =
-                breakpoint_store.read(cx).active_position().cloned()
+            let Some(active_position) = breakpoint_store
+                .read(cx)
+                .active_position()
+                .map(|frame| frame.position)
             else {
                 self.clear_row_highlights::<DebugCurrentRowHighlight>();
                 return None;
@@ -16452,7 +16585,7 @@ impl Editor {
                 return;
             };
 
-            if buffer.read(cx).file().is_none() {
+            if buffer.read(cx).file().is_none() || buffer.read(cx).is_large_file() {
                 return;
             }
 
@@ -17327,6 +17460,7 @@ impl Editor {
                 }
                 if let Some(buffer) = buffer_edited {
                     let buffer_id = buffer.read(cx).remote_id();
+                    self.refresh_buffer_data(Some(buffer_id), true, cx);
                     if !self.registered_buffers.contains_key(&buffer_id) {
                         if let Some(project) = self.project.as_ref() {
                             project.update(cx, |project, cx| {
@@ -17402,9 +17536,11 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.buffer_data_excerpts_added(buffer_id, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.update_buffer_data(cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
@@ -17424,6 +17560,7 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.update_buffer_data(cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
@@ -17437,6 +17574,7 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                self.refresh_buffer_data(Some(*buffer_id), false, cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
@@ -17478,6 +17616,8 @@ impl Editor {
             )),
             cx,
         );
+        self.buffer_data_settings_changed(cx);
+        self.inline_values_settings_changed(cx);
 
         let old_cursor_shape = self.cursor_shape;
 
@@ -18845,6 +18985,65 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
 
+    fn semantic_tokens(
+        &self,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<BufferSemanticTokens>>>>;
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<FoldingRange>>>>>;
+
+    /// Fetches the values to show inline in the given range of the buffer, while the
+    /// debugger is stopped in the given stack frame.
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        stack_frame_id: u64,
+        stopped_location: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<InlineValue>>>>>;
+
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<DocumentLink>>>;
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentColor>>>>;
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        color: lsp::Color,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>>;
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<Vec<Vec<Range<text::Anchor>>>>>>>;
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -19315,6 +19514,93 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn semantic_tokens(
+        &self,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<BufferSemanticTokens>>>> {
+        Some(self.update(cx, |project, cx| project.semantic_tokens(buffer, cx)))
+    }
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<FoldingRange>>>>> {
+        Some(self.update(cx, |project, cx| project.folding_ranges(buffer, cx)))
+    }
+
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        stack_frame_id: u64,
+        stopped_location: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<InlineValue>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.inline_values(buffer, range, stack_frame_id, stopped_location, cx)
+        }))
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer, cx)
+        }))
+    }
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentColor>>>> {
+        Some(self.update(cx, |project, cx| project.document_colors(buffer, cx)))
+    }
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        color: lsp::Color,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.color_presentations(buffer, range, color, cx)
+        }))
+    }
+
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
+        self.update(cx, |project, cx| {
+            buffer.update(cx, |buffer, cx| {
+                project.any_language_server_supports_selection_ranges(buffer, cx)
+            })
+        })
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<Vec<Vec<Range<text::Anchor>>>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -19667,7 +19953,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_fold(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
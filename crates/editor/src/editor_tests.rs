@@ -6347,6 +6347,67 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_smaller_selection_range(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    // The server knows the structure of the embedded query, which the syntax
+    // tree doesn't.
+    cx.set_state(indoc! {"
+        let query = \"SELECT ˇid FROM users\";
+    "});
+    let range =
+        |start, end| lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end));
+    cx.set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(
+        move |_, _, _| async move {
+            Ok(Some(vec![lsp::SelectionRange {
+                range: range(20, 22),
+                parent: Some(Box::new(lsp::SelectionRange {
+                    range: range(13, 22),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: range(13, 33),
+                        parent: None,
+                    })),
+                })),
+            }]))
+        },
+    );
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        let query = \"SELECT «idˇ» FROM users\";
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        let query = \"«SELECT id FROM usersˇ»\";
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        let query = \"«SELECT idˇ» FROM users\";
+    "});
+}
+
 #[gpui::test]
 async fn test_select_larger_smaller_syntax_node_for_string(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -6527,6 +6588,77 @@ async fn test_select_larger_smaller_syntax_node_for_string(cx: &mut TestAppConte
     });
 }
 
+#[gpui::test]
+async fn test_sticky_scopes(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+            (impl_item "impl" @context type: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let text = r#"
+        impl Foo {
+            fn one() {
+                a();
+                b();
+                c();
+                d();
+            }
+
+            fn two() {}
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    let snapshot = editor.update_in(cx, |editor, window, cx| editor.snapshot(window, cx));
+    let sticky_rows = |scroll_top: f32, max_depth: usize| {
+        snapshot
+            .sticky_scopes(scroll_top, max_depth)
+            .into_iter()
+            .map(|(scope, offset)| (scope.start_row.0, scope.end_row.0, offset))
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing is pinned while the first line of the outermost scope is visible.
+    assert!(sticky_rows(0., 5).is_empty());
+    assert_eq!(sticky_rows(1., 5), [(0, 9, 0.)]);
+    assert_eq!(sticky_rows(2., 5), [(0, 9, 0.), (1, 6, 1.)]);
+    assert_eq!(sticky_rows(2., 1), [(0, 9, 0.)]);
+    // The end of the inner scope pushes its pinned line up...
+    assert_eq!(sticky_rows(4.5, 5), [(0, 9, 0.), (1, 6, 0.5)]);
+    // ...until it is no longer pinned.
+    assert_eq!(sticky_rows(5., 5), [(0, 9, 0.)]);
+    assert_eq!(sticky_rows(8., 5), [(0, 9, 0.)]);
+
+    // Clicking a pinned line moves the cursor to the start of its scope.
+    editor.update_in(cx, |editor, window, cx| {
+        let (scope, _) = snapshot.sticky_scopes(2., 5).pop().unwrap();
+        editor.jump_to_sticky_scope(&scope, window, cx);
+        assert_eq!(
+            editor.selections.display_ranges(cx),
+            [DisplayPoint::new(DisplayRow(1), 4)..DisplayPoint::new(DisplayRow(1), 4)]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_fold_function_bodies(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
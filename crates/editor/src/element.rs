@@ -13,8 +13,9 @@ use crate::{
         Block, BlockContext, BlockStyle, DisplaySnapshot, FoldId, HighlightedChunk, ToDisplayPoint,
     },
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ScrollbarAxes, ScrollbarDiagnostics, ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, MinimapSide, MinimapThumb,
+        MultiCursorModifier, ScrollBeyondLastLine, ScrollbarAxes, ScrollbarDiagnostics,
+        ShowMinimap, ShowScrollbar,
     },
     git::blame::{BlameRenderer, GitBlame, GlobalBlameRenderer},
     hover_popover::{
@@ -39,7 +40,7 @@ use gpui::{
     InteractiveElement, IntoElement, Keystroke, Length, ModifiersChangedEvent, MouseButton,
     MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
     ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled,
-    TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
+    StyledText, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
     linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
     transparent_black,
 };
@@ -78,6 +79,8 @@ use util::{RangeExt, ResultExt, debug_panic};
 use workspace::{Workspace, item::Item, notifications::NotifyTaskExt};
 
 const INLINE_BLAME_PADDING_EM_WIDTHS: f32 = 7.;
+/// How much smaller the text is drawn in the minimap than in the editor.
+const MINIMAP_SCALE: f32 = 0.125;
 
 /// Determines what kinds of highlights should be applied to a lines background.
 #[derive(Clone, Copy, Default)]
@@ -348,6 +351,11 @@ impl EditorElement {
         register_action(editor, window, Editor::go_to_prev_diagnostic);
         register_action(editor, window, Editor::go_to_next_hunk);
         register_action(editor, window, Editor::go_to_prev_hunk);
+        register_action(editor, window, Editor::go_to_conflict);
+        register_action(editor, window, Editor::go_to_previous_conflict);
+        register_action(editor, window, Editor::accept_conflict_ours);
+        register_action(editor, window, Editor::accept_conflict_theirs);
+        register_action(editor, window, Editor::accept_conflict_both);
         register_action(editor, window, |editor, action, window, cx| {
             editor
                 .go_to_definition(action, window, cx)
@@ -527,6 +535,7 @@ impl EditorElement {
         register_action(editor, window, Editor::context_menu_next);
         register_action(editor, window, Editor::context_menu_last);
         register_action(editor, window, Editor::display_cursor_names);
+        register_action(editor, window, Editor::enable_large_file_editing);
         register_action(editor, window, Editor::unique_lines_case_insensitive);
         register_action(editor, window, Editor::unique_lines_case_sensitive);
         register_action(editor, window, Editor::accept_partial_inline_completion);
@@ -659,6 +668,18 @@ impl EditorElement {
         }
 
         let point_for_position = position_map.point_for_position(event.position);
+        if click_count == 1
+            && editor.click_document_color(
+                &point_for_position,
+                &position_map.snapshot,
+                event.position,
+                window,
+                cx,
+            )
+        {
+            cx.stop_propagation();
+            return;
+        }
         let position = point_for_position.previous_valid;
         if modifiers == COLUMNAR_SELECTION_MODIFIERS {
             editor.select(
@@ -1463,6 +1484,232 @@ impl EditorElement {
         ))
     }
 
+    /// Splits the space for the minimap off the editor's bounds, returning the bounds left for
+    /// the rest of the editor and the minimap's bounds, if it is shown. A minimap shown on hover
+    /// is drawn over the editor instead, so that the text doesn't move when it appears.
+    fn split_minimap_bounds(
+        &self,
+        bounds: Bounds<Pixels>,
+        cx: &App,
+    ) -> (Bounds<Pixels>, Option<Bounds<Pixels>>) {
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        if minimap_settings.show == ShowMinimap::Never || !self.editor.read(cx).mode.is_full() {
+            return (bounds, None);
+        }
+
+        // Keep most of a narrow editor for the text.
+        let width = px(minimap_settings.width.max(0.)).min(bounds.size.width / 4.);
+        let height = bounds.size.height;
+        if minimap_settings.show == ShowMinimap::Hover {
+            let left = match minimap_settings.side {
+                MinimapSide::Left => bounds.left(),
+                MinimapSide::Right => bounds.right() - width,
+            };
+            return (
+                bounds,
+                Some(Bounds::new(point(left, bounds.top()), size(width, height))),
+            );
+        }
+        match minimap_settings.side {
+            MinimapSide::Left => (
+                Bounds::new(
+                    point(bounds.left() + width, bounds.top()),
+                    size(bounds.size.width - width, height),
+                ),
+                Some(Bounds::new(bounds.origin, size(width, height))),
+            ),
+            MinimapSide::Right => (
+                Bounds::new(bounds.origin, size(bounds.size.width - width, height)),
+                Some(Bounds::new(
+                    point(bounds.right() - width, bounds.top()),
+                    size(width, height),
+                )),
+            ),
+        }
+    }
+
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        scroll_position: gpui::Point<f32>,
+        max_scroll_top: f32,
+        height_in_lines: f32,
+        line_height: Pixels,
+        em_advance: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> MinimapLayout {
+        let minimap_line_height = (line_height * MINIMAP_SCALE).max(px(1.));
+        let char_width = (em_advance * MINIMAP_SCALE).max(px(0.5));
+        let max_row = snapshot.max_point().row();
+        let total_rows = max_row.next_row().as_f32();
+        let minimap_rows = bounds.size.height / minimap_line_height;
+
+        // When the buffer doesn't fit, the minimap scrolls along with the editor, so that both
+        // reach the end of the buffer at the same time.
+        let (scroll_top, thumb_pixels_per_row) =
+            if total_rows <= minimap_rows || max_scroll_top <= 0. {
+                (0., minimap_line_height)
+            } else {
+                let overflow_rows = total_rows - minimap_rows;
+                (
+                    (scroll_position.y / max_scroll_top).clamp(0., 1.) * overflow_rows,
+                    (minimap_line_height * (max_scroll_top - overflow_rows) / max_scroll_top)
+                        .max(px(0.1)),
+                )
+            };
+        let row_top =
+            |row: DisplayRow| bounds.top() + (row.as_f32() - scroll_top) * minimap_line_height;
+        let start_row = DisplayRow(scroll_top as u32);
+        let end_row =
+            DisplayRow(((scroll_top + minimap_rows).ceil() as u32).min(max_row.next_row().0));
+
+        let theme = cx.theme().clone();
+        let mut overlay_quads = Vec::new();
+        let row_range_quad = |rows: Range<DisplayRow>, left: Pixels, width: Pixels, color| {
+            let top = row_top(rows.start).max(bounds.top());
+            let bottom = row_top(rows.end.next_row()).min(bounds.bottom());
+            fill(
+                Bounds::from_corners(point(left, top), point(left + width, bottom)),
+                color,
+            )
+        };
+
+        let start_point = DisplayPoint::new(start_row, 0).to_point(snapshot);
+        let end_point = DisplayPoint::new(end_row, 0).to_point(snapshot);
+        let selections = self.editor.update(cx, |editor, cx| {
+            let buffer_snapshot = &snapshot.buffer_snapshot;
+            editor.selections.disjoint_in_range::<Point>(
+                buffer_snapshot.anchor_before(start_point)..buffer_snapshot.anchor_after(end_point),
+                cx,
+            )
+        });
+        let selection_color = theme.players().local().selection;
+        for selection in selections {
+            let start_row = selection.start.to_display_point(snapshot).row();
+            let end_row = selection.end.to_display_point(snapshot).row();
+            overlay_quads.push(row_range_quad(
+                start_row..end_row,
+                bounds.left(),
+                bounds.size.width,
+                selection_color,
+            ));
+        }
+
+        for diagnostic in snapshot
+            .buffer_snapshot
+            .diagnostics_in_range::<Point>(start_point..end_point)
+            .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity))
+        {
+            let color = match diagnostic.diagnostic.severity {
+                DiagnosticSeverity::ERROR => theme.status().error,
+                DiagnosticSeverity::WARNING => theme.status().warning,
+                DiagnosticSeverity::INFORMATION => theme.status().info,
+                _ => theme.status().hint,
+            };
+            let start_row = diagnostic.range.start.to_display_point(snapshot).row();
+            let end_row = diagnostic.range.end.to_display_point(snapshot).row();
+            overlay_quads.push(row_range_quad(
+                start_row..end_row,
+                bounds.left(),
+                bounds.size.width,
+                color.opacity(0.3),
+            ));
+        }
+
+        for hunk in snapshot
+            .buffer_snapshot
+            .diff_hunks_in_range(start_point..end_point)
+        {
+            let start_row = MultiBufferPoint::new(hunk.row_range.start.0, 0)
+                .to_display_point(snapshot)
+                .row();
+            let mut end_row = MultiBufferPoint::new(hunk.row_range.end.0, 0)
+                .to_display_point(snapshot)
+                .row();
+            if end_row != start_row {
+                end_row.0 -= 1;
+            }
+            let color = match hunk.status().kind {
+                DiffHunkStatusKind::Added => theme.colors().version_control_added,
+                DiffHunkStatusKind::Modified => theme.colors().version_control_modified,
+                DiffHunkStatusKind::Deleted => theme.colors().version_control_deleted,
+            };
+            overlay_quads.push(row_range_quad(
+                start_row..end_row,
+                bounds.left(),
+                px(2.),
+                color,
+            ));
+        }
+
+        // Draw each run of non-whitespace characters as a block in its syntax color.
+        let max_columns = (bounds.size.width / char_width) as usize;
+        let glyph_height = (minimap_line_height * 0.8).max(px(1.));
+        let mut text_quads = Vec::new();
+        let mut row = start_row;
+        let mut column = 0;
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            // Tabs are expanded to the display columns they take up in the editor.
+            if chunk.is_tab {
+                column += chunk.text.len();
+                continue;
+            }
+            let color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            for (ix, line) in chunk.text.split('\n').enumerate() {
+                if ix > 0 {
+                    row.0 += 1;
+                    column = 0;
+                }
+                let top = row_top(row);
+                let mut run_start = None;
+                for ch in line.chars().chain(Some(' ')) {
+                    if !ch.is_whitespace() {
+                        run_start.get_or_insert(column);
+                    } else if let Some(start) = run_start.take() {
+                        let end = column.min(max_columns);
+                        if start < end {
+                            text_quads.push(fill(
+                                Bounds::new(
+                                    point(bounds.left() + char_width * start as f32, top),
+                                    size(char_width * (end - start) as f32, glyph_height),
+                                ),
+                                color,
+                            ));
+                        }
+                    }
+                    column += 1;
+                }
+                // Account for the space that was chained to end the last run.
+                column -= 1;
+            }
+        }
+
+        let thumb_bounds = Bounds::new(
+            point(
+                bounds.left(),
+                bounds.top() + (scroll_position.y - scroll_top) * minimap_line_height,
+            ),
+            size(bounds.size.width, minimap_line_height * height_in_lines),
+        );
+
+        MinimapLayout {
+            hitbox: window.insert_hitbox(bounds, false),
+            text_quads,
+            overlay_quads,
+            thumb_bounds,
+            scroll_top,
+            line_height: minimap_line_height,
+            thumb_pixels_per_row,
+            visible_rows: height_in_lines,
+            max_scroll_top,
+        }
+    }
+
     fn prepaint_crease_toggles(
         &self,
         crease_toggles: &mut [Option<AnyElement>],
@@ -3320,6 +3567,86 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        content_origin: gpui::Point<Pixels>,
+        text_hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || !snapshot.mode.is_full() {
+            return None;
+        }
+        let scopes = snapshot.sticky_scopes(scroll_position.y, settings.max_depth);
+        if scopes.is_empty() {
+            return None;
+        }
+
+        let colors = cx.theme().colors();
+        let text_x = content_origin.x - text_hitbox.origin.x - scroll_pixel_position.x;
+        let scope_count = scopes.len();
+        let mut element = div()
+            .relative()
+            .w(text_hitbox.size.width)
+            .h(scope_count as f32 * line_height)
+            .children(scopes.into_iter().enumerate().map(|(ix, (scope, offset))| {
+                let mut text = String::new();
+                let mut highlights = Vec::new();
+                for chunk in snapshot.highlighted_chunks(
+                    scope.start_row..scope.start_row.next_row(),
+                    true,
+                    &self.style,
+                ) {
+                    let chunk_text = chunk.text.trim_end_matches('\n');
+                    if let Some(style) = chunk.style {
+                        highlights.push((text.len()..text.len() + chunk_text.len(), style));
+                    }
+                    text.push_str(chunk_text);
+                }
+
+                div()
+                    .id(("sticky-scope", ix))
+                    .absolute()
+                    .top(offset * line_height)
+                    .left_0()
+                    .w_full()
+                    .h(line_height)
+                    .overflow_hidden()
+                    .cursor_pointer()
+                    .bg(colors.editor_background)
+                    .when(ix + 1 == scope_count, |div| {
+                        div.border_b_1().border_color(colors.border_variant)
+                    })
+                    .child(div().absolute().left(text_x).whitespace_nowrap().child(
+                        StyledText::new(text).with_default_highlights(&self.style.text, highlights),
+                    ))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .on_click(window.listener_for(
+                        &self.editor,
+                        move |editor, _: &ClickEvent, window, cx| {
+                            editor.jump_to_sticky_scope(&scope, window, cx);
+                        },
+                    ))
+            }))
+            .into_any_element();
+
+        element.prepaint_as_root(
+            text_hitbox.origin,
+            size(
+                AvailableSpace::Definite(text_hitbox.size.width),
+                AvailableSpace::MinContent,
+            ),
+            window,
+            cx,
+        );
+        Some(element)
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -5115,6 +5442,130 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
+        let Some(minimap) = layout.minimap.as_mut() else {
+            return;
+        };
+        let hitbox = minimap.hitbox.clone();
+        let (is_dragging, is_hovered) = {
+            let scroll_manager = &self.editor.read(cx).scroll_manager;
+            (
+                scroll_manager.is_dragging_minimap(),
+                scroll_manager.minimap_hovered(),
+            )
+        };
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let show_thumb =
+            minimap_settings.thumb == MinimapThumb::Always || is_hovered || is_dragging;
+        // A minimap shown on hover keeps tracking the mouse while it's hidden, so that it
+        // appears once the mouse moves over it.
+        let is_visible = minimap_settings.show != ShowMinimap::Hover || is_hovered || is_dragging;
+
+        if is_visible {
+            window.paint_layer(hitbox.bounds, |window| {
+                window.paint_quad(fill(hitbox.bounds, cx.theme().colors().editor_background));
+                for quad in mem::take(&mut minimap.overlay_quads)
+                    .into_iter()
+                    .chain(mem::take(&mut minimap.text_quads))
+                {
+                    window.paint_quad(quad);
+                }
+                if show_thumb {
+                    window.paint_quad(quad(
+                        minimap.thumb_bounds,
+                        Corners::default(),
+                        cx.theme().colors().scrollbar_thumb_background.opacity(0.5),
+                        Edges::all(ScrollbarLayout::BORDER_WIDTH),
+                        cx.theme().colors().scrollbar_thumb_border,
+                        BorderStyle::Solid,
+                    ));
+                }
+            });
+            window.set_cursor_style(CursorStyle::Arrow, Some(&hitbox));
+        }
+
+        let MinimapLayout {
+            thumb_bounds,
+            scroll_top,
+            line_height,
+            thumb_pixels_per_row,
+            visible_rows,
+            max_scroll_top,
+            ..
+        } = *minimap;
+
+        window.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = hitbox.clone();
+            let mut mouse_position = window.mouse_position();
+            move |event: &MouseMoveEvent, phase, window, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    editor
+                        .scroll_manager
+                        .set_minimap_hovered(hitbox.is_hovered(window), cx);
+                    if editor.scroll_manager.is_dragging_minimap() {
+                        if event.pressed_button == Some(MouseButton::Left) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (position.y
+                                + (event.position.y - mouse_position.y) / thumb_pixels_per_row)
+                                .clamp(0., max_scroll_top);
+                            editor.set_scroll_position(position, window, cx);
+                            cx.stop_propagation();
+                        } else {
+                            editor.scroll_manager.set_dragging_minimap(false, cx);
+                        }
+                    }
+                });
+                mouse_position = event.position;
+            }
+        });
+
+        if is_dragging {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, _, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else if is_visible {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                move |event: &MouseDownEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(window)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_dragging_minimap(true, cx);
+                        // Clicking outside the thumb jumps to that part of the buffer, and
+                        // then the thumb can be dragged from there.
+                        if !thumb_bounds.contains(&event.position) {
+                            let row = scroll_top + (event.position.y - hitbox.top()) / line_height;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (row - visible_rows / 2.).clamp(0., max_scroll_top);
+                            editor.set_scroll_position(position, window, cx);
+                        }
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -5453,6 +5904,10 @@ impl EditorElement {
             let position_map = layout.position_map.clone();
             let editor = self.editor.clone();
             let hitbox = layout.hitbox.clone();
+            let minimap_hitbox = layout
+                .minimap
+                .as_ref()
+                .map(|minimap| minimap.hitbox.clone());
             let mut delta = ScrollDelta::default();
 
             // Set a minimum scroll_sensitivity of 0.01 to make sure the user doesn't
@@ -5460,7 +5915,11 @@ impl EditorElement {
             let scroll_sensitivity = EditorSettings::get_global(cx).scroll_sensitivity.max(0.01);
 
             move |event: &ScrollWheelEvent, phase, window, cx| {
-                if phase == DispatchPhase::Bubble && hitbox.is_hovered(window) {
+                let is_hovered = hitbox.is_hovered(window)
+                    || minimap_hitbox
+                        .as_ref()
+                        .is_some_and(|hitbox| hitbox.is_hovered(window));
+                if phase == DispatchPhase::Bubble && is_hovered {
                     delta = delta.coalesce(event.delta);
                     editor.update(cx, |editor, cx| {
                         let position_map: &PositionMap = &position_map;
@@ -6597,6 +7056,7 @@ impl Element for EditorElement {
         window.with_rem_size(rem_size, |window| {
             window.with_text_style(Some(text_style), |window| {
                 window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                    let (bounds, minimap_bounds) = self.split_minimap_bounds(bounds, cx);
                     let (mut snapshot, is_read_only) = self.editor.update(cx, |editor, cx| {
                         (editor.snapshot(window, cx), editor.read_only(cx))
                     });
@@ -7132,6 +7592,17 @@ impl Element for EditorElement {
                         scroll_position.y * line_height,
                     );
 
+                    let sticky_scopes = self.layout_sticky_scopes(
+                        &snapshot,
+                        scroll_position,
+                        scroll_pixel_position,
+                        line_height,
+                        content_origin,
+                        &text_hitbox,
+                        window,
+                        cx,
+                    );
+
                     let indent_guides = self.layout_indent_guides(
                         content_origin,
                         text_hitbox.origin,
@@ -7313,6 +7784,20 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let minimap = minimap_bounds.map(|minimap_bounds| {
+                        self.layout_minimap(
+                            &snapshot,
+                            minimap_bounds,
+                            scroll_position,
+                            max_scroll_top,
+                            height_in_lines,
+                            line_height,
+                            em_advance,
+                            window,
+                            cx,
+                        )
+                    });
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let mut code_actions_indicator = None;
@@ -7568,6 +8053,7 @@ impl Element for EditorElement {
                         display_hunks,
                         content_origin,
                         scrollbars_layout,
+                        minimap,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -7593,6 +8079,7 @@ impl Element for EditorElement {
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                         expand_toggles,
                     }
                 })
@@ -7654,6 +8141,10 @@ impl Element for EditorElement {
                         });
                     }
 
+                    if let Some(mut sticky_scopes) = layout.sticky_scopes.take() {
+                        sticky_scopes.paint(window, cx);
+                    }
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -7661,6 +8152,7 @@ impl Element for EditorElement {
                     });
 
                     self.paint_scrollbars(layout, window, cx);
+                    self.paint_minimap(layout, window, cx);
                     self.paint_inline_completion_popover(layout, window, cx);
                     self.paint_mouse_context_menu(layout, window, cx);
                 });
@@ -7739,6 +8231,7 @@ pub struct EditorLayout {
     gutter_hitbox: Hitbox,
     content_origin: gpui::Point<Pixels>,
     scrollbars_layout: Option<EditorScrollbars>,
+    minimap: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -7770,6 +8263,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Option<AnyElement>,
 }
 
 impl EditorLayout {
@@ -7908,6 +8402,25 @@ impl EditorScrollbars {
     }
 }
 
+/// A scaled-down overview of the buffer, shown next to the text.
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// Blocks of syntax-colored text.
+    text_quads: Vec<PaintQuad>,
+    /// Selections, diagnostics and git hunks, painted beneath the text.
+    overlay_quads: Vec<PaintQuad>,
+    /// The part of the minimap that is visible in the editor.
+    thumb_bounds: Bounds<Pixels>,
+    /// The display row at the top of the minimap.
+    scroll_top: f32,
+    line_height: Pixels,
+    /// How far the thumb moves when the editor scrolls by one row.
+    thumb_pixels_per_row: Pixels,
+    /// The number of rows visible in the editor.
+    visible_rows: f32,
+    max_scroll_top: f32,
+}
+
 #[derive(Clone)]
 struct ScrollbarLayout {
     hitbox: Hitbox,
@@ -8530,6 +9043,7 @@ mod tests {
     use crate::{
         Editor, MultiBuffer,
         display_map::{BlockPlacement, BlockProperties},
+        editor_settings::MinimapContent,
         editor_tests::{init_test, update_test_language_settings},
     };
     use gpui::{TestAppContext, VisualTestContext};
@@ -8756,6 +9270,113 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_layout_with_minimap(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(100, 20, 'a'), cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        assert!(state.minimap.is_none());
+        let text_width = state.position_map.text_hitbox.size.width;
+
+        cx.update(|_, cx| {
+            cx.update_global::<settings::SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        side: Some(MinimapSide::Right),
+                        width: Some(80.),
+                        thumb: None,
+                    });
+                });
+            });
+        });
+
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        let minimap = state.minimap.as_ref().unwrap();
+        assert_eq!(minimap.hitbox.size.width, px(80.));
+        assert_eq!(minimap.hitbox.right(), px(1000.));
+        assert_eq!(
+            state.position_map.text_hitbox.size.width,
+            text_width - px(80.)
+        );
+        assert!(minimap.text_quads.len() >= minimap.visible_rows as usize);
+
+        // A minimap shown on hover is drawn over the text.
+        cx.update(|_, cx| {
+            cx.update_global::<settings::SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    if let Some(minimap) = settings.minimap.as_mut() {
+                        minimap.show = Some(ShowMinimap::Hover);
+                    }
+                });
+            });
+        });
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style),
+        );
+        let minimap = state.minimap.as_ref().unwrap();
+        assert_eq!(minimap.hitbox.right(), px(1000.));
+        assert_eq!(state.position_map.text_hitbox.size.width, text_width);
+    }
+
+    #[gpui::test]
+    fn test_minimap_expands_tabs(cx: &mut TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.tab_size = NonZeroU32::new(4);
+        });
+        cx.update(|cx| {
+            cx.update_global::<settings::SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        side: Some(MinimapSide::Right),
+                        width: Some(80.),
+                        thumb: None,
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple("\tfoo\n", cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style),
+        );
+        let minimap = state.minimap.as_ref().unwrap();
+        // The run of `foo` starts after the four columns of the tab.
+        assert_eq!(minimap.text_quads.len(), 1);
+        let quad = &minimap.text_quads[0];
+        let char_width = quad.bounds.size.width / 3.;
+        let start_column = (quad.bounds.left() - minimap.hitbox.left()) / char_width;
+        assert!((start_column - 4.).abs() < 0.01, "{start_column}");
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -0,0 +1,95 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use gpui::{App, Context, Entity, Task};
+use language::{
+    Buffer,
+    language_settings::{FoldingRangesSource, language_settings},
+};
+use multi_buffer::Anchor;
+use project::FoldingRange;
+use text::BufferId;
+
+use crate::{
+    Editor,
+    buffer_data_cache::{BufferData, BufferDataCache},
+};
+
+/// Folds code using the folding ranges of language servers. Buffers whose
+/// language servers don't provide folding ranges keep folding by indentation.
+pub(super) enum FoldingRanges {}
+
+impl BufferData for FoldingRanges {
+    type Data = Vec<FoldingRange>;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data> {
+        &mut editor.folding_ranges
+    }
+
+    fn enabled(buffer: &Buffer, cx: &App) -> bool {
+        language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).folding_ranges
+            == FoldingRangesSource::LanguageServer
+    }
+
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<Result<Option<Self::Data>>>> {
+        editor
+            .semantics_provider
+            .as_ref()?
+            .folding_ranges(buffer, cx)
+    }
+
+    fn update(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+        editor.update_folding_ranges(buffer_id, cx);
+    }
+}
+
+impl Editor {
+    fn update_folding_ranges(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        let Some(ranges) = self.folding_ranges.get(&buffer_id) else {
+            self.display_map.update(cx, |display_map, _| {
+                display_map.clear_folding_ranges(buffer_id)
+            });
+            cx.notify();
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut folding_ranges: Vec<Range<Anchor>> = Vec::new();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(buffer_id, cx) {
+            let context = excerpt_range.context;
+            for range in ranges {
+                // Ranges that don't fit in the excerpt would fold its boundary.
+                if range
+                    .range
+                    .start
+                    .cmp(&context.start, &buffer_snapshot)
+                    .is_lt()
+                    || range.range.end.cmp(&context.end, &buffer_snapshot).is_gt()
+                {
+                    continue;
+                }
+                let (Some(start), Some(end)) = (
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.range.start),
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.range.end),
+                ) else {
+                    continue;
+                };
+                folding_ranges.push(start..end);
+            }
+        }
+
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_folding_ranges(buffer_id, Arc::from(folding_ranges))
+        });
+        cx.notify();
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
 };
-use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, Window, px};
+use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, WeakEntity, Window, px};
 use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
@@ -538,7 +538,19 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(&this, &buffer, project.clone(), buffer_position, cx)
+                            .await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -658,6 +670,35 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the document link reported by the language server at the given
+/// position, and where it points to.
+async fn find_document_link(
+    editor: &WeakEntity<Editor>,
+    buffer: &Entity<language::Buffer>,
+    project: Option<Entity<Project>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let link = editor
+        .update(cx, |editor, cx| {
+            editor.document_link_at(buffer, position, cx)
+        })
+        .ok()??
+        .await?;
+    let target = link.target?;
+    if target.scheme() != "file" {
+        return Some((link.range, HoverLink::Url(target.to_string())));
+    }
+    let path = target.to_file_path().ok()?;
+    let resolved_path = project?
+        .update(cx, |project, cx| {
+            project.resolve_abs_path(&path.to_string_lossy(), cx)
+        })
+        .ok()?
+        .await?;
+    Some((link.range, HoverLink::File(resolved_path)))
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
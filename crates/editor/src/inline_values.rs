@@ -0,0 +1,504 @@
+use collections::{BTreeMap, HashMap};
+use dap::{EvaluateArgumentsContext, debugger_settings::DebuggerSettings};
+use futures::future::join_all;
+use gpui::{Context, Entity, Task};
+use language::{Buffer, BufferSnapshot, Point, ToOffset as _, ToPoint as _};
+use project::{InlineValue, InlineValueKind};
+use settings::Settings as _;
+use util::{ResultExt as _, post_inc};
+
+use crate::{Editor, InlayId, display_map::Inlay};
+
+/// How many rows above the active one are searched for variables at most,
+/// even when the function containing the active row starts further up.
+const MAX_ROWS_ABOVE_ACTIVE_ROW: u32 = 50;
+/// How many characters of a value are shown before it is truncated.
+const MAX_VALUE_LEN: usize = 50;
+
+/// The values of the active stack frame's variables, shown at the end of the
+/// rows referencing them while a debug session is paused.
+///
+/// The language server decides which values to show where it provides
+/// `textDocument/inlineValue`; otherwise, the identifiers of the active function
+/// are matched against the names of the frame's variables.
+#[derive(Default)]
+pub(super) struct InlineValuesState {
+    inlays: Vec<(InlayId, String)>,
+    fetch_task: Option<Task<()>>,
+    /// Whether inline values were enabled in the settings when they were last refreshed.
+    enabled: bool,
+}
+
+impl Editor {
+    /// Fetches the values to show for the active stack frame if it is in one of
+    /// the editor's buffers, or clears the shown values otherwise.
+    pub(super) fn refresh_inline_values(&mut self, cx: &mut Context<Self>) {
+        let enabled = DebuggerSettings::get_global(cx).inline_values;
+        self.inline_values.enabled = enabled;
+        let active_frame = self
+            .breakpoint_store
+            .as_ref()
+            .filter(|_| enabled && self.mode.is_full())
+            .and_then(|store| store.read(cx).active_position().cloned());
+        let buffer = active_frame
+            .as_ref()
+            .and_then(|frame| self.buffer.read(cx).buffer(frame.position.buffer_id?));
+        let session = active_frame.as_ref().and_then(|frame| {
+            self.project
+                .as_ref()?
+                .read(cx)
+                .dap_store()
+                .read(cx)
+                .session_by_id(frame.session_id)
+        });
+        let (Some(frame), Some(buffer), Some(session)) = (active_frame, buffer, session) else {
+            self.inline_values.fetch_task = None;
+            self.splice_inline_values(Vec::new(), cx);
+            return;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let active_row = frame.position.to_point(&snapshot).row;
+        let range = snapshot.anchor_before(Point::new(
+            active_row.saturating_sub(MAX_ROWS_ABOVE_ACTIVE_ROW),
+            0,
+        ))
+            ..snapshot.anchor_after(Point::new(active_row, snapshot.line_len(active_row)));
+        let lsp_values = self.semantics_provider.as_ref().and_then(|provider| {
+            provider.inline_values(
+                &buffer,
+                range,
+                frame.stack_frame_id,
+                frame.position..frame.position,
+                cx,
+            )
+        });
+        let variables = session.update(cx, |session, cx| {
+            session.frame_variables(frame.stack_frame_id, cx)
+        });
+        self.inline_values.fetch_task = Some(cx.spawn(async move |editor, cx| {
+            let Some(variables) = variables.await.log_err() else {
+                return;
+            };
+            let lsp_values = match lsp_values {
+                Some(lsp_values) => lsp_values.await.log_err().flatten(),
+                None => None,
+            };
+
+            let labels = match lsp_values {
+                Some(lsp_values) => {
+                    let Ok(evaluations) = session.update(cx, |session, cx| {
+                        lsp_values
+                            .iter()
+                            .map(|value| {
+                                let InlineValueKind::EvaluatableExpression(expression) =
+                                    &value.kind
+                                else {
+                                    return Task::ready(None);
+                                };
+                                let expression = expression.clone().unwrap_or_else(|| {
+                                    snapshot.text_for_range(value.range.clone()).collect()
+                                });
+                                session.evaluate(
+                                    expression,
+                                    Some(EvaluateArgumentsContext::Watch),
+                                    Some(frame.stack_frame_id),
+                                    None,
+                                    cx,
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    }) else {
+                        return;
+                    };
+                    let evaluations = join_all(evaluations)
+                        .await
+                        .into_iter()
+                        .map(|response| response.map(|response| response.result))
+                        .collect::<Vec<_>>();
+                    lsp_value_labels(&snapshot, &lsp_values, &variables, &evaluations)
+                }
+                None => {
+                    let values = variables
+                        .iter()
+                        .map(|variable| (variable.name.as_str(), variable.value.as_str()))
+                        .collect::<HashMap<_, _>>();
+                    inline_value_labels(&snapshot, active_row, &values)
+                }
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    editor.update_inline_values(&buffer, &snapshot, labels, cx)
+                })
+                .ok();
+        }));
+    }
+
+    /// Refreshes the inline values if they were enabled or disabled in the settings.
+    pub(super) fn inline_values_settings_changed(&mut self, cx: &mut Context<Self>) {
+        if DebuggerSettings::get_global(cx).inline_values != self.inline_values.enabled {
+            self.refresh_inline_values(cx);
+        }
+    }
+
+    fn update_inline_values(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        buffer_snapshot: &BufferSnapshot,
+        labels: Vec<(u32, String)>,
+        cx: &mut Context<Self>,
+    ) {
+        let multi_buffer = self.buffer.read(cx);
+        let buffer_id = buffer.read(cx).remote_id();
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let excerpts = multi_buffer.excerpts_for_buffer(buffer_id, cx);
+        let mut new_inlays = Vec::new();
+        for (row, label) in labels {
+            let position =
+                buffer_snapshot.anchor_after(Point::new(row, buffer_snapshot.line_len(row)));
+            for (excerpt_id, excerpt_range) in &excerpts {
+                let context = &excerpt_range.context;
+                if position.cmp(&context.start, buffer_snapshot).is_lt()
+                    || position.cmp(&context.end, buffer_snapshot).is_gt()
+                {
+                    continue;
+                }
+                if let Some(position) =
+                    multi_buffer_snapshot.anchor_in_excerpt(*excerpt_id, position)
+                {
+                    new_inlays.push((position, label.clone()));
+                }
+            }
+        }
+
+        let to_insert = new_inlays
+            .into_iter()
+            .map(|(position, label)| {
+                Inlay::debugger_value(post_inc(&mut self.next_inlay_id), position, label)
+            })
+            .collect();
+        self.splice_inline_values(to_insert, cx);
+    }
+
+    fn splice_inline_values(&mut self, to_insert: Vec<Inlay>, cx: &mut Context<Self>) {
+        let old_inlay_ids = self
+            .inline_values
+            .inlays
+            .drain(..)
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        self.inline_values.inlays = to_insert
+            .iter()
+            .map(|inlay| (inlay.id, inlay.text.to_string()))
+            .collect();
+        if !old_inlay_ids.is_empty() || !to_insert.is_empty() {
+            self.splice_inlays(&old_inlay_ids, to_insert, cx);
+        }
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn inline_values(&self) -> Vec<String> {
+        self.inline_values
+            .inlays
+            .iter()
+            .map(|(_, text)| text.clone())
+            .collect()
+    }
+}
+
+/// Finds the identifiers naming one of the given variables between the start
+/// of the function containing the active row and the end of that row, and
+/// returns the label listing their values for each row they appear on.
+fn inline_value_labels(
+    snapshot: &BufferSnapshot,
+    active_row: u32,
+    values: &HashMap<&str, &str>,
+) -> Vec<(u32, String)> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let active_offset = snapshot.point_to_offset(Point::new(
+        active_row,
+        snapshot.indent_size_for_line(active_row).len,
+    ));
+    let Some(layer) = snapshot.syntax_layer_at(active_offset) else {
+        return Vec::new();
+    };
+    let root = layer.node();
+
+    let mut start_row = active_row.saturating_sub(MAX_ROWS_ABOVE_ACTIVE_ROW);
+    let mut node = root.descendant_for_byte_range(active_offset, active_offset);
+    while let Some(current) = node {
+        let kind = current.kind();
+        if kind.contains("function") || kind.contains("method") {
+            start_row = start_row.max(current.start_position().row as u32);
+            break;
+        }
+        node = current.parent();
+    }
+    let range = snapshot.point_to_offset(Point::new(start_row, 0))
+        ..snapshot.point_to_offset(Point::new(active_row, snapshot.line_len(active_row)));
+
+    let mut names_by_row = BTreeMap::<u32, Vec<(usize, String)>>::default();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.end_byte() <= range.start || node.start_byte() >= range.end {
+            continue;
+        }
+        if is_variable_identifier(node.kind()) {
+            let name = snapshot
+                .text_for_range(node.byte_range())
+                .collect::<String>();
+            if values.contains_key(name.as_str()) {
+                let names = names_by_row
+                    .entry(node.start_position().row as u32)
+                    .or_default();
+                if !names.iter().any(|(_, existing)| *existing == name) {
+                    names.push((node.start_byte(), name));
+                }
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    names_by_row
+        .into_iter()
+        .map(|(row, mut names)| {
+            names.sort_by_key(|(offset, _)| *offset);
+            let label = names
+                .iter()
+                .map(|(_, name)| format!("{name} = {}", format_value(values[name.as_str()])))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (row, format!("  {label}"))
+        })
+        .collect()
+}
+
+/// Whether the syntax node kind is one of the identifiers that can name a variable,
+/// e.g. `identifier`, `field_identifier` or `shorthand_property_identifier`.
+fn is_variable_identifier(kind: &str) -> bool {
+    kind.ends_with("identifier") && !kind.contains("type")
+}
+
+/// Returns the label listing the inline values the language server provided for
+/// each row, looking the variables up among the given ones and taking the result
+/// of each evaluatable expression from `evaluations`, at the same index.
+fn lsp_value_labels(
+    snapshot: &BufferSnapshot,
+    lsp_values: &[InlineValue],
+    variables: &[dap::Variable],
+    evaluations: &[Option<String>],
+) -> Vec<(u32, String)> {
+    let mut values_by_row = BTreeMap::<u32, Vec<(usize, String)>>::default();
+    for (ix, value) in lsp_values.iter().enumerate() {
+        let range_text = || {
+            snapshot
+                .text_for_range(value.range.clone())
+                .collect::<String>()
+        };
+        let label = match &value.kind {
+            InlineValueKind::Text(text) => Some(text.clone()),
+            InlineValueKind::VariableLookup {
+                variable_name,
+                case_sensitive,
+            } => {
+                let name = variable_name.clone().unwrap_or_else(range_text);
+                variables
+                    .iter()
+                    .find(|variable| {
+                        if *case_sensitive {
+                            variable.name == name
+                        } else {
+                            variable.name.eq_ignore_ascii_case(&name)
+                        }
+                    })
+                    .map(|variable| format!("{name} = {}", format_value(&variable.value)))
+            }
+            InlineValueKind::EvaluatableExpression(expression) => {
+                let expression = expression.clone().unwrap_or_else(range_text);
+                evaluations
+                    .get(ix)
+                    .cloned()
+                    .flatten()
+                    .map(|result| format!("{expression} = {}", format_value(&result)))
+            }
+        };
+        let Some(label) = label else {
+            continue;
+        };
+        let row = value.range.start.to_point(snapshot).row;
+        let labels = values_by_row.entry(row).or_default();
+        if !labels.iter().any(|(_, existing)| *existing == label) {
+            labels.push((value.range.start.to_offset(snapshot), label));
+        }
+    }
+
+    values_by_row
+        .into_iter()
+        .map(|(row, mut labels)| {
+            labels.sort_by_key(|(offset, _)| *offset);
+            let label = labels
+                .into_iter()
+                .map(|(_, label)| label)
+                .collect::<Vec<_>>()
+                .join(", ");
+            (row, format!("  {label}"))
+        })
+        .collect()
+}
+
+fn format_value(value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.chars().count() > MAX_VALUE_LEN {
+        let mut truncated = value.chars().take(MAX_VALUE_LEN).collect::<String>();
+        truncated.push('…');
+        truncated
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use indoc::indoc;
+    use language::{Buffer, Language, LanguageConfig};
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_inline_value_labels(cx: &mut TestAppContext) {
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        ));
+        let text = indoc! {r#"
+            fn unrelated(x: i32) -> i32 {
+                x
+            }
+
+            fn main() {
+                let x = 10;
+                let name = "a very long value";
+                let y = x + x * 2;
+                println!("{}", y);
+                let z = y;
+                let point = Point { z };
+            }
+        "#};
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        cx.condition(&buffer, |buffer, _| !buffer.is_parsing())
+            .await;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        let values = HashMap::from_iter([
+            ("x", "10"),
+            ("y", "30"),
+            ("z", "<uninitialized>"),
+            (
+                "name",
+                "\"a very long value that does not fit\nat the end of the line\"",
+            ),
+        ]);
+        assert_eq!(
+            inline_value_labels(&snapshot, 8, &values),
+            [
+                (5, "  x = 10".to_string()),
+                (
+                    6,
+                    "  name = \"a very long value that does not fit at the end of…".to_string()
+                ),
+                (7, "  y = 30, x = 10".to_string()),
+                (8, "  y = 30".to_string()),
+            ]
+        );
+        assert_eq!(
+            inline_value_labels(&snapshot, 10, &values)[4..],
+            [
+                (9, "  z = <uninitialized>, y = 30".to_string()),
+                (10, "  z = <uninitialized>".to_string()),
+            ]
+        );
+
+        assert!(inline_value_labels(&snapshot, 8, &HashMap::default()).is_empty());
+    }
+
+    #[gpui::test]
+    fn test_lsp_value_labels(cx: &mut TestAppContext) {
+        let text = indoc! {r#"
+            let count = 1;
+            let total = count * 2;
+            print(total + count);
+        "#};
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let range = |row: u32, start: u32, end: u32| {
+            snapshot.anchor_after(Point::new(row, start))
+                ..snapshot.anchor_before(Point::new(row, end))
+        };
+
+        let lsp_values = [
+            InlineValue {
+                range: range(0, 4, 9),
+                kind: InlineValueKind::VariableLookup {
+                    variable_name: None,
+                    case_sensitive: true,
+                },
+            },
+            InlineValue {
+                range: range(1, 4, 9),
+                kind: InlineValueKind::VariableLookup {
+                    variable_name: Some("TOTAL".into()),
+                    case_sensitive: false,
+                },
+            },
+            InlineValue {
+                range: range(1, 12, 17),
+                kind: InlineValueKind::VariableLookup {
+                    variable_name: Some("COUNT".into()),
+                    case_sensitive: true,
+                },
+            },
+            InlineValue {
+                range: range(2, 6, 19),
+                kind: InlineValueKind::EvaluatableExpression(None),
+            },
+            InlineValue {
+                range: range(2, 0, 5),
+                kind: InlineValueKind::Text("prints".into()),
+            },
+        ];
+        let variables = [("count", "1"), ("total", "2")]
+            .into_iter()
+            .map(|(name, value)| dap::Variable {
+                name: name.into(),
+                value: value.into(),
+                type_: None,
+                presentation_hint: None,
+                evaluate_name: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                declaration_location_reference: None,
+                value_location_reference: None,
+            })
+            .collect::<Vec<_>>();
+        let evaluations = [None, None, None, Some("3".to_string()), None];
+
+        assert_eq!(
+            lsp_value_labels(&snapshot, &lsp_values, &variables, &evaluations),
+            [
+                (0, "  count = 1".to_string()),
+                (1, "  TOTAL = 2".to_string()),
+                (2, "  prints, total + count = 3".to_string()),
+            ]
+        );
+    }
+}
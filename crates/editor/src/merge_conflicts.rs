@@ -0,0 +1,608 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashSet;
+use gpui::{Context, Entity, Hsla, Task, Window};
+use language::Buffer;
+use multi_buffer::{Anchor, MultiBufferSnapshot};
+use text::{BufferId, ToOffset as _};
+use ui::{ButtonLike, prelude::*};
+
+use crate::{
+    AcceptConflictBoth, AcceptConflictOurs, AcceptConflictTheirs, Editor, GoToConflict,
+    GoToPreviousConflict,
+    buffer_data_cache::{BufferData, BufferDataCache},
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+};
+
+/// The merge conflicts found in each buffer of the editor, and the blocks
+/// offering to resolve them.
+#[derive(Default)]
+pub(super) struct MergeConflictsState {
+    conflicts: BufferDataCache<Vec<ConflictRegion>>,
+    /// The conflicts shown in the editor, in the order of the multibuffer.
+    displayed: Vec<DisplayedConflict>,
+    block_ids: HashSet<CustomBlockId>,
+}
+
+/// A merge conflict left in a buffer by Git, delimited by conflict markers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct ConflictRegion {
+    /// From the start of the `<<<<<<<` line to the end of the `>>>>>>>` line.
+    range: Range<text::Anchor>,
+    ours: Range<text::Anchor>,
+    /// The common ancestor's version, only present with the `diff3` and
+    /// `zdiff3` conflict styles.
+    base: Option<Range<text::Anchor>>,
+    theirs: Range<text::Anchor>,
+    ours_label: SharedString,
+    theirs_label: SharedString,
+}
+
+#[derive(Clone, Debug)]
+struct DisplayedConflict {
+    range: Range<Anchor>,
+    ours: Range<Anchor>,
+    theirs: Range<Anchor>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+enum ConflictOursHighlight {}
+enum ConflictBaseHighlight {}
+enum ConflictTheirsHighlight {}
+
+/// Highlights the merge conflicts left in buffers by Git, and resolves them.
+pub(super) enum MergeConflicts {}
+
+impl BufferData for MergeConflicts {
+    type Data = Vec<ConflictRegion>;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data> {
+        &mut editor.merge_conflicts.conflicts
+    }
+
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<anyhow::Result<Option<Self::Data>>>> {
+        // Only look for markers in files Git reports as conflicted, so that files
+        // merely containing them, such as documentation, aren't mistaken for conflicts.
+        let buffer_id = buffer.read(cx).remote_id();
+        let is_conflicted = editor
+            .project
+            .as_ref()?
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+            .is_some_and(|(repository, repo_path)| {
+                let repository = repository.read(cx);
+                repository.has_conflict(&repo_path)
+                    || repository
+                        .status_for_path(&repo_path)
+                        .is_some_and(|entry| entry.status.is_conflicted())
+            });
+        if !is_conflicted {
+            return Some(Task::ready(Ok(None)));
+        }
+
+        let snapshot = buffer.read(cx).text_snapshot();
+        Some(cx.background_spawn(async move { Ok(Some(parse_conflicts(&snapshot))) }))
+    }
+
+    fn update(editor: &mut Editor, _: BufferId, cx: &mut Context<Editor>) {
+        editor.update_merge_conflicts(cx);
+    }
+
+    fn update_all(editor: &mut Editor, cx: &mut Context<Editor>) {
+        editor.update_merge_conflicts(cx);
+    }
+}
+
+impl Editor {
+    /// Highlights the conflicts found in all excerpts of the editor, and shows
+    /// the actions resolving them above each of them.
+    pub(super) fn update_merge_conflicts(&mut self, cx: &mut Context<Self>) {
+        let has_conflicts = self
+            .merge_conflicts
+            .conflicts
+            .iter()
+            .any(|(_, conflicts)| !conflicts.is_empty());
+        if !has_conflicts && self.merge_conflicts.displayed.is_empty() {
+            return;
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut displayed = Vec::new();
+        let mut highlights = Vec::new();
+        for (buffer_id, conflicts) in self.merge_conflicts.conflicts.iter() {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                let context = excerpt_range.context;
+                for conflict in conflicts {
+                    if conflict
+                        .range
+                        .end
+                        .cmp(&context.start, &buffer_snapshot)
+                        .is_le()
+                    {
+                        continue;
+                    }
+                    if conflict
+                        .range
+                        .start
+                        .cmp(&context.end, &buffer_snapshot)
+                        .is_ge()
+                    {
+                        break;
+                    }
+                    let to_multi_buffer = |range: &Range<text::Anchor>| {
+                        Some(
+                            multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                                ..multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                        )
+                    };
+                    // Highlight whole rows, without spilling onto the row of the next marker.
+                    let to_rows = |start: &text::Anchor, end: &text::Anchor| {
+                        let start = start.to_offset(&buffer_snapshot);
+                        let end = end.to_offset(&buffer_snapshot).saturating_sub(1).max(start);
+                        to_multi_buffer(
+                            &(buffer_snapshot.anchor_after(start)
+                                ..buffer_snapshot.anchor_before(end)),
+                        )
+                    };
+
+                    let (Some(range), Some(ours), Some(theirs)) = (
+                        to_multi_buffer(&conflict.range),
+                        to_multi_buffer(&conflict.ours),
+                        to_multi_buffer(&conflict.theirs),
+                    ) else {
+                        continue;
+                    };
+                    highlights.push((
+                        ConflictPart::Ours,
+                        to_rows(&conflict.range.start, &conflict.ours.end),
+                    ));
+                    if let Some(base) = &conflict.base {
+                        highlights.push((ConflictPart::Base, to_rows(&base.start, &base.end)));
+                    }
+                    highlights.push((
+                        ConflictPart::Theirs,
+                        to_rows(&conflict.theirs.start, &conflict.range.end),
+                    ));
+                    displayed.push((
+                        DisplayedConflict {
+                            range,
+                            ours,
+                            theirs,
+                        },
+                        conflict.ours_label.clone(),
+                        conflict.theirs_label.clone(),
+                    ));
+                }
+            }
+        }
+        displayed
+            .sort_by(|(a, ..), (b, ..)| a.range.start.cmp(&b.range.start, &multi_buffer_snapshot));
+
+        self.clear_row_highlights::<ConflictOursHighlight>();
+        self.clear_row_highlights::<ConflictBaseHighlight>();
+        self.clear_row_highlights::<ConflictTheirsHighlight>();
+        for (part, range) in highlights {
+            let Some(range) = range else {
+                continue;
+            };
+            let color = part.color(cx);
+            match part {
+                ConflictPart::Ours => {
+                    self.highlight_rows::<ConflictOursHighlight>(range, color, false, cx)
+                }
+                ConflictPart::Base => {
+                    self.highlight_rows::<ConflictBaseHighlight>(range, color, false, cx)
+                }
+                ConflictPart::Theirs => {
+                    self.highlight_rows::<ConflictTheirsHighlight>(range, color, false, cx)
+                }
+            }
+        }
+
+        let old_block_ids = std::mem::take(&mut self.merge_conflicts.block_ids);
+        if !old_block_ids.is_empty() {
+            self.remove_blocks(old_block_ids, None, cx);
+        }
+        let editor = cx.entity().downgrade();
+        let blocks = displayed
+            .iter()
+            .enumerate()
+            .map(|(ix, (conflict, ours_label, theirs_label))| {
+                let editor = editor.clone();
+                let conflict = conflict.clone();
+                let ours_label = ours_label.clone();
+                let theirs_label = theirs_label.clone();
+                BlockProperties {
+                    placement: BlockPlacement::Above(conflict.range.start),
+                    height: Some(1),
+                    style: BlockStyle::Fixed,
+                    render: Arc::new(move |cx| {
+                        let button = |id: &'static str, label: String, resolution| {
+                            let editor = editor.clone();
+                            let conflict = conflict.clone();
+                            ButtonLike::new((id, ix))
+                                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                                .on_click(move |_, window, cx| {
+                                    editor
+                                        .update(cx, |editor, cx| {
+                                            editor
+                                                .resolve_conflict(&conflict, resolution, window, cx)
+                                        })
+                                        .ok();
+                                })
+                        };
+                        h_flex()
+                            .h(cx.line_height)
+                            .pl(cx.gutter_dimensions.full_width())
+                            .gap_1()
+                            .child(button(
+                                "accept-conflict-ours",
+                                accept_label("Accept Ours", &ours_label),
+                                ConflictResolution::Ours,
+                            ))
+                            .child(button(
+                                "accept-conflict-theirs",
+                                accept_label("Accept Theirs", &theirs_label),
+                                ConflictResolution::Theirs,
+                            ))
+                            .child(button(
+                                "accept-conflict-both",
+                                "Accept Both".to_string(),
+                                ConflictResolution::Both,
+                            ))
+                            .into_any_element()
+                    }),
+                    priority: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.merge_conflicts.block_ids = self.insert_blocks(blocks, None, cx).into_iter().collect();
+        self.merge_conflicts.displayed = displayed
+            .into_iter()
+            .map(|(conflict, ..)| conflict)
+            .collect();
+        cx.notify();
+    }
+
+    fn resolve_conflict(
+        &mut self,
+        conflict: &DisplayedConflict,
+        resolution: ConflictResolution,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let text_for_range =
+            |range: &Range<Anchor>| snapshot.text_for_range(range.clone()).collect::<String>();
+        let new_text = match resolution {
+            ConflictResolution::Ours => text_for_range(&conflict.ours),
+            ConflictResolution::Theirs => text_for_range(&conflict.theirs),
+            ConflictResolution::Both => {
+                text_for_range(&conflict.ours) + &text_for_range(&conflict.theirs)
+            }
+        };
+        let range = conflict.range.clone();
+        self.transact(window, cx, |editor, _, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(range, new_text)], None, cx);
+            });
+        });
+    }
+
+    fn conflict_at_cursor(&self, snapshot: &MultiBufferSnapshot) -> Option<DisplayedConflict> {
+        let cursor = self.selections.newest_anchor().head();
+        self.merge_conflicts
+            .displayed
+            .iter()
+            .find(|conflict| {
+                conflict.range.start.cmp(&cursor, snapshot).is_le()
+                    && conflict.range.end.cmp(&cursor, snapshot).is_ge()
+            })
+            .cloned()
+    }
+
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        resolution: ConflictResolution,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(conflict) = self.conflict_at_cursor(&snapshot) {
+            self.resolve_conflict(&conflict, resolution, window, cx);
+        }
+    }
+
+    pub fn accept_conflict_ours(
+        &mut self,
+        _: &AcceptConflictOurs,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Ours, window, cx);
+    }
+
+    pub fn accept_conflict_theirs(
+        &mut self,
+        _: &AcceptConflictTheirs,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Theirs, window, cx);
+    }
+
+    pub fn accept_conflict_both(
+        &mut self,
+        _: &AcceptConflictBoth,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Both, window, cx);
+    }
+
+    pub fn go_to_conflict(
+        &mut self,
+        _: &GoToConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor = self.selections.newest_anchor().head();
+        let conflicts = &self.merge_conflicts.displayed;
+        let target = conflicts
+            .iter()
+            .find(|conflict| conflict.range.start.cmp(&cursor, &snapshot).is_gt())
+            .or_else(|| conflicts.first())
+            .map(|conflict| conflict.range.start);
+        if let Some(target) = target {
+            self.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                selections.select_anchor_ranges([target..target])
+            });
+        }
+    }
+
+    pub fn go_to_previous_conflict(
+        &mut self,
+        _: &GoToPreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor = self.selections.newest_anchor().head();
+        let conflicts = &self.merge_conflicts.displayed;
+        let target = conflicts
+            .iter()
+            .rev()
+            .find(|conflict| conflict.range.start.cmp(&cursor, &snapshot).is_lt())
+            .or_else(|| conflicts.last())
+            .map(|conflict| conflict.range.start);
+        if let Some(target) = target {
+            self.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                selections.select_anchor_ranges([target..target])
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ConflictPart {
+    Ours,
+    Base,
+    Theirs,
+}
+
+impl ConflictPart {
+    fn color(self, cx: &App) -> Hsla {
+        match self {
+            ConflictPart::Ours => cx.theme().colors().version_control_added.opacity(0.15),
+            ConflictPart::Base => cx.theme().colors().version_control_modified.opacity(0.15),
+            ConflictPart::Theirs => cx.theme().status().info.opacity(0.15),
+        }
+    }
+}
+
+fn accept_label(action: &str, marker_label: &str) -> String {
+    if marker_label.is_empty() {
+        action.to_string()
+    } else {
+        format!("{action} ({marker_label})")
+    }
+}
+
+/// Returns the label following a conflict marker at the start of the line.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Finds the conflicts delimited by Git's conflict markers in a buffer.
+fn parse_conflicts(snapshot: &text::BufferSnapshot) -> Vec<ConflictRegion> {
+    struct PendingConflict {
+        start: usize,
+        ours_label: SharedString,
+        ours_start: usize,
+        ours_end: Option<usize>,
+        base_start: Option<usize>,
+        base_end: Option<usize>,
+        theirs_start: Option<usize>,
+    }
+
+    let len = snapshot.len();
+    let mut conflicts = Vec::new();
+    let mut pending: Option<PendingConflict> = None;
+    let mut lines = snapshot.as_rope().chunks().lines();
+    let mut offset = 0;
+    while let Some(line) = lines.next() {
+        let line_start = offset;
+        offset = (offset + line.len() + 1).min(len);
+        let content = line.trim_end_matches('\r');
+
+        if let Some(ours_label) = marker_label(content, "<<<<<<<") {
+            pending = Some(PendingConflict {
+                start: line_start,
+                ours_label: ours_label.to_string().into(),
+                ours_start: offset,
+                ours_end: None,
+                base_start: None,
+                base_end: None,
+                theirs_start: None,
+            });
+            continue;
+        }
+        let Some(conflict) = pending.as_mut() else {
+            continue;
+        };
+        if conflict.theirs_start.is_none() {
+            if conflict.ours_end.is_none() && marker_label(content, "|||||||").is_some() {
+                conflict.ours_end = Some(line_start);
+                conflict.base_start = Some(offset);
+            } else if marker_label(content, "=======").is_some_and(str::is_empty) {
+                if conflict.ours_end.is_none() {
+                    conflict.ours_end = Some(line_start);
+                } else {
+                    conflict.base_end = Some(line_start);
+                }
+                conflict.theirs_start = Some(offset);
+            }
+        } else if let Some(theirs_label) = marker_label(content, ">>>>>>>") {
+            let anchor_range = |range: Range<usize>| {
+                snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+            };
+            let ours_end = conflict.ours_end.unwrap_or(conflict.ours_start);
+            let base = conflict
+                .base_start
+                .zip(conflict.base_end)
+                .map(|(start, end)| anchor_range(start..end));
+            conflicts.push(ConflictRegion {
+                range: anchor_range(conflict.start..offset),
+                ours: anchor_range(conflict.ours_start..ours_end),
+                base,
+                theirs: anchor_range(conflict.theirs_start.unwrap_or(line_start)..line_start),
+                ours_label: conflict.ours_label.clone(),
+                theirs_label: theirs_label.to_string().into(),
+            });
+            pending = None;
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::Buffer;
+
+    fn parse(text: &str) -> Vec<(String, String, Option<String>, String, String, String)> {
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.to_string());
+        let snapshot = buffer.snapshot();
+        let text_for = |range: &Range<text::Anchor>| {
+            snapshot.text_for_range(range.clone()).collect::<String>()
+        };
+        parse_conflicts(&snapshot)
+            .into_iter()
+            .map(|conflict| {
+                (
+                    text_for(&conflict.range),
+                    text_for(&conflict.ours),
+                    conflict.base.as_ref().map(text_for),
+                    text_for(&conflict.theirs),
+                    conflict.ours_label.to_string(),
+                    conflict.theirs_label.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_conflicts() {
+        let text = "\
+            before\n\
+            <<<<<<< HEAD\n\
+            ours 1\n\
+            ours 2\n\
+            =======\n\
+            theirs\n\
+            >>>>>>> feature\n\
+            after\n";
+        assert_eq!(
+            parse(text),
+            vec![(
+                "<<<<<<< HEAD\nours 1\nours 2\n=======\ntheirs\n>>>>>>> feature\n".to_string(),
+                "ours 1\nours 2\n".to_string(),
+                None,
+                "theirs\n".to_string(),
+                "HEAD".to_string(),
+                "feature".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_diff3_conflicts() {
+        let text = "\
+            <<<<<<< HEAD\n\
+            ours\n\
+            ||||||| base\n\
+            base\n\
+            =======\n\
+            theirs\n\
+            >>>>>>> feature\n\
+            middle\n\
+            <<<<<<< HEAD\n\
+            =======\n\
+            only theirs\n\
+            >>>>>>> feature";
+        assert_eq!(
+            parse(text),
+            vec![
+                (
+                    "<<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> feature\n"
+                        .to_string(),
+                    "ours\n".to_string(),
+                    Some("base\n".to_string()),
+                    "theirs\n".to_string(),
+                    "HEAD".to_string(),
+                    "feature".to_string(),
+                ),
+                (
+                    "<<<<<<< HEAD\n=======\nonly theirs\n>>>>>>> feature".to_string(),
+                    "".to_string(),
+                    None,
+                    "only theirs\n".to_string(),
+                    "HEAD".to_string(),
+                    "feature".to_string(),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_incomplete_conflicts() {
+        assert_eq!(parse("<<<<<<< HEAD\nours\n=======\ntheirs\n"), vec![]);
+        assert_eq!(parse("<<<<<<<< not a marker\n=======\n>>>>>>> x\n"), vec![]);
+        assert_eq!(parse("=======\n>>>>>>> x\n"), vec![]);
+    }
+}
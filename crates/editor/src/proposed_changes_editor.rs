@@ -495,6 +495,81 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         self.0.definitions(&buffer, position, kind, cx)
     }
 
+    fn semantic_tokens(
+        &self,
+        _: Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<project::BufferSemanticTokens>>>> {
+        None
+    }
+
+    fn folding_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<project::FoldingRange>>>>> {
+        None
+    }
+
+    fn inline_values(
+        &self,
+        _: &Entity<Buffer>,
+        _: Range<text::Anchor>,
+        _: u64,
+        _: Range<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<project::InlineValue>>>>> {
+        None
+    }
+
+    fn supports_selection_ranges(&self, _: &Entity<Buffer>, _: &mut App) -> bool {
+        false
+    }
+
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentLink>>>> {
+        None
+    }
+
+    fn resolve_document_link(
+        &self,
+        _: project::DocumentLink,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<project::DocumentLink>>> {
+        None
+    }
+
+    fn document_colors(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentColor>>>> {
+        None
+    }
+
+    fn color_presentations(
+        &self,
+        _: &Entity<Buffer>,
+        _: Range<text::Anchor>,
+        _: lsp::Color,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::ColorPresentation>>>> {
+        None
+    }
+
+    fn selection_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: Vec<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<Vec<Vec<Range<text::Anchor>>>>>>> {
+        None
+    }
+
     fn range_for_rename(
         &self,
         _: &Entity<Buffer>,
@@ -132,6 +132,8 @@ pub struct ScrollManager {
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: Option<Axis>,
+    dragging_minimap: bool,
+    minimap_hovered: bool,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
 }
@@ -146,6 +148,8 @@ impl ScrollManager {
             show_scrollbars: true,
             hide_scrollbar_task: None,
             dragging_scrollbar: None,
+            dragging_minimap: false,
+            minimap_hovered: false,
             last_autoscroll: None,
             visible_line_count: None,
             forbid_vertical_scroll: false,
@@ -338,6 +342,28 @@ impl ScrollManager {
         }
     }
 
+    pub fn is_dragging_minimap(&self) -> bool {
+        self.dragging_minimap
+    }
+
+    pub fn set_dragging_minimap(&mut self, dragging: bool, cx: &mut Context<Editor>) {
+        if self.dragging_minimap != dragging {
+            self.dragging_minimap = dragging;
+            cx.notify();
+        }
+    }
+
+    pub fn minimap_hovered(&self) -> bool {
+        self.minimap_hovered
+    }
+
+    pub fn set_minimap_hovered(&mut self, hovered: bool, cx: &mut Context<Editor>) {
+        if self.minimap_hovered != hovered {
+            self.minimap_hovered = hovered;
+            cx.notify();
+        }
+    }
+
     pub fn clamp_scroll_left(&mut self, max: f32) -> bool {
         if max < self.anchor.offset.x {
             self.anchor.offset.x = max;
@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+use collections::{HashMap, HashSet};
+use futures::future;
+use gpui::{Context, Task, Window};
+use language::{Selection, SelectionGoal};
+use multi_buffer::{ExcerptId, ToOffset as _};
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{Editor, scroll::Autoscroll};
+
+impl Editor {
+    /// Whether a language server may provide selection ranges for any of the
+    /// buffers containing the selections.
+    pub(super) fn selection_ranges_supported(&self, cx: &mut Context<Self>) -> bool {
+        let Some(semantics_provider) = self.semantics_provider.clone() else {
+            return false;
+        };
+        let buffer_ids = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .filter_map(|selection| selection.head().buffer_id)
+            .collect::<HashSet<_>>();
+        let multi_buffer = self.buffer.read(cx);
+        let buffers = buffer_ids
+            .into_iter()
+            .filter_map(|buffer_id| multi_buffer.buffer(buffer_id))
+            .collect::<Vec<_>>();
+        buffers
+            .iter()
+            .any(|buffer| semantics_provider.supports_selection_ranges(buffer, cx))
+    }
+
+    /// Expands each selection to the smallest enclosing range reported by the
+    /// language servers, or to its syntax node's parent when they report none.
+    pub(super) fn select_larger_selection_ranges(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Run after any pending expansion, as it changes the selections.
+        let previous_task = self.select_selection_ranges_task.take();
+        self.select_selection_ranges_task = Some(cx.spawn_in(window, async move |editor, cx| {
+            if let Some(previous_task) = previous_task {
+                previous_task.await;
+            }
+            let Some((old_selections, selection_ranges)) = editor
+                .update(cx, |editor, cx| {
+                    let old_selections: Box<[_]> = editor.selections.all::<usize>(cx).into();
+                    let selection_ranges = editor.selection_ranges(&old_selections, cx);
+                    (old_selections, selection_ranges)
+                })
+                .ok()
+            else {
+                return;
+            };
+            let selection_ranges = selection_ranges.await;
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    if editor.selections.all::<usize>(cx)[..] == old_selections[..] {
+                        editor.select_larger_ranges(old_selections, &selection_ranges, window, cx);
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Restores the selections from before the last expansion or, if there
+    /// was none, shrinks each selection to the largest range within it that
+    /// the language servers report.
+    pub(super) fn select_smaller_selection_ranges(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_task = self.select_selection_ranges_task.take();
+        self.select_selection_ranges_task = Some(cx.spawn_in(window, async move |editor, cx| {
+            if let Some(previous_task) = previous_task {
+                previous_task.await;
+            }
+            let Some((old_selections, selection_ranges)) = editor
+                .update_in(cx, |editor, window, cx| {
+                    if editor.pop_select_syntax_node_history(window, cx) {
+                        return None;
+                    }
+                    let old_selections = editor.selections.all::<usize>(cx);
+                    let selection_ranges = editor.selection_ranges(&old_selections, cx);
+                    Some((old_selections, selection_ranges))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let selection_ranges = selection_ranges.await;
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    if editor.selections.all::<usize>(cx) != old_selections {
+                        return;
+                    }
+                    let mut selected_smaller_range = false;
+                    let new_selections = old_selections
+                        .iter()
+                        .zip(selection_ranges)
+                        .map(|(selection, ranges)| {
+                            let old_range = selection.range();
+                            let new_range = ranges.and_then(|ranges| {
+                                ranges.into_iter().rev().find(|range| {
+                                    range.start >= old_range.start
+                                        && range.end <= old_range.end
+                                        && *range != old_range
+                                })
+                            });
+                            let Some(new_range) = new_range else {
+                                return selection.clone();
+                            };
+                            selected_smaller_range = true;
+                            Selection {
+                                id: selection.id,
+                                start: new_range.start,
+                                end: new_range.end,
+                                goal: SelectionGoal::None,
+                                reversed: selection.reversed,
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    if selected_smaller_range {
+                        editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                            s.select(new_selections);
+                        });
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Fetches the ranges that language servers would select around each of
+    /// the given selections, from the innermost to the outermost, limited to
+    /// the selection's excerpt. Resolves to `None` for the selections whose
+    /// buffers have no language server that provides them.
+    fn selection_ranges(
+        &self,
+        selections: &[Selection<usize>],
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Option<Vec<Range<usize>>>>> {
+        let selection_count = selections.len();
+        let Some(semantics_provider) = self.semantics_provider.clone() else {
+            return Task::ready(vec![None; selection_count]);
+        };
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut positions_by_buffer =
+            HashMap::<BufferId, Vec<(usize, ExcerptId, text::Anchor)>>::default();
+        for (ix, selection) in selections.iter().enumerate() {
+            let Some(mut excerpt) = snapshot.excerpt_containing(selection.range()) else {
+                continue;
+            };
+            let position = excerpt
+                .buffer()
+                .anchor_before(excerpt.map_offset_to_buffer(selection.start));
+            positions_by_buffer
+                .entry(excerpt.buffer_id())
+                .or_default()
+                .push((ix, excerpt.id(), position));
+        }
+        let requests = positions_by_buffer
+            .into_iter()
+            .filter_map(|(buffer_id, positions)| Some((multi_buffer.buffer(buffer_id)?, positions)))
+            .collect::<Vec<_>>();
+        let requests = requests
+            .into_iter()
+            .filter_map(|(buffer, positions)| {
+                let request = semantics_provider.selection_ranges(
+                    &buffer,
+                    positions.iter().map(|(_, _, position)| *position).collect(),
+                    cx,
+                )?;
+                Some(async move { (positions, request.await) })
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |editor, cx| {
+            let responses = future::join_all(requests).await;
+            editor
+                .update(cx, |editor, cx| {
+                    let snapshot = editor.buffer.read(cx).snapshot(cx);
+                    let mut selection_ranges = vec![None; selection_count];
+                    for (positions, response) in responses {
+                        let Some(Some(response)) = response.log_err() else {
+                            continue;
+                        };
+                        for ((ix, excerpt_id, _), ranges) in positions.into_iter().zip(response) {
+                            let (Some(buffer), Some(context)) = (
+                                snapshot.buffer_for_excerpt(excerpt_id),
+                                snapshot.context_range_for_excerpt(excerpt_id),
+                            ) else {
+                                continue;
+                            };
+                            let ranges = ranges
+                                .into_iter()
+                                .take_while(|range| {
+                                    range.start.cmp(&context.start, buffer).is_ge()
+                                        && range.end.cmp(&context.end, buffer).is_le()
+                                })
+                                .filter_map(|range| {
+                                    let start =
+                                        snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                                    let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                                    Some(start.to_offset(&snapshot)..end.to_offset(&snapshot))
+                                })
+                                .collect();
+                            selection_ranges[ix] = Some(ranges);
+                        }
+                    }
+                    selection_ranges
+                })
+                .unwrap_or_default()
+        })
+    }
+}
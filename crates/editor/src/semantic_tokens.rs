@@ -0,0 +1,139 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, Context, Entity, HighlightStyle, Task};
+use language::{Buffer, HighlightMap, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::BufferSemanticTokens;
+use text::BufferId;
+use theme::ActiveTheme as _;
+
+use crate::{
+    Editor,
+    buffer_data_cache::{BufferData, BufferDataCache},
+};
+
+/// Highlights code using the semantic tokens of language servers.
+pub(super) enum SemanticTokens {}
+
+impl BufferData for SemanticTokens {
+    type Data = BufferSemanticTokens;
+
+    fn cache(editor: &mut Editor) -> &mut BufferDataCache<Self::Data> {
+        &mut editor.semantic_tokens
+    }
+
+    fn enabled(buffer: &Buffer, cx: &App) -> bool {
+        language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+            .semantic_tokens
+            .enabled
+    }
+
+    fn fetch(
+        editor: &mut Editor,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Editor>,
+    ) -> Option<Task<Result<Option<Self::Data>>>> {
+        editor
+            .semantics_provider
+            .as_ref()?
+            .semantic_tokens(buffer.clone(), cx)
+    }
+
+    fn update(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+        editor.update_semantic_token_highlights(buffer_id, cx);
+    }
+}
+
+impl Editor {
+    fn update_semantic_token_highlights(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        let Some(tokens) = self.semantic_tokens.get(&buffer_id) else {
+            self.display_map.update(cx, |display_map, _| {
+                display_map.clear_semantic_token_highlights(buffer_id)
+            });
+            cx.notify();
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let buffer_snapshot = buffer.snapshot();
+        let settings = language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+            .semantic_tokens
+            .clone();
+
+        // Resolve each distinct combination of token type and modifiers to a
+        // highlight name, and each highlight name to a style of the theme.
+        let mut highlight_names = Vec::new();
+        let mut highlight_ids = HashMap::default();
+        for token in tokens.tokens.iter() {
+            highlight_ids
+                .entry((token.token_type, token.token_modifiers))
+                .or_insert_with(|| {
+                    let token_type = tokens.token_types.get(token.token_type as usize)?;
+                    let modifiers = (0..u32::BITS)
+                        .filter(|bit| token.token_modifiers & (1 << bit) != 0)
+                        .filter_map(|bit| tokens.token_modifiers.get(bit as usize))
+                        .map(String::as_str);
+                    let name = settings.highlight_name(token_type, modifiers)?;
+                    let ix = highlight_names
+                        .iter()
+                        .position(|existing| *existing == name)
+                        .unwrap_or_else(|| {
+                            highlight_names.push(name);
+                            highlight_names.len() - 1
+                        });
+                    Some(ix as u32)
+                });
+        }
+        let syntax_theme = cx.theme().syntax().clone();
+        let highlight_map = HighlightMap::new(&highlight_names, &syntax_theme);
+
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut highlights: Vec<(Range<Anchor>, HighlightStyle)> = Vec::new();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(buffer_id, cx) {
+            let context = excerpt_range.context;
+            for token in tokens.tokens.iter() {
+                if token
+                    .range
+                    .end
+                    .cmp(&context.start, &buffer_snapshot)
+                    .is_le()
+                {
+                    continue;
+                }
+                if token
+                    .range
+                    .start
+                    .cmp(&context.end, &buffer_snapshot)
+                    .is_ge()
+                {
+                    break;
+                }
+                let Some(style) = highlight_ids
+                    .get(&(token.token_type, token.token_modifiers))
+                    .copied()
+                    .flatten()
+                    .and_then(|ix| highlight_map.get(ix).style(&syntax_theme))
+                else {
+                    continue;
+                };
+                let (Some(start), Some(end)) = (
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+                ) else {
+                    continue;
+                };
+                highlights.push((start..end, style));
+            }
+        }
+
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_semantic_token_highlights(buffer_id, Arc::from(highlights))
+        });
+        cx.notify();
+    }
+}
@@ -0,0 +1,76 @@
+use gpui::{Context, Window};
+use multi_buffer::Anchor;
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, EditorSnapshot, RowExt, ToDisplayPoint, scroll::Autoscroll,
+};
+
+/// An outline item enclosing the top of the viewport, whose first line is pinned to the top of
+/// the editor while the rest of it is scrolled into view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StickyScope {
+    pub start: Anchor,
+    /// The display row of the scope's first line, which is shown in the pinned line.
+    pub start_row: DisplayRow,
+    /// The display row of the scope's last line.
+    pub end_row: DisplayRow,
+}
+
+impl EditorSnapshot {
+    /// Returns the scopes whose first line has been scrolled out of view, from outermost to
+    /// innermost, along with the position at which each of them is pinned, in lines from the top
+    /// of the viewport.
+    ///
+    /// The `n`th scope is only pinned once its first line would be hidden behind the `n` scopes
+    /// pinned above it, and a scope is pushed up by the line that ends it so the two never overlap.
+    pub fn sticky_scopes(&self, scroll_top: f32, max_depth: usize) -> Vec<(StickyScope, f32)> {
+        if max_depth == 0 || self.buffer_snapshot.show_headers() {
+            return Vec::new();
+        }
+
+        let top_row = DisplayRow(scroll_top as u32);
+        let position = DisplayPoint::new(top_row, 0).to_point(self);
+        let Some((_, items)) = self.buffer_snapshot.symbols_containing(position, None) else {
+            return Vec::new();
+        };
+
+        let mut scopes = Vec::new();
+        for item in items {
+            if scopes.len() == max_depth {
+                break;
+            }
+            let pinned_lines = scopes.len() as f32;
+            let start_row = item.range.start.to_display_point(self).row();
+            let end_row = item.range.end.to_display_point(self).row();
+            if start_row.as_f32() - scroll_top >= pinned_lines
+                || end_row.as_f32() - scroll_top <= pinned_lines
+            {
+                break;
+            }
+            let offset = pinned_lines.min(end_row.as_f32() - scroll_top - 1.);
+            scopes.push((
+                StickyScope {
+                    start: item.range.start,
+                    start_row,
+                    end_row,
+                },
+                offset,
+            ));
+        }
+        scopes
+    }
+}
+
+impl Editor {
+    pub fn jump_to_sticky_scope(
+        &mut self,
+        scope: &StickyScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let start = scope.start;
+        self.change_selections(Some(Autoscroll::fit()), window, cx, |selections| {
+            selections.select_anchor_ranges([start..start]);
+        });
+    }
+}
@@ -0,0 +1,67 @@
+use editor::Editor;
+use gpui::{
+    Action, Context, Corner, Entity, IntoElement, ParentElement, Render, Subscription, Window, div,
+};
+use language::Buffer;
+use project::Encoding;
+use ui::{Button, ButtonCommon, ContextMenu, FluentBuilder, LabelSize, PopoverMenu, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{ReopenWithEncoding, SaveWithEncoding};
+
+#[derive(Default)]
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    fn update_encoding(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.active_encoding = Some(buffer.read(cx).encoding());
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.active_encoding, |el, active_encoding| {
+            el.child(
+                PopoverMenu::new("encoding-menu")
+                    .menu(|window, cx| {
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            menu.action("Reopen with Encoding…", ReopenWithEncoding.boxed_clone())
+                                .action("Save with Encoding…", SaveWithEncoding.boxed_clone())
+                        }))
+                    })
+                    .anchor(Corner::BottomRight)
+                    .trigger_with_tooltip(
+                        Button::new("change-encoding", active_encoding.display_name())
+                            .label_size(LabelSize::Small),
+                        Tooltip::text("File Encoding"),
+                    ),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = active_pane_item
+            .and_then(|item| item.downcast::<Editor>())
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+        if let Some(buffer) = buffer {
+            self._observe_active_buffer = Some(cx.observe(&buffer, Self::update_encoding));
+            self.update_encoding(buffer, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
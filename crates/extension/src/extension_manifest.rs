@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     ffi::OsStr,
     fmt,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
@@ -84,6 +84,8 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
+    pub debug_adapters: BTreeMap<Arc<str>, DebugAdapterManifestEntry>,
+    #[serde(default)]
     pub snippets: Option<PathBuf>,
     #[serde(default)]
     pub capabilities: Vec<ExtensionCapability>,
@@ -206,6 +208,26 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct DebugAdapterManifestEntry {
+    /// The path to the JSON schema of the adapter's configuration, relative to
+    /// the extension directory.
+    #[serde(default)]
+    pub schema_path: Option<PathBuf>,
+}
+
+impl DebugAdapterManifestEntry {
+    /// Returns the path to the JSON schema of the given adapter's configuration,
+    /// relative to the extension directory.
+    ///
+    /// Defaults to `debug_adapter_schemas/<adapter name>.json`.
+    pub fn schema_path(&self, adapter_name: &str) -> PathBuf {
+        self.schema_path.clone().unwrap_or_else(|| {
+            Path::new("debug_adapter_schemas").join(format!("{adapter_name}.json"))
+        })
+    }
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -231,10 +253,32 @@ impl ExtensionManifest {
                 .load(&extension_manifest_path)
                 .await
                 .with_context(|| format!("failed to load {extension_name} extension.toml"))?;
-            toml::from_str(&manifest_content)
-                .with_context(|| format!("invalid extension.toml for extension {extension_name}"))
+            let manifest: Self = toml::from_str(&manifest_content).with_context(|| {
+                format!("invalid extension.toml for extension {extension_name}")
+            })?;
+            manifest.validate_paths().with_context(|| {
+                format!("invalid extension.toml for extension {extension_name}")
+            })?;
+            Ok(manifest)
         }
     }
+
+    /// Checks that the paths in the manifest, which are relative to the extension
+    /// directory, don't point outside of it.
+    fn validate_paths(&self) -> Result<()> {
+        for (adapter_name, entry) in &self.debug_adapters {
+            let schema_path = entry.schema_path(adapter_name);
+            let is_within_extension = schema_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !is_within_extension {
+                bail!(
+                    "schema path {schema_path:?} of debug adapter {adapter_name} is not within the extension directory"
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 fn manifest_from_old_manifest(
@@ -272,6 +316,7 @@ fn manifest_from_old_manifest(
         context_servers: BTreeMap::default(),
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
+        debug_adapters: BTreeMap::default(),
         snippets: None,
         capabilities: Vec::new(),
     }
@@ -299,11 +344,65 @@ mod tests {
             context_servers: BTreeMap::default(),
             slash_commands: BTreeMap::default(),
             indexed_docs_providers: BTreeMap::default(),
+            debug_adapters: BTreeMap::default(),
             snippets: None,
             capabilities: vec![],
         }
     }
 
+    #[test]
+    fn test_debug_adapters() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "ruby"
+            name = "Ruby"
+            version = "1.0.0"
+            schema_version = 1
+
+            [debug_adapters.rdbg]
+
+            [debug_adapters.ruby-debug]
+            schema_path = "schemas/ruby-debug.json"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest
+                .debug_adapters
+                .iter()
+                .map(|(name, entry)| (name.as_ref(), entry.schema_path(name)))
+                .collect::<Vec<_>>(),
+            [
+                ("rdbg", PathBuf::from("debug_adapter_schemas/rdbg.json")),
+                ("ruby-debug", PathBuf::from("schemas/ruby-debug.json")),
+            ]
+        );
+        manifest.validate_paths().unwrap();
+
+        for schema_path in [
+            "../secrets.json",
+            "schemas/../../secrets.json",
+            "/etc/passwd",
+        ] {
+            let manifest = ExtensionManifest {
+                debug_adapters: BTreeMap::from_iter([(
+                    "rdbg".into(),
+                    DebugAdapterManifestEntry {
+                        schema_path: Some(schema_path.into()),
+                    },
+                )]),
+                ..extension_manifest()
+            };
+            assert!(manifest.validate_paths().is_err(), "{schema_path}");
+        }
+        let manifest = ExtensionManifest {
+            debug_adapters: BTreeMap::from_iter([("../rdbg".into(), Default::default())]),
+            ..extension_manifest()
+        };
+        assert!(manifest.validate_paths().is_err());
+    }
+
     #[test]
     fn test_allow_exact_match() {
         let manifest = ExtensionManifest {
@@ -47,6 +47,16 @@ pub mod lsp {
     };
 }
 
+/// Constructs for interacting with debug adapters over the
+/// Debug Adapter Protocol (DAP).
+pub mod dap {
+    pub use crate::wit::zed::extension::dap::{
+        AttachRequest, DebugAdapterBinary, DebugRequest, DebugTaskDefinition, LaunchRequest,
+        StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest, TcpArguments,
+        TcpArgumentsTemplate, resolve_tcp_template,
+    };
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -177,6 +187,20 @@ pub trait Extension: Send + Sync {
     ) -> Result<(), String> {
         Err("`index_docs` not implemented".to_string())
     }
+
+    /// Returns how to start up the specified debug adapter for the given debug task.
+    ///
+    /// The user-installed path is the path to the adapter configured in the user's
+    /// settings, if any.
+    fn get_dap_binary(
+        &mut self,
+        _adapter_name: String,
+        _config: dap::DebugTaskDefinition,
+        _user_installed_path: Option<String>,
+        _worktree: &Worktree,
+    ) -> Result<dap::DebugAdapterBinary, String> {
+        Err("`get_dap_binary` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -353,6 +377,15 @@ impl wit::Guest for Component {
     ) -> Result<(), String> {
         extension().index_docs(provider, package, database)
     }
+
+    fn get_dap_binary(
+        adapter_name: String,
+        config: DebugTaskDefinition,
+        user_installed_path: Option<String>,
+        worktree: &Worktree,
+    ) -> Result<DebugAdapterBinary, String> {
+        extension().get_dap_binary(adapter_name, config, user_installed_path, worktree)
+    }
 }
 
 /// The ID of a language server.
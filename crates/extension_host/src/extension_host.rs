@@ -14,9 +14,10 @@ use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionEvents, ExtensionGrammarProxy, ExtensionHostProxy,
-    ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
+    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
+    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSlashCommandProxy,
+    ExtensionSnippetProxy, ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -53,7 +54,7 @@ use std::{
     time::{Duration, Instant},
 };
 use url::Url;
-use util::ResultExt;
+use util::{ResultExt, maybe};
 use wasm_host::{
     WasmExtension, WasmHost,
     wit::{is_supported_wasm_api_version, wasm_api_version_range},
@@ -1102,6 +1103,10 @@ impl ExtensionStore {
                         .remove_language_server(&language, language_server_name);
                 }
             }
+            for debug_adapter_name in extension.manifest.debug_adapters.keys() {
+                self.proxy
+                    .unregister_debug_adapter(extension_id.clone(), debug_adapter_name.clone());
+            }
         }
 
         self.wasm_extensions
@@ -1234,12 +1239,31 @@ impl ExtensionStore {
             .await;
 
             let mut wasm_extensions = Vec::new();
+            let mut debug_adapter_schemas = HashMap::default();
             for extension in extension_entries {
                 if extension.manifest.lib.kind.is_none() {
                     continue;
                 };
 
                 let extension_path = root_dir.join(extension.manifest.id.as_ref());
+                for (debug_adapter_name, debug_adapter) in &extension.manifest.debug_adapters {
+                    let schema_path =
+                        extension_path.join(debug_adapter.schema_path(debug_adapter_name));
+                    if !fs.is_file(&schema_path).await {
+                        continue;
+                    }
+                    let schema = maybe!(async {
+                        let contents = fs.load(&schema_path).await?;
+                        serde_json_lenient::from_str::<serde_json::Value>(&contents).with_context(
+                            || format!("invalid schema for debug adapter {debug_adapter_name}"),
+                        )
+                    })
+                    .await;
+                    if let Some(schema) = schema.log_err() {
+                        debug_adapter_schemas.insert(debug_adapter_name.clone(), schema);
+                    }
+                }
+
                 let wasm_extension = WasmExtension::load(
                     extension_path,
                     &extension.manifest,
@@ -1298,6 +1322,14 @@ impl ExtensionStore {
                         this.proxy
                             .register_indexed_docs_provider(extension.clone(), provider_id.clone());
                     }
+
+                    for debug_adapter_name in manifest.debug_adapters.keys() {
+                        this.proxy.register_debug_adapter(
+                            extension.clone(),
+                            debug_adapter_name.clone(),
+                            debug_adapter_schemas.remove(debug_adapter_name),
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1354,10 +1386,15 @@ impl ExtensionStore {
             }
 
             if let Ok(index_json) = serde_json::to_string_pretty(&index) {
-                fs.save(&index_path, &index_json.as_str().into(), Default::default())
-                    .await
-                    .context("failed to save extension index")
-                    .log_err();
+                fs.save(
+                    &index_path,
+                    &index_json.as_str().into(),
+                    Default::default(),
+                    Default::default(),
+                )
+                .await
+                .context("failed to save extension index")
+                .log_err();
             }
 
             log::info!("rebuilt extension index in {:?}", start_time.elapsed());
@@ -1522,6 +1559,7 @@ impl ExtensionStore {
                     &tmp_dir.join(EXTENSION_TOML),
                     &Rope::from(manifest_toml),
                     language::LineEnding::Unix,
+                    Default::default(),
                 )
                 .await?;
             } else {
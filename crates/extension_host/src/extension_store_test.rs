@@ -162,6 +162,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        debug_adapters: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
                     }),
@@ -191,6 +192,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
+                        debug_adapters: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
                     }),
@@ -357,6 +359,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 context_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
+                debug_adapters: BTreeMap::default(),
                 snippets: None,
                 capabilities: Vec::new(),
             }),
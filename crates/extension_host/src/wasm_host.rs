@@ -3,6 +3,7 @@ pub mod wit;
 use crate::ExtensionManifest;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
+use dap::adapters::DebugAdapterBinary;
 use extension::{
     CodeLabel, Command, Completion, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate,
     SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
@@ -28,6 +29,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
 };
+use task::DebugTaskDefinition;
 use wasmtime::{
     Engine, Store,
     component::{Component, ResourceTable},
@@ -346,6 +348,37 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn get_dap_binary(
+        &self,
+        dap_name: Arc<str>,
+        config: DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let user_installed_path = user_installed_path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned());
+                let binary = extension
+                    .call_get_dap_binary(
+                        store,
+                        &dap_name,
+                        &config.into(),
+                        user_installed_path.as_deref(),
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+
+                binary.try_into()
+            }
+            .boxed()
+        })
+        .await
+    }
 }
 
 pub struct WasmState {
@@ -24,6 +24,7 @@ use wasmtime::{
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
     CodeLabel, CodeLabelSpan, Command, ExtensionProject, Range, SlashCommand,
+    zed::extension::dap::{DebugAdapterBinary, DebugTaskDefinition},
     zed::extension::lsp::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
@@ -683,6 +684,30 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_get_dap_binary(
+        &self,
+        store: &mut Store<WasmState>,
+        adapter_name: &str,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<&str>,
+        resource: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<DebugAdapterBinary, String>> {
+        match self {
+            Extension::V0_4_0(ext) => {
+                ext.call_get_dap_binary(store, adapter_name, config, user_installed_path, resource)
+                    .await
+            }
+            Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                Err(anyhow!("`get_dap_binary` not available prior to v0.4.0"))
+            }
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
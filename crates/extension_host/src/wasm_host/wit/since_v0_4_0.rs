@@ -18,6 +18,7 @@ use project::project_settings::ProjectSettings;
 use semantic_version::SemanticVersion;
 use std::{
     env,
+    net::Ipv4Addr,
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
 };
@@ -607,6 +608,110 @@ impl process::Host for WasmState {
     }
 }
 
+impl From<::task::TcpArgumentsTemplate> for dap::TcpArgumentsTemplate {
+    fn from(value: ::task::TcpArgumentsTemplate) -> Self {
+        Self {
+            port: value.port,
+            host: value.host.map(u32::from),
+            timeout: value.timeout,
+        }
+    }
+}
+
+impl From<dap::TcpArgumentsTemplate> for ::task::TcpArgumentsTemplate {
+    fn from(value: dap::TcpArgumentsTemplate) -> Self {
+        Self {
+            port: value.port,
+            host: value.host.map(Ipv4Addr::from),
+            timeout: value.timeout,
+        }
+    }
+}
+
+impl From<dap::TcpArguments> for ::dap::adapters::TcpArguments {
+    fn from(value: dap::TcpArguments) -> Self {
+        Self {
+            port: value.port,
+            host: Ipv4Addr::from(value.host),
+            timeout: value.timeout,
+        }
+    }
+}
+
+impl From<::task::DebugRequest> for dap::DebugRequest {
+    fn from(value: ::task::DebugRequest) -> Self {
+        match value {
+            ::task::DebugRequest::Launch(request) => Self::Launch(dap::LaunchRequest {
+                program: request.program,
+                cwd: request.cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+                args: request.args,
+            }),
+            ::task::DebugRequest::Attach(request) => Self::Attach(dap::AttachRequest {
+                process_id: request.process_id,
+            }),
+        }
+    }
+}
+
+impl From<::task::DebugTaskDefinition> for dap::DebugTaskDefinition {
+    fn from(value: ::task::DebugTaskDefinition) -> Self {
+        Self {
+            label: value.label,
+            adapter: value.adapter,
+            request: value.request.into(),
+            initialize_args: value.initialize_args.map(|args| args.to_string()),
+            tcp_connection: value.tcp_connection.map(Into::into),
+            stop_on_entry: value.stop_on_entry,
+        }
+    }
+}
+
+impl TryFrom<dap::DebugAdapterBinary> for ::dap::adapters::DebugAdapterBinary {
+    type Error = anyhow::Error;
+
+    fn try_from(value: dap::DebugAdapterBinary) -> Result<Self> {
+        let request = match value.request_args.request {
+            dap::StartDebuggingRequestArgumentsRequest::Launch => {
+                ::dap::StartDebuggingRequestArgumentsRequest::Launch
+            }
+            dap::StartDebuggingRequestArgumentsRequest::Attach => {
+                ::dap::StartDebuggingRequestArgumentsRequest::Attach
+            }
+        };
+        Ok(Self {
+            command: value.command,
+            arguments: value.arguments,
+            envs: value.envs.into_iter().collect(),
+            cwd: value.cwd.map(PathBuf::from),
+            connection: value.connection.map(Into::into),
+            request_args: ::dap::StartDebuggingRequestArguments {
+                configuration: serde_json::from_str(&value.request_args.configuration)
+                    .context("invalid debug adapter configuration")?,
+                request,
+            },
+        })
+    }
+}
+
+impl dap::Host for WasmState {
+    async fn resolve_tcp_template(
+        &mut self,
+        template: dap::TcpArgumentsTemplate,
+    ) -> wasmtime::Result<Result<dap::TcpArguments, String>> {
+        maybe!(async {
+            let template = ::task::TcpArgumentsTemplate::from(template);
+            let port = ::dap::transport::TcpTransport::port(&template).await?;
+            Ok(dap::TcpArguments {
+                port,
+                host: template.host().into(),
+                timeout: template.timeout,
+            })
+        })
+        .await
+        .to_wasmtime_result()
+    }
+}
+
 #[async_trait]
 impl slash_command::Host for WasmState {}
 
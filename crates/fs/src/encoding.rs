@@ -0,0 +1,254 @@
+use anyhow::{Result, anyhow};
+use std::borrow::Cow;
+
+/// How many bytes at the start of a file are inspected when looking for
+/// UTF-16 text or binary content.
+const DETECTION_SAMPLE_LEN: usize = 8 * 1024;
+
+/// The encodings offered when reopening or saving a file with an explicit encoding.
+const SUPPORTED_ENCODINGS: &[(&str, bool)] = &[
+    ("UTF-8", false),
+    ("UTF-8", true),
+    ("UTF-16LE", true),
+    ("UTF-16BE", true),
+    ("windows-1252", false),
+    ("ISO-8859-2", false),
+    ("ISO-8859-15", false),
+    ("windows-1250", false),
+    ("windows-1251", false),
+    ("KOI8-R", false),
+    ("windows-1253", false),
+    ("windows-1254", false),
+    ("ISO-8859-7", false),
+    ("windows-1255", false),
+    ("windows-1256", false),
+    ("Shift_JIS", false),
+    ("EUC-JP", false),
+    ("EUC-KR", false),
+    ("GBK", false),
+    ("gb18030", false),
+    ("Big5", false),
+];
+
+/// The character encoding of a text file on disk, and whether it starts with a byte order mark.
+///
+/// Buffers are always UTF-8 in memory, files are decoded when loaded and encoded again when saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    has_bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self {
+            encoding: encoding_rs::UTF_8,
+            has_bom: false,
+        }
+    }
+}
+
+impl Encoding {
+    /// Looks up an encoding by its name or by any of its WHATWG labels.
+    pub fn from_name(name: &str, has_bom: bool) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(name.as_bytes())?;
+        Some(Self { encoding, has_bom })
+    }
+
+    /// The encodings that can be picked explicitly.
+    pub fn all() -> impl Iterator<Item = Self> {
+        SUPPORTED_ENCODINGS
+            .iter()
+            .filter_map(|(name, has_bom)| Self::from_name(name, *has_bom))
+    }
+
+    /// Guesses the encoding of a file's contents, returning `None` when they don't look like text.
+    ///
+    /// A byte order mark always wins. Otherwise, UTF-16 is recognized by its zero bytes and valid
+    /// UTF-8 is assumed to be UTF-8, even when it contains zero bytes. Other contents with zero bytes
+    /// are considered binary, and the remaining text is decoded as Shift JIS when that yields
+    /// Japanese kana, or as Windows-1252, which accepts any byte sequence.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Some(Self {
+                encoding,
+                has_bom: true,
+            });
+        }
+
+        let sample = &bytes[..bytes.len().min(DETECTION_SAMPLE_LEN)];
+        if let Some(encoding) = detect_utf16(sample) {
+            return Some(Self {
+                encoding,
+                has_bom: false,
+            });
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Self::default());
+        }
+        if sample.contains(&0) {
+            return None;
+        }
+
+        let encoding = if encoding_rs::SHIFT_JIS
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .is_some_and(|text| text.chars().any(|c| ('\u{3040}'..='\u{30ff}').contains(&c)))
+        {
+            encoding_rs::SHIFT_JIS
+        } else {
+            encoding_rs::WINDOWS_1252
+        };
+        Some(Self {
+            encoding,
+            has_bom: false,
+        })
+    }
+
+    /// The canonical name of the encoding, such as `UTF-8` or `Shift_JIS`.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.has_bom
+    }
+
+    /// Whether files in this encoding can be written without converting the buffer's text.
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8 && !self.has_bom
+    }
+
+    /// The name to show in the UI, which mentions the byte order mark where relevant.
+    pub fn display_name(&self) -> Cow<'static, str> {
+        match (self.name(), self.has_bom) {
+            ("UTF-8", true) => "UTF-8 with BOM".into(),
+            ("UTF-16LE", _) => "UTF-16 LE".into(),
+            ("UTF-16BE", _) => "UTF-16 BE".into(),
+            (name, _) => name.into(),
+        }
+    }
+
+    /// Decodes a file's contents, dropping the byte order mark and replacing malformed
+    /// sequences with U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let bytes = match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, bom_len)) if encoding == self.encoding => &bytes[bom_len..],
+            _ => bytes,
+        };
+        self.encoding
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned()
+    }
+
+    /// Encodes text to be written to disk, including the byte order mark if the encoding has one.
+    ///
+    /// Fails if the text contains characters that the encoding can't represent.
+    pub fn encode<'a>(&self, chunks: impl IntoIterator<Item = &'a str>) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if self.has_bom {
+            bytes.extend_from_slice(self.bom());
+        }
+        for chunk in chunks {
+            if self.encoding == encoding_rs::UTF_16LE {
+                bytes.extend(chunk.encode_utf16().flat_map(u16::to_le_bytes));
+            } else if self.encoding == encoding_rs::UTF_16BE {
+                bytes.extend(chunk.encode_utf16().flat_map(u16::to_be_bytes));
+            } else {
+                let (encoded, _, had_unmappable) = self.encoding.encode(chunk);
+                if had_unmappable {
+                    return Err(anyhow!(
+                        "the text contains characters that can't be represented in {}",
+                        self.display_name()
+                    ));
+                }
+                bytes.extend_from_slice(&encoded);
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn bom(&self) -> &'static [u8] {
+        if self.encoding == encoding_rs::UTF_16LE {
+            b"\xFF\xFE"
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            b"\xFE\xFF"
+        } else if self.encoding == encoding_rs::UTF_8 {
+            b"\xEF\xBB\xBF"
+        } else {
+            b""
+        }
+    }
+}
+
+/// Recognizes UTF-16 without a byte order mark, in which mostly-ASCII text has a zero byte in
+/// every other position.
+fn detect_utf16(sample: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs == 0 {
+        return None;
+    }
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_zeros += (pair[0] == 0) as usize;
+        odd_zeros += (pair[1] == 0) as usize;
+    }
+    if odd_zeros * 2 > pairs && even_zeros * 10 < pairs {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_zeros * 2 > pairs && odd_zeros * 10 < pairs {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        let detect = |bytes: &[u8]| Encoding::detect(bytes).map(|e| e.display_name());
+
+        assert_eq!(detect(b""), Some("UTF-8".into()));
+        assert_eq!(detect("héllo wörld".as_bytes()), Some("UTF-8".into()));
+        assert_eq!(detect(b"\xEF\xBB\xBFhello"), Some("UTF-8 with BOM".into()));
+        assert_eq!(detect(b"\xFF\xFEh\0i\0"), Some("UTF-16 LE".into()));
+        assert_eq!(detect(b"h\0e\0l\0l\0o\0"), Some("UTF-16 LE".into()));
+        assert_eq!(detect(b"\0h\0e\0l\0l\0o"), Some("UTF-16 BE".into()));
+        assert_eq!(detect(b"caf\xE9 cr\xE8me"), Some("windows-1252".into()));
+        // "こんにちは" in Shift JIS.
+        assert_eq!(
+            detect(b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD"),
+            Some("Shift_JIS".into())
+        );
+        assert_eq!(detect(b"hello\0world\n"), Some("UTF-8".into()));
+        assert_eq!(
+            detect(b"\x7FELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\0>\0\x01\0\0\0\xC0\x10"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        for encoding in Encoding::all() {
+            let text = if encoding.name().starts_with("UTF") {
+                "hello, wörld ✓\n"
+            } else {
+                "hello, world\n"
+            };
+            let bytes = encoding.encode([text]).unwrap();
+            assert_eq!(
+                Encoding::detect(&bytes).is_some_and(|detected| detected.has_bom()),
+                encoding.has_bom(),
+                "{}",
+                encoding.display_name()
+            );
+            assert_eq!(encoding.decode(&bytes), text, "{}", encoding.display_name());
+        }
+
+        let latin1 = Encoding::from_name("latin1", false).unwrap();
+        assert_eq!(latin1.encode(["café"]).unwrap(), b"caf\xE9");
+        assert!(latin1.encode(["✓"]).is_err());
+    }
+}
@@ -5,8 +5,10 @@ use futures::future::{self, BoxFuture};
 use git::{
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions,
+        GitRepository, GitRepositoryCheckpoint, GitWorktree, LogEntry, LogOptions, PushOptions,
+        RebaseTodoEntry, Remote, RepoPath, ResetMode, SequencerOperation, StashEntry, StashOptions,
+        Submodule, Tag,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -34,9 +36,21 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    /// The stash entries, from the most recent one to the oldest one.
+    pub stashes: Vec<FakeStash>,
+    /// The number of stash entries ever pushed, to give each a distinct commit.
+    pub stashes_pushed: usize,
     pub simulated_index_write_error_message: Option<String>,
 }
 
+/// Stashed changes, which only cover the index of the fake repository.
+#[derive(Debug, Clone)]
+pub struct FakeStash {
+    pub sha: String,
+    pub message: String,
+    pub index_contents: HashMap<RepoPath, Option<String>>,
+}
+
 impl FakeGitRepositoryState {
     pub fn new(event_emitter: smol::channel::Sender<PathBuf>) -> Self {
         FakeGitRepositoryState {
@@ -47,6 +61,8 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            stashes: Default::default(),
+            stashes_pushed: 0,
             simulated_index_write_error_message: Default::default(),
         }
     }
@@ -144,6 +160,10 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn log(&self, _options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>> {
+        unimplemented!()
+    }
+
     fn reset(
         &self,
         _commit: String,
@@ -335,6 +355,36 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        unimplemented!()
+    }
+
+    fn create_tag(
+        &self,
+        _name: String,
+        _target: Option<String>,
+        _message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn delete_tag(
+        &self,
+        _name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn checkout_detached(
+        &self,
+        _revision: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -383,6 +433,17 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn push_tag(
+        &self,
+        _tag_name: String,
+        _remote: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn pull(
         &self,
         _branch: String,
@@ -415,6 +476,210 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+        _options: StashOptions,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let changed_paths = state
+                .head_contents
+                .keys()
+                .chain(state.index_contents.keys())
+                .filter(|path| {
+                    paths.is_empty() || paths.iter().any(|prefix| path.starts_with(prefix))
+                })
+                .filter(|path| state.head_contents.get(*path) != state.index_contents.get(*path))
+                .cloned()
+                .collect::<HashSet<_>>();
+            if changed_paths.is_empty() {
+                return Err(anyhow!("No local changes to save"));
+            }
+
+            let mut index_contents = HashMap::default();
+            for path in changed_paths {
+                let content = match state.head_contents.get(&path) {
+                    Some(content) => state.index_contents.insert(path.clone(), content.clone()),
+                    None => state.index_contents.remove(&path),
+                };
+                index_contents.insert(path, content);
+            }
+            let message = message.unwrap_or_else(|| {
+                let branch = state
+                    .current_branch_name
+                    .as_deref()
+                    .unwrap_or("(no branch)");
+                format!("WIP on {branch}")
+            });
+            state.stashes_pushed += 1;
+            let sha = format!("{:040x}", state.stashes_pushed);
+            state.stashes.insert(
+                0,
+                FakeStash {
+                    sha,
+                    message,
+                    index_contents,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        self.with_state_async(false, |state| {
+            Ok(state
+                .stashes
+                .iter()
+                .enumerate()
+                .map(|(index, stash)| StashEntry {
+                    index,
+                    sha: stash.sha.clone().into(),
+                    message: stash.message.clone().into(),
+                    timestamp: 0,
+                })
+                .collect())
+        })
+    }
+
+    fn stash_show(&self, index: usize, _cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        self.with_state_async(false, move |state| {
+            let stash = state
+                .stashes
+                .get(index)
+                .with_context(|| format!("no stash entry at index {index}"))?;
+            let mut files = stash
+                .index_contents
+                .iter()
+                .map(|(path, content)| CommitFile {
+                    path: path.clone(),
+                    old_text: state.head_contents.get(path).cloned(),
+                    new_text: content.clone(),
+                })
+                .collect::<Vec<_>>();
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(CommitDiff { files })
+        })
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let stash = fake_stash_entry(state, index, sha)?.clone();
+            apply_fake_stash(state, stash);
+            Ok(())
+        })
+    }
+
+    fn stash_pop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            fake_stash_entry(state, index, sha)?;
+            let stash = state.stashes.remove(index);
+            apply_fake_stash(state, stash);
+            Ok(())
+        })
+    }
+
+    fn stash_drop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            fake_stash_entry(state, index, sha)?;
+            state.stashes.remove(index);
+            Ok(())
+        })
+    }
+
+    fn sequencer_operation(&self) -> Option<SequencerOperation> {
+        None
+    }
+
+    fn rebase_interactive(
+        &self,
+        _onto: Option<String>,
+        _todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn cherry_pick(
+        &self,
+        _commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn revert(&self, _commit: String, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn continue_operation(
+        &self,
+        _operation: SequencerOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn abort_operation(
+        &self,
+        _operation: SequencerOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        _path: PathBuf,
+        _branch: String,
+        _create_branch: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn remove_worktree(
+        &self,
+        _path: PathBuf,
+        _force: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        unimplemented!()
+    }
+
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn sync_submodules(
+        &self,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
         unimplemented!()
     }
@@ -443,3 +708,27 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 }
+
+fn fake_stash_entry(
+    state: &FakeGitRepositoryState,
+    index: usize,
+    sha: Option<String>,
+) -> Result<&FakeStash> {
+    let stash = state
+        .stashes
+        .get(index)
+        .with_context(|| format!("no stash entry at index {index}"))?;
+    if sha.is_some_and(|sha| sha != stash.sha) {
+        return Err(anyhow!("stash entry at index {index} has changed"));
+    }
+    Ok(stash)
+}
+
+fn apply_fake_stash(state: &mut FakeGitRepositoryState, stash: FakeStash) {
+    for (path, content) in stash.index_contents {
+        match content {
+            Some(content) => state.index_contents.insert(path, content),
+            None => state.index_contents.remove(&path),
+        };
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(target_os = "macos")]
 mod mac_watcher;
 
+mod encoding;
+
 #[cfg(not(target_os = "macos"))]
 pub mod fs_watcher;
 
@@ -36,6 +38,8 @@ use std::{
 use tempfile::{NamedTempFile, TempDir};
 use text::LineEnding;
 
+pub use encoding::Encoding;
+
 #[cfg(any(test, feature = "test-support"))]
 mod fake_git_repo;
 #[cfg(any(test, feature = "test-support"))]
@@ -109,8 +113,36 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads a text file, decoding it with `encoding`, or with the encoding detected from its
+    /// contents when there is none.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let mut bytes = self.load_bytes(path).await?;
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => Encoding::detect(&bytes)
+                .with_context(|| format!("{path:?} does not contain text"))?,
+        };
+        // Reuse the bytes of UTF-8 files instead of holding a decoded copy next to them.
+        if encoding.is_utf8() {
+            match String::from_utf8(bytes) {
+                Ok(text) => return Ok((text, encoding)),
+                Err(error) => bytes = error.into_bytes(),
+            }
+        }
+        Ok((encoding.decode(&bytes), encoding))
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
@@ -554,15 +586,32 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        // Encode before truncating the file, so that failing to represent the text in the
+        // encoding leaves its previous contents in place.
+        let encoded = if encoding.is_utf8() {
+            None
+        } else {
+            Some(encoding.encode(chunks(text, line_ending))?)
+        };
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
-        for chunk in chunks(text, line_ending) {
-            writer.write_all(chunk.as_bytes()).await?;
+        if let Some(encoded) = encoded {
+            writer.write_all(&encoded).await?;
+        } else {
+            for chunk in chunks(text, line_ending) {
+                writer.write_all(chunk.as_bytes()).await?;
+            }
         }
         writer.flush().await?;
         Ok(())
@@ -2103,14 +2152,20 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect::<String>();
+        let content = encoding.encode(chunks(text, line_ending))?;
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes(), false)?;
+        self.write_file_internal(path, content, false)?;
         Ok(())
     }
 
@@ -39,6 +39,9 @@ actions!(
         // per-file
         StageFile,
         UnstageFile,
+        StashFile,
+        FileHistory,
+        LineHistory,
         // repo-wide
         StageAll,
         UnstageAll,
@@ -49,6 +52,17 @@ actions!(
         ForcePush,
         Pull,
         Fetch,
+        StashAll,
+        StashIncludingUntracked,
+        StashPop,
+        ViewStash,
+        ViewHistory,
+        RebaseInteractive,
+        ContinueOperation,
+        AbortOperation,
+        CreateTag,
+        AddWorktree,
+        SyncSubmodules,
         Commit,
         Amend,
         Cancel,
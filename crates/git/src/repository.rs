@@ -18,6 +18,7 @@ use std::process::{ExitStatus, Stdio};
 use std::sync::LazyLock;
 use std::{
     cmp::Ordering,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -160,6 +161,195 @@ impl CommitDetails {
     }
 }
 
+/// A commit listed by `git log`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parent_shas: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+}
+
+impl LogEntry {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+
+    pub fn summary(&self) -> CommitSummary {
+        CommitSummary {
+            sha: self.sha.clone(),
+            subject: self.subject.clone(),
+            commit_timestamp: self.commit_timestamp,
+            has_parent: !self.parent_shas.is_empty(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Only list the commits that changed one of these paths.
+    pub paths: Vec<RepoPath>,
+    /// Keep listing the history of a single path beyond renames.
+    pub follow: bool,
+    /// Only list the commits that changed these rows of a single path,
+    /// as they are in the current revision.
+    pub row_range: Option<Range<u32>>,
+    /// Only list the commits in this range, such as `main..HEAD`, instead of
+    /// the ones reachable from HEAD.
+    pub revision_range: Option<String>,
+    /// The number of commits to skip, for paging.
+    pub skip: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, the most recent one being `0`.
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    /// The name Git refers to the entry by, such as `stash@{0}`.
+    pub fn name(&self) -> String {
+        stash_name(self.index)
+    }
+}
+
+fn stash_name(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+/// Checks that the commit of a stash entry, which may come from a collaborator,
+/// is a hex object id rather than an arbitrary revision or option.
+fn validate_stash_sha(sha: &str) -> Result<()> {
+    anyhow::ensure!(
+        (4..=64).contains(&sha.len()) && sha.bytes().all(|byte| byte.is_ascii_hexdigit()),
+        "invalid stash entry commit {sha:?}"
+    );
+    Ok(())
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to.
+    pub sha: SharedString,
+    /// The subject of the tag's message when it's annotated, or else of the commit's.
+    pub subject: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+    pub is_annotated: bool,
+}
+
+/// A working tree of a repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, if it isn't bare.
+    pub sha: Option<SharedString>,
+    /// The branch checked out in the worktree, unless its HEAD is detached.
+    pub branch: Option<SharedString>,
+    /// Whether this is the main worktree, rather than a linked one.
+    pub is_main: bool,
+    pub is_locked: bool,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SubmoduleStatus {
+    Uninitialized,
+    UpToDate,
+    /// The checked out commit differs from the one recorded in the superproject.
+    Modified,
+    Conflicted,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    pub sha: SharedString,
+    pub status: SubmoduleStatus,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct StashOptions {
+    pub include_untracked: bool,
+}
+
+/// An operation that Git can pause to let conflicts be resolved, before it
+/// is either continued or aborted.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SequencerOperation {
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl SequencerOperation {
+    /// The Git subcommand running the operation.
+    pub fn command(&self) -> &'static str {
+        match self {
+            SequencerOperation::Rebase => "rebase",
+            SequencerOperation::CherryPick => "cherry-pick",
+            SequencerOperation::Revert => "revert",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    #[default]
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [RebaseAction; 5] = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ];
+
+    /// The command for the action in a rebase todo list.
+    pub fn command(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is folded into the one that precedes it.
+    pub fn melds(&self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// A commit replayed by an interactive rebase, from the oldest to the newest.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of a reworded commit.
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Remote {
     pub name: SharedString,
@@ -211,6 +401,28 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<Result<()>>;
 
+    /// Returns the tags, from the most recently created one to the oldest one.
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `target`, or at HEAD when it's `None`. The tag
+    /// is annotated when a message is given, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn delete_tag(&self, name: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Checks out a tag, or any revision naming a commit, detaching HEAD.
+    fn checkout_detached(
+        &self,
+        revision: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
     fn reset(
         &self,
         commit: String,
@@ -228,6 +440,10 @@ pub trait GitRepository: Send + Sync {
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Lists the commits reachable from `HEAD`, the most recent one first.
+    fn log(&self, options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -273,6 +489,17 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<Result<RemoteCommandOutput>>;
 
+    fn push_tag(
+        &self,
+        tag_name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     fn pull(
         &self,
         branch_name: String,
@@ -301,6 +528,124 @@ pub trait GitRepository: Send + Sync {
     /// Run git diff
     fn diff(&self, diff: DiffType) -> BoxFuture<Result<String>>;
 
+    /// Stashes the changes to the given paths, or to all paths if none are given.
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+        options: StashOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the stash entries, from the most recent one to the oldest one.
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>>;
+
+    /// Returns the changes saved in a stash entry, including its untracked files.
+    fn stash_show(&self, index: usize, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Applies the changes of a stash entry, keeping it in the stash.
+    ///
+    /// When the commit `sha` of the entry is given, fails if the entry at `index`
+    /// is no longer that commit, e.g. because the stash changed since it was listed.
+    fn stash_apply(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Applies the changes of a stash entry and removes it from the stash.
+    ///
+    /// Checks the entry against `sha` like [`GitRepository::stash_apply`].
+    fn stash_pop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Removes a stash entry without applying it.
+    ///
+    /// Checks the entry against `sha` like [`GitRepository::stash_apply`].
+    fn stash_drop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the operation that is paused in the repository, if any.
+    fn sequencer_operation(&self) -> Option<SequencerOperation>;
+
+    /// Replays the commits following `onto`, or all of them when it's `None`,
+    /// as described by `todo`.
+    fn rebase_interactive(
+        &self,
+        onto: Option<String>,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Applies the changes introduced by a commit on top of HEAD.
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Commits the reverse of the changes introduced by a commit.
+    fn revert(&self, commit: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Resumes a paused operation, once its conflicts are resolved and staged.
+    fn continue_operation(
+        &self,
+        operation: SequencerOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Cancels a paused operation, restoring the state from before it started.
+    fn abort_operation(
+        &self,
+        operation: SequencerOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the main worktree of the repository, followed by the linked ones.
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>>;
+
+    /// Adds a linked worktree at `path` with `branch` checked out, creating the
+    /// branch from HEAD when `create_branch` is true.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Removes a linked worktree. Unless `force` is true, this fails when the
+    /// worktree has uncommitted changes.
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the submodules of the repository, without recursing into them.
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>>;
+
+    /// Initializes the submodules and checks out the commits recorded for them,
+    /// recursively, after syncing their remote URLs with `.gitmodules`.
+    fn sync_submodules(
+        &self,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     /// Creates a checkpoint for the repository.
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>>;
 
@@ -370,6 +715,154 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    /// Runs `git stash` with the given arguments, after checking that the entry
+    /// at the given index, if any, is still the given commit.
+    fn stash_command(
+        &self,
+        args: Vec<OsString>,
+        expected_entry: Option<(usize, String)>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if let Some((index, expected_sha)) = expected_entry {
+                    validate_stash_sha(&expected_sha)?;
+                    let stash = stash_name(index);
+                    let output = new_smol_command(&git_binary_path)
+                        .current_dir(&working_directory)
+                        .args(["rev-parse", "--quiet", "--verify", &stash])
+                        .output()
+                        .await?;
+                    let sha = String::from_utf8_lossy(&output.stdout);
+                    if !output.status.success() || sha.trim() != expected_sha {
+                        return Err(anyhow!(
+                            "{stash} has changed since the stash was listed, refresh it and try again"
+                        ));
+                    }
+                }
+
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .envs(env.iter())
+                    .arg("stash")
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to run git stash:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn tag_command(
+        &self,
+        args: Vec<OsString>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .arg("tag")
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to run git tag:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn worktree_command(
+        &self,
+        args: Vec<OsString>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .arg("worktree")
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to run git worktree:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    /// Runs a command that starts or resumes an operation that may pause.
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        args: Vec<OsString>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let dot_git_path = self.path();
+        let rebase_dir = dot_git_path.join(REBASE_DIR_NAME);
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command.current_dir(&working_directory?).envs(env.iter());
+                if operation == SequencerOperation::Rebase && rebase_dir.is_dir() {
+                    // The script replaces the todo list Git generates and the messages of
+                    // reworded commits. Git runs editors with a shell, which expands the
+                    // variable, so the path of the script doesn't need quoting.
+                    command
+                        .env(REBASE_DIR_VAR, &rebase_dir)
+                        .env("GIT_SEQUENCE_EDITOR", REBASE_EDITOR_COMMAND)
+                        .env("GIT_EDITOR", REBASE_EDITOR_COMMAND);
+                } else {
+                    // Keep the messages Git prepared when resuming, instead of waiting on
+                    // an editor.
+                    command.env("GIT_EDITOR", "true");
+                }
+                let output = command.args(args).output().await?;
+                let paused = sequencer_operation(&dot_git_path) == Some(operation);
+                if operation == SequencerOperation::Rebase && !paused {
+                    remove_rebase_dir(&rebase_dir);
+                }
+                // Git reports an error when it stops because of conflicts, but the
+                // operation isn't over: it can be continued once they are resolved.
+                if !output.status.success() && !paused {
+                    return Err(anyhow!(
+                        "Failed to {}:\n{}",
+                        operation.command(),
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -421,7 +914,65 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+    fn log(&self, options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut args: Vec<OsString> = vec![
+                    "--no-optional-locks".into(),
+                    "log".into(),
+                    format!("--format={LOG_FORMAT}").into(),
+                ];
+                if options.skip > 0 {
+                    args.push(format!("--skip={}", options.skip).into());
+                }
+                if let Some(limit) = options.limit {
+                    args.push(format!("--max-count={limit}").into());
+                }
+                if let Some(revision_range) = options.revision_range {
+                    args.push(revision_range.into());
+                }
+                match (options.paths.as_slice(), options.row_range) {
+                    ([path], Some(row_range)) => {
+                        // `-L` can't be combined with a pathspec, and always follows renames.
+                        let mut line_range: OsString =
+                            format!("-L{},{}:", row_range.start + 1, row_range.end).into();
+                        line_range.push(path.as_os_str());
+                        args.push(line_range);
+                    }
+                    (paths, _) => {
+                        if !paths.is_empty() {
+                            // Rewrite the parents to the commits that are listed, so that
+                            // the history of a path forms a connected graph.
+                            args.push("--parents".into());
+                        }
+                        if options.follow && paths.len() == 1 {
+                            args.push("--follow".into());
+                        }
+                        args.push("--".into());
+                        args.extend(paths.iter().map(|path| path.as_os_str().to_owned()));
+                    }
+                }
+
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    if stderr.contains("does not have any commits yet") {
+                        return Ok(Vec::new());
+                    }
+                    return Err(anyhow!("Failed to run git log:\n{stderr}"));
+                }
+                parse_log(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
             return future::ready(Err(anyhow!("no working directory"))).boxed();
@@ -449,75 +1000,7 @@ impl GitRepository for RealGitRepository {
             let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
             let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
 
-            let mut cat_file_process = util::command::new_std_command("git")
-                .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|e| anyhow!("Failed to start git cat-file process: {e}"))?;
-
-            use std::io::Write as _;
-            let mut files = Vec::<CommitFile>::new();
-            let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
-            let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
-            let mut info_line = String::new();
-            let mut newline = [b'\0'];
-            for (path, status_code) in changes {
-                match status_code {
-                    StatusCode::Modified => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    StatusCode::Added => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                    }
-                    StatusCode::Deleted => {
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    _ => continue,
-                }
-                stdin.flush()?;
-
-                info_line.clear();
-                stdout.read_line(&mut info_line)?;
-
-                let len = info_line.trim_end().parse().with_context(|| {
-                    format!("invalid object size output from cat-file {info_line}")
-                })?;
-                let mut text = vec![0; len];
-                stdout.read_exact(&mut text)?;
-                stdout.read_exact(&mut newline)?;
-                let text = String::from_utf8_lossy(&text).to_string();
-
-                let mut old_text = None;
-                let mut new_text = None;
-                match status_code {
-                    StatusCode::Modified => {
-                        info_line.clear();
-                        stdout.read_line(&mut info_line)?;
-                        let len = info_line.trim_end().parse().with_context(|| {
-                            format!("invalid object size output from cat-file {}", info_line)
-                        })?;
-                        let mut parent_text = vec![0; len];
-                        stdout.read_exact(&mut parent_text)?;
-                        stdout.read_exact(&mut newline)?;
-                        old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
-                        new_text = Some(text);
-                    }
-                    StatusCode::Added => new_text = Some(text),
-                    StatusCode::Deleted => old_text = Some(text),
-                    _ => continue,
-                }
-
-                files.push(CommitFile {
-                    path: path.into(),
-                    old_text,
-                    new_text,
-                })
-            }
-
+            let files = load_changed_files(&working_directory, changes, parent_sha, &commit)?;
             Ok(CommitDiff { files })
         })
         .boxed()
@@ -868,6 +1351,88 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(creatordate:unix)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "for-each-ref",
+                        "refs/tags",
+                        "--sort=-creatordate",
+                        "--format",
+                    ])
+                    .arg(&fields)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list tags:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                parse_tag_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args: Vec<OsString> = Vec::new();
+        if let Some(message) = message {
+            args.extend(["--annotate".into(), "--message".into(), message.into()]);
+        }
+        args.push(name.into());
+        args.extend(target.map(Into::into));
+        self.tag_command(args, env)
+    }
+
+    fn delete_tag(&self, name: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.tag_command(vec!["--delete".into(), name.into()], env)
+    }
+
+    fn checkout_detached(
+        &self,
+        revision: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["checkout", "--quiet", "--detach", &revision, "--"])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to check out {revision}:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -917,6 +1482,351 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        paths: Vec<RepoPath>,
+        options: StashOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec![OsString::from("push"), OsString::from("--quiet")];
+        if options.include_untracked {
+            args.push("--include-untracked".into());
+        }
+        if let Some(message) = message {
+            args.push("--message".into());
+            args.push(message.into());
+        }
+        if !paths.is_empty() {
+            args.push("--".into());
+            args.extend(paths.iter().map(|path| path.as_os_str().to_owned()));
+        }
+        self.stash_command(args, None, env)
+    }
+
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["stash", "list", "--format=%H%x00%ct%x00%gs"])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list stash entries:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                parse_stash_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn stash_show(&self, index: usize, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        cx.background_spawn(async move {
+            let working_directory = working_directory?;
+            // The entry is referred to by its index, so only `stash@{N}` reaches git.
+            let stash = stash_name(index);
+            let name_status = |args: &[&str]| -> Result<String> {
+                let output = new_std_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .arg("--no-optional-locks")
+                    .args(args)
+                    .args(["-z", "--no-renames", "--name-status"])
+                    .stdin(Stdio::null())
+                    .output()?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to show stash entry:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+            };
+
+            // The stash commit records the tracked changes relative to its
+            // first parent, and its third parent, if any, the untracked files.
+            let tracked_changes = name_status(&["stash", "show", &stash])?;
+            let mut files = load_changed_files(
+                &working_directory,
+                parse_git_diff_name_status(&tracked_changes),
+                &format!("{stash}^1"),
+                &stash,
+            )?;
+            let untracked_commit = format!("{stash}^3");
+            let has_untracked_files = new_std_command(&git_binary_path)
+                .current_dir(&working_directory)
+                .args(["rev-parse", "--quiet", "--verify", &untracked_commit])
+                .stdin(Stdio::null())
+                .output()?
+                .status
+                .success();
+            if has_untracked_files {
+                let untracked_changes = name_status(&["show", "--format=", &untracked_commit])?;
+                files.extend(load_changed_files(
+                    &working_directory,
+                    parse_git_diff_name_status(&untracked_changes),
+                    &untracked_commit,
+                    &untracked_commit,
+                )?);
+            }
+            Ok(CommitDiff { files })
+        })
+        .boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.stash_command(
+            vec!["apply".into(), stash_name(index).into()],
+            sha.map(|sha| (index, sha)),
+            env,
+        )
+    }
+
+    fn stash_pop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.stash_command(
+            vec!["pop".into(), stash_name(index).into()],
+            sha.map(|sha| (index, sha)),
+            env,
+        )
+    }
+
+    fn stash_drop(
+        &self,
+        index: usize,
+        sha: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.stash_command(
+            vec!["drop".into(), "--quiet".into(), stash_name(index).into()],
+            sha.map(|sha| (index, sha)),
+            env,
+        )
+    }
+
+    fn sequencer_operation(&self) -> Option<SequencerOperation> {
+        sequencer_operation(&self.path())
+    }
+
+    fn rebase_interactive(
+        &self,
+        onto: Option<String>,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let rebase_dir = self.path().join(REBASE_DIR_NAME);
+        let prepare = (|| {
+            if rebase_dir.exists() {
+                std::fs::remove_dir_all(&rebase_dir)?;
+            }
+            std::fs::create_dir_all(&rebase_dir)?;
+            std::fs::write(rebase_dir.join("git-rebase-todo"), rebase_todo(&todo)?)?;
+            for entry in &todo {
+                if let (RebaseAction::Reword, Some(message)) = (entry.action, &entry.message) {
+                    std::fs::write(rebase_dir.join(format!("message-{}", entry.sha)), message)?;
+                }
+            }
+            std::fs::write(rebase_dir.join("editor.sh"), REBASE_EDITOR_SCRIPT)?;
+            anyhow::Ok(())
+        })();
+        if let Err(error) = prepare {
+            std::fs::remove_dir_all(&rebase_dir).ok();
+            return future::ready(Err(error)).boxed();
+        }
+
+        let mut args: Vec<OsString> = vec!["rebase".into(), "--interactive".into()];
+        args.push(onto.unwrap_or_else(|| "--root".to_string()).into());
+        self.sequencer_command(SequencerOperation::Rebase, args, env)
+    }
+
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.sequencer_command(
+            SequencerOperation::CherryPick,
+            vec!["cherry-pick".into(), commit.into()],
+            env,
+        )
+    }
+
+    fn revert(&self, commit: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.sequencer_command(
+            SequencerOperation::Revert,
+            vec!["revert".into(), "--no-edit".into(), commit.into()],
+            env,
+        )
+    }
+
+    fn continue_operation(
+        &self,
+        operation: SequencerOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.sequencer_command(
+            operation,
+            vec![operation.command().into(), "--continue".into()],
+            env,
+        )
+    }
+
+    fn abort_operation(
+        &self,
+        operation: SequencerOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let rebase_dir = self.path().join(REBASE_DIR_NAME);
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args([operation.command(), "--abort"])
+                    .output()
+                    .await?;
+                if operation == SequencerOperation::Rebase {
+                    remove_rebase_dir(&rebase_dir);
+                }
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to abort {}:\n{}",
+                        operation.command(),
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "list", "--porcelain"])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list worktrees:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                parse_worktree_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let args = if create_branch {
+            vec!["add".into(), "-b".into(), branch.into(), path.into()]
+        } else {
+            vec!["add".into(), path.into(), branch.into()]
+        };
+        self.worktree_command(args, env)
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args: Vec<OsString> = vec!["remove".into()];
+        if force {
+            args.push("--force".into());
+        }
+        args.push(path.into());
+        self.worktree_command(args, env)
+    }
+
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["submodule", "status"])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list submodules:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                parse_submodule_status(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn sync_submodules(
+        &self,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        async move {
+            let working_directory = working_directory?;
+            // Syncing only rewrites the configuration, the update is what fetches.
+            let output = new_smol_command(&git_binary_path)
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(["submodule", "sync", "--recursive"])
+                .output()
+                .await?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "Failed to sync submodules:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+
+            let mut command = new_smol_command("git");
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(["submodule", "update", "--init", "--recursive"])
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -1049,6 +1959,34 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn push_tag(
+        &self,
+        tag_name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let working_directory = working_directory?;
+            let mut command = new_smol_command("git");
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory)
+                .args(["push"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{tag_name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn pull(
         &self,
         branch_name: String,
@@ -1646,6 +2584,312 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+/// Reads the old and new contents of the given changed files, from the
+/// revisions they were changed between.
+fn load_changed_files<'a>(
+    working_directory: &Path,
+    changes: impl IntoIterator<Item = (&'a Path, StatusCode)>,
+    old_revision: &str,
+    new_revision: &str,
+) -> Result<Vec<CommitFile>> {
+    let mut cat_file_process = util::command::new_std_command("git")
+        .current_dir(working_directory)
+        .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git cat-file process: {e}"))?;
+
+    use std::io::Write as _;
+    let mut files = Vec::<CommitFile>::new();
+    let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
+    let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
+    let mut info_line = String::new();
+    let mut newline = [b'\0'];
+    for (path, status_code) in changes {
+        match status_code {
+            StatusCode::Modified => {
+                writeln!(&mut stdin, "{new_revision}:{}", path.display())?;
+                writeln!(&mut stdin, "{old_revision}:{}", path.display())?;
+            }
+            StatusCode::Added => {
+                writeln!(&mut stdin, "{new_revision}:{}", path.display())?;
+            }
+            StatusCode::Deleted => {
+                writeln!(&mut stdin, "{old_revision}:{}", path.display())?;
+            }
+            _ => continue,
+        }
+        stdin.flush()?;
+
+        info_line.clear();
+        stdout.read_line(&mut info_line)?;
+
+        let len = info_line
+            .trim_end()
+            .parse()
+            .with_context(|| format!("invalid object size output from cat-file {info_line}"))?;
+        let mut text = vec![0; len];
+        stdout.read_exact(&mut text)?;
+        stdout.read_exact(&mut newline)?;
+        let text = String::from_utf8_lossy(&text).to_string();
+
+        let mut old_text = None;
+        let mut new_text = None;
+        match status_code {
+            StatusCode::Modified => {
+                info_line.clear();
+                stdout.read_line(&mut info_line)?;
+                let len = info_line.trim_end().parse().with_context(|| {
+                    format!("invalid object size output from cat-file {}", info_line)
+                })?;
+                let mut parent_text = vec![0; len];
+                stdout.read_exact(&mut parent_text)?;
+                stdout.read_exact(&mut newline)?;
+                old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
+                new_text = Some(text);
+            }
+            StatusCode::Added => new_text = Some(text),
+            StatusCode::Deleted => old_text = Some(text),
+            _ => continue,
+        }
+
+        files.push(CommitFile {
+            path: path.into(),
+            old_text,
+            new_text,
+        })
+    }
+    Ok(files)
+}
+
+/// Each commit is preceded by a record separator, as `git log -L` follows it
+/// with the diff of the selected lines.
+const LOG_FORMAT: &str = "%x1e%H%x00%P%x00%an%x00%ae%x00%ct%x00%s";
+
+fn parse_log(input: &str) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for record in input.split('\x1e') {
+        let Some(line) = record.lines().next().filter(|line| !line.is_empty()) else {
+            continue;
+        };
+        let mut fields = line.split('\x00');
+        let sha: SharedString = fields.next().context("no commit hash")?.to_string().into();
+        let parent_shas = fields
+            .next()
+            .context("no parent hashes")?
+            .split_whitespace()
+            .map(|sha| SharedString::from(sha.to_string()))
+            .collect();
+        let author_name: SharedString = fields.next().context("no author name")?.to_string().into();
+        let author_email: SharedString =
+            fields.next().context("no author email")?.to_string().into();
+        let commit_timestamp = fields.next().context("no committer date")?.parse::<i64>()?;
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+        entries.push(LogEntry {
+            sha,
+            parent_shas,
+            subject,
+            author_name,
+            author_email,
+            commit_timestamp,
+        });
+    }
+    Ok(entries)
+}
+
+/// Returns the operation paused in the repository with the given `.git` directory.
+fn sequencer_operation(dot_git_path: &Path) -> Option<SequencerOperation> {
+    if dot_git_path.join("rebase-merge").is_dir() || dot_git_path.join("rebase-apply").is_dir() {
+        Some(SequencerOperation::Rebase)
+    } else if dot_git_path.join("CHERRY_PICK_HEAD").is_file() {
+        Some(SequencerOperation::CherryPick)
+    } else if dot_git_path.join("REVERT_HEAD").is_file() {
+        Some(SequencerOperation::Revert)
+    } else {
+        None
+    }
+}
+
+/// The directory, next to Git's own rebase state, holding the todo list and the
+/// messages of reworded commits prepared for an interactive rebase.
+const REBASE_DIR_NAME: &str = "zed-rebase";
+
+/// The environment variable telling the editor script where [`REBASE_DIR_NAME`] is.
+const REBASE_DIR_VAR: &str = "ZED_REBASE_DIR";
+
+const REBASE_EDITOR_COMMAND: &str = "sh \"$ZED_REBASE_DIR/editor.sh\"";
+
+/// Run by Git as the editor of an interactive rebase. It replaces the todo list Git
+/// generated with the prepared one, and the message of a reworded commit, which Git
+/// records last in `rebase-merge/done` before editing it, with the new one. Other
+/// messages, such as those of squashed commits, are kept as Git prepared them.
+const REBASE_EDITOR_SCRIPT: &str = r#"#!/bin/sh
+case "$1" in
+*git-rebase-todo)
+    exec cp "$ZED_REBASE_DIR/git-rebase-todo" "$1"
+    ;;
+esac
+set -- "$1" $(tail -n 1 "$ZED_REBASE_DIR/../rebase-merge/done")
+case "$2" in
+reword | r)
+    if [ -f "$ZED_REBASE_DIR/message-$3" ]; then
+        exec cp "$ZED_REBASE_DIR/message-$3" "$1"
+    fi
+    ;;
+esac
+"#;
+
+/// Removes the files prepared for an interactive rebase, once it is over.
+fn remove_rebase_dir(rebase_dir: &Path) {
+    if rebase_dir.exists() {
+        std::fs::remove_dir_all(rebase_dir).log_err();
+    }
+}
+
+/// Builds the todo list of an interactive rebase.
+fn rebase_todo(entries: &[RebaseTodoEntry]) -> Result<String> {
+    if entries
+        .iter()
+        .find(|entry| entry.action != RebaseAction::Drop)
+        .is_some_and(|entry| entry.action.melds())
+    {
+        return Err(anyhow!(
+            "The first commit of a rebase can't be squashed into a previous one"
+        ));
+    }
+
+    let mut todo = String::new();
+    for entry in entries {
+        todo.push_str(&format!(
+            "{} {} {}\n",
+            entry.action.command(),
+            entry.sha,
+            entry.subject
+        ));
+    }
+    Ok(todo)
+}
+
+fn parse_stash_list(input: &str) -> Result<Vec<StashEntry>> {
+    let mut entries = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let sha: SharedString = fields.next().context("no commit hash")?.to_string().into();
+        let timestamp = fields.next().context("no committer date")?.parse::<i64>()?;
+        let message: SharedString = fields
+            .next()
+            .context("no reflog subject")?
+            .to_string()
+            .into();
+        entries.push(StashEntry {
+            index: entries.len(),
+            sha,
+            message,
+            timestamp,
+        });
+    }
+    Ok(entries)
+}
+
+fn parse_tag_list(input: &str) -> Result<Vec<Tag>> {
+    let mut tags = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let name: SharedString = fields.next().context("no refname")?.to_string().into();
+        let is_annotated = fields.next().context("no objecttype")? == "tag";
+        let object_sha = fields.next().context("no objectname")?;
+        // Annotated tags are objects of their own, which point at the commit.
+        let peeled_sha = fields.next().context("no peeled objectname")?;
+        let sha: SharedString = if is_annotated { peeled_sha } else { object_sha }
+            .to_string()
+            .into();
+        let timestamp = fields.next().context("no creatordate")?.parse::<i64>()?;
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+        tags.push(Tag {
+            name,
+            sha,
+            subject,
+            timestamp,
+            is_annotated,
+        });
+    }
+    Ok(tags)
+}
+
+fn parse_worktree_list(input: &str) -> Result<Vec<GitWorktree>> {
+    let mut worktrees = Vec::new();
+    // Each worktree is described by a block of lines, the first one of which
+    // gives its path.
+    for block in input.split("\n\n") {
+        let mut lines = block.lines();
+        let Some(first_line) = lines.next() else {
+            continue;
+        };
+        let path = first_line
+            .strip_prefix("worktree ")
+            .with_context(|| format!("unexpected worktree line: {first_line}"))?;
+        let mut worktree = GitWorktree {
+            path: PathBuf::from(path),
+            sha: None,
+            branch: None,
+            is_main: worktrees.is_empty(),
+            is_locked: false,
+        };
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "HEAD" => worktree.sha = Some(value.to_string().into()),
+                "branch" => {
+                    let branch = value.strip_prefix("refs/heads/").unwrap_or(value);
+                    worktree.branch = Some(branch.to_string().into());
+                }
+                "locked" => worktree.is_locked = true,
+                _ => {}
+            }
+        }
+        worktrees.push(worktree);
+    }
+    Ok(worktrees)
+}
+
+fn parse_submodule_status(input: &str) -> Result<Vec<Submodule>> {
+    let mut submodules = Vec::new();
+    for line in input.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let (status, rest) = line.split_at(1);
+        let status = match status {
+            "-" => SubmoduleStatus::Uninitialized,
+            "+" => SubmoduleStatus::Modified,
+            "U" => SubmoduleStatus::Conflicted,
+            _ => SubmoduleStatus::UpToDate,
+        };
+        let (sha, rest) = rest
+            .split_once(' ')
+            .with_context(|| format!("unexpected submodule line: {line}"))?;
+        // Initialized submodules are followed by a description of their commit.
+        let path = rest
+            .rsplit_once(" (")
+            .filter(|(_, description)| description.ends_with(')'))
+            .map_or(rest, |(path, _)| path);
+        submodules.push(Submodule {
+            path: RepoPath::from_str(path),
+            sha: sha.to_string().into(),
+            status,
+        });
+    }
+    Ok(submodules)
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -1937,6 +3181,337 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_stash(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        smol::fs::write(repo_dir.path().join("a"), "a1")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("b"), "b1")
+            .await
+            .unwrap();
+        repo.stage_paths(
+            vec![RepoPath::from_str("a"), RepoPath::from_str("b")],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("a"), "a2")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("b"), "b2")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("c"), "c1")
+            .await
+            .unwrap();
+        repo.stash_push(
+            Some("only a".into()),
+            vec![RepoPath::from_str("a")],
+            StashOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.stash_push(
+            None,
+            Vec::new(),
+            StashOptions {
+                include_untracked: true,
+            },
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "a1"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("c"))
+                .await
+                .ok(),
+            None
+        );
+
+        let entries = repo.stash_list().await.unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.index).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(entries[0].message.starts_with("WIP on "));
+        assert!(entries[0].message.ends_with(" Initial commit"));
+        assert!(entries[1].message.ends_with(": only a"));
+
+        let diff = repo.stash_show(0, cx.to_async()).await.unwrap();
+        assert_eq!(
+            diff.files
+                .iter()
+                .map(|file| (
+                    file.path.to_string(),
+                    file.old_text.as_deref(),
+                    file.new_text.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("b".to_string(), Some("b1"), Some("b2")),
+                ("c".to_string(), None, Some("c1")),
+            ]
+        );
+
+        assert!(
+            repo.stash_drop(0, Some("--all".to_string()), env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(repo.stash_list().await.unwrap().len(), 2);
+
+        // Entries that moved since they were listed are left alone.
+        smol::fs::write(repo_dir.path().join("a"), "a3")
+            .await
+            .unwrap();
+        repo.stash_push(None, Vec::new(), StashOptions::default(), env.clone())
+            .await
+            .unwrap();
+        assert!(
+            repo.stash_drop(0, Some(entries[0].sha.to_string()), env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(repo.stash_list().await.unwrap().len(), 3);
+        repo.stash_drop(0, None, env.clone()).await.unwrap();
+
+        repo.stash_drop(0, Some(entries[0].sha.to_string()), env.clone())
+            .await
+            .unwrap();
+        repo.stash_pop(0, Some(entries[1].sha.to_string()), env.clone())
+            .await
+            .unwrap();
+        assert!(repo.stash_list().await.unwrap().is_empty());
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "a2"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("b"))
+                .await
+                .unwrap(),
+            "b1"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rebase_interactive(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        let mut shas = Vec::new();
+        for name in ["a", "b", "c"] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Add {name}").into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            shas.push(repo.head_sha().unwrap());
+        }
+
+        repo.rebase_interactive(
+            Some(shas[0].clone()),
+            vec![
+                RebaseTodoEntry {
+                    action: RebaseAction::Reword,
+                    sha: shas[2].clone().into(),
+                    subject: "Add c".into(),
+                    message: Some("Add b and c".into()),
+                },
+                RebaseTodoEntry {
+                    action: RebaseAction::Fixup,
+                    sha: shas[1].clone().into(),
+                    subject: "Add b".into(),
+                    message: None,
+                },
+            ],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.sequencer_operation(), None);
+        assert!(!repo_dir.path().join(".git").join(REBASE_DIR_NAME).exists());
+        let log = repo.log(LogOptions::default()).await.unwrap();
+        assert_eq!(
+            log.iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add b and c", "Add a"]
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("b"))
+                .await
+                .unwrap(),
+            "b"
+        );
+
+        // Picking the commit adding `a` again, once `a` was added back with other
+        // contents, stops on the conflict.
+        repo.revert(shas[0].clone(), env.clone()).await.unwrap();
+        assert_eq!(repo.sequencer_operation(), None);
+        assert!(!repo_dir.path().join("a").exists());
+        smol::fs::write(repo_dir.path().join("a"), "a2")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Restore a".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.cherry_pick(shas[0].clone(), env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.sequencer_operation(),
+            Some(SequencerOperation::CherryPick)
+        );
+        repo.abort_operation(SequencerOperation::CherryPick, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.sequencer_operation(), None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "a2"
+        );
+    }
+
+    #[test]
+    fn test_rebase_todo() {
+        let entry = |action, sha: &str| RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: format!("Commit {sha}").into(),
+            message: (action == RebaseAction::Reword).then(|| format!("Reworded {sha}")),
+        };
+        let todo = rebase_todo(&[
+            entry(RebaseAction::Drop, "1"),
+            entry(RebaseAction::Reword, "2"),
+            entry(RebaseAction::Squash, "3"),
+            entry(RebaseAction::Fixup, "4"),
+            entry(RebaseAction::Pick, "5"),
+        ])
+        .unwrap();
+        assert_eq!(
+            todo,
+            "drop 1 Commit 1\n\
+             reword 2 Commit 2\n\
+             squash 3 Commit 3\n\
+             fixup 4 Commit 4\n\
+             pick 5 Commit 5\n"
+        );
+
+        assert!(
+            rebase_todo(&[
+                entry(RebaseAction::Drop, "1"),
+                entry(RebaseAction::Fixup, "2")
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_log_parsing() {
+        // suppress "help: octal escapes are not supported, `\0` is always null"
+        #[allow(clippy::octal_escapes)]
+        let input = "\x1e0123456789abcdef0123456789abcdef01234567\0fedcba9876543210fedcba9876543210fedcba98 00112233445566778899aabbccddeeff00112233\0Jane Doe\0jane@example.com\01733187470\0Merge branch 'feature'\n\n\x1efedcba9876543210fedcba9876543210fedcba98\0\0John Doe\0john@example.com\01733187000\0Initial commit\n\ndiff --git a/a.txt b/a.txt\n--- /dev/null\n+++ b/a.txt\n@@ -0,0 +1,1 @@\n+a\n";
+        assert_eq!(
+            parse_log(input).unwrap(),
+            vec![
+                LogEntry {
+                    sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                    parent_shas: vec![
+                        "fedcba9876543210fedcba9876543210fedcba98".into(),
+                        "00112233445566778899aabbccddeeff00112233".into(),
+                    ],
+                    subject: "Merge branch 'feature'".into(),
+                    author_name: "Jane Doe".into(),
+                    author_email: "jane@example.com".into(),
+                    commit_timestamp: 1733187470,
+                },
+                LogEntry {
+                    sha: "fedcba9876543210fedcba9876543210fedcba98".into(),
+                    parent_shas: Vec::new(),
+                    subject: "Initial commit".into(),
+                    author_name: "John Doe".into(),
+                    author_email: "john@example.com".into(),
+                    commit_timestamp: 1733187000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stash_list_parsing() {
+        // suppress "help: octal escapes are not supported, `\0` is always null"
+        #[allow(clippy::octal_escapes)]
+        let input = "0123456789abcdef0123456789abcdef01234567\01733187470\0On main: only a\nfedcba9876543210fedcba9876543210fedcba98\01733187000\0WIP on main: 060964d generated protobuf\n";
+        assert_eq!(
+            parse_stash_list(input).unwrap(),
+            vec![
+                StashEntry {
+                    index: 0,
+                    sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                    message: "On main: only a".into(),
+                    timestamp: 1733187470,
+                },
+                StashEntry {
+                    index: 1,
+                    sha: "fedcba9876543210fedcba9876543210fedcba98".into(),
+                    message: "WIP on main: 060964d generated protobuf".into(),
+                    timestamp: 1733187000,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -1964,6 +3539,107 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_tag_list_parsing() {
+        let input = concat!(
+            "v2.0\x00tag\x00a1b2c3d4e5f60718293a4b5c6d7e8f901234567\x00060964da10574cd9bf06463a53bf6e0769c5c45e\x001733187500\x00Release 2.0\n",
+            "v1.0\x00commit\x003c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f\x00\x001733187470\x00generated protobuf\n",
+        );
+        assert_eq!(
+            parse_tag_list(input).unwrap(),
+            vec![
+                Tag {
+                    name: "v2.0".into(),
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    subject: "Release 2.0".into(),
+                    timestamp: 1733187500,
+                    is_annotated: true,
+                },
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f".into(),
+                    subject: "generated protobuf".into(),
+                    timestamp: 1733187470,
+                    is_annotated: false,
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn test_worktree_list_parsing() {
+        let input = "\
+worktree /home/user/project
+HEAD 060964da10574cd9bf06463a53bf6e0769c5c45e
+branch refs/heads/main
+
+worktree /home/user/project-feature
+HEAD 3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f
+branch refs/heads/feature/login
+locked
+
+worktree /home/user/project-review
+HEAD a1b2c3d4e5f60718293a4b5c6d7e8f901234567
+detached
+
+";
+        assert_eq!(
+            parse_worktree_list(input).unwrap(),
+            vec![
+                GitWorktree {
+                    path: PathBuf::from("/home/user/project"),
+                    sha: Some("060964da10574cd9bf06463a53bf6e0769c5c45e".into()),
+                    branch: Some("main".into()),
+                    is_main: true,
+                    is_locked: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/user/project-feature"),
+                    sha: Some("3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f".into()),
+                    branch: Some("feature/login".into()),
+                    is_main: false,
+                    is_locked: true,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/user/project-review"),
+                    sha: Some("a1b2c3d4e5f60718293a4b5c6d7e8f901234567".into()),
+                    branch: None,
+                    is_main: false,
+                    is_locked: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_submodule_status_parsing() {
+        let input = "\
+-060964da10574cd9bf06463a53bf6e0769c5c45e vendor/uninitialized
+ 3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f vendor/lib (v1.2.0)
++a1b2c3d4e5f60718293a4b5c6d7e8f901234567 vendor/with space (heads/main)
+";
+        assert_eq!(
+            parse_submodule_status(input).unwrap(),
+            vec![
+                Submodule {
+                    path: RepoPath::from_str("vendor/uninitialized"),
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    status: SubmoduleStatus::Uninitialized,
+                },
+                Submodule {
+                    path: RepoPath::from_str("vendor/lib"),
+                    sha: "3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f".into(),
+                    status: SubmoduleStatus::UpToDate,
+                },
+                Submodule {
+                    path: RepoPath::from_str("vendor/with space"),
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f901234567".into(),
+                    status: SubmoduleStatus::Modified,
+                },
+            ]
+        );
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
@@ -1,11 +1,11 @@
 use anyhow::{Context as _, anyhow};
 use fuzzy::StringMatchCandidate;
 
-use git::repository::Branch;
+use git::repository::{Branch, Tag};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, Window, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
@@ -17,10 +17,38 @@ use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::git_panel::GitPanel;
+use crate::repository_selector::open_worktree;
+use crate::tag_modal::CreateTagModal;
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
     workspace.register_action(checkout_branch);
+    workspace.register_action(add_worktree);
+}
+
+pub fn add_worktree(
+    workspace: &mut Workspace,
+    _: &git::AddWorktree,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    // Collab guests can't open a worktree created on the host.
+    if workspace.project().read(cx).is_via_collab() {
+        return;
+    }
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        BranchList::new(
+            repository,
+            BranchListStyle::Modal,
+            BranchListMode::AddWorktree,
+            rems(34.),
+            window,
+            cx,
+        )
+    })
 }
 
 pub fn checkout_branch(
@@ -50,7 +78,14 @@ pub fn open(
     let repository = workspace.project().read(cx).active_repository(cx).clone();
     let style = BranchListStyle::Modal;
     workspace.toggle_modal(window, cx, |window, cx| {
-        BranchList::new(repository, style, rems(34.), window, cx)
+        BranchList::new(
+            repository,
+            style,
+            BranchListMode::Checkout,
+            rems(34.),
+            window,
+            cx,
+        )
     })
 }
 
@@ -60,7 +95,14 @@ pub fn popover(
     cx: &mut App,
 ) -> Entity<BranchList> {
     cx.new(|cx| {
-        let list = BranchList::new(repository, BranchListStyle::Popover, rems(20.), window, cx);
+        let list = BranchList::new(
+            repository,
+            BranchListStyle::Popover,
+            BranchListMode::Checkout,
+            rems(20.),
+            window,
+            cx,
+        );
         list.focus_handle(cx).focus(window);
         list
     })
@@ -72,6 +114,13 @@ enum BranchListStyle {
     Popover,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BranchListMode {
+    Checkout,
+    /// Picks the branch to check out in a new linked worktree.
+    AddWorktree,
+}
+
 pub struct BranchList {
     width: Rems,
     pub picker: Entity<Picker<BranchListDelegate>>,
@@ -82,6 +131,7 @@ impl BranchList {
     fn new(
         repository: Option<Entity<Repository>>,
         style: BranchListStyle,
+        mode: BranchListMode,
         width: Rems,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -89,11 +139,20 @@ impl BranchList {
         let all_branches_request = repository
             .clone()
             .map(|repository| repository.update(cx, |repository, _| repository.branches()));
+        let all_tags_request = repository
+            .clone()
+            .filter(|_| mode == BranchListMode::Checkout)
+            .map(|repository| repository.update(cx, |repository, _| repository.tags()));
 
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
                 .context("No active repository")?
                 .await??;
+            // Failing to list the tags shouldn't prevent switching branches.
+            let all_tags = match all_tags_request {
+                Some(request) => request.await?.log_err().unwrap_or_default(),
+                None => Vec::new(),
+            };
 
             all_branches.sort_by_key(|branch| {
                 branch
@@ -105,6 +164,7 @@ impl BranchList {
             this.update_in(cx, |this, window, cx| {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.all_branches = Some(all_branches);
+                    picker.delegate.all_tags = all_tags;
                     picker.refresh(window, cx);
                 })
             })?;
@@ -113,7 +173,7 @@ impl BranchList {
         })
         .detach_and_log_err(cx);
 
-        let delegate = BranchListDelegate::new(repository.clone(), style);
+        let delegate = BranchListDelegate::new(repository.clone(), style, mode);
         let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
 
         let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
@@ -163,29 +223,48 @@ impl Render for BranchList {
 }
 
 #[derive(Debug, Clone)]
-struct BranchEntry {
-    branch: Branch,
-    positions: Vec<usize>,
-    is_new: bool,
+enum BranchEntry {
+    Branch {
+        branch: Branch,
+        positions: Vec<usize>,
+    },
+    Tag {
+        tag: Tag,
+        positions: Vec<usize>,
+    },
+    NewBranch {
+        name: SharedString,
+    },
+    NewTag {
+        name: SharedString,
+    },
+    /// A commit that is checked out by detaching HEAD.
+    Revision {
+        revision: SharedString,
+    },
 }
 
 pub struct BranchListDelegate {
     matches: Vec<BranchEntry>,
     all_branches: Option<Vec<Branch>>,
+    all_tags: Vec<Tag>,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
+    mode: BranchListMode,
     selected_index: usize,
     last_query: String,
     modifiers: Modifiers,
 }
 
 impl BranchListDelegate {
-    fn new(repo: Option<Entity<Repository>>, style: BranchListStyle) -> Self {
+    fn new(repo: Option<Entity<Repository>>, style: BranchListStyle, mode: BranchListMode) -> Self {
         Self {
             matches: vec![],
             repo,
             style,
+            mode,
             all_branches: None,
+            all_tags: Vec::new(),
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
@@ -218,13 +297,150 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    fn create_tag(&self, name: SharedString, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(repo), Some(workspace)) =
+            (self.repo.clone(), window.root::<Workspace>().flatten())
+        else {
+            return;
+        };
+        cx.emit(DismissEvent);
+        // Let the picker be dismissed before the modal takes the focus.
+        window.defer(cx, move |window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                CreateTagModal::toggle(repo, None, Some(name), workspace, window, cx);
+            });
+        });
+    }
+
+    fn checkout_detached(
+        &self,
+        revision: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.checkout_detached(revision))?
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to check out", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    /// Creates a worktree for `branch` next to the repository's working directory and opens it.
+    fn add_worktree(
+        &self,
+        branch: SharedString,
+        create_branch: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let (Some(repo), Some(workspace)) =
+            (self.repo.clone(), window.root::<Workspace>().flatten())
+        else {
+            return;
+        };
+        let work_directory = repo.read(cx).work_directory_abs_path.clone();
+        let Some(directory_name) = work_directory.file_name() else {
+            return;
+        };
+        let path = work_directory.with_file_name(format!(
+            "{}-{}",
+            directory_name.to_string_lossy(),
+            branch.replace('/', "-")
+        ));
+        let workspace = workspace.downgrade();
+        cx.spawn_in(window, async move |_, cx| {
+            repo.update(cx, |repo, _| {
+                repo.create_worktree(path.clone(), branch.to_string(), create_branch)
+            })?
+            .await??;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    open_worktree(path, workspace, window, cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err("Failed to add worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn selected_tag(&self) -> Option<&Tag> {
+        match self.matches.get(self.selected_index)? {
+            BranchEntry::Tag { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+
+    fn push_selected_tag(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.selected_tag() else {
+            return;
+        };
+        let Some(panel) = window
+            .root::<Workspace>()
+            .flatten()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        let tag_name = tag.name.clone();
+        panel.update(cx, |panel, cx| panel.push_tag(tag_name, window, cx));
+        cx.emit(DismissEvent);
+    }
+
+    fn delete_selected_tag(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(tag), Some(repo)) = (self.selected_tag().cloned(), self.repo.clone()) else {
+            return;
+        };
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!("Delete tag {}?", tag.name),
+            Some("The tag is only deleted locally, remotes it was pushed to keep it."),
+            &["Delete", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |picker, cx| {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            repo.update(cx, |repo, _| repo.delete_tag(tag.name.to_string()))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker
+                    .delegate
+                    .all_tags
+                    .retain(|existing_tag| existing_tag.name != tag.name);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+/// Whether the query could be the abbreviated or full SHA of a commit.
+fn is_commit_sha(query: &str) -> bool {
+    (4..=40).contains(&query.len()) && query.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
-        "Select branch...".into()
+        match self.mode {
+            BranchListMode::Checkout => "Select branch or tag...".into(),
+            BranchListMode::AddWorktree => "Select branch for the new worktree...".into(),
+        }
     }
 
     fn editor_position(&self) -> PickerEditorPosition {
@@ -260,24 +476,37 @@ impl PickerDelegate for BranchListDelegate {
         let Some(all_branches) = self.all_branches.clone() else {
             return Task::ready(());
         };
+        let all_tags = self.all_tags.clone();
+        let mode = self.mode;
 
         const RECENT_BRANCHES_COUNT: usize = 10;
+        const RECENT_TAGS_COUNT: usize = 5;
         cx.spawn_in(window, async move |picker, cx| {
             let mut matches: Vec<BranchEntry> = if query.is_empty() {
-                all_branches
-                    .into_iter()
-                    .take(RECENT_BRANCHES_COUNT)
-                    .map(|branch| BranchEntry {
-                        branch,
-                        positions: Vec::new(),
-                        is_new: false,
-                    })
-                    .collect()
+                let recent_branches =
+                    all_branches
+                        .into_iter()
+                        .take(RECENT_BRANCHES_COUNT)
+                        .map(|branch| BranchEntry::Branch {
+                            branch,
+                            positions: Vec::new(),
+                        });
+                let recent_tags =
+                    all_tags
+                        .into_iter()
+                        .take(RECENT_TAGS_COUNT)
+                        .map(|tag| BranchEntry::Tag {
+                            tag,
+                            positions: Vec::new(),
+                        });
+                recent_branches.chain(recent_tags).collect()
             } else {
                 let candidates = all_branches
                     .iter()
+                    .map(|branch| &branch.name)
+                    .chain(all_tags.iter().map(|tag| &tag.name))
                     .enumerate()
-                    .map(|(ix, command)| StringMatchCandidate::new(ix, &command.name.clone()))
+                    .map(|(ix, name)| StringMatchCandidate::new(ix, name))
                     .collect::<Vec<StringMatchCandidate>>();
                 fuzzy::match_strings(
                     &candidates,
@@ -288,33 +517,44 @@ impl PickerDelegate for BranchListDelegate {
                     cx.background_executor().clone(),
                 )
                 .await
-                .iter()
-                .cloned()
-                .map(|candidate| BranchEntry {
-                    branch: all_branches[candidate.candidate_id].clone(),
-                    positions: candidate.positions,
-                    is_new: false,
+                .into_iter()
+                .map(|candidate| match all_branches.get(candidate.candidate_id) {
+                    Some(branch) => BranchEntry::Branch {
+                        branch: branch.clone(),
+                        positions: candidate.positions,
+                    },
+                    None => BranchEntry::Tag {
+                        tag: all_tags[candidate.candidate_id - all_branches.len()].clone(),
+                        positions: candidate.positions,
+                    },
                 })
                 .collect()
             };
             picker
                 .update(cx, |picker, _| {
-                    #[allow(clippy::nonminimal_bool)]
-                    if !query.is_empty()
-                        && !matches
-                            .first()
-                            .is_some_and(|entry| entry.branch.name == query)
-                    {
-                        matches.push(BranchEntry {
-                            branch: Branch {
+                    if !query.is_empty() {
+                        // Branches and tags share a namespace when checking out,
+                        // so only offer to create names that aren't taken.
+                        let name_taken = matches.iter().any(|entry| match entry {
+                            BranchEntry::Branch { branch, .. } => branch.name == query,
+                            BranchEntry::Tag { tag, .. } => tag.name == query,
+                            _ => false,
+                        });
+                        if !name_taken {
+                            matches.push(BranchEntry::NewBranch {
                                 name: query.clone().into(),
-                                is_head: false,
-                                upstream: None,
-                                most_recent_commit: None,
-                            },
-                            positions: Vec::new(),
-                            is_new: true,
-                        })
+                            });
+                            if mode == BranchListMode::Checkout {
+                                matches.push(BranchEntry::NewTag {
+                                    name: query.clone().into(),
+                                });
+                            }
+                        }
+                        if mode == BranchListMode::Checkout && is_commit_sha(&query) {
+                            matches.push(BranchEntry::Revision {
+                                revision: query.clone().into(),
+                            });
+                        }
                     }
                     let delegate = &mut picker.delegate;
                     delegate.matches = matches;
@@ -334,10 +574,37 @@ impl PickerDelegate for BranchListDelegate {
         let Some(entry) = self.matches.get(self.selected_index()) else {
             return;
         };
-        if entry.is_new {
-            self.create_branch(entry.branch.name.clone(), window, cx);
+        if self.mode == BranchListMode::AddWorktree {
+            match entry {
+                BranchEntry::Branch { branch, .. } => {
+                    self.add_worktree(branch.name.clone(), false, window, cx)
+                }
+                BranchEntry::NewBranch { name } => {
+                    self.add_worktree(name.clone(), true, window, cx)
+                }
+                _ => {}
+            }
             return;
         }
+        let selected_branch = match entry {
+            BranchEntry::Branch { branch, .. } => branch,
+            BranchEntry::Tag { tag, .. } => {
+                self.checkout_detached(format!("refs/tags/{}", tag.name), window, cx);
+                return;
+            }
+            BranchEntry::NewBranch { name } => {
+                self.create_branch(name.clone(), window, cx);
+                return;
+            }
+            BranchEntry::NewTag { name } => {
+                self.create_tag(name.clone(), window, cx);
+                return;
+            }
+            BranchEntry::Revision { revision } => {
+                self.checkout_detached(revision.to_string(), window, cx);
+                return;
+            }
+        };
 
         let current_branch = self.repo.as_ref().map(|repo| {
             repo.update(cx, |repo, _| {
@@ -347,14 +614,14 @@ impl PickerDelegate for BranchListDelegate {
 
         if current_branch
             .flatten()
-            .is_some_and(|current_branch| current_branch == entry.branch.name)
+            .is_some_and(|current_branch| current_branch == selected_branch.name)
         {
             cx.emit(DismissEvent);
             return;
         }
 
         cx.spawn_in(window, {
-            let branch = entry.branch.clone();
+            let branch = selected_branch.clone();
             async move |picker, cx| {
                 let branch_change_task = picker.update(cx, |this, cx| {
                     let repo = this
@@ -403,22 +670,80 @@ impl PickerDelegate for BranchListDelegate {
     ) -> Option<Self::ListItem> {
         let entry = &self.matches[ix];
 
-        let (commit_time, subject) = entry
-            .branch
-            .most_recent_commit
-            .as_ref()
-            .map(|commit| {
-                let subject = commit.subject.clone();
-                let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
-                    .unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let formatted_time = format_local_timestamp(
-                    commit_time,
-                    OffsetDateTime::now_utc(),
-                    time_format::TimestampFormat::Relative,
-                );
-                (Some(formatted_time), Some(subject))
-            })
-            .unwrap_or_else(|| (None, None));
+        let format_time = |timestamp: i64| {
+            let time = OffsetDateTime::from_unix_timestamp(timestamp)
+                .unwrap_or_else(|_| OffsetDateTime::now_utc());
+            format_local_timestamp(
+                time,
+                OffsetDateTime::now_utc(),
+                time_format::TimestampFormat::Relative,
+            )
+        };
+        let current_branch = || {
+            self.repo
+                .as_ref()
+                .and_then(|repo| repo.read(cx).branch.as_ref().map(|b| b.name.clone()))
+        };
+
+        let (label, time, message) = match entry {
+            BranchEntry::Branch { branch, positions } => (
+                HighlightedLabel::new(branch.name.clone(), positions.clone())
+                    .truncate()
+                    .into_any_element(),
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| format_time(commit.commit_timestamp)),
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map_or("no commits found".to_string(), |commit| {
+                        commit.subject.to_string()
+                    }),
+            ),
+            BranchEntry::Tag { tag, positions } => (
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Hash)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(HighlightedLabel::new(tag.name.clone(), positions.clone()).truncate())
+                    .into_any_element(),
+                Some(format_time(tag.timestamp)),
+                tag.subject.to_string(),
+            ),
+            BranchEntry::NewBranch { name } => (
+                Label::new(format!("Create branch \"{}\"…", name))
+                    .single_line()
+                    .into_any_element(),
+                None,
+                if let Some(current_branch) = current_branch() {
+                    format!("based off {}", current_branch)
+                } else {
+                    "based off the current branch".to_string()
+                },
+            ),
+            BranchEntry::NewTag { name } => (
+                Label::new(format!("Create tag \"{}\"…", name))
+                    .single_line()
+                    .into_any_element(),
+                None,
+                if let Some(current_branch) = current_branch() {
+                    format!("on {}", current_branch)
+                } else {
+                    "on the current commit".to_string()
+                },
+            ),
+            BranchEntry::Revision { revision } => (
+                Label::new(format!("Check out commit {}…", revision))
+                    .single_line()
+                    .into_any_element(),
+                None,
+                "in detached HEAD state".to_string(),
+            ),
+        };
 
         Some(
             ListItem::new(SharedString::from(format!("vcs-menu-{ix}")))
@@ -439,26 +764,10 @@ impl PickerDelegate for BranchListDelegate {
                                 .overflow_x_hidden()
                                 .gap_2()
                                 .justify_between()
-                                .child(div().flex_shrink().overflow_x_hidden().child(
-                                    if entry.is_new {
-                                        Label::new(format!(
-                                            "Create branch \"{}\"…",
-                                            entry.branch.name
-                                        ))
-                                        .single_line()
-                                        .into_any_element()
-                                    } else {
-                                        HighlightedLabel::new(
-                                            entry.branch.name.clone(),
-                                            entry.positions.clone(),
-                                        )
-                                        .truncate()
-                                        .into_any_element()
-                                    },
-                                ))
-                                .when_some(commit_time, |el, commit_time| {
+                                .child(div().flex_shrink().overflow_x_hidden().child(label))
+                                .when_some(time, |el, time| {
                                     el.child(
-                                        Label::new(commit_time)
+                                        Label::new(time)
                                             .size(LabelSize::Small)
                                             .color(Color::Muted)
                                             .into_element(),
@@ -466,30 +775,57 @@ impl PickerDelegate for BranchListDelegate {
                                 }),
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
-                            el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
-                                    if let Some(current_branch) =
-                                        self.repo.as_ref().and_then(|repo| {
-                                            repo.read(cx).branch.as_ref().map(|b| b.name.clone())
-                                        })
-                                    {
-                                        format!("based off {}", current_branch)
-                                    } else {
-                                        "based off the current branch".to_string()
-                                    }
-                                } else {
-                                    subject.unwrap_or("no commits found".into()).to_string()
-                                };
-                                Label::new(message)
-                                    .size(LabelSize::Small)
-                                    .truncate()
-                                    .color(Color::Muted)
-                            }))
+                            el.child(
+                                div().max_w_96().child(
+                                    Label::new(message)
+                                        .size(LabelSize::Small)
+                                        .truncate()
+                                        .color(Color::Muted),
+                                ),
+                            )
                         }),
                 ),
         )
     }
 
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        self.selected_tag()?;
+        let can_push = window
+            .root::<Workspace>()
+            .flatten()
+            .is_some_and(|workspace| !workspace.read(cx).project().read(cx).is_via_collab());
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("delete-tag", "Delete")
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|picker, _, window, cx| {
+                            picker.delegate.delete_selected_tag(window, cx)
+                        })),
+                )
+                .when(can_push, |this| {
+                    this.child(
+                        Button::new("push-tag", "Push")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|picker, _, window, cx| {
+                                picker.delegate.push_selected_tag(window, cx)
+                            })),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+
     fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
         None
     }
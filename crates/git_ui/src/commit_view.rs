@@ -1,10 +1,11 @@
 use anyhow::{Result, anyhow};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use futures::channel::oneshot;
+use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath, StashEntry};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
+    FocusHandle, Focusable, IntoElement, Render, SharedString, WeakEntity, Window,
 };
 use language::{
     Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
@@ -19,18 +20,26 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use ui::{Color, Icon, IconName, Label, LabelCommon as _};
+use ui::{Tooltip, prelude::*};
 use util::{ResultExt, truncate_and_trailoff};
 use workspace::{
     Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
     item::{BreadcrumbText, ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr,
     searchable::SearchableItemHandle,
 };
 
+use crate::rebase_editor::RebaseEditor;
+use crate::tag_modal::CreateTagModal;
+
 pub struct CommitView {
     commit: CommitDetails,
     editor: Entity<Editor>,
     multibuffer: Entity<MultiBuffer>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    /// Whether the commit is a stash entry, which can't be picked or reverted.
+    is_stash: bool,
 }
 
 struct GitBlob {
@@ -58,12 +67,36 @@ impl CommitView {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit.sha.to_string()))
             .ok();
-        let commit_details = repo
-            .update(cx, |repo, _| repo.show(commit.sha.to_string()))
-            .ok();
+        Self::open_with_diff(commit.sha, commit_diff, false, repo, workspace, window, cx);
+    }
+
+    /// Opens the changes recorded by a stash entry, relative to the commit it
+    /// was created on top of.
+    pub fn open_stash(
+        entry: StashEntry,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let stash_diff = repo.update(cx, |repo, _| repo.stash_show(entry.index)).ok();
+        Self::open_with_diff(entry.sha, stash_diff, true, repo, workspace, window, cx);
+    }
+
+    fn open_with_diff(
+        sha: SharedString,
+        commit_diff: Option<oneshot::Receiver<Result<CommitDiff>>>,
+        is_stash: bool,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let commit_details = repo.update(cx, |repo, _| repo.show(sha.to_string())).ok();
 
         window
             .spawn(cx, async move |cx| {
+                let workspace_handle = workspace.clone();
                 let (commit_diff, commit_details) = futures::join!(commit_diff?, commit_details?);
                 let commit_diff = commit_diff.log_err()?.log_err()?;
                 let commit_details = commit_details.log_err()?.log_err()?;
@@ -76,8 +109,10 @@ impl CommitView {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
+                                is_stash,
                                 repo,
                                 project.clone(),
+                                workspace_handle,
                                 window,
                                 cx,
                             )
@@ -87,8 +122,7 @@ impl CommitView {
                         pane.update(cx, |pane, cx| {
                             let ix = pane.items().position(|item| {
                                 let commit_view = item.downcast::<CommitView>();
-                                commit_view
-                                    .map_or(false, |view| view.read(cx).commit.sha == commit.sha)
+                                commit_view.map_or(false, |view| view.read(cx).commit.sha == sha)
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
@@ -106,8 +140,10 @@ impl CommitView {
     fn new(
         commit: CommitDetails,
         commit_diff: CommitDiff,
+        is_stash: bool,
         repository: Entity<Repository>,
         project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -160,6 +196,9 @@ impl CommitView {
             });
         }
 
+        cx.observe(&repository, |_, _, cx| cx.notify()).detach();
+        let repository_handle = repository.clone();
+
         cx.spawn(async move |this, mut cx| {
             for file in commit_diff.files {
                 let is_deleted = file.new_text.is_none();
@@ -211,8 +250,104 @@ impl CommitView {
             commit,
             editor,
             multibuffer,
+            repository: repository_handle,
+            workspace,
+            is_stash,
         }
     }
+
+    fn cherry_pick(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let sha = self.commit.sha.to_string();
+        let cherry_pick = self
+            .repository
+            .update(cx, |repository, _| repository.cherry_pick(sha));
+        cx.spawn(async move |_, _| {
+            cherry_pick.await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to cherry-pick", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn revert(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let sha = self.commit.sha.to_string();
+        let revert = self
+            .repository
+            .update(cx, |repository, _| repository.revert(sha));
+        cx.spawn(async move |_, _| {
+            revert.await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to revert", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn rebase_interactive(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = self.repository.clone();
+        let onto = self.commit.sha.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                RebaseEditor::toggle(repository, onto, workspace, window, cx)
+            })
+            .ok();
+    }
+
+    fn create_tag(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = self.repository.clone();
+        let target = self.commit.sha.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                CreateTagModal::toggle(repository, Some(target), None, workspace, window, cx)
+            })
+            .ok();
+    }
+
+    fn render_commit_actions(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let short_sha = self.commit.short_sha();
+        let is_paused = self.repository.read(cx).sequencer_operation.is_some();
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_end()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                Button::new("create-tag", "Create Tag…")
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text(format!("git tag <name> {short_sha}")))
+                    .on_click(cx.listener(|this, _, window, cx| this.create_tag(window, cx))),
+            )
+            .child(
+                Button::new("rebase-interactive", "Rebase Interactively…")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_paused)
+                    .tooltip(Tooltip::text(format!(
+                        "git rebase --interactive {short_sha}"
+                    )))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.rebase_interactive(window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("revert-commit", "Revert")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_paused)
+                    .tooltip(Tooltip::text(format!("git revert {short_sha}")))
+                    .on_click(cx.listener(|this, _, window, cx| this.revert(window, cx))),
+            )
+            .child(
+                Button::new("cherry-pick-commit", "Cherry-pick")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_paused)
+                    .tooltip(Tooltip::text(format!("git cherry-pick {short_sha}")))
+                    .on_click(cx.listener(|this, _, window, cx| this.cherry_pick(window, cx))),
+            )
+    }
 }
 
 impl language::File for GitBlob {
@@ -513,7 +648,12 @@ impl Item for CommitView {
 }
 
 impl Render for CommitView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .when(!self.is_stash, |this| {
+                this.child(self.render_commit_actions(cx))
+            })
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
     }
 }
@@ -3,6 +3,7 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::StatusStyle;
+use crate::log_view::{LogScope, LogView};
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
@@ -22,11 +23,15 @@ use futures::StreamExt as _;
 use git::blame::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, PushOptions, Remote,
-    RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    RemoteCommandOutput, ResetMode, SequencerOperation, StashOptions, Upstream, UpstreamTracking,
+    UpstreamTrackingStatus,
 };
 use git::status::StageStatus;
+use git::{
+    AbortOperation, ContinueOperation, ExpandCommitEditor, RestoreTrackedFiles, StageAll, StashAll,
+    StashIncludingUntracked, StashPop, TrashUntrackedFiles, UnstageAll, ViewStash,
+};
 use git::{Amend, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
 use gpui::{
     Action, Animation, AnimationExt as _, Axis, ClickEvent, Corner, DismissEvent, Entity,
     EventEmitter, FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior,
@@ -137,6 +142,7 @@ fn git_panel_context_menu(
             })
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action("View History", git::ViewHistory.boxed_clone())
             .separator()
             .map(|menu| {
                 if state.has_tracked_changes {
@@ -155,6 +161,29 @@ fn git_panel_context_menu(
                     menu.disabled_action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
                 }
             })
+            .separator()
+            .map(|menu| {
+                if state.has_tracked_changes {
+                    menu.action("Stash All", StashAll.boxed_clone())
+                } else {
+                    menu.disabled_action("Stash All", StashAll.boxed_clone())
+                }
+            })
+            .map(|menu| {
+                if state.has_tracked_changes || state.has_new_changes {
+                    menu.action(
+                        "Stash Including Untracked",
+                        StashIncludingUntracked.boxed_clone(),
+                    )
+                } else {
+                    menu.disabled_action(
+                        "Stash Including Untracked",
+                        StashIncludingUntracked.boxed_clone(),
+                    )
+                }
+            })
+            .action("Pop Stash", StashPop.boxed_clone())
+            .action("View Stash", ViewStash.boxed_clone())
     })
 }
 
@@ -1227,6 +1256,156 @@ impl GitPanel {
         self.change_file_stage(false, entries, cx);
     }
 
+    pub fn stash_all(&mut self, _: &StashAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash(Vec::new(), StashOptions::default(), window, cx);
+    }
+
+    pub fn stash_including_untracked(
+        &mut self,
+        _: &StashIncludingUntracked,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stash(
+            Vec::new(),
+            StashOptions {
+                include_untracked: true,
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn stash_selected(&mut self, _: &git::StashFile, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(status_entry) = self
+            .get_selected_entry()
+            .and_then(|entry| entry.status_entry())
+            .cloned()
+        else {
+            return;
+        };
+        let options = StashOptions {
+            include_untracked: status_entry.status.is_created(),
+        };
+        self.stash(vec![status_entry.repo_path], options, window, cx);
+    }
+
+    fn stash(
+        &mut self,
+        paths: Vec<RepoPath>,
+        options: StashOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Stashed");
+        let stash = active_repository.update(cx, |repo, _| repo.stash_push(None, paths, options));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = stash.await?;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash", e, cx);
+                }
+            })
+            .ok();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn stash_pop(&mut self, _: &StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let pop = active_repository.update(cx, |repo, _| repo.stash_pop(0, None));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = pop.await?;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash pop", e, cx);
+                }
+            })
+            .ok();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn continue_operation(
+        &mut self,
+        _: &ContinueOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_sequencer_operation(false, window, cx);
+    }
+
+    pub fn abort_operation(
+        &mut self,
+        _: &AbortOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_sequencer_operation(true, window, cx);
+    }
+
+    fn resolve_sequencer_operation(
+        &mut self,
+        abort: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = active_repository.read(cx).sequencer_operation else {
+            return;
+        };
+        let task = active_repository.update(cx, |repo, _| {
+            if abort {
+                repo.abort_operation(operation)
+            } else {
+                repo.continue_operation(operation)
+            }
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await?;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(operation.command(), e, cx);
+                }
+            })
+            .ok();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn view_selected_file_history(
+        &mut self,
+        _: &git::FileHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(status_entry) = self
+            .get_selected_entry()
+            .and_then(|entry| entry.status_entry())
+        else {
+            return;
+        };
+        let scope = LogScope::File(status_entry.repo_path.clone());
+        self.workspace
+            .update(cx, |workspace, cx| {
+                LogView::open(active_repository, scope, workspace, window, cx);
+            })
+            .ok();
+    }
+
     fn toggle_staged_for_entry(
         &mut self,
         entry: &GitListEntry,
@@ -1819,6 +1998,37 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    pub(crate) fn sync_submodules(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let askpass = self.askpass_delegate("git submodule update", window, cx);
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                let sync = repo.update(cx, |repo, _| repo.sync_submodules(askpass))?;
+
+                let remote_message = sync.await?;
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::SyncSubmodules;
+                    match remote_message {
+                        Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                        Err(e) => {
+                            log::error!("Error while syncing submodules {:?}", e);
+                            this.show_error_toast(action.name(), e, cx)
+                        }
+                    }
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
     pub(crate) fn git_init(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let worktrees = self
             .project
@@ -2024,6 +2234,58 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag_name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let remote = self.get_current_remote(window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let push = repo.update(cx, |repo, _| {
+                repo.push_tag(tag_name.clone(), remote.name.clone(), askpass_delegate)
+            })?;
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::PushTag(tag_name, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn askpass_delegate(
         &self,
         operation: impl Into<SharedString>,
@@ -2066,14 +2328,13 @@ impl GitPanel {
                 return Err(anyhow::anyhow!("No active repository"));
             };
 
+            // Without a current branch, e.g. when pushing a tag from a detached
+            // HEAD, all the remotes are candidates.
             let mut current_remotes: Vec<Remote> = repo
                 .update(&mut cx, |repo, _| {
-                    let Some(current_branch) = repo.branch.as_ref() else {
-                        return Err(anyhow::anyhow!("No active branch"));
-                    };
-
-                    Ok(repo.get_remotes(Some(current_branch.name.to_string())))
-                })??
+                    let current_branch = repo.branch.as_ref();
+                    repo.get_remotes(current_branch.map(|branch| branch.name.to_string()))
+                })?
                 .await??;
 
             if current_remotes.len() == 0 {
@@ -3234,6 +3495,67 @@ impl GitPanel {
         Some(footer)
     }
 
+    fn render_sequencer_operation(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let repository = self.active_repository.as_ref()?.read(cx);
+        let operation = repository.sequencer_operation?;
+        let title = match operation {
+            SequencerOperation::Rebase => "Rebase paused",
+            SequencerOperation::CherryPick => "Cherry-pick paused",
+            SequencerOperation::Revert => "Revert paused",
+        };
+        let has_conflicts = repository
+            .status()
+            .any(|entry| entry.status.is_conflicted());
+
+        Some(
+            h_flex()
+                .items_center()
+                .py_2()
+                .px(px(8.))
+                .gap_1p5()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    Icon::new(IconName::Warning)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
+                .child(
+                    Label::new(if has_conflicts {
+                        format!("{title}: resolve and stage the conflicts to continue")
+                    } else {
+                        title.to_string()
+                    })
+                    .size(LabelSize::Small)
+                    .truncate(),
+                )
+                .child(div().flex_1())
+                .child(
+                    panel_button("Abort")
+                        .tooltip(Tooltip::for_action_title_in(
+                            format!("git {} --abort", operation.command()),
+                            &AbortOperation,
+                            &self.focus_handle,
+                        ))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.abort_operation(&AbortOperation, window, cx)
+                        })),
+                )
+                .child(
+                    panel_filled_button("Continue")
+                        .tooltip(Tooltip::for_action_title_in(
+                            format!("git {} --continue", operation.command()),
+                            &ContinueOperation,
+                            &self.focus_handle,
+                        ))
+                        .disabled(has_conflicts)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.continue_operation(&ContinueOperation, window, cx)
+                        })),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .py_2()
@@ -3749,9 +4071,11 @@ impl GitPanel {
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
+                .action("Stash File", git::StashFile.boxed_clone())
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
+                .action("View File History", git::FileHistory.boxed_clone())
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -4086,6 +4410,12 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::restore_tracked_files))
                     .on_action(cx.listener(Self::revert_selected))
                     .on_action(cx.listener(Self::clean_all))
+                    .on_action(cx.listener(Self::stash_all))
+                    .on_action(cx.listener(Self::stash_including_untracked))
+                    .on_action(cx.listener(Self::stash_selected))
+                    .on_action(cx.listener(Self::stash_pop))
+                    .on_action(cx.listener(Self::continue_operation))
+                    .on_action(cx.listener(Self::abort_operation))
                     .on_action(cx.listener(Self::generate_commit_message_action))
             })
             .on_action(cx.listener(Self::select_first))
@@ -4095,6 +4425,7 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::close_panel))
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
+            .on_action(cx.listener(Self::view_selected_file_history))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
@@ -4124,6 +4455,7 @@ impl Render for GitPanel {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.render_sequencer_operation(cx))
                     .children(self.render_footer(window, cx))
                     .when(self.amend_pending, |this| {
                         this.child(self.render_pending_amend(cx))
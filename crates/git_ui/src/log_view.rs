@@ -0,0 +1,796 @@
+use std::ops::Range;
+
+use editor::Editor;
+use git::repository::{LogEntry, LogOptions, RepoPath};
+use gpui::{
+    AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, PathBuilder, Pixels, Point, Render, ScrollStrategy, SharedString, Subscription,
+    Task, UniformListScrollHandle, WeakEntity, Window, canvas, fill, point, px, uniform_list,
+};
+use language::Point as TextPoint;
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::git_store::{Repository, RepositoryEvent};
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+};
+
+use crate::commit_view::CommitView;
+
+/// The number of commits loaded at a time.
+const PAGE_SIZE: usize = 500;
+/// Load the next page once the visible rows are this close to the last loaded one.
+const LOAD_MORE_THRESHOLD: usize = 100;
+const LANE_WIDTH: Pixels = px(14.);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::ViewHistory, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        LogView::open(repository, LogScope::Repository, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+        open_active_editor_history(false, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::LineHistory, window, cx| {
+        open_active_editor_history(true, workspace, window, cx);
+    });
+}
+
+/// Opens the history of the file of the active editor, or of its selected
+/// lines if `lines` is true.
+fn open_active_editor_history(
+    lines: bool,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, rows)) = editor.update(cx, |editor, cx| {
+        let selection = editor.selections.newest::<TextPoint>(cx);
+        let multi_buffer = editor.buffer().read(cx);
+        let (buffer, start, _) = multi_buffer.point_to_buffer_point(selection.start, cx)?;
+        let (_, end, _) = multi_buffer.point_to_buffer_point(selection.end, cx)?;
+        let end_row = if end.column == 0 && end.row > start.row {
+            end.row
+        } else {
+            end.row + 1
+        };
+        Some((buffer, start.row..end_row.max(start.row + 1)))
+    }) else {
+        return;
+    };
+    let buffer_id = buffer.read(cx).remote_id();
+    let Some((repository, path)) = workspace
+        .project()
+        .read(cx)
+        .git_store()
+        .read(cx)
+        .repository_and_path_for_buffer_id(buffer_id, cx)
+    else {
+        return;
+    };
+    if !lines {
+        LogView::open(repository, LogScope::File(path), workspace, window, cx);
+        return;
+    }
+
+    // Git reads the line range as lines of the committed file, so the rows of
+    // the buffer are mapped through its uncommitted changes first.
+    let uncommitted_diff = workspace.project().update(cx, |project, cx| {
+        project.open_uncommitted_diff(buffer.clone(), cx)
+    });
+    cx.spawn_in(window, async move |workspace, cx| {
+        let uncommitted_diff = uncommitted_diff.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let buffer_snapshot = buffer.read(cx).text_snapshot();
+            let diff = uncommitted_diff.read(cx);
+            let base_text = diff.base_text();
+            let hunk_rows = diff
+                .hunks(&buffer_snapshot, cx)
+                .map(|hunk| {
+                    let base_range = base_text.offset_to_point(hunk.diff_base_byte_range.start)
+                        ..base_text.offset_to_point(hunk.diff_base_byte_range.end);
+                    (row_range(hunk.range), row_range(base_range))
+                })
+                .collect::<Vec<_>>();
+            let rows = committed_rows(rows, &hunk_rows);
+            if rows.is_empty() {
+                workspace.show_error(&"The selected lines haven't been committed yet", cx);
+                return;
+            }
+            LogView::open(
+                repository,
+                LogScope::Lines { path, rows },
+                workspace,
+                window,
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The rows spanned by a range, including the last row unless the range ends at its start.
+fn row_range(range: Range<TextPoint>) -> Range<u32> {
+    let end_row = if range.end.column == 0 || range.is_empty() {
+        range.end.row
+    } else {
+        range.end.row + 1
+    };
+    range.start.row..end_row
+}
+
+/// Maps rows of a buffer to the rows of its committed text, given the buffer and committed rows
+/// of its uncommitted hunks, in order. A range starting or ending within a hunk is extended to
+/// all of the hunk's committed rows.
+fn committed_rows(rows: Range<u32>, hunk_rows: &[(Range<u32>, Range<u32>)]) -> Range<u32> {
+    let committed_row = |row: u32, is_end: bool| {
+        let mut delta = 0i64;
+        for (buffer_rows, committed_rows) in hunk_rows {
+            let is_within_hunk = if is_end {
+                buffer_rows.start < row && row <= buffer_rows.end
+            } else {
+                buffer_rows.start <= row && row < buffer_rows.end
+            };
+            if is_within_hunk {
+                return if is_end {
+                    committed_rows.end
+                } else {
+                    committed_rows.start
+                };
+            }
+            let is_before = if is_end {
+                buffer_rows.end < row
+            } else {
+                buffer_rows.end <= row
+            };
+            if !is_before {
+                break;
+            }
+            delta = committed_rows.end as i64 - buffer_rows.end as i64;
+        }
+        (row as i64 + delta).max(0) as u32
+    };
+    committed_row(rows.start, false)..committed_row(rows.end, true)
+}
+
+/// Which part of the repository's history a [`LogView`] lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogScope {
+    Repository,
+    /// The commits that changed a file, following it across renames.
+    File(RepoPath),
+    /// The commits that changed the given rows of a file.
+    Lines {
+        path: RepoPath,
+        rows: Range<u32>,
+    },
+}
+
+impl LogScope {
+    fn options(&self, skip: usize) -> LogOptions {
+        let (paths, row_range) = match self {
+            LogScope::Repository => (Vec::new(), None),
+            LogScope::File(path) => (vec![path.clone()], None),
+            LogScope::Lines { path, rows } => (vec![path.clone()], Some(rows.clone())),
+        };
+        LogOptions {
+            paths,
+            follow: true,
+            row_range,
+            revision_range: None,
+            skip,
+            limit: Some(PAGE_SIZE),
+        }
+    }
+
+    fn title(&self) -> SharedString {
+        match self {
+            LogScope::Repository => "History".into(),
+            LogScope::File(path) => format!("History: {}", file_name(path)).into(),
+            LogScope::Lines { path, rows } => format!(
+                "History: {}:{}-{}",
+                file_name(path),
+                rows.start + 1,
+                rows.end
+            )
+            .into(),
+        }
+    }
+}
+
+fn file_name(path: &RepoPath) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// A scrollable list of commits, alongside a graph of their ancestry.
+pub struct LogView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    scope: LogScope,
+    entries: Vec<LogEntry>,
+    graph: LogGraph,
+    selected_index: Option<usize>,
+    has_more: bool,
+    error: Option<SharedString>,
+    head_sha: Option<SharedString>,
+    load_task: Option<Task<()>>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl LogView {
+    pub fn open(
+        repository: Entity<Repository>,
+        scope: LogScope,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let repository_id = repository.read(cx).id;
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .filter_map(|item| item.downcast::<LogView>())
+            .find(|view| {
+                let view = view.read(cx);
+                view.repository.read(cx).id == repository_id && view.scope == scope
+            });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let log_view = cx.new(|cx| LogView::new(repository, scope, workspace_handle, cx));
+        workspace.add_item_to_active_pane(Box::new(log_view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        scope: LogScope,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscription = cx.subscribe(&repository, |this, repository, event, cx| {
+            if let RepositoryEvent::Updated { .. } = event {
+                let head_sha = repository
+                    .read(cx)
+                    .head_commit
+                    .as_ref()
+                    .map(|commit| commit.sha.clone());
+                if head_sha != this.head_sha {
+                    this.reload(cx);
+                }
+            }
+        });
+        let head_sha = repository
+            .read(cx)
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.clone());
+
+        let mut this = Self {
+            repository,
+            workspace,
+            scope,
+            entries: Vec::new(),
+            graph: LogGraph::default(),
+            selected_index: None,
+            has_more: true,
+            error: None,
+            head_sha,
+            load_task: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _subscription,
+        };
+        this.load_more(cx);
+        this
+    }
+
+    /// Reloads the history from the start, as `HEAD` has moved.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.head_sha = self
+            .repository
+            .read(cx)
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.clone());
+        self.entries.clear();
+        self.graph = LogGraph::default();
+        self.selected_index = None;
+        self.has_more = true;
+        self.error = None;
+        self.load_task = None;
+        self.load_more(cx);
+        cx.notify();
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.load_task.is_some() || !self.has_more {
+            return;
+        }
+        let options = self.scope.options(self.entries.len());
+        let page = self
+            .repository
+            .update(cx, |repository, _| repository.log(options));
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let page = page
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|page| page);
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                match page {
+                    Ok(page) => {
+                        this.has_more = page.len() == PAGE_SIZE;
+                        for entry in &page {
+                            this.graph.push(entry);
+                        }
+                        this.entries.extend(page);
+                        if this.selected_index.is_none() && !this.entries.is_empty() {
+                            this.selected_index = Some(0);
+                        }
+                    }
+                    Err(error) => {
+                        this.has_more = false;
+                        this.error = Some(error.to_string().trim().to_string().into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(self.entries.len() - 1, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_index) = self.selected_index {
+            if selected_index + 1 < self.entries.len() {
+                self.select_entry(selected_index + 1, cx);
+            }
+        } else if !self.entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_index) = self.selected_index {
+            self.select_entry(selected_index.saturating_sub(1), cx);
+        } else if !self.entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_index) = self.selected_index {
+            self.open_commit(selected_index, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        CommitView::open(
+            entry.summary(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn render_entry(&self, ix: usize, graph_width: Pixels, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let row = self.graph.rows[ix].clone();
+        let accents = cx.theme().accents().clone();
+
+        let commit_time = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+        let tooltip_text: SharedString = format!(
+            "{}\n{} <{}>",
+            entry.subject, entry.author_name, entry.author_email
+        )
+        .into();
+
+        h_flex()
+            .id(("log-entry", ix))
+            .h_6()
+            .w_full()
+            .pr_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == Some(ix), |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, _| paint_graph_row(&row, bounds, &accents, window),
+                )
+                .flex_none()
+                .w(graph_width)
+                .h_full(),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    Label::new(entry.subject.clone())
+                        .size(LabelSize::Small)
+                        .single_line()
+                        .truncate(),
+                ),
+            )
+            .child(
+                div().flex_none().w_32().overflow_hidden().child(
+                    Label::new(entry.author_name.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .single_line()
+                        .truncate(),
+                ),
+            )
+            .child(
+                div().flex_none().w_24().child(
+                    Label::new(formatted_time)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .single_line(),
+                ),
+            )
+            .child(
+                Label::new(entry.short_sha())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .tooltip(Tooltip::text(tooltip_text))
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.selected_index = Some(ix);
+                cx.notify();
+                if event.down.click_count > 1 {
+                    this.open_commit(ix, window, cx);
+                }
+            }))
+            .into_any_element()
+    }
+}
+
+fn paint_graph_row(
+    row: &GraphRow,
+    bounds: gpui::Bounds<Pixels>,
+    accents: &theme::AccentColors,
+    window: &mut Window,
+) {
+    let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+    let top = bounds.top();
+    let middle = bounds.center().y;
+    let bottom = bounds.bottom();
+
+    let mut paint_line = |from: Point<Pixels>, to: Point<Pixels>, lane: usize| {
+        let mut builder = PathBuilder::stroke(px(1.5));
+        builder.move_to(from);
+        builder.line_to(to);
+        if let Ok(path) = builder.build() {
+            window.paint_path(path, accents.color_for_index(lane as u32));
+        }
+    };
+    for &(from, to) in &row.upper {
+        paint_line(point(lane_x(from), top), point(lane_x(to), middle), from);
+    }
+    for &(from, to) in &row.lower {
+        paint_line(point(lane_x(from), middle), point(lane_x(to), bottom), to);
+    }
+
+    let radius = px(3.5);
+    let center = point(lane_x(row.lane), middle);
+    window.paint_quad(
+        fill(
+            gpui::Bounds::new(
+                point(center.x - radius, center.y - radius),
+                gpui::size(radius * 2., radius * 2.),
+            ),
+            accents.color_for_index(row.lane as u32),
+        )
+        .corner_radii(radius),
+    );
+}
+
+/// The lanes of a commit graph, assigned one commit at a time from the most
+/// recent commit to the oldest one.
+#[derive(Default)]
+struct LogGraph {
+    /// The commit each lane is waiting for, at the bottom of the last row.
+    lanes: Vec<Option<SharedString>>,
+    rows: Vec<GraphRow>,
+    /// The number of lanes of the widest row.
+    width: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GraphRow {
+    /// The lane of the row's commit.
+    lane: usize,
+    /// Lines from a lane at the top of the row to a lane at its middle.
+    upper: Vec<(usize, usize)>,
+    /// Lines from a lane at the middle of the row to a lane at its bottom.
+    lower: Vec<(usize, usize)>,
+}
+
+impl LogGraph {
+    fn push(&mut self, entry: &LogEntry) {
+        let mut row = GraphRow::default();
+        let expected = Some(&entry.sha);
+        row.lane = match self.lanes.iter().position(|lane| lane.as_ref() == expected) {
+            Some(lane) => lane,
+            None => self.free_lane(),
+        };
+
+        for (ix, lane) in self.lanes.iter_mut().enumerate() {
+            if lane.is_none() {
+                continue;
+            }
+            if lane.as_ref() == expected {
+                row.upper.push((ix, row.lane));
+                *lane = None;
+            } else {
+                row.upper.push((ix, ix));
+                row.lower.push((ix, ix));
+            }
+        }
+
+        for (parent_ix, parent_sha) in entry.parent_shas.iter().enumerate() {
+            let existing = self
+                .lanes
+                .iter()
+                .position(|lane| lane.as_ref() == Some(parent_sha));
+            let target = match existing {
+                Some(lane) => lane,
+                None => {
+                    let lane = if parent_ix == 0 && self.lanes[row.lane].is_none() {
+                        row.lane
+                    } else {
+                        self.free_lane()
+                    };
+                    self.lanes[lane] = Some(parent_sha.clone());
+                    lane
+                }
+            };
+            row.lower.push((row.lane, target));
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+        let row_width = row
+            .upper
+            .iter()
+            .chain(&row.lower)
+            .flat_map(|&(from, to)| [from, to])
+            .chain([row.lane])
+            .max()
+            .map_or(0, |lane| lane + 1);
+        self.width = self.width.max(row_width);
+        self.rows.push(row);
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for LogView {}
+
+impl Focusable for LogView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LogView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new(self.scope.title())
+            .color(params.text_color())
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        match &self.scope {
+            LogScope::Repository => None,
+            LogScope::File(path) | LogScope::Lines { path, .. } => {
+                Some(path.to_string_lossy().to_string().into())
+            }
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git Log View Opened")
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+}
+
+impl Render for LogView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        let graph_width = LANE_WIDTH * self.graph.width.max(1) as f32;
+
+        v_flex()
+            .key_context("GitLog menu")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .map(|this| {
+                if entry_count > 0 {
+                    this.child(
+                        uniform_list(cx.entity(), "log-entries", entry_count, {
+                            move |this, range, _window, cx| {
+                                if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                                    this.load_more(cx);
+                                }
+                                range
+                                    .map(|ix| this.render_entry(ix, graph_width, cx))
+                                    .collect()
+                            }
+                        })
+                        .track_scroll(self.scroll_handle.clone())
+                        .size_full()
+                        .py_1(),
+                    )
+                } else {
+                    let message: SharedString = if let Some(error) = &self.error {
+                        error.clone()
+                    } else if self.load_task.is_some() {
+                        "Loading history…".into()
+                    } else {
+                        "No commits".into()
+                    };
+                    this.items_center().justify_center().child(
+                        Label::new(message)
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sha: &str, parent_shas: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parent_shas: parent_shas
+                .iter()
+                .map(|sha| SharedString::from(sha.to_string()))
+                .collect(),
+            subject: SharedString::default(),
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            commit_timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_committed_rows() {
+        // Rows 2..4 were added, row 6 was changed into rows 8..10, and row 10 was deleted.
+        let hunk_rows = [(2..4, 2..2), (8..10, 6..7), (13..13, 10..11)];
+
+        assert_eq!(committed_rows(0..2, &hunk_rows), 0..2);
+        assert_eq!(committed_rows(4..6, &hunk_rows), 2..4);
+        assert_eq!(committed_rows(1..5, &hunk_rows), 1..3);
+        assert_eq!(committed_rows(2..4, &hunk_rows), 2..2);
+        assert_eq!(committed_rows(9..12, &hunk_rows), 6..9);
+        assert_eq!(committed_rows(12..13, &hunk_rows), 9..10);
+        assert_eq!(committed_rows(12..14, &hunk_rows), 9..12);
+    }
+
+    #[test]
+    fn test_log_graph_lanes() {
+        // d   merge of c into b
+        // |\
+        // b |
+        // | c
+        // |/
+        // a
+        let mut graph = LogGraph::default();
+        for entry in [
+            entry("d", &["b", "c"]),
+            entry("b", &["a"]),
+            entry("c", &["a"]),
+            entry("a", &[]),
+        ] {
+            graph.push(&entry);
+        }
+
+        assert_eq!(
+            graph.rows,
+            vec![
+                GraphRow {
+                    lane: 0,
+                    upper: vec![],
+                    lower: vec![(0, 0), (0, 1)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper: vec![(0, 0), (1, 1)],
+                    lower: vec![(1, 1), (0, 0)],
+                },
+                GraphRow {
+                    lane: 1,
+                    upper: vec![(0, 0), (1, 1)],
+                    lower: vec![(0, 0), (1, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper: vec![(0, 0)],
+                    lower: vec![],
+                },
+            ]
+        );
+        assert_eq!(graph.width, 2);
+        assert!(graph.lanes.is_empty());
+    }
+}
@@ -0,0 +1,504 @@
+use editor::{Editor, EditorEvent};
+use git::SHORT_SHA_LENGTH;
+use git::repository::{LogOptions, RebaseAction, RebaseTodoEntry};
+use gpui::{
+    App, ClickEvent, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyContext, ScrollStrategy, SharedString, Subscription, Task, UniformListScrollHandle, Window,
+    actions, uniform_list,
+};
+use menu::{Cancel, Confirm, SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use ui::{KeyBinding, Tooltip, prelude::*};
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    git_rebase,
+    [
+        MoveUp,
+        MoveDown,
+        PickCommit,
+        RewordCommit,
+        SquashCommit,
+        FixupCommit,
+        DropCommit,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::RebaseInteractive, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        RebaseEditor::toggle(repository, "@{upstream}".into(), workspace, window, cx);
+    });
+}
+
+/// Edits the todo list of an interactive rebase of the commits following
+/// `onto`, from the oldest to the newest one, like `git rebase --interactive`.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    onto: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    selected_index: usize,
+    /// The entry whose message is shown in the message editor.
+    message_entry: Option<usize>,
+    message_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+    _message_task: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl RebaseEditor {
+    pub fn toggle(
+        repository: Entity<Repository>,
+        onto: SharedString,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            RebaseEditor::new(repository, onto, window, cx)
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        onto: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(8, window, cx);
+            editor.set_placeholder_text("Commit message", cx);
+            editor
+        });
+        let _subscription = cx.subscribe(&message_editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.save_message(cx);
+            }
+        });
+
+        let log = repository.update(cx, |repository, _| {
+            repository.log(LogOptions {
+                revision_range: Some(format!("{onto}..HEAD")),
+                ..LogOptions::default()
+            })
+        });
+        let load_task = cx.spawn(async move |this, cx| {
+            let result = async { log.await? }.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    // Like Git, leave out merge commits, and replay the others
+                    // from the oldest to the newest.
+                    Ok(log) => {
+                        this.entries = log
+                            .into_iter()
+                            .rev()
+                            .filter(|entry| entry.parent_shas.len() <= 1)
+                            .map(|entry| RebaseTodoEntry {
+                                action: RebaseAction::Pick,
+                                sha: entry.sha,
+                                subject: entry.subject,
+                                message: None,
+                            })
+                            .collect();
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                this.load_task.take();
+                cx.notify();
+            })
+            .ok();
+        });
+
+        Self {
+            repository,
+            onto,
+            entries: Vec::new(),
+            selected_index: 0,
+            message_entry: None,
+            message_editor,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            error: None,
+            load_task: Some(load_task),
+            _message_task: None,
+            _subscription,
+        }
+    }
+
+    fn dispatch_context(&self, window: &Window, cx: &Context<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("RebaseEditor");
+        if self.focus_handle.contains_focused(window, cx)
+            && !self.message_editor.focus_handle(cx).is_focused(window)
+        {
+            dispatch_context.add("menu");
+        }
+        dispatch_context
+    }
+
+    fn select(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_index = ix;
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        self.show_message(window, cx);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.select(self.selected_index + 1, window, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index > 0 {
+            self.select(self.selected_index - 1, window, cx);
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index > 0 && self.selected_index < self.entries.len() {
+            self.entries
+                .swap(self.selected_index - 1, self.selected_index);
+            self.select(self.selected_index - 1, window, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.entries
+                .swap(self.selected_index, self.selected_index + 1);
+            self.select(self.selected_index + 1, window, cx);
+        }
+    }
+
+    fn set_action(&mut self, action: RebaseAction, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get_mut(self.selected_index) else {
+            return;
+        };
+        entry.action = action;
+        self.show_message(window, cx);
+        cx.notify();
+    }
+
+    fn cycle_action(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let position = RebaseAction::ALL
+            .iter()
+            .position(|action| *action == entry.action)
+            .unwrap_or_default();
+        let action = RebaseAction::ALL[(position + 1) % RebaseAction::ALL.len()];
+        self.selected_index = ix;
+        self.set_action(action, window, cx);
+    }
+
+    /// Shows the message of the selected entry in the message editor when it
+    /// is reworded, loading the current message of the commit if needed.
+    fn show_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index;
+        let Some(entry) = self
+            .entries
+            .get(ix)
+            .filter(|entry| entry.action == RebaseAction::Reword)
+        else {
+            self.message_entry = None;
+            return;
+        };
+        if self.message_entry == Some(ix) {
+            return;
+        }
+
+        // Clear the entry before setting the text, so that it doesn't receive
+        // the message of the previous one.
+        self.message_entry = None;
+        if let Some(message) = entry.message.clone() {
+            self.message_editor
+                .update(cx, |editor, cx| editor.set_text(message, window, cx));
+            self.message_entry = Some(ix);
+            return;
+        }
+
+        let sha = entry.sha.clone();
+        let show = self
+            .repository
+            .update(cx, |repository, _| repository.show(sha.to_string()));
+        self._message_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(details)) = show.await else {
+                return;
+            };
+            this.update_in(cx, |this, window, cx| {
+                let Some(ix) = this.entries.iter().position(|entry| entry.sha == sha) else {
+                    return;
+                };
+                let message = details.message.trim_end().to_string();
+                this.entries[ix].message = Some(message.clone());
+                if this.selected_index == ix && this.entries[ix].action == RebaseAction::Reword {
+                    this.message_editor
+                        .update(cx, |editor, cx| editor.set_text(message, window, cx));
+                    this.message_entry = Some(ix);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn save_message(&mut self, cx: &mut Context<Self>) {
+        let Some(ix) = self.message_entry else {
+            return;
+        };
+        let message = self.message_editor.read(cx).text(cx);
+        if let Some(entry) = self.entries.get_mut(ix) {
+            entry.message = Some(message);
+        }
+    }
+
+    fn start(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let onto = self.onto.to_string();
+        let todo = self.entries.clone();
+        let rebase = self.repository.update(cx, |repository, _| {
+            repository.rebase_interactive(Some(onto), todo)
+        });
+        cx.spawn(async move |_, _| {
+            rebase.await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to rebase", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let dropped = entry.action == RebaseAction::Drop;
+        let action_color = match entry.action {
+            RebaseAction::Pick => Color::Default,
+            RebaseAction::Reword => Color::Accent,
+            RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+            RebaseAction::Drop => Color::Deleted,
+        };
+        let subject = entry
+            .message
+            .as_deref()
+            .filter(|_| entry.action == RebaseAction::Reword)
+            .and_then(|message| message.lines().next())
+            .map(|line| SharedString::from(line.to_string()))
+            .unwrap_or_else(|| entry.subject.clone());
+
+        h_flex()
+            .id(("rebase-entry", ix))
+            .h_7()
+            .w_full()
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == ix, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                window.focus(&this.focus_handle);
+                this.select(ix, window, cx);
+            }))
+            .child(
+                Button::new(("rebase-action", ix), entry.action.command())
+                    .label_size(LabelSize::Small)
+                    .color(action_color)
+                    .width(rems(4.5).into())
+                    .tooltip(Tooltip::text("Change Action"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.cycle_action(ix, window, cx);
+                    })),
+            )
+            .child(
+                Label::new(short_sha(&entry.sha))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    // Indent the commits folded into the previous one.
+                    .when(entry.action.melds(), |this| this.pl_4())
+                    .child(
+                        Label::new(subject)
+                            .size(LabelSize::Small)
+                            .when(dropped, |label| label.strikethrough().color(Color::Muted))
+                            .single_line()
+                            .truncate(),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_footer(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_between()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new("Click an action or press p, r, s, f or d to change it")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        Button::new("cancel-rebase", "Cancel")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(
+                                &Cancel,
+                                &focus_handle,
+                                window,
+                                cx,
+                            ))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.cancel(&Cancel, window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("start-rebase", "Rebase")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(self.entries.is_empty())
+                            .key_binding(KeyBinding::for_action_in(
+                                &Confirm,
+                                &focus_handle,
+                                window,
+                                cx,
+                            ))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.start(&Confirm, window, cx)),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl ModalView for RebaseEditor {}
+impl EventEmitter<DismissEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        let show_message_editor = self
+            .entries
+            .get(self.selected_index)
+            .is_some_and(|entry| entry.action == RebaseAction::Reword);
+
+        v_flex()
+            .key_context(self.dispatch_context(window, cx))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::start))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                this.set_action(RebaseAction::Pick, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                this.set_action(RebaseAction::Reword, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                this.set_action(RebaseAction::Squash, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                this.set_action(RebaseAction::Fixup, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                this.set_action(RebaseAction::Drop, window, cx)
+            }))
+            .w(rems(40.))
+            .elevation_3(cx)
+            .overflow_hidden()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1p5()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new("Rebase onto"))
+                    .child(Label::new(short_sha(&self.onto)).buffer_font(cx)),
+            )
+            .map(|this| {
+                if entry_count > 0 {
+                    this.child(
+                        uniform_list(cx.entity(), "rebase-entries", entry_count, {
+                            move |this, range, _window, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }
+                        })
+                        .track_scroll(self.scroll_handle.clone())
+                        .h(rems(20.))
+                        .py_1(),
+                    )
+                } else {
+                    let message: SharedString = if let Some(error) = &self.error {
+                        error.clone()
+                    } else if self.load_task.is_some() {
+                        "Loading commits…".into()
+                    } else {
+                        "No commits to rebase".into()
+                    };
+                    this.child(
+                        h_flex().p_4().justify_center().child(
+                            Label::new(message)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    )
+                }
+            })
+            .when(show_message_editor, |this| {
+                this.child(
+                    div()
+                        .p_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .bg(cx.theme().colors().editor_background)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .child(self.render_footer(window, cx))
+    }
+}
+
+/// Shortens a full SHA, leaving other revisions, such as `@{upstream}`, as is.
+fn short_sha(revision: &str) -> SharedString {
+    if revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit()) {
+        revision[..SHORT_SHA_LENGTH].to_string().into()
+    } else {
+        revision.to_string().into()
+    }
+}
@@ -1,11 +1,16 @@
+use git::repository::{GitWorktree, Submodule, SubmoduleStatus};
 use gpui::{
-    AnyElement, App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity,
+    Action, AnyElement, App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    PromptLevel, Task, WeakEntity,
 };
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate};
 use project::{Project, git_store::Repository};
+use std::path::PathBuf;
 use std::sync::Arc;
 use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
 pub fn register(workspace: &mut Workspace) {
@@ -24,6 +29,28 @@ pub fn open(
     })
 }
 
+/// Opens a worktree created outside of the project. Local projects get a new window, while
+/// remote ones add the worktree to the current project, as there is no way to open another
+/// connection from here.
+pub(crate) fn open_worktree(
+    path: PathBuf,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<()>> {
+    if workspace.project().read(cx).is_local() {
+        workspace.open_workspace_for_paths(false, vec![path], window, cx)
+    } else {
+        let create = workspace.project().update(cx, |project, cx| {
+            project.find_or_create_worktree(path, true, cx)
+        });
+        cx.background_spawn(async move {
+            create.await?;
+            Ok(())
+        })
+    }
+}
+
 pub struct RepositorySelector {
     width: Rems,
     picker: Entity<Picker<RepositorySelectorDelegate>>,
@@ -44,7 +71,7 @@ impl RepositorySelector {
                 .cloned()
                 .collect::<Vec<_>>()
         });
-        let filtered_repositories = repository_entries.clone();
+        let active_repository = project_handle.read(cx).active_repository(cx);
 
         let widest_item_ix = repository_entries.iter().position_max_by(|a, b| {
             a.read(cx)
@@ -53,12 +80,17 @@ impl RepositorySelector {
                 .cmp(&b.read(cx).display_name().len())
         });
 
-        let delegate = RepositorySelectorDelegate {
+        let mut delegate = RepositorySelectorDelegate {
             repository_selector: cx.entity().downgrade(),
-            repository_entries,
-            filtered_repositories,
+            workspace: window.root::<Workspace>().flatten().map(|w| w.downgrade()),
+            active_repository: active_repository.clone(),
+            repository_entries: Vec::new(),
+            worktrees: Vec::new(),
+            submodules: Vec::new(),
+            filtered_entries: Vec::new(),
             selected_index: 0,
         };
+        delegate.update_repository_entries(repository_entries, cx);
 
         let picker = cx.new(|cx| {
             Picker::nonsearchable_uniform_list(delegate, window, cx)
@@ -66,10 +98,31 @@ impl RepositorySelector {
                 .max_height(Some(rems(20.).into()))
         });
 
+        // Collab guests can't open paths on the host, so only list worktrees and submodules
+        // for projects whose file system we can reach.
+        if let Some(repository) = active_repository
+            && !project_handle.read(cx).is_via_collab()
+        {
+            let (worktrees, submodules) = repository.update(cx, |repository, _| {
+                (repository.worktrees(), repository.submodules())
+            });
+            let picker = picker.downgrade();
+            cx.spawn_in(window, async move |_, cx| {
+                let worktrees = worktrees.await?.log_err().unwrap_or_default();
+                let submodules = submodules.await?.log_err().unwrap_or_default();
+                picker.update_in(cx, |picker, window, cx| {
+                    picker.delegate.worktrees = worktrees;
+                    picker.delegate.submodules = submodules;
+                    picker.delegate.rebuild_entries(cx);
+                    picker.refresh(window, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+
         RepositorySelector { picker, width }
     }
 }
-
 //pub(crate) fn filtered_repository_entries(
 //    git_store: &GitStore,
 //    cx: &App,
@@ -117,18 +170,134 @@ impl Render for RepositorySelector {
 
 impl ModalView for RepositorySelector {}
 
+#[derive(Clone)]
+enum RepositoryEntry {
+    Repository(Entity<Repository>),
+    Worktree(GitWorktree),
+    Submodule(Submodule),
+}
+
 pub struct RepositorySelectorDelegate {
     repository_selector: WeakEntity<RepositorySelector>,
+    workspace: Option<WeakEntity<Workspace>>,
+    active_repository: Option<Entity<Repository>>,
     repository_entries: Vec<Entity<Repository>>,
-    filtered_repositories: Vec<Entity<Repository>>,
+    worktrees: Vec<GitWorktree>,
+    submodules: Vec<Submodule>,
+    filtered_entries: Vec<RepositoryEntry>,
     selected_index: usize,
 }
 
 impl RepositorySelectorDelegate {
-    pub fn update_repository_entries(&mut self, all_repositories: Vec<Entity<Repository>>) {
-        self.repository_entries = all_repositories.clone();
-        self.filtered_repositories = all_repositories;
+    pub fn update_repository_entries(
+        &mut self,
+        all_repositories: Vec<Entity<Repository>>,
+        cx: &App,
+    ) {
+        self.repository_entries = all_repositories;
         self.selected_index = 0;
+        self.rebuild_entries(cx);
+    }
+
+    /// Lists the open repositories, followed by the worktrees and submodules of the active
+    /// repository that aren't open in the project already.
+    fn rebuild_entries(&mut self, cx: &App) {
+        let open_paths = self
+            .repository_entries
+            .iter()
+            .map(|repository| repository.read(cx).work_directory_abs_path.clone())
+            .collect::<Vec<_>>();
+        let is_open = |path: &std::path::Path| open_paths.iter().any(|open| open.as_ref() == path);
+        let submodule_root = self
+            .active_repository
+            .as_ref()
+            .map(|repository| repository.read(cx).work_directory_abs_path.clone());
+
+        let mut entries = self
+            .repository_entries
+            .iter()
+            .cloned()
+            .map(RepositoryEntry::Repository)
+            .collect::<Vec<_>>();
+        entries.extend(
+            self.worktrees
+                .iter()
+                .filter(|worktree| !is_open(&worktree.path))
+                .cloned()
+                .map(RepositoryEntry::Worktree),
+        );
+        if let Some(submodule_root) = submodule_root {
+            entries.extend(
+                self.submodules
+                    .iter()
+                    .filter(|submodule| !is_open(&submodule_root.join(&submodule.path)))
+                    .cloned()
+                    .map(RepositoryEntry::Submodule),
+            );
+        }
+        self.filtered_entries = entries;
+        self.selected_index = self
+            .selected_index
+            .min(self.filtered_entries.len().saturating_sub(1));
+    }
+
+    fn open_selected_worktree(
+        &self,
+        worktree: &GitWorktree,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(workspace) = self.workspace.as_ref().and_then(|w| w.upgrade()) else {
+            return;
+        };
+        let path = worktree.path.clone();
+        workspace
+            .update(cx, |workspace, cx| {
+                open_worktree(path, workspace, window, cx)
+            })
+            .detach_and_prompt_err("Failed to open worktree", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    }
+
+    fn remove_selected_worktree(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(RepositoryEntry::Worktree(worktree)) =
+            self.filtered_entries.get(self.selected_index).cloned()
+        else {
+            return;
+        };
+        let Some(repository) = self.active_repository.clone() else {
+            return;
+        };
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!("Remove worktree {}?", worktree.path.display()),
+            Some("The worktree directory will be deleted. Its branch is kept."),
+            &["Remove", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |picker, cx| {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            repository
+                .update(cx, |repository, _| {
+                    repository.remove_worktree(worktree.path.clone(), false)
+                })?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker
+                    .delegate
+                    .worktrees
+                    .retain(|existing| existing.path != worktree.path);
+                picker.delegate.rebuild_entries(cx);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
     }
 }
 
@@ -136,7 +305,7 @@ impl PickerDelegate for RepositorySelectorDelegate {
     type ListItem = ListItem;
 
     fn match_count(&self) -> usize {
-        self.filtered_repositories.len()
+        self.filtered_entries.len()
     }
 
     fn selected_index(&self) -> usize {
@@ -149,7 +318,7 @@ impl PickerDelegate for RepositorySelectorDelegate {
         _window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) {
-        self.selected_index = ix.min(self.filtered_repositories.len().saturating_sub(1));
+        self.selected_index = ix.min(self.filtered_entries.len().saturating_sub(1));
         cx.notify();
     }
 
@@ -159,45 +328,30 @@ impl PickerDelegate for RepositorySelectorDelegate {
 
     fn update_matches(
         &mut self,
-        query: String,
-        window: &mut Window,
+        _query: String,
+        _window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Task<()> {
-        let all_repositories = self.repository_entries.clone();
-
-        cx.spawn_in(window, async move |this, cx| {
-            let filtered_repositories = cx
-                .background_spawn(async move {
-                    if query.is_empty() {
-                        all_repositories
-                    } else {
-                        all_repositories
-                            .into_iter()
-                            .filter(|_repo_info| {
-                                // TODO: Implement repository filtering logic
-                                true
-                            })
-                            .collect()
-                    }
-                })
-                .await;
-
-            this.update_in(cx, |this, window, cx| {
-                this.delegate.filtered_repositories = filtered_repositories;
-                this.delegate.set_selected_index(0, window, cx);
-                cx.notify();
-            })
-            .ok();
-        })
+        self.rebuild_entries(cx);
+        cx.notify();
+        Task::ready(())
     }
 
     fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
-        let Some(selected_repo) = self.filtered_repositories.get(self.selected_index) else {
+        let Some(entry) = self.filtered_entries.get(self.selected_index).cloned() else {
             return;
         };
-        selected_repo.update(cx, |selected_repo, cx| {
-            selected_repo.set_as_active_repository(cx)
-        });
+        match entry {
+            RepositoryEntry::Repository(repository) => {
+                repository.update(cx, |repository, cx| repository.set_as_active_repository(cx));
+            }
+            RepositoryEntry::Worktree(worktree) => {
+                self.open_selected_worktree(&worktree, window, cx);
+            }
+            RepositoryEntry::Submodule(_) => {
+                window.dispatch_action(git::SyncSubmodules.boxed_clone(), cx);
+            }
+        }
         self.dismissed(window, cx);
     }
 
@@ -223,14 +377,114 @@ impl PickerDelegate for RepositorySelectorDelegate {
         _window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Option<Self::ListItem> {
-        let repo_info = self.filtered_repositories.get(ix)?;
-        let display_name = repo_info.read(cx).display_name();
+        let (icon, name, detail): (_, SharedString, Option<SharedString>) =
+            match self.filtered_entries.get(ix)? {
+                RepositoryEntry::Repository(repository) => {
+                    return Some(
+                        ListItem::new(ix)
+                            .inset(true)
+                            .spacing(ListItemSpacing::Sparse)
+                            .toggle_state(selected)
+                            .child(Label::new(repository.read(cx).display_name())),
+                    );
+                }
+                RepositoryEntry::Worktree(worktree) => {
+                    let name = worktree
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| worktree.path.display().to_string());
+                    let detail = if worktree.is_main {
+                        "main worktree".into()
+                    } else {
+                        worktree.branch.clone().unwrap_or_else(|| "detached".into())
+                    };
+                    (IconName::GitBranch, name.into(), Some(detail))
+                }
+                RepositoryEntry::Submodule(submodule) => {
+                    let detail = match submodule.status {
+                        SubmoduleStatus::Uninitialized => Some("not initialized".into()),
+                        SubmoduleStatus::UpToDate => None,
+                        SubmoduleStatus::Modified => Some("modified".into()),
+                        SubmoduleStatus::Conflicted => Some("conflicted".into()),
+                    };
+                    (IconName::Folder, submodule.path.to_string().into(), detail)
+                }
+            };
         Some(
             ListItem::new(ix)
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
-                .child(Label::new(display_name)),
+                .start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(Label::new(name).truncate())
+                        .children(detail.map(|detail| {
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                        })),
+                ),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.workspace.is_none() || self.worktrees.is_empty() {
+            return None;
+        }
+        let selected_linked_worktree = matches!(
+            self.filtered_entries.get(self.selected_index),
+            Some(RepositoryEntry::Worktree(worktree)) if !worktree.is_main
+        );
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    h_flex()
+                        .gap_0p5()
+                        .child(
+                            Button::new("add-worktree", "Add Worktree…")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|picker, _, window, cx| {
+                                    window.dispatch_action(git::AddWorktree.boxed_clone(), cx);
+                                    picker.delegate.dismissed(window, cx);
+                                })),
+                        )
+                        .when(!self.submodules.is_empty(), |this| {
+                            this.child(
+                                Button::new("sync-submodules", "Sync Submodules")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|picker, _, window, cx| {
+                                        window
+                                            .dispatch_action(git::SyncSubmodules.boxed_clone(), cx);
+                                        picker.delegate.dismissed(window, cx);
+                                    })),
+                            )
+                        }),
+                )
+                .when(selected_linked_worktree, |this| {
+                    this.child(
+                        Button::new("remove-worktree", "Remove")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|picker, _, window, cx| {
+                                picker.delegate.remove_selected_worktree(window, cx)
+                            })),
+                    )
+                })
+                .into_any_element(),
         )
     }
 }
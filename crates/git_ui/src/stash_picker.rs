@@ -0,0 +1,427 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::StashEntry;
+use gpui::{
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, PromptLevel, Render, SharedString, Styled,
+    Subscription, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::ViewStash,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        StashList::new(repository, workspace_handle, rems(34.), window, cx)
+    })
+}
+
+pub struct StashList {
+    width: Rems,
+    pub picker: Entity<Picker<StashListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = StashListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for StashList {}
+impl EventEmitter<DismissEvent> for StashList {}
+
+impl Focusable for StashList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(self.width)
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StashListEntry {
+    stash: StashEntry,
+    positions: Vec<usize>,
+}
+
+pub struct StashListDelegate {
+    matches: Vec<StashListEntry>,
+    all_stashes: Option<Vec<StashEntry>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl StashListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: vec![],
+            all_stashes: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    /// Fetches the entries of the stash and refreshes the matches.
+    fn reload(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let stash_list_request = self
+            .repo
+            .clone()
+            .map(|repository| repository.update(cx, |repository, _| repository.stash_list()));
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let all_stashes = stash_list_request
+                .context("No active repository")?
+                .await??;
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_stashes = Some(all_stashes);
+                picker.refresh(window, cx);
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn selected_stash(&self) -> Option<StashEntry> {
+        self.matches
+            .get(self.selected_index)
+            .map(|entry| entry.stash.clone())
+    }
+
+    fn view_selected(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(stash), Some(repo)) = (self.selected_stash(), self.repo.as_ref()) else {
+            return;
+        };
+        CommitView::open_stash(stash, repo.downgrade(), self.workspace.clone(), window, cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn drop_selected(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(stash), Some(repo)) = (self.selected_stash(), self.repo.clone()) else {
+            return;
+        };
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!("Drop {}?", stash.name()),
+            Some(&format!(
+                "The changes in \"{}\" will be lost.",
+                stash.message
+            )),
+            &["Drop", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |picker, cx| {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            repo.update(cx, |repo, _| {
+                repo.stash_drop(stash.index, Some(stash.sha.to_string()))
+            })?
+            .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to drop stash", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn restore_selected(&self, pop: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (Some(stash), Some(repo)) = (self.selected_stash(), self.repo.clone()) else {
+            return;
+        };
+        let prompt_title = if pop {
+            "Failed to pop stash"
+        } else {
+            "Failed to apply stash"
+        };
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| {
+                if pop {
+                    repo.stash_pop(stash.index, Some(stash.sha.to_string()))
+                } else {
+                    repo.stash_apply(stash.index, Some(stash.sha.to_string()))
+                }
+            })?
+            .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(prompt_title, window, cx, |e, _, _| Some(e.to_string()));
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for StashListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select stash…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_stashes) = self.all_stashes.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<StashListEntry> = if query.is_empty() {
+                all_stashes
+                    .into_iter()
+                    .map(|stash| StashListEntry {
+                        stash,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_stashes
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, stash)| StringMatchCandidate::new(ix, &stash.message))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| StashListEntry {
+                    stash: all_stashes[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.restore_selected(!secondary, window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+
+        let stash_time = OffsetDateTime::from_unix_timestamp(entry.stash.timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            stash_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+
+        Some(
+            ListItem::new(SharedString::from(format!("stash-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .flex_shrink()
+                                .overflow_x_hidden()
+                                .gap_2()
+                                .justify_between()
+                                .child(
+                                    div().flex_shrink().overflow_x_hidden().child(
+                                        HighlightedLabel::new(
+                                            entry.stash.message.clone(),
+                                            entry.positions.clone(),
+                                        )
+                                        .truncate(),
+                                    ),
+                                )
+                                .child(
+                                    Label::new(formatted_time)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                        .child(
+                            Label::new(entry.stash.name())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.all_stashes.as_ref().is_some_and(Vec::is_empty) {
+            Some("No stash entries".into())
+        } else {
+            None
+        }
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    h_flex()
+                        .gap_0p5()
+                        .child(
+                            Button::new("view-stash", "View Changes")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|picker, _, window, cx| {
+                                    picker.delegate.view_selected(window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("drop-stash", "Drop")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|picker, _, window, cx| {
+                                    picker.delegate.drop_selected(window, cx)
+                                })),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .gap_0p5()
+                        .child(
+                            Button::new("apply-stash", "Apply")
+                                .label_size(LabelSize::Small)
+                                .key_binding(KeyBinding::for_action(
+                                    &menu::SecondaryConfirm,
+                                    window,
+                                    cx,
+                                ))
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                                }),
+                        )
+                        .child(
+                            Button::new("pop-stash", "Pop")
+                                .label_size(LabelSize::Small)
+                                .key_binding(KeyBinding::for_action(&menu::Confirm, window, cx))
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                                }),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -0,0 +1,190 @@
+use editor::Editor;
+use git::SHORT_SHA_LENGTH;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, SharedString, Window,
+};
+use menu::{Cancel, Confirm};
+use project::git_store::Repository;
+use ui::{KeyBinding, prelude::*};
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::CreateTag, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        CreateTagModal::toggle(repository, None, None, workspace, window, cx);
+    });
+}
+
+/// Creates a tag on the `target` commit, or on HEAD when there is none. The tag is
+/// annotated when a message is entered, and lightweight otherwise.
+pub struct CreateTagModal {
+    repository: Entity<Repository>,
+    target: Option<SharedString>,
+    name_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+}
+
+impl CreateTagModal {
+    pub fn toggle(
+        repository: Entity<Repository>,
+        target: Option<SharedString>,
+        name: Option<SharedString>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            CreateTagModal::new(repository, target, name, window, cx)
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        target: Option<SharedString>,
+        name: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag name", cx);
+            if let Some(name) = name {
+                editor.set_text(name, window, cx);
+            }
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(6, window, cx);
+            editor.set_placeholder_text("Message (creates an annotated tag)", cx);
+            editor
+        });
+        Self {
+            repository,
+            target,
+            name_editor,
+            message_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let message = self.message_editor.read(cx).text(cx);
+        let message = (!message.trim().is_empty()).then_some(message);
+        let target = self.target.as_ref().map(|target| target.to_string());
+
+        let create = self.repository.update(cx, |repository, _| {
+            repository.create_tag(name, target, message)
+        });
+        cx.spawn(async move |_, _| create.await?)
+            .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for CreateTagModal {}
+impl EventEmitter<DismissEvent> for CreateTagModal {}
+
+impl Focusable for CreateTagModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateTagModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.name_editor.focus_handle(cx);
+        let title = match &self.target {
+            Some(target) => format!(
+                "Create tag on {}",
+                target.chars().take(SHORT_SHA_LENGTH).collect::<String>()
+            ),
+            None => "Create tag on HEAD".to_string(),
+        };
+        let editor_container = || {
+            div()
+                .px_2()
+                .py_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(cx.theme().colors().border_variant)
+                .bg(cx.theme().colors().editor_background)
+        };
+
+        v_flex()
+            .key_context("CreateTagModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w(rems(34.))
+            .elevation_3(cx)
+            .overflow_hidden()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1p5()
+                    .gap_1p5()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::Hash)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(title)),
+            )
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_2()
+                    .child(editor_container().child(self.name_editor.clone()))
+                    .child(editor_container().child(self.message_editor.clone())),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_1p5()
+                    .gap_0p5()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Button::new("cancel-tag", "Cancel")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(
+                                &Cancel,
+                                &focus_handle,
+                                window,
+                                cx,
+                            ))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.cancel(&Cancel, window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("create-tag", "Create Tag")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .key_binding(KeyBinding::for_action_in(
+                                &Confirm,
+                                &focus_handle,
+                                window,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(&Confirm, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
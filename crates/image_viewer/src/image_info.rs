@@ -4,10 +4,11 @@ use settings::Settings;
 use ui::prelude::*;
 use workspace::{ItemHandle, StatusItemView, Workspace};
 
-use crate::{ImageFileSizeUnit, ImageView, ImageViewerSettings};
+use crate::{HoveredPixel, ImageFileSizeUnit, ImageView, ImageViewerSettings};
 
 pub struct ImageInfo {
     metadata: Option<ImageMetadata>,
+    hovered_pixel: Option<HoveredPixel>,
     _observe_active_image: Option<Subscription>,
     observe_image_item: Option<Subscription>,
 }
@@ -16,12 +17,19 @@ impl ImageInfo {
     pub fn new(_workspace: &Workspace) -> Self {
         Self {
             metadata: None,
+            hovered_pixel: None,
             _observe_active_image: None,
             observe_image_item: None,
         }
     }
 
     fn update_metadata(&mut self, image_view: &Entity<ImageView>, cx: &mut Context<Self>) {
+        let hovered_pixel = image_view.read(cx).hovered_pixel();
+        if hovered_pixel != self.hovered_pixel {
+            self.hovered_pixel = hovered_pixel;
+            cx.notify();
+        }
+
         let image_item = image_view.read(cx).image_item.clone();
         let current_metadata = image_item.read(cx).image_metadata;
         if current_metadata.is_some() {
@@ -36,6 +44,15 @@ impl ImageInfo {
     }
 }
 
+/// Formats a color as `#RRGGBB`, or `#RRGGBBAA` if it isn't opaque.
+fn format_color([r, g, b, a]: [u8; 4]) -> String {
+    if a == u8::MAX {
+        format!("#{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
 fn format_file_size(size: u64, image_unit_type: ImageFileSizeUnit) -> String {
     match image_unit_type {
         ImageFileSizeUnit::Binary => {
@@ -68,6 +85,14 @@ impl Render for ImageInfo {
         };
 
         let mut components = Vec::new();
+        if let Some(pixel) = self.hovered_pixel {
+            components.push(format!(
+                "{}, {} {}",
+                pixel.x,
+                pixel.y,
+                format_color(pixel.color)
+            ));
+        }
         components.push(format!("{}x{}", metadata.width, metadata.height));
         components.push(format_file_size(metadata.file_size, settings.unit));
 
@@ -118,6 +143,7 @@ impl StatusItemView for ImageInfo {
             }));
         } else {
             self.metadata = None;
+            self.hovered_pixel = None;
         }
         cx.notify();
     }
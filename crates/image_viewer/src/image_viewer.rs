@@ -1,22 +1,25 @@
 mod image_info;
 mod image_viewer_settings;
 
-use std::path::PathBuf;
+use std::{io::Cursor, path::PathBuf, sync::Arc};
 
 use anyhow::Context as _;
 use editor::{EditorSettings, items::entry_git_aware_label_color};
 use file_icons::FileIcons;
 use gpui::{
-    AnyElement, App, Bounds, Context, Entity, EventEmitter, FocusHandle, Focusable,
-    InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, Styled, Task, WeakEntity,
-    Window, canvas, div, fill, img, opaque_grey, point, size,
+    AnyElement, App, Bounds, ClickEvent, ContentMask, Context, CursorStyle, DispatchPhase, Entity,
+    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, MouseButton,
+    MouseDownEvent, MouseMoveEvent, ObjectFit, ParentElement, PinchEvent, Point, Render,
+    ScrollDelta, ScrollWheelEvent, Size, Styled, Task, WeakEntity, Window, actions, canvas, div,
+    fill, img, opaque_grey, point, size,
 };
+use image::{ImageReader, RgbaImage};
 use persistence::IMAGE_VIEWER;
 use project::{ImageItem, Project, ProjectPath, image_store::ImageItemEvent};
 use settings::Settings;
 use theme::Theme;
-use ui::prelude::*;
-use util::paths::PathExt;
+use ui::{Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt};
 use workspace::{
     ItemId, ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId,
     item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
@@ -25,10 +28,45 @@ use workspace::{
 pub use crate::image_info::*;
 pub use crate::image_viewer_settings::*;
 
+actions!(image_viewer, [ZoomIn, ZoomOut, ZoomToFit, ActualSize]);
+
+/// The factor by which each zoom step or mouse wheel notch changes the zoom level.
+const ZOOM_STEP: f32 = 1.25;
+const MIN_ZOOM: f32 = 0.01;
+const MAX_ZOOM: f32 = 64.;
+/// How far a precise scroll with the zoom modifier held needs to move to double the zoom level.
+const PIXELS_PER_ZOOM_DOUBLING: f32 = 200.;
+const CHECKERBOARD_SQUARE_SIZE: f32 = 16.;
+
 pub struct ImageView {
     image_item: Entity<ImageItem>,
     project: Entity<Project>,
     focus_handle: FocusHandle,
+    /// The width and height of the image in pixels, read from its header.
+    image_size: Option<Size<f32>>,
+    /// The decoded image, used to read the pixel under the cursor. It is only decoded once
+    /// the mouse moves over the image.
+    pixels: Option<Arc<RgbaImage>>,
+    decode_pixels_task: Option<Task<()>>,
+    /// The zoom level, or `None` while the image is scaled down to fit the pane.
+    zoom: Option<f32>,
+    /// The offset of the center of the image from the center of the pane.
+    pan_offset: Point<Pixels>,
+    /// The mouse position of an in-progress drag, as of the last mouse move.
+    drag_position: Option<Point<Pixels>>,
+    /// The bounds of the pane in the last frame.
+    viewport: Bounds<Pixels>,
+    mouse_position: Option<Point<Pixels>>,
+    hovered_pixel: Option<HoveredPixel>,
+}
+
+/// The image pixel under the mouse cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HoveredPixel {
+    pub x: u32,
+    pub y: u32,
+    /// The red, green, blue and alpha components of the pixel's color.
+    pub color: [u8; 4],
 }
 
 impl ImageView {
@@ -38,13 +76,274 @@ impl ImageView {
         cx: &mut Context<Self>,
     ) -> Self {
         cx.subscribe(&image_item, Self::on_image_event).detach();
+        let image_size = read_image_size(&image_item.read(cx).image);
         Self {
             image_item,
             project,
             focus_handle: cx.focus_handle(),
+            image_size,
+            pixels: None,
+            decode_pixels_task: None,
+            zoom: None,
+            pan_offset: Point::default(),
+            drag_position: None,
+            viewport: Bounds::default(),
+            mouse_position: None,
+            hovered_pixel: None,
+        }
+    }
+
+    pub fn hovered_pixel(&self) -> Option<HoveredPixel> {
+        self.hovered_pixel
+    }
+
+    /// The zoom level the image is shown at, where `1.0` is its actual size.
+    pub fn scale(&self) -> Option<f32> {
+        self.zoom
+            .or_else(|| Some(fit_scale(self.viewport.size, self.image_size?)))
+    }
+
+    /// Decodes the image to read its pixels, unless that was already done or started.
+    fn decode_pixels(&mut self, cx: &mut Context<Self>) {
+        if self.decode_pixels_task.is_some() {
+            return;
+        }
+        let image = self.image_item.read(cx).image.clone();
+        self.decode_pixels_task = Some(cx.spawn(async move |this, cx| {
+            let pixels = cx
+                .background_spawn(async move {
+                    image::load_from_memory(&image.bytes).map(|image| image.to_rgba8())
+                })
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.pixels = pixels.map(Arc::new);
+                this.update_hovered_pixel(cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn image_bounds(&self) -> Option<Bounds<Pixels>> {
+        Some(image_bounds(
+            self.viewport,
+            self.image_size?,
+            self.zoom,
+            self.pan_offset,
+        ))
+    }
+
+    /// Reads the pixel under the mouse, decoding the image first if needed.
+    fn update_hovered_pixel(&mut self, cx: &mut Context<Self>) {
+        let hovered_pixel = match (self.mouse_position, self.image_bounds()) {
+            (Some(position), Some(bounds)) if bounds.contains(&position) => {
+                match self.pixels.as_ref() {
+                    Some(pixels) => pixel_at(pixels, bounds, position),
+                    None => {
+                        self.decode_pixels(cx);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if hovered_pixel != self.hovered_pixel {
+            self.hovered_pixel = hovered_pixel;
+            cx.notify();
+        }
+    }
+
+    /// Sets the zoom level, keeping the point of the image at `anchor` in place.
+    fn set_zoom(&mut self, zoom: f32, anchor: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(scale) = self.scale() else {
+            return;
+        };
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan_offset = zoomed_pan_offset(self.viewport, self.pan_offset, anchor, scale, zoom);
+        self.zoom = Some(zoom);
+        self.clamp_pan_offset();
+        cx.notify();
+    }
+
+    fn zoom_by(&mut self, factor: f32, anchor: Point<Pixels>, cx: &mut Context<Self>) {
+        if let Some(scale) = self.scale() {
+            self.set_zoom(scale * factor, anchor, cx);
+        }
+    }
+
+    fn pan_by(&mut self, delta: Point<Pixels>, cx: &mut Context<Self>) {
+        if self.zoom.is_none() {
+            return;
+        }
+        self.pan_offset = self.pan_offset + delta;
+        self.clamp_pan_offset();
+        cx.notify();
+    }
+
+    fn clamp_pan_offset(&mut self) {
+        if let (Some(image_size), Some(scale)) = (self.image_size, self.scale()) {
+            self.pan_offset = clamp_pan_offset(self.pan_offset, image_size, scale);
+        }
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_by(ZOOM_STEP, self.viewport.center(), cx);
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_by(1. / ZOOM_STEP, self.viewport.center(), cx);
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom = None;
+        self.pan_offset = Point::default();
+        cx.notify();
+    }
+
+    fn actual_size(&mut self, _: &ActualSize, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom = Some(1.);
+        self.pan_offset = Point::default();
+        cx.notify();
+    }
+
+    fn toggle_actual_size(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.zoom.is_some() {
+            self.zoom_to_fit(&ZoomToFit, window, cx);
+        } else {
+            self.actual_size(&ActualSize, window, cx);
+        }
+    }
+
+    fn handle_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event.delta {
+            ScrollDelta::Lines(delta) => self.zoom_by(ZOOM_STEP.powf(delta.y), event.position, cx),
+            ScrollDelta::Pixels(delta) if event.modifiers.secondary() => self.zoom_by(
+                2f32.powf(delta.y / px(PIXELS_PER_ZOOM_DOUBLING)),
+                event.position,
+                cx,
+            ),
+            ScrollDelta::Pixels(delta) => self.pan_by(delta, cx),
+        }
+    }
+
+    fn handle_pinch(&mut self, event: &PinchEvent, cx: &mut Context<Self>) {
+        self.zoom_by(1. + event.delta, event.position, cx);
+    }
+
+    fn handle_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle);
+        if self.zoom.is_some() {
+            self.drag_position = Some(event.position);
+            cx.notify();
+        }
+    }
+
+    fn handle_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(drag_position) = self.drag_position {
+            if event.dragging() {
+                self.drag_position = Some(event.position);
+                self.pan_by(event.position - drag_position, cx);
+            } else {
+                self.drag_position = None;
+                cx.notify();
+            }
+        }
+
+        self.mouse_position = Some(event.position);
+        self.update_hovered_pixel(cx);
+    }
+
+    fn handle_mouse_up(&mut self, cx: &mut Context<Self>) {
+        if self.drag_position.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn handle_click(&mut self, event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if event.up.click_count == 2 {
+            self.toggle_actual_size(window, cx);
+        }
+    }
+
+    fn handle_hover(&mut self, hovered: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        if !hovered {
+            self.mouse_position = None;
+            if self.hovered_pixel.take().is_some() {
+                cx.notify();
+            }
         }
     }
 
+    fn render_zoom_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let zoom_label = match self.zoom {
+            Some(zoom) => format!("{}%", (zoom * 100.).round()),
+            None => "Fit".to_string(),
+        };
+        let toggle_tooltip = if self.zoom.is_some() {
+            Tooltip::for_action_title_in("Zoom to Fit", &ZoomToFit, &focus_handle)
+        } else {
+            Tooltip::for_action_title_in("Actual Size", &ActualSize, &focus_handle)
+        };
+
+        h_flex()
+            .absolute()
+            .bottom_2()
+            .right_2()
+            .gap_0p5()
+            .p_0p5()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().elevated_surface_background)
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                IconButton::new("zoom-out", IconName::Dash)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Zoom Out",
+                        &ZoomOut,
+                        &focus_handle,
+                    ))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.zoom_out(&ZoomOut, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("zoom-level", zoom_label)
+                    .label_size(LabelSize::Small)
+                    .tooltip(toggle_tooltip)
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.toggle_actual_size(window, cx)),
+                    ),
+            )
+            .child(
+                IconButton::new("zoom-in", IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Zoom In",
+                        &ZoomIn,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| this.zoom_in(&ZoomIn, window, cx))),
+            )
+    }
+
     fn on_image_event(
         &mut self,
         _: Entity<ImageItem>,
@@ -52,9 +351,15 @@ impl ImageView {
         cx: &mut Context<Self>,
     ) {
         match event {
-            ImageItemEvent::MetadataUpdated
-            | ImageItemEvent::FileHandleChanged
-            | ImageItemEvent::Reloaded => {
+            ImageItemEvent::MetadataUpdated | ImageItemEvent::FileHandleChanged => {
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::Reloaded => {
+                self.image_size = read_image_size(&self.image_item.read(cx).image);
+                self.pixels = None;
+                self.decode_pixels_task = None;
+                self.update_hovered_pixel(cx);
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
@@ -171,11 +476,7 @@ impl Item for ImageView {
     where
         Self: Sized,
     {
-        Some(cx.new(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
-        }))
+        Some(cx.new(|cx| Self::new(self.image_item.clone(), self.project.clone(), cx)))
     }
 }
 
@@ -280,58 +581,214 @@ impl Focusable for ImageView {
     }
 }
 
+/// Reads the width and height of an image from its header, without decoding it.
+fn read_image_size(image: &gpui::Image) -> Option<Size<f32>> {
+    let (width, height) = ImageReader::new(Cursor::new(&image.bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    Some(size(width as f32, height as f32))
+}
+
+/// Returns the scale at which an image fits in the viewport, without enlarging it.
+fn fit_scale(viewport: Size<Pixels>, image_size: Size<f32>) -> f32 {
+    if image_size.width <= 0. || image_size.height <= 0. {
+        return 1.;
+    }
+    (viewport.width.0 / image_size.width)
+        .min(viewport.height.0 / image_size.height)
+        .clamp(0., 1.)
+}
+
+/// Returns the bounds of an image of the given size in pixels, when shown centered in the
+/// viewport at the given zoom level and offset.
+fn image_bounds(
+    viewport: Bounds<Pixels>,
+    image_size: Size<f32>,
+    zoom: Option<f32>,
+    pan_offset: Point<Pixels>,
+) -> Bounds<Pixels> {
+    let scale = zoom.unwrap_or_else(|| fit_scale(viewport.size, image_size));
+    let size = size(px(image_size.width * scale), px(image_size.height * scale));
+    let center = viewport.center() + pan_offset;
+    Bounds::new(
+        point(center.x - size.width / 2., center.y - size.height / 2.),
+        size,
+    )
+}
+
+/// Returns the offset that keeps the point of the image at `anchor` in place when the scale of
+/// the image changes from `scale` to `zoom`.
+fn zoomed_pan_offset(
+    viewport: Bounds<Pixels>,
+    pan_offset: Point<Pixels>,
+    anchor: Point<Pixels>,
+    scale: f32,
+    zoom: f32,
+) -> Point<Pixels> {
+    let anchor = anchor - viewport.center();
+    anchor + (pan_offset - anchor) * (zoom / scale)
+}
+
+/// Keeps the center of the pane covered by the image, so it can't be panned out of view.
+fn clamp_pan_offset(pan_offset: Point<Pixels>, image_size: Size<f32>, scale: f32) -> Point<Pixels> {
+    let max_x = px(image_size.width * scale / 2.);
+    let max_y = px(image_size.height * scale / 2.);
+    point(
+        pan_offset.x.clamp(-max_x, max_x),
+        pan_offset.y.clamp(-max_y, max_y),
+    )
+}
+
+/// Returns the pixel of the image shown in `bounds` at `position`, if any.
+fn pixel_at(
+    pixels: &RgbaImage,
+    bounds: Bounds<Pixels>,
+    position: Point<Pixels>,
+) -> Option<HoveredPixel> {
+    if !bounds.contains(&position) || pixels.width() == 0 || pixels.height() == 0 {
+        return None;
+    }
+    let offset = position - bounds.origin;
+    let x = (offset.x / bounds.size.width * pixels.width() as f32) as u32;
+    let y = (offset.y / bounds.size.height * pixels.height() as f32) as u32;
+    let x = x.min(pixels.width() - 1);
+    let y = y.min(pixels.height() - 1);
+    Some(HoveredPixel {
+        x,
+        y,
+        color: pixels.get_pixel(x, y).0,
+    })
+}
+
 impl Render for ImageView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let image = self.image_item.read(cx).image.clone();
-        let checkered_background = |bounds: Bounds<Pixels>,
-                                    _,
-                                    window: &mut Window,
-                                    _cx: &mut App| {
-            let square_size = 32.0;
-
-            let start_y = bounds.origin.y.0;
-            let height = bounds.size.height.0;
-            let start_x = bounds.origin.x.0;
-            let width = bounds.size.width.0;
-
-            let mut y = start_y;
-            let mut x = start_x;
-            let mut color_swapper = true;
-            // draw checkerboard pattern
-            while y <= start_y + height {
-                // Keeping track of the grid in order to be resilient to resizing
-                let start_swap = color_swapper;
-                while x <= start_x + width {
-                    let rect =
-                        Bounds::new(point(px(x), px(y)), size(px(square_size), px(square_size)));
-
-                    let color = if color_swapper {
-                        opaque_grey(0.6, 0.4)
-                    } else {
-                        opaque_grey(0.7, 0.4)
-                    };
-
-                    window.paint_quad(fill(rect, color));
-                    color_swapper = !color_swapper;
-                    x += square_size;
+        let image_size = self.image_size;
+        let zoom = self.zoom;
+        let pan_offset = self.pan_offset;
+
+        let this = cx.entity();
+        // Draws a checkerboard behind the image, so transparent areas can be told apart.
+        let checkered_background = canvas(
+            {
+                let this = this.clone();
+                move |bounds, window, cx| {
+                    this.update(cx, |this, _| this.viewport = bounds);
+                    window.insert_hitbox(bounds, false)
                 }
-                x = start_x;
-                color_swapper = !start_swap;
-                y += square_size;
-            }
-        };
+            },
+            move |bounds, hitbox, window, _cx| {
+                window.on_mouse_event({
+                    let this = this.clone();
+                    move |event: &PinchEvent, phase, window, cx| {
+                        if phase == DispatchPhase::Bubble && hitbox.is_hovered(window) {
+                            this.update(cx, |this, cx| this.handle_pinch(event, cx));
+                        }
+                    }
+                });
 
-        let checkered_background = canvas(|_, _, _| (), checkered_background)
-            .border_2()
-            .border_color(cx.theme().styles.colors.border)
-            .size_full()
-            .absolute()
-            .top_0()
-            .left_0();
+                let Some(image_size) = image_size else {
+                    return;
+                };
+                let image_bounds = image_bounds(bounds, image_size, zoom, pan_offset);
+                let visible_bounds = image_bounds.intersect(&bounds);
+                if visible_bounds.is_empty() {
+                    return;
+                }
+
+                let square_size = px(CHECKERBOARD_SQUARE_SIZE);
+                // Align the squares to the image, so they move along with it when panning.
+                let first_column =
+                    ((visible_bounds.left() - image_bounds.left()) / square_size).floor() as i32;
+                let first_row =
+                    ((visible_bounds.top() - image_bounds.top()) / square_size).floor() as i32;
+                window.with_content_mask(
+                    Some(ContentMask {
+                        bounds: visible_bounds,
+                    }),
+                    |window| {
+                        let mut row = first_row;
+                        let mut y = image_bounds.top() + square_size * row as f32;
+                        while y < visible_bounds.bottom() {
+                            let mut column = first_column;
+                            let mut x = image_bounds.left() + square_size * column as f32;
+                            while x < visible_bounds.right() {
+                                let color = if (row + column) % 2 == 0 {
+                                    opaque_grey(0.6, 0.4)
+                                } else {
+                                    opaque_grey(0.7, 0.4)
+                                };
+                                window.paint_quad(fill(
+                                    Bounds::new(point(x, y), size(square_size, square_size)),
+                                    color,
+                                ));
+                                column += 1;
+                                x += square_size;
+                            }
+                            row += 1;
+                            y += square_size;
+                        }
+                    },
+                );
+            },
+        )
+        .size_full()
+        .absolute()
+        .top_0()
+        .left_0();
+
+        let image = match (zoom, image_size) {
+            (Some(zoom), Some(image_size)) => img(image)
+                .object_fit(ObjectFit::Fill)
+                .flex_none()
+                .relative()
+                .left(pan_offset.x)
+                .top(pan_offset.y)
+                .w(px(image_size.width * zoom))
+                .h(px(image_size.height * zoom))
+                .id("img"),
+            _ => img(image)
+                .object_fit(ObjectFit::ScaleDown)
+                .max_w_full()
+                .max_h_full()
+                .id("img"),
+        };
 
         div()
+            .id("image-viewer")
+            .key_context("ImageViewer")
             .track_focus(&self.focus_handle(cx))
             .size_full()
+            .relative()
+            .overflow_hidden()
+            .border_2()
+            .border_color(cx.theme().colors().border)
+            .when(self.zoom.is_some(), |this| {
+                this.cursor(if self.drag_position.is_some() {
+                    CursorStyle::ClosedHand
+                } else {
+                    CursorStyle::OpenHand
+                })
+            })
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::actual_size))
+            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_move(cx.listener(Self::handle_mouse_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| this.handle_mouse_up(cx)),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| this.handle_mouse_up(cx)),
+            )
+            .on_click(cx.listener(Self::handle_click))
+            .on_hover(cx.listener(Self::handle_hover))
             .child(checkered_background)
             .child(
                 div()
@@ -341,14 +798,9 @@ impl Render for ImageView {
                     .w_full()
                     // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
                     .h_full()
-                    .child(
-                        img(image)
-                            .object_fit(ObjectFit::ScaleDown)
-                            .max_w_full()
-                            .max_h_full()
-                            .id("img"),
-                    ),
+                    .child(image),
             )
+            .child(self.render_zoom_controls(cx))
     }
 }
 
@@ -457,3 +909,138 @@ mod persistence {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn viewport() -> Bounds<Pixels> {
+        Bounds::new(point(px(100.), px(50.)), size(px(400.), px(300.)))
+    }
+
+    #[test]
+    fn test_fit_scale() {
+        let viewport = viewport().size;
+        // Large images are scaled down by the axis that needs it most.
+        assert_eq!(fit_scale(viewport, size(800., 300.)), 0.5);
+        assert_eq!(fit_scale(viewport, size(400., 600.)), 0.5);
+        // Small images aren't enlarged.
+        assert_eq!(fit_scale(viewport, size(40., 30.)), 1.);
+        assert_eq!(fit_scale(viewport, size(400., 300.)), 1.);
+        // Images without pixels are shown at their actual size.
+        assert_eq!(fit_scale(viewport, size(0., 300.)), 1.);
+        assert_eq!(fit_scale(viewport, size(400., 0.)), 1.);
+        // An empty viewport hides the image.
+        assert_eq!(fit_scale(size(px(0.), px(0.)), size(40., 30.)), 0.);
+    }
+
+    #[test]
+    fn test_image_bounds() {
+        // Fitted images are centered in the viewport.
+        assert_eq!(
+            image_bounds(viewport(), size(800., 300.), None, Point::default()),
+            Bounds::new(point(px(100.), px(125.)), size(px(400.), px(150.)))
+        );
+        // Zoomed images are scaled around the center and moved by the pan offset.
+        assert_eq!(
+            image_bounds(
+                viewport(),
+                size(100., 50.),
+                Some(2.),
+                point(px(10.), px(-20.))
+            ),
+            Bounds::new(point(px(210.), px(130.)), size(px(200.), px(100.)))
+        );
+    }
+
+    #[test]
+    fn test_zoomed_pan_offset() {
+        let image_size = size(200., 100.);
+        let pan_offset = point(px(30.), px(-10.));
+        let scale = 1.;
+        for anchor in [
+            viewport().center(),
+            point(px(150.), px(80.)),
+            point(px(480.), px(300.)),
+        ] {
+            let bounds = image_bounds(viewport(), image_size, Some(scale), pan_offset);
+            let zoomed_pan_offset = zoomed_pan_offset(viewport(), pan_offset, anchor, scale, 3.);
+            let zoomed_bounds = image_bounds(viewport(), image_size, Some(3.), zoomed_pan_offset);
+            // The point of the image under the anchor stays there.
+            let relative_position = |bounds: Bounds<Pixels>| {
+                let offset = anchor - bounds.origin;
+                point(offset.x / bounds.size.width, offset.y / bounds.size.height)
+            };
+            let before = relative_position(bounds);
+            let after = relative_position(zoomed_bounds);
+            assert!((before.x - after.x).abs() < 1e-4, "{before:?} {after:?}");
+            assert!((before.y - after.y).abs() < 1e-4, "{before:?} {after:?}");
+        }
+
+        // Zooming around the center of the viewport only scales the offset.
+        assert_eq!(
+            zoomed_pan_offset(viewport(), pan_offset, viewport().center(), 2., 1.),
+            point(px(15.), px(-5.))
+        );
+    }
+
+    #[test]
+    fn test_clamp_pan_offset() {
+        let image_size = size(200., 100.);
+        // Offsets keeping the center of the viewport on the image are left as is.
+        assert_eq!(
+            clamp_pan_offset(point(px(50.), px(-20.)), image_size, 1.),
+            point(px(50.), px(-20.))
+        );
+        assert_eq!(
+            clamp_pan_offset(point(px(100.), px(-50.)), image_size, 1.),
+            point(px(100.), px(-50.))
+        );
+        // Other offsets are moved back to the edges of the image.
+        assert_eq!(
+            clamp_pan_offset(point(px(500.), px(-500.)), image_size, 1.),
+            point(px(100.), px(-50.))
+        );
+        assert_eq!(
+            clamp_pan_offset(point(px(-500.), px(500.)), image_size, 2.),
+            point(px(-200.), px(100.))
+        );
+    }
+
+    #[test]
+    fn test_pixel_at() {
+        let pixels = RgbaImage::from_fn(4, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        // Each image pixel is shown as a 10×10 square.
+        let bounds = Bounds::new(point(px(100.), px(50.)), size(px(40.), px(20.)));
+        let pixel = |x: f32, y: f32| {
+            pixel_at(&pixels, bounds, point(px(x), px(y))).map(|pixel| (pixel.x, pixel.y))
+        };
+
+        assert_eq!(
+            pixel_at(&pixels, bounds, point(px(125.), px(65.))),
+            Some(HoveredPixel {
+                x: 2,
+                y: 1,
+                color: [2, 1, 0, 255],
+            })
+        );
+        assert_eq!(pixel(100., 50.), Some((0, 0)));
+        assert_eq!(pixel(109.9, 59.9), Some((0, 0)));
+        assert_eq!(pixel(110., 60.), Some((1, 1)));
+        // The bottom right edge belongs to the last pixel.
+        assert_eq!(pixel(140., 70.), Some((3, 1)));
+
+        // Positions outside of the image have no pixel.
+        assert_eq!(pixel(99.9, 60.), None);
+        assert_eq!(pixel(120., 49.9), None);
+        assert_eq!(pixel(140.1, 60.), None);
+        assert_eq!(pixel(120., 70.1), None);
+
+        // Neither do empty images.
+        assert_eq!(
+            pixel_at(&RgbaImage::new(0, 0), bounds, point(px(120.), px(60.))),
+            None
+        );
+    }
+}
@@ -22,7 +22,7 @@ use async_watch as watch;
 use clock::Lamport;
 pub use clock::ReplicaId;
 use collections::HashMap;
-use fs::MTime;
+use fs::{Encoding, MTime};
 use futures::channel::oneshot;
 use gpui::{
     App, AppContext as _, Context, Entity, EventEmitter, HighlightStyle, SharedString, StyledText,
@@ -101,6 +101,11 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding of the file on disk, which is used when reloading and saving it.
+    encoding: Encoding,
+    /// Whether the file was too large to be opened with the full feature set. Such buffers are
+    /// not assigned a language automatically, and are kept out of language servers and git diffs.
+    large_file: bool,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -116,6 +121,8 @@ pub struct Buffer {
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
     diagnostics_timestamp: clock::Lamport,
+    /// Servers whose diagnostics are computed on each replica and never sent to collaborators.
+    local_diagnostic_servers: BTreeSet<LanguageServerId>,
     completion_triggers: BTreeSet<String>,
     completion_triggers_per_language_server: HashMap<LanguageServerId, BTreeSet<String>>,
     completion_triggers_timestamp: clock::Lamport,
@@ -386,6 +393,12 @@ pub trait LocalFile: File {
 
     /// Loads the file's contents from disk.
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>>;
+
+    /// Loads the file contents from disk, decoding them from the given encoding.
+    fn load_with_encoding(&self, encoding: Encoding, cx: &App) -> Task<Result<String>> {
+        let bytes = self.load_bytes(cx);
+        cx.background_spawn(async move { Ok(encoding.decode(&bytes.await?)) })
+    }
 }
 
 /// The auto-indent behavior associated with an editing operation.
@@ -823,6 +836,14 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        if let Some(encoding) = message
+            .encoding
+            .as_ref()
+            .and_then(proto::deserialize_encoding)
+        {
+            this.encoding = encoding;
+        }
+        this.large_file = message.large_file;
         Ok(this)
     }
 
@@ -835,6 +856,8 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding)),
+            large_file: self.large_file,
         }
     }
 
@@ -857,6 +880,9 @@ impl Buffer {
         }));
 
         for (server_id, diagnostics) in &self.diagnostics {
+            if self.local_diagnostic_servers.contains(server_id) {
+                continue;
+            }
             operations.push(proto::serialize_operation(&Operation::UpdateDiagnostics {
                 lamport_timestamp: self.diagnostics_timestamp,
                 server_id: *server_id,
@@ -912,6 +938,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            large_file: false,
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -932,6 +960,7 @@ impl Buffer {
             remote_selections: Default::default(),
             diagnostics: Default::default(),
             diagnostics_timestamp: Default::default(),
+            local_diagnostic_servers: Default::default(),
             completion_triggers: Default::default(),
             completion_triggers_per_language_server: Default::default(),
             completion_triggers_timestamp: Default::default(),
@@ -1046,6 +1075,8 @@ impl Buffer {
                 }),
                 language: self.language.clone(),
                 has_conflict: self.has_conflict,
+                encoding: self.encoding,
+                large_file: self.large_file,
                 has_unsaved_edits: Cell::new(self.has_unsaved_edits.get_mut().clone()),
                 _subscriptions: vec![cx.subscribe(&this, Self::on_base_buffer_event)],
                 ..Self::build(self.text.branch(), self.file.clone(), self.capability())
@@ -1207,6 +1238,30 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding of the buffer's file on disk.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the encoding the buffer's file is written with when saved, and decoded from when
+    /// reloaded.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut Context<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
+    /// Whether the buffer was opened in large-file mode.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Marks the buffer as being opened in large-file mode.
+    pub fn set_large_file(&mut self, large_file: bool) {
+        self.large_file = large_file;
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.non_text_state_update_count += 1;
@@ -1266,7 +1321,10 @@ impl Buffer {
             let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((
+                    file.disk_state().mtime(),
+                    file.load_with_encoding(this.encoding, cx),
+                ))
             })?
             else {
                 return Ok(());
@@ -1535,6 +1593,23 @@ impl Buffer {
         self.send_operation(op, true, cx);
     }
 
+    /// Assign to the buffer a set of diagnostics that are only shown on this replica.
+    ///
+    /// Unlike [`Buffer::update_diagnostics`], no operation is broadcast to collaborators,
+    /// and the diagnostics are left out when the buffer is replicated to new ones.
+    pub fn update_local_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        diagnostics: DiagnosticSet,
+        cx: &mut Context<Self>,
+    ) {
+        self.local_diagnostic_servers.insert(server_id);
+        self.set_diagnostics(server_id, diagnostics);
+        self.non_text_state_update_count += 1;
+        cx.notify();
+        cx.emit(BufferEvent::DiagnosticsUpdated);
+    }
+
     pub fn get_diagnostics(&self, server_id: LanguageServerId) -> Option<&DiagnosticSet> {
         let Ok(idx) = self.diagnostics.binary_search_by_key(&server_id, |v| v.0) else {
             return None;
@@ -1801,11 +1876,27 @@ impl Buffer {
     pub fn diff(&self, mut new_text: String, cx: &App) -> Task<Diff> {
         let old_text = self.as_rope().clone();
         let base_version = self.version();
+        let large_file = self.large_file;
         cx.background_executor()
             .spawn_labeled(*BUFFER_DIFF_TASK, async move {
-                let old_text = old_text.to_string();
                 let line_ending = LineEnding::detect(&new_text);
                 LineEnding::normalize(&mut new_text);
+                // Large files are usually logs that only grow, so avoid diffing their whole
+                // contents when the new text just appends to the old one.
+                if large_file {
+                    if let Some(appended) = appended_text(&old_text, &new_text) {
+                        let end = old_text.len();
+                        return Diff {
+                            base_version,
+                            line_ending,
+                            edits: (!appended.is_empty())
+                                .then(|| (end..end, Arc::from(appended)))
+                                .into_iter()
+                                .collect(),
+                        };
+                    }
+                }
+                let old_text = old_text.to_string();
                 let edits = text_diff(&old_text, &new_text);
                 Diff {
                     base_version,
@@ -2513,17 +2604,7 @@ impl Buffer {
         cx: &mut Context<Self>,
     ) {
         if lamport_timestamp > self.diagnostics_timestamp {
-            let ix = self.diagnostics.binary_search_by_key(&server_id, |e| e.0);
-            if diagnostics.is_empty() {
-                if let Ok(ix) = ix {
-                    self.diagnostics.remove(ix);
-                }
-            } else {
-                match ix {
-                    Err(ix) => self.diagnostics.insert(ix, (server_id, diagnostics)),
-                    Ok(ix) => self.diagnostics[ix].1 = diagnostics,
-                };
-            }
+            self.set_diagnostics(server_id, diagnostics);
             self.diagnostics_timestamp = lamport_timestamp;
             self.non_text_state_update_count += 1;
             self.text.lamport_clock.observe(lamport_timestamp);
@@ -2532,6 +2613,20 @@ impl Buffer {
         }
     }
 
+    fn set_diagnostics(&mut self, server_id: LanguageServerId, diagnostics: DiagnosticSet) {
+        let ix = self.diagnostics.binary_search_by_key(&server_id, |e| e.0);
+        if diagnostics.is_empty() {
+            if let Ok(ix) = ix {
+                self.diagnostics.remove(ix);
+            }
+        } else {
+            match ix {
+                Err(ix) => self.diagnostics.insert(ix, (server_id, diagnostics)),
+                Ok(ix) => self.diagnostics[ix].1 = diagnostics,
+            };
+        }
+    }
+
     fn send_operation(&mut self, operation: Operation, is_local: bool, cx: &mut Context<Self>) {
         self.was_changed();
         cx.emit(BufferEvent::Operation {
@@ -3843,6 +3938,80 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of the buffer that contain prose to spell check, such as comments,
+    /// strings and the text of markup languages.
+    ///
+    /// These are the nodes captured as `@spell` by the spellcheck query of each language in the
+    /// buffer, minus the nodes captured as `@nospell`. Buffers without a grammar are plain text,
+    /// and are checked in full.
+    pub fn spellcheck_ranges<T: ToOffset>(&self, range: Range<T>) -> Vec<Range<usize>> {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        if self
+            .language
+            .as_ref()
+            .map_or(true, |language| language.grammar().is_none())
+        {
+            return vec![offset_range];
+        }
+
+        let mut syntax_matches = self.syntax.matches(offset_range.clone(), self, |grammar| {
+            grammar
+                .spellcheck_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.spellcheck_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut spell_ranges = Vec::new();
+        let mut nospell_ranges = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    let node_range = capture.node.byte_range();
+                    let node_range = node_range.start.max(offset_range.start)
+                        ..node_range.end.min(offset_range.end);
+                    if Some(capture.index) == config.spell_capture_ix {
+                        spell_ranges.push(node_range);
+                    } else if Some(capture.index) == config.nospell_capture_ix {
+                        nospell_ranges.push(node_range);
+                    }
+                }
+            }
+            syntax_matches.advance();
+        }
+        let spell_ranges = merge_ranges(spell_ranges);
+        let nospell_ranges = merge_ranges(nospell_ranges);
+
+        let mut ranges = Vec::new();
+        let mut nospell_ix = 0;
+        for spell_range in spell_ranges {
+            let mut start = spell_range.start;
+            while nospell_ranges
+                .get(nospell_ix)
+                .is_some_and(|nospell_range| nospell_range.end <= start)
+            {
+                nospell_ix += 1;
+            }
+            for nospell_range in nospell_ranges[nospell_ix..]
+                .iter()
+                .take_while(|nospell_range| nospell_range.start < spell_range.end)
+            {
+                if nospell_range.start > start {
+                    ranges.push(start..nospell_range.start);
+                }
+                start = start.max(nospell_range.end);
+            }
+            if start < spell_range.end {
+                ranges.push(start..spell_range.end);
+            }
+        }
+        ranges
+    }
+
     pub fn injections_intersecting_range<T: ToOffset>(
         &self,
         range: Range<T>,
@@ -4800,3 +4969,30 @@ pub fn trailing_whitespace_ranges(rope: &Rope) -> Vec<Range<usize>> {
 
     ranges
 }
+
+/// Returns the text that was appended to `old_text` to produce `new_text`, or `None` if
+/// `new_text` doesn't start with `old_text`.
+fn appended_text<'a>(old_text: &Rope, new_text: &'a str) -> Option<&'a str> {
+    let mut remaining = new_text;
+    for chunk in old_text.chunks() {
+        remaining = remaining.strip_prefix(chunk)?;
+    }
+    Some(remaining)
+}
+
+/// Sorts the given ranges and merges the ones that overlap, dropping empty ranges.
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if let Some(last) = merged.last_mut() {
+            if range.start <= last.end {
+                last.end = last.end.max(range.end);
+                continue;
+            }
+        }
+        merged.push(range);
+    }
+    merged
+}
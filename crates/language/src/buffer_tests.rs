@@ -969,6 +969,39 @@ fn test_text_objects(cx: &mut App) {
     )
 }
 
+#[gpui::test]
+fn test_spellcheck_ranges(cx: &mut App) {
+    let language = rust_lang()
+        .with_spellcheck_query(
+            r#"
+            (block_comment) @spell
+            (string_literal) @spell
+            (escape_sequence) @nospell
+            "#,
+        )
+        .unwrap();
+    let text = r#"fn main() { /* helo wrld */ let s = "abc\tdef"; }"#;
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let ranges = |range: Range<usize>| {
+        snapshot
+            .spellcheck_ranges(range)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ranges(0..text.len()), ["/* helo wrld */", "\"abc", "def\""]);
+    // Ranges are clipped to the queried range.
+    let start = text.find("wrld").unwrap();
+    assert_eq!(ranges(start..start + 8), ["wrld */"]);
+
+    // Buffers without a grammar are checked in full.
+    let buffer = cx.new(|cx| Buffer::local("plain text", cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(snapshot.spellcheck_ranges(0..10), [0..10]);
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut App) {
     let mut assert = |selection_text, range_markers| {
@@ -1014,6 +1014,7 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketsConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) spellcheck_config: Option<SpellcheckConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
@@ -1103,6 +1104,12 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct SpellcheckConfig {
+    pub query: Query,
+    pub spell_capture_ix: Option<u32>,
+    pub nospell_capture_ix: Option<u32>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum RunnableCapture {
     Named(SharedString),
@@ -1174,6 +1181,7 @@ impl Language {
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    spellcheck_config: None,
                     runnable_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").ok(),
                     ts_language,
@@ -1236,6 +1244,11 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.spellcheck {
+            self = self
+                .with_spellcheck_query(query.as_ref())
+                .context("Error loading spellcheck query")?;
+        }
         if let Some(query) = queries.runnables {
             self = self
                 .with_runnable_query(query.as_ref())
@@ -1616,6 +1629,33 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_spellcheck_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut spell_capture_ix = None;
+        let mut nospell_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("spell", &mut spell_capture_ix),
+                ("nospell", &mut nospell_capture_ix),
+            ],
+        );
+
+        if spell_capture_ix.is_some() || nospell_capture_ix.is_some() {
+            grammar.spellcheck_config = Some(SpellcheckConfig {
+                query,
+                spell_capture_ix,
+                nospell_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
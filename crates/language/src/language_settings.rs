@@ -128,6 +128,13 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Semantic token related settings.
+    pub semantic_tokens: SemanticTokensSettings,
+    /// Where the regions that code can be folded at come from.
+    pub folding_ranges: FoldingRangesSource,
+    /// Whether to show swatches next to the colors that language servers
+    /// report in the document.
+    pub document_colors: bool,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     /// Whether to automatically surround text with brackets.
@@ -498,6 +505,20 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Semantic token related settings.
+    #[serde(default)]
+    pub semantic_tokens: Option<SemanticTokensSettingsContent>,
+    /// Where the regions that code can be folded at come from.
+    ///
+    /// Default: language_server
+    #[serde(default)]
+    pub folding_ranges: Option<FoldingRangesSource>,
+    /// Whether to show swatches next to the colors that language servers
+    /// report in the document.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub document_colors: Option<bool>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -740,6 +761,18 @@ impl<'de> Deserialize<'de> for FormatOnSave {
     }
 }
 
+/// Controls where the regions that code can be folded at come from.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingRangesSource {
+    /// Use the folding ranges reported by the language server when it
+    /// provides them, and fold by indentation otherwise.
+    #[default]
+    LanguageServer,
+    /// Always fold by indentation.
+    Indentation,
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -963,6 +996,54 @@ pub enum IndentGuideBackgroundColoring {
     IndentAware,
 }
 
+/// The settings for semantic tokens.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SemanticTokensSettings {
+    /// Whether to highlight the semantic tokens reported by language servers.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// The highlight names used to style semantic tokens, keyed by token type.
+    /// Keys of the form `type.modifier` take precedence over the plain token
+    /// type for tokens with that modifier.
+    #[serde(default)]
+    pub highlights: HashMap<String, String>,
+}
+
+impl SemanticTokensSettings {
+    /// Returns the highlight name for a token of the given type and modifiers.
+    pub fn highlight_name<'a>(
+        &'a self,
+        token_type: &str,
+        token_modifiers: impl IntoIterator<Item = &'a str>,
+    ) -> Option<&'a str> {
+        token_modifiers
+            .into_iter()
+            .find_map(|modifier| {
+                self.highlights
+                    .get(&format!("{token_type}.{modifier}"))
+                    .map(String::as_str)
+            })
+            .or_else(|| self.highlights.get(token_type).map(String::as_str))
+    }
+}
+
+/// The settings for semantic tokens, as specified in a settings file.
+/// Highlights are merged with those of the previous settings layer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SemanticTokensSettingsContent {
+    /// Whether to highlight the semantic tokens reported by language servers.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The highlight names used to style semantic tokens, keyed by token type.
+    /// Keys of the form `type.modifier` take precedence over the plain token
+    /// type for tokens with that modifier.
+    #[serde(default)]
+    pub highlights: HashMap<String, String>,
+}
+
 /// The settings for inlay hints.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct InlayHintSettings {
@@ -1443,6 +1524,18 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.folding_ranges, src.folding_ranges);
+    merge(&mut settings.document_colors, src.document_colors);
+    if let Some(semantic_tokens) = &src.semantic_tokens {
+        merge(
+            &mut settings.semantic_tokens.enabled,
+            semantic_tokens.enabled,
+        );
+        settings
+            .semantic_tokens
+            .highlights
+            .extend(semantic_tokens.highlights.clone());
+    }
     merge(
         &mut settings.show_completions_on_input,
         src.show_completions_on_input,
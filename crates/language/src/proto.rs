@@ -27,6 +27,19 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes an [`fs::Encoding`] from the RPC representation, unless its name is unknown.
+pub fn deserialize_encoding(message: &proto::Encoding) -> Option<fs::Encoding> {
+    fs::Encoding::from_name(&message.name, message.has_bom)
+}
+
+/// Serializes an [`fs::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: fs::Encoding) -> proto::Encoding {
+    proto::Encoding {
+        name: encoding.name().to_string(),
+        has_bom: encoding.has_bom(),
+    }
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
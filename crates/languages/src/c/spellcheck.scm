@@ -0,0 +1,4 @@
+[
+  (comment)
+  (string_literal)
+] @spell
@@ -0,0 +1,5 @@
+[
+  (comment)
+  (string_literal)
+  (raw_string_literal)
+] @spell
@@ -0,0 +1,5 @@
+[
+  (comment)
+  (interpreted_string_literal)
+  (raw_string_literal)
+] @spell
@@ -0,0 +1,5 @@
+[
+  (code_span)
+  (link_destination)
+  (uri_autolink)
+] @nospell
@@ -0,0 +1 @@
+(inline) @spell
@@ -0,0 +1,6 @@
+[
+  (comment)
+  (string)
+] @spell
+
+(interpolation) @nospell
@@ -0,0 +1,6 @@
+[
+  (line_comment)
+  (block_comment)
+  (string_literal)
+  (raw_string_literal)
+] @spell
@@ -0,0 +1,8 @@
+[
+  (comment)
+  (string)
+  (template_string)
+  (jsx_text)
+] @spell
+
+(template_substitution) @nospell
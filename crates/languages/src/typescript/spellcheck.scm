@@ -0,0 +1,7 @@
+[
+  (comment)
+  (string)
+  (template_string)
+] @spell
+
+(template_substitution) @nospell
@@ -1,13 +1,14 @@
 use crate::{
     ProjectItem as _, ProjectPath,
     lsp_store::OpenLspBufferHandle,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
-use fs::Fs;
+use fs::{Encoding, Fs};
 use futures::{Future, FutureExt as _, StreamExt, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -15,14 +16,15 @@ use gpui::{
 use language::{
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
     proto::{
-        deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
-        split_operations,
+        deserialize_encoding, deserialize_line_ending, deserialize_version, serialize_encoding,
+        serialize_line_ending, serialize_version, split_operations,
     },
 };
 use rpc::{
     AnyProtoClient, ErrorExt as _, TypedEnvelope,
     proto::{self, ToProto},
 };
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{io, path::Path, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
@@ -123,6 +125,7 @@ impl RemoteBufferStore {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id().into();
         let version = buffer.version();
+        let encoding = buffer.encoding();
         let rpc = self.upstream_client.clone();
         let project_id = self.project_id;
         cx.spawn(async move |_, cx| {
@@ -132,6 +135,7 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: Some(serialize_encoding(encoding)),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
@@ -159,6 +163,12 @@ impl RemoteBufferStore {
         {
             proto::create_buffer_for_peer::Variant::State(mut state) => {
                 let buffer_id = BufferId::new(state.id)?;
+                let capability =
+                    if state.large_file && ProjectSettings::get_global(cx).large_files.read_only {
+                        Capability::ReadOnly
+                    } else {
+                        capability
+                    };
 
                 let buffer_result = maybe!({
                     let mut buffer_file = None;
@@ -329,6 +339,7 @@ impl RemoteBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
@@ -338,6 +349,7 @@ impl RemoteBufferStore {
                 .iter()
                 .map(|buffer| buffer.read(cx).remote_id().to_proto())
                 .collect(),
+            encoding: encoding.map(serialize_encoding),
         });
 
         cx.spawn(async move |this, cx| {
@@ -366,6 +378,7 @@ impl LocalBufferStore {
 
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         let file = buffer.file().cloned();
@@ -377,7 +390,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(async move |this, cx| {
@@ -400,6 +413,7 @@ impl LocalBufferStore {
                             buffer_id: buffer_id.to_proto(),
                             version: serialize_version(&version),
                             mtime: mtime.map(|time| time.into()),
+                            encoding: Some(serialize_encoding(encoding)),
                         })
                         .log_err();
                 }
@@ -610,16 +624,77 @@ impl LocalBufferStore {
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
-            cx.spawn(async move |_, cx| {
-                let loaded = load_file.await?;
+            let large_file_settings = ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.id(),
+                    path: &path,
+                }),
+                cx,
+            )
+            .large_files;
+            // Decide whether the file is opened in large-file mode before reading it, so that
+            // the decision doesn't depend on having loaded the whole file first.
+            let entry_size = worktree.entry_for_path(&path).map(|entry| entry.size);
+            let fs = worktree.as_local().map(|worktree| worktree.fs().clone());
+            let abs_path = worktree.absolutize(&path);
+            let path = path.clone();
+            cx.spawn(async move |worktree, cx| {
+                let size = match (entry_size, fs, abs_path) {
+                    (Some(size), _, _) => Some(size),
+                    (None, Some(fs), Ok(abs_path)) => fs
+                        .metadata(&abs_path)
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(|metadata| metadata.len),
+                    _ => None,
+                };
+                let large_file = size.is_some_and(|size| large_file_settings.is_large_file(size));
+                let capability = if large_file && large_file_settings.read_only {
+                    Capability::ReadOnly
+                } else {
+                    Capability::ReadWrite
+                };
+
+                // Large files are read in chunks, without ever holding all of their text in a
+                // single string.
+                if large_file {
+                    let loaded = worktree
+                        .update(cx, |worktree, cx| worktree.load_large_file(&path, cx))?
+                        .await?;
+                    if let Some(loaded) = loaded {
+                        let text_buffer = cx
+                            .background_spawn(async move {
+                                text::Buffer::new_normalized(
+                                    0,
+                                    buffer_id,
+                                    loaded.line_ending,
+                                    loaded.text,
+                                )
+                            })
+                            .await;
+                        return cx.insert_entity(reservation, |_| {
+                            let mut buffer =
+                                Buffer::build(text_buffer, Some(loaded.file), capability);
+                            buffer.set_large_file(true);
+                            buffer
+                        });
+                    }
+                }
+
+                let loaded = worktree
+                    .update(cx, |worktree, cx| worktree.load_file(&path, cx))?
+                    .await?;
                 let text_buffer = cx
                     .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer = Buffer::build(text_buffer, Some(loaded.file), capability);
+                    buffer.set_encoding(loaded.encoding, cx);
+                    buffer.set_large_file(large_file);
+                    buffer
                 })
             })
         });
@@ -1131,6 +1206,7 @@ impl BufferStore {
                         version: serialize_version(&buffer.version()),
                         mtime: buffer.saved_mtime().map(|t| t.into()),
                         line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
             }
@@ -1247,6 +1323,7 @@ impl BufferStore {
                         mtime: buffer.saved_mtime().map(|time| time.into()),
                         line_ending: language::proto::serialize_line_ending(buffer.line_ending())
                             as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
 
@@ -1361,7 +1438,17 @@ impl BufferStore {
                 buffer.wait_for_version(deserialize_version(&envelope.payload.version))
             })?
             .await?;
-        let buffer_id = buffer.update(&mut cx, |buffer, _| buffer.remote_id())?;
+        let encoding = envelope
+            .payload
+            .encoding
+            .as_ref()
+            .and_then(deserialize_encoding);
+        let buffer_id = buffer.update(&mut cx, |buffer, cx| {
+            if let Some(encoding) = encoding {
+                buffer.set_encoding(encoding, cx);
+            }
+            buffer.remote_id()
+        })?;
 
         if let Some(new_path) = envelope.payload.new_path {
             let new_path = ProjectPath::from_proto(new_path);
@@ -1379,6 +1466,7 @@ impl BufferStore {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: Some(serialize_encoding(buffer.encoding())),
         })
     }
 
@@ -1415,9 +1503,17 @@ impl BufferStore {
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let version = deserialize_version(&envelope.payload.version);
         let mtime = envelope.payload.mtime.clone().map(|time| time.into());
+        let encoding = envelope
+            .payload
+            .encoding
+            .as_ref()
+            .and_then(deserialize_encoding);
         this.update(&mut cx, move |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -1429,6 +1525,7 @@ impl BufferStore {
                         buffer_id: buffer_id.into(),
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1447,9 +1544,17 @@ impl BufferStore {
             proto::LineEnding::from_i32(envelope.payload.line_ending)
                 .ok_or_else(|| anyhow!("missing line ending"))?,
         );
+        let encoding = envelope
+            .payload
+            .encoding
+            .as_ref()
+            .and_then(deserialize_encoding);
         this.update(&mut cx, |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_reload(version, line_ending, mtime, cx);
                 });
             }
@@ -1462,6 +1567,7 @@ impl BufferStore {
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
                         line_ending: envelope.payload.line_ending,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1479,7 +1585,28 @@ impl BufferStore {
         }
         match &self.state {
             BufferStoreState::Local(this) => this.reload_buffers(buffers, push_to_history, cx),
-            BufferStoreState::Remote(this) => this.reload_buffers(buffers, push_to_history, cx),
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, None, push_to_history, cx)
+            }
+        }
+    }
+
+    /// Reloads the buffer from disk, decoding its file with the given encoding from now on.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let buffers = HashSet::from_iter([buffer.clone()]);
+        match &self.state {
+            BufferStoreState::Local(this) => {
+                buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+                this.reload_buffers(buffers, true, cx)
+            }
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, Some(encoding), true, cx)
+            }
         }
     }
 
@@ -1489,11 +1616,20 @@ impl BufferStore {
         mut cx: AsyncApp,
     ) -> Result<proto::ReloadBuffersResponse> {
         let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let encoding = envelope
+            .payload
+            .encoding
+            .as_ref()
+            .and_then(deserialize_encoding);
         let reload = this.update(&mut cx, |this, cx| {
             let mut buffers = HashSet::default();
             for buffer_id in &envelope.payload.buffer_ids {
                 let buffer_id = BufferId::new(*buffer_id)?;
-                buffers.insert(this.get_existing(buffer_id)?);
+                let buffer = this.get_existing(buffer_id)?;
+                if let Some(encoding) = encoding {
+                    buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+                }
+                buffers.insert(buffer);
             }
             Ok::<_, anyhow::Error>(this.reload_buffers(buffers, false, cx))
         })??;
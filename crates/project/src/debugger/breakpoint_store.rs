@@ -4,7 +4,7 @@
 use anyhow::{Result, anyhow};
 use breakpoints_in_file::BreakpointsInFile;
 use collections::BTreeMap;
-use dap::client::SessionId;
+use dap::{StackFrameId, client::SessionId};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, Subscription, Task};
 use itertools::Itertools;
 use language::{Buffer, BufferSnapshot, proto::serialize_anchor as serialize_text_anchor};
@@ -110,8 +110,10 @@ enum BreakpointStoreMode {
 }
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    function_breakpoints: Vec<FunctionBreakpoint>,
+    data_breakpoints: Vec<DataBreakpoint>,
     downstream_client: Option<(AnyProtoClient, u64)>,
-    active_stack_frame: Option<(SessionId, Arc<Path>, text::Anchor)>,
+    active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
     mode: BreakpointStoreMode,
 }
@@ -120,10 +122,14 @@ impl BreakpointStore {
     pub fn init(client: &AnyProtoClient) {
         client.add_entity_request_handler(Self::handle_toggle_breakpoint);
         client.add_entity_message_handler(Self::handle_breakpoints_for_file);
+        client.add_entity_request_handler(Self::handle_set_function_and_data_breakpoints);
+        client.add_entity_message_handler(Self::handle_function_and_data_breakpoints);
     }
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
@@ -136,6 +142,8 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
+            data_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -240,8 +248,77 @@ impl BreakpointStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_function_and_data_breakpoints(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::FunctionAndDataBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let breakpoints = cx.update(|cx| this.read(cx).breakpoint_store())?;
+        breakpoints.update(&mut cx, |this, cx| {
+            // These come from the host, so they're applied without being synced back upstream.
+            this.function_breakpoints = message
+                .payload
+                .function_breakpoints
+                .into_iter()
+                .map(FunctionBreakpoint::from_proto)
+                .collect();
+            this.data_breakpoints = message
+                .payload
+                .data_breakpoints
+                .into_iter()
+                .map(DataBreakpoint::from_proto)
+                .collect();
+
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+            cx.notify();
+        })?;
+
+        Ok(())
+    }
+
+    async fn handle_set_function_and_data_breakpoints(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::SetFunctionAndDataBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let breakpoints = this.update(&mut cx, |this, _| this.breakpoint_store())?;
+        breakpoints.update(&mut cx, |this, cx| {
+            this.function_breakpoints = message
+                .payload
+                .function_breakpoints
+                .into_iter()
+                .map(FunctionBreakpoint::from_proto)
+                .collect();
+            this.function_breakpoints_changed(cx);
+
+            this.data_breakpoints = message
+                .payload
+                .data_breakpoints
+                .into_iter()
+                .map(DataBreakpoint::from_proto)
+                .collect();
+            this.data_breakpoints_changed(cx);
+        })?;
+
+        Ok(proto::Ack {})
+    }
+
     pub(crate) fn broadcast(&self) {
         if let Some((client, project_id)) = &self.downstream_client {
+            let _ = client.send(proto::FunctionAndDataBreakpoints {
+                project_id: *project_id,
+                function_breakpoints: self
+                    .function_breakpoints
+                    .iter()
+                    .map(FunctionBreakpoint::to_proto)
+                    .collect(),
+                data_breakpoints: self
+                    .data_breakpoints
+                    .iter()
+                    .map(DataBreakpoint::to_proto)
+                    .collect(),
+            });
             for (path, breakpoint_set) in &self.breakpoints {
                 let _ = client.send(proto::BreakpointsForFile {
                     project_id: *project_id,
@@ -465,6 +542,140 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+
+        if !self.function_breakpoints.is_empty() {
+            self.function_breakpoints.clear();
+            self.function_breakpoints_changed(cx);
+        }
+        if !self.data_breakpoints.is_empty() {
+            self.data_breakpoints.clear();
+            self.data_breakpoints_changed(cx);
+        }
+    }
+
+    pub fn function_breakpoints(&self) -> &[FunctionBreakpoint] {
+        &self.function_breakpoints
+    }
+
+    pub fn data_breakpoints(&self) -> &[DataBreakpoint] {
+        &self.data_breakpoints
+    }
+
+    /// Adds a breakpoint on the function with the given name, unless one already exists.
+    pub fn add_function_breakpoint(&mut self, name: Arc<str>, cx: &mut Context<Self>) {
+        if name.is_empty()
+            || self
+                .function_breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.name == name)
+        {
+            return;
+        }
+
+        self.function_breakpoints.push(FunctionBreakpoint {
+            name,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        });
+        self.function_breakpoints_changed(cx);
+    }
+
+    pub fn remove_function_breakpoint(&mut self, name: &str, cx: &mut Context<Self>) {
+        let len_before = self.function_breakpoints.len();
+        self.function_breakpoints
+            .retain(|breakpoint| breakpoint.name.as_ref() != name);
+        if len_before != self.function_breakpoints.len() {
+            self.function_breakpoints_changed(cx);
+        }
+    }
+
+    pub fn toggle_function_breakpoint_state(&mut self, name: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self
+            .function_breakpoints
+            .iter_mut()
+            .find(|breakpoint| breakpoint.name.as_ref() == name)
+        {
+            breakpoint.state = breakpoint.state.inverted();
+            self.function_breakpoints_changed(cx);
+        }
+    }
+
+    /// Adds a data breakpoint, replacing any existing breakpoint on the same data.
+    pub fn add_data_breakpoint(&mut self, breakpoint: DataBreakpoint, cx: &mut Context<Self>) {
+        if let Some(existing) = self
+            .data_breakpoints
+            .iter_mut()
+            .find(|existing| existing.data_id == breakpoint.data_id)
+        {
+            *existing = breakpoint;
+        } else {
+            self.data_breakpoints.push(breakpoint);
+        }
+        self.data_breakpoints_changed(cx);
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        let len_before = self.data_breakpoints.len();
+        self.data_breakpoints
+            .retain(|breakpoint| breakpoint.data_id.as_ref() != data_id);
+        if len_before != self.data_breakpoints.len() {
+            self.data_breakpoints_changed(cx);
+        }
+    }
+
+    pub fn toggle_data_breakpoint_state(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self
+            .data_breakpoints
+            .iter_mut()
+            .find(|breakpoint| breakpoint.data_id.as_ref() == data_id)
+        {
+            breakpoint.state = breakpoint.state.inverted();
+            self.data_breakpoints_changed(cx);
+        }
+    }
+
+    fn function_breakpoints_changed(&mut self, cx: &mut Context<Self>) {
+        self.sync_function_and_data_breakpoints(cx);
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
+    }
+
+    fn data_breakpoints_changed(&mut self, cx: &mut Context<Self>) {
+        self.sync_function_and_data_breakpoints(cx);
+        cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+        cx.notify();
+    }
+
+    /// Function and data breakpoints aren't tied to a buffer, so peers always exchange the whole set.
+    fn sync_function_and_data_breakpoints(&self, cx: &mut Context<Self>) {
+        let function_breakpoints = self
+            .function_breakpoints
+            .iter()
+            .map(FunctionBreakpoint::to_proto)
+            .collect();
+        let data_breakpoints = self
+            .data_breakpoints
+            .iter()
+            .map(DataBreakpoint::to_proto)
+            .collect();
+
+        if let BreakpointStoreMode::Remote(remote) = &self.mode {
+            cx.background_spawn(remote.upstream_client.request(
+                proto::SetFunctionAndDataBreakpoints {
+                    project_id: remote._upstream_project_id,
+                    function_breakpoints,
+                    data_breakpoints,
+                },
+            ))
+            .detach();
+        } else if let Some((client, project_id)) = &self.downstream_client {
+            let _ = client.send(proto::FunctionAndDataBreakpoints {
+                project_id: *project_id,
+                function_breakpoints,
+                data_breakpoints,
+            });
+        }
     }
 
     pub fn breakpoints<'a>(
@@ -493,7 +704,7 @@ impl BreakpointStore {
             })
     }
 
-    pub fn active_position(&self) -> Option<&(SessionId, Arc<Path>, text::Anchor)> {
+    pub fn active_position(&self) -> Option<&ActiveStackFrame> {
         self.active_stack_frame.as_ref()
     }
 
@@ -504,7 +715,7 @@ impl BreakpointStore {
     ) {
         if let Some(session_id) = session_id {
             self.active_stack_frame
-                .take_if(|(id, _, _)| *id == session_id);
+                .take_if(|frame| frame.session_id == session_id);
         } else {
             self.active_stack_frame.take();
         }
@@ -513,11 +724,7 @@ impl BreakpointStore {
         cx.notify();
     }
 
-    pub fn set_active_position(
-        &mut self,
-        position: (SessionId, Arc<Path>, text::Anchor),
-        cx: &mut Context<Self>,
-    ) {
+    pub fn set_active_position(&mut self, position: ActiveStackFrame, cx: &mut Context<Self>) {
         self.active_stack_frame = Some(position);
         cx.emit(BreakpointStoreEvent::ActiveDebugLineChanged);
         cx.notify();
@@ -672,6 +879,21 @@ impl BreakpointStore {
         }
     }
 
+    pub fn with_serialized_function_and_data_breakpoints(
+        &mut self,
+        function_breakpoints: Vec<FunctionBreakpoint>,
+        data_breakpoints: Vec<DataBreakpoint>,
+        cx: &mut Context<BreakpointStore>,
+    ) {
+        if let BreakpointStoreMode::Local(_) = &self.mode {
+            self.function_breakpoints = function_breakpoints;
+            self.data_breakpoints = data_breakpoints;
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+            cx.emit(BreakpointStoreEvent::DataBreakpointsUpdated);
+            cx.notify();
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) fn breakpoint_paths(&self) -> Vec<Arc<Path>> {
         self.breakpoints.keys().cloned().collect()
@@ -684,10 +906,21 @@ pub enum BreakpointUpdatedReason {
     FileSaved,
 }
 
+/// The stack frame selected in a debug session, whose line is highlighted in the editor.
+#[derive(Clone, Debug)]
+pub struct ActiveStackFrame {
+    pub session_id: SessionId,
+    pub stack_frame_id: StackFrameId,
+    pub path: Arc<Path>,
+    pub position: text::Anchor,
+}
+
 pub enum BreakpointStoreEvent {
     ActiveDebugLineChanged,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    FunctionBreakpointsUpdated,
+    DataBreakpointsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -727,6 +960,28 @@ impl BreakpointState {
             BreakpointState::Disabled => 1,
         }
     }
+
+    #[inline]
+    pub fn inverted(&self) -> Self {
+        match self {
+            BreakpointState::Enabled => BreakpointState::Disabled,
+            BreakpointState::Disabled => BreakpointState::Enabled,
+        }
+    }
+
+    fn to_proto(&self) -> i32 {
+        match self {
+            BreakpointState::Enabled => proto::BreakpointState::Enabled.into(),
+            BreakpointState::Disabled => proto::BreakpointState::Disabled.into(),
+        }
+    }
+
+    fn from_proto(state: i32) -> Self {
+        match proto::BreakpointState::from_i32(state) {
+            Some(proto::BreakpointState::Disabled) => BreakpointState::Disabled,
+            None | Some(proto::BreakpointState::Enabled) => BreakpointState::Enabled,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -832,3 +1087,157 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
         }
     }
 }
+
+/// Breakpoint that stops when a function with the given name is entered.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<BreakpointMessage>,
+    pub hit_condition: Option<BreakpointMessage>,
+    pub state: BreakpointState,
+}
+
+impl FunctionBreakpoint {
+    fn to_proto(&self) -> proto::FunctionBreakpoint {
+        proto::FunctionBreakpoint {
+            name: self.name.to_string(),
+            state: self.state.to_proto(),
+            condition: self.condition.as_ref().map(|s| String::from(s.as_ref())),
+            hit_condition: self
+                .hit_condition
+                .as_ref()
+                .map(|s| String::from(s.as_ref())),
+        }
+    }
+
+    fn from_proto(breakpoint: proto::FunctionBreakpoint) -> Self {
+        Self {
+            name: breakpoint.name.into(),
+            state: BreakpointState::from_proto(breakpoint.state),
+            condition: breakpoint.condition.map(Into::into),
+            hit_condition: breakpoint.hit_condition.map(Into::into),
+        }
+    }
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DataBreakpointAccessType {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl DataBreakpointAccessType {
+    #[inline]
+    pub fn to_int(&self) -> i32 {
+        match self {
+            DataBreakpointAccessType::Read => 0,
+            DataBreakpointAccessType::Write => 1,
+            DataBreakpointAccessType::ReadWrite => 2,
+        }
+    }
+
+    pub fn from_int(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(DataBreakpointAccessType::Read),
+            1 => Some(DataBreakpointAccessType::Write),
+            2 => Some(DataBreakpointAccessType::ReadWrite),
+            _ => None,
+        }
+    }
+}
+
+impl From<dap::DataBreakpointAccessType> for DataBreakpointAccessType {
+    fn from(access_type: dap::DataBreakpointAccessType) -> Self {
+        match access_type {
+            dap::DataBreakpointAccessType::Read => DataBreakpointAccessType::Read,
+            dap::DataBreakpointAccessType::Write => DataBreakpointAccessType::Write,
+            dap::DataBreakpointAccessType::ReadWrite => DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+impl From<DataBreakpointAccessType> for dap::DataBreakpointAccessType {
+    fn from(access_type: DataBreakpointAccessType) -> Self {
+        match access_type {
+            DataBreakpointAccessType::Read => dap::DataBreakpointAccessType::Read,
+            DataBreakpointAccessType::Write => dap::DataBreakpointAccessType::Write,
+            DataBreakpointAccessType::ReadWrite => dap::DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+/// Breakpoint that stops when the data identified by the adapter is accessed, e.g. when a
+/// variable's value changes.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DataBreakpoint {
+    /// The adapter's identifier for the data, as returned from a `dataBreakpointInfo` request.
+    pub data_id: Arc<str>,
+    pub description: Arc<str>,
+    pub access_type: Option<DataBreakpointAccessType>,
+    pub condition: Option<BreakpointMessage>,
+    pub hit_condition: Option<BreakpointMessage>,
+    pub state: BreakpointState,
+    /// Whether the adapter guarantees that `data_id` stays valid across debug sessions.
+    pub can_persist: bool,
+}
+
+impl DataBreakpoint {
+    fn to_proto(&self) -> proto::DataBreakpoint {
+        proto::DataBreakpoint {
+            data_id: self.data_id.to_string(),
+            description: self.description.to_string(),
+            access_type: self.access_type.map(|access_type| access_type.to_int()),
+            state: self.state.to_proto(),
+            condition: self.condition.as_ref().map(|s| String::from(s.as_ref())),
+            hit_condition: self
+                .hit_condition
+                .as_ref()
+                .map(|s| String::from(s.as_ref())),
+            can_persist: self.can_persist,
+        }
+    }
+
+    fn from_proto(breakpoint: proto::DataBreakpoint) -> Self {
+        Self {
+            data_id: breakpoint.data_id.into(),
+            description: breakpoint.description.into(),
+            access_type: breakpoint
+                .access_type
+                .and_then(DataBreakpointAccessType::from_int),
+            state: BreakpointState::from_proto(breakpoint.state),
+            condition: breakpoint.condition.map(Into::into),
+            hit_condition: breakpoint.hit_condition.map(Into::into),
+            can_persist: breakpoint.can_persist,
+        }
+    }
+}
+
+impl From<DataBreakpoint> for dap::DataBreakpoint {
+    fn from(bp: DataBreakpoint) -> Self {
+        Self {
+            data_id: String::from(bp.data_id.as_ref()),
+            access_type: bp.access_type.map(Into::into),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
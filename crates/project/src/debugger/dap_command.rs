@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::{Ok, Result, anyhow};
+use base64::prelude::*;
 use dap::{
     Capabilities, ContinueArguments, ExceptionFilterOptions, InitializeRequestArguments,
     InitializeRequestArgumentsPathFormat, NextArguments, SetVariableResponse, SourceBreakpoint,
@@ -14,6 +15,11 @@ use rpc::proto;
 use serde_json::Value;
 use util::ResultExt;
 
+use super::{
+    breakpoint_store::DataBreakpointAccessType,
+    session::{MemoryChunk, parse_memory_address},
+};
+
 pub(crate) trait LocalDapCommand: 'static + Send + Sync + std::fmt::Debug {
     type Response: 'static + Send + std::fmt::Debug;
     type DapRequest: 'static + Send + dap::requests::Request;
@@ -1704,6 +1710,62 @@ impl LocalDapCommand for SetExceptionBreakpoints {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetFunctionBreakpoints {
+    pub(super) breakpoints: Vec<dap::FunctionBreakpoint>,
+}
+
+impl LocalDapCommand for SetFunctionBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetFunctionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_function_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetFunctionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetDataBreakpoints {
+    pub(super) breakpoints: Vec<dap::DataBreakpoint>,
+}
+
+impl LocalDapCommand for SetDataBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetDataBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetDataBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub(super) struct LocationsCommand {
     pub(super) reference: u64,
@@ -1774,3 +1836,382 @@ impl DapCommand for LocationsCommand {
         })
     }
 }
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct DisassembleCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub instruction_offset: Option<i64>,
+    pub instruction_count: u64,
+    pub resolve_symbols: Option<bool>,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: self.resolve_symbols,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
+
+impl DapCommand for DisassembleCommand {
+    type ProtoRequest = proto::DapDisassembleRequest;
+    type ProtoResponse = proto::DapDisassembleResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            instruction_offset: request.instruction_offset,
+            instruction_count: request.instruction_count,
+            resolve_symbols: request.resolve_symbols,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapDisassembleRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: self.resolve_symbols,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapDisassembleResponse {
+            client_id: debug_client_id.to_proto(),
+            instructions: message
+                .into_iter()
+                .map(|instruction| proto::DapDisassembledInstruction {
+                    address: instruction.address,
+                    instruction_bytes: instruction.instruction_bytes,
+                    instruction: instruction.instruction,
+                    symbol: instruction.symbol,
+                    location: instruction.location.map(|source| source.to_proto()),
+                    line: instruction.line,
+                    column: instruction.column,
+                    end_line: instruction.end_line,
+                    end_column: instruction.end_column,
+                })
+                .collect(),
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(message
+            .instructions
+            .into_iter()
+            .map(|instruction| dap::DisassembledInstruction {
+                address: instruction.address,
+                instruction_bytes: instruction.instruction_bytes,
+                instruction: instruction.instruction,
+                symbol: instruction.symbol,
+                location: instruction.location.map(dap::Source::from_proto),
+                line: instruction.line,
+                column: instruction.column,
+                end_line: instruction.end_line,
+                end_column: instruction.end_column,
+                presentation_hint: None,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct ReadMemoryCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub count: u64,
+}
+
+impl LocalDapCommand for ReadMemoryCommand {
+    type Response = MemoryChunk;
+    type DapRequest = dap::requests::ReadMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_read_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::ReadMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        let address = parse_memory_address(&message.address)
+            .ok_or_else(|| anyhow!("Invalid memory address: {}", message.address))?;
+        let data = message
+            .data
+            .map(|data| BASE64_STANDARD.decode(data))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(MemoryChunk {
+            address,
+            data,
+            unreadable_bytes: message.unreadable_bytes.unwrap_or_default(),
+        })
+    }
+}
+
+impl DapCommand for ReadMemoryCommand {
+    type ProtoRequest = proto::DapReadMemoryRequest;
+    type ProtoResponse = proto::DapReadMemoryResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            count: request.count,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapReadMemoryRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapReadMemoryResponse {
+            client_id: debug_client_id.to_proto(),
+            address: message.address,
+            data: message.data,
+            unreadable_bytes: message.unreadable_bytes,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(MemoryChunk {
+            address: message.address,
+            data: message.data,
+            unreadable_bytes: message.unreadable_bytes,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct WriteMemoryCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub data: Vec<u8>,
+    pub allow_partial: Option<bool>,
+}
+
+impl LocalDapCommand for WriteMemoryCommand {
+    type Response = dap::WriteMemoryResponse;
+    type DapRequest = dap::requests::WriteMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_write_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::WriteMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            allow_partial: self.allow_partial,
+            data: BASE64_STANDARD.encode(&self.data),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for WriteMemoryCommand {
+    type ProtoRequest = proto::DapWriteMemoryRequest;
+    type ProtoResponse = proto::DapWriteMemoryResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            data: request.data.clone(),
+            allow_partial: request.allow_partial,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapWriteMemoryRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            data: self.data.clone(),
+            allow_partial: self.allow_partial,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapWriteMemoryResponse {
+            client_id: debug_client_id.to_proto(),
+            offset: message.offset,
+            bytes_written: message.bytes_written,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(dap::WriteMemoryResponse {
+            offset: message.offset,
+            bytes_written: message.bytes_written,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct DataBreakpointInfoCommand {
+    pub variables_reference: Option<u64>,
+    pub name: String,
+    pub frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for DataBreakpointInfoCommand {
+    type Response = dap::DataBreakpointInfoResponse;
+    type DapRequest = dap::requests::DataBreakpointInfo;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DataBreakpointInfoArguments {
+            variables_reference: self.variables_reference,
+            name: self.name.clone(),
+            frame_id: self.frame_id,
+            bytes: None,
+            as_address: None,
+            mode: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for DataBreakpointInfoCommand {
+    type ProtoRequest = proto::DapDataBreakpointInfoRequest;
+    type ProtoResponse = proto::DapDataBreakpointInfoResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            variables_reference: request.variables_reference,
+            name: request.name.clone(),
+            frame_id: request.frame_id,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapDataBreakpointInfoRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            variables_reference: self.variables_reference,
+            name: self.name.clone(),
+            frame_id: self.frame_id,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapDataBreakpointInfoResponse {
+            client_id: debug_client_id.to_proto(),
+            data_id: message.data_id,
+            description: message.description,
+            access_types: message
+                .access_types
+                .unwrap_or_default()
+                .into_iter()
+                .map(|access_type| DataBreakpointAccessType::from(access_type).to_int())
+                .collect(),
+            can_persist: message.can_persist,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(dap::DataBreakpointInfoResponse {
+            data_id: message.data_id,
+            description: message.description,
+            access_types: Some(
+                message
+                    .access_types
+                    .into_iter()
+                    .filter_map(DataBreakpointAccessType::from_int)
+                    .map(Into::into)
+                    .collect(),
+            ),
+            can_persist: message.can_persist,
+        })
+    }
+}
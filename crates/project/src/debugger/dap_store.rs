@@ -397,6 +397,7 @@ impl DapStore {
         DapAdapterDelegate::new(
             local_store.fs.clone(),
             worktree.read(cx).id(),
+            worktree.read(cx).abs_path(),
             local_store.node_runtime.clone(),
             local_store.http_client.clone(),
             local_store.language_registry.clone(),
@@ -961,6 +962,7 @@ fn create_new_session(
 pub struct DapAdapterDelegate {
     fs: Arc<dyn Fs>,
     worktree_id: WorktreeId,
+    worktree_root_path: Arc<Path>,
     node_runtime: NodeRuntime,
     http_client: Arc<dyn HttpClient>,
     language_registry: Arc<LanguageRegistry>,
@@ -973,6 +975,7 @@ impl DapAdapterDelegate {
     pub fn new(
         fs: Arc<dyn Fs>,
         worktree_id: WorktreeId,
+        worktree_root_path: Arc<Path>,
         node_runtime: NodeRuntime,
         http_client: Arc<dyn HttpClient>,
         language_registry: Arc<LanguageRegistry>,
@@ -982,6 +985,7 @@ impl DapAdapterDelegate {
         Self {
             fs,
             worktree_id,
+            worktree_root_path,
             http_client,
             node_runtime,
             toolchain_store,
@@ -998,6 +1002,10 @@ impl dap::adapters::DapDelegate for DapAdapterDelegate {
         self.worktree_id
     }
 
+    fn worktree_root_path(&self) -> &Path {
+        &self.worktree_root_path
+    }
+
     fn http_client(&self) -> Arc<dyn HttpClient> {
         self.http_client.clone()
     }
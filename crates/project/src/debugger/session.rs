@@ -2,12 +2,13 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand,
-    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
-    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, ReadMemoryCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand, StepBackCommand,
+    StepCommand, StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand,
+    ThreadsCommand, VariablesCommand, WriteMemoryCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
@@ -76,6 +77,29 @@ impl From<dap::StackFrame> for StackFrame {
     }
 }
 
+/// A contiguous range of debuggee memory, as returned by a `readMemory` request.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryChunk {
+    /// The address of the first byte of `data`.
+    pub address: u64,
+    pub data: Vec<u8>,
+    /// The number of bytes after `data` that couldn't be read.
+    pub unreadable_bytes: u64,
+}
+
+/// Parses a memory address as reported by a debug adapter, which is either a hexadecimal
+/// string prefixed with `0x` or a decimal number.
+pub fn parse_memory_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    match address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => address.parse().ok(),
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum ThreadStatus {
     #[default]
@@ -306,6 +330,7 @@ impl LocalMode {
     fn send_exception_breakpoints(
         &self,
         filters: Vec<ExceptionBreakpointsFilter>,
+        conditions: &HashMap<String, Arc<str>>,
         supports_filter_options: bool,
         cx: &App,
     ) -> Task<Result<Vec<dap::Breakpoint>>> {
@@ -314,8 +339,10 @@ impl LocalMode {
                 filters: filters
                     .into_iter()
                     .map(|filter| ExceptionFilterOptions {
+                        condition: conditions
+                            .get(&filter.filter)
+                            .map(|condition| condition.to_string()),
                         filter_id: filter.filter,
-                        condition: None,
                         mode: None,
                     })
                     .collect(),
@@ -328,6 +355,62 @@ impl LocalMode {
         self.request(arg, cx.background_executor().clone())
     }
 
+    fn send_function_breakpoints(&self, ignore_breakpoints: bool, cx: &App) -> Task<()> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            self.breakpoint_store.read_with(cx, |store, _| {
+                store
+                    .function_breakpoints()
+                    .iter()
+                    .filter(|bp| bp.state.is_enabled())
+                    .cloned()
+                    .map(Into::into)
+                    .collect()
+            })
+        };
+
+        let task = self.request(
+            dap_command::SetFunctionBreakpoints { breakpoints },
+            cx.background_executor().clone(),
+        );
+
+        cx.background_spawn(async move {
+            match task.await {
+                Ok(_) => {}
+                Err(err) => log::warn!("Set function breakpoints request failed: {}", err),
+            }
+        })
+    }
+
+    fn send_data_breakpoints(&self, ignore_breakpoints: bool, cx: &App) -> Task<()> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            self.breakpoint_store.read_with(cx, |store, _| {
+                store
+                    .data_breakpoints()
+                    .iter()
+                    .filter(|bp| bp.state.is_enabled())
+                    .cloned()
+                    .map(Into::into)
+                    .collect()
+            })
+        };
+
+        let task = self.request(
+            dap_command::SetDataBreakpoints { breakpoints },
+            cx.background_executor().clone(),
+        );
+
+        cx.background_spawn(async move {
+            match task.await {
+                Ok(_) => {}
+                Err(err) => log::warn!("Set data breakpoints request failed: {}", err),
+            }
+        })
+    }
+
     fn send_source_breakpoints(
         &self,
         ignore_breakpoints: bool,
@@ -426,6 +509,9 @@ impl LocalMode {
         let supports_exception_filters = capabilities
             .supports_exception_filter_options
             .unwrap_or_default();
+        let supports_function_breakpoints =
+            dap_command::SetFunctionBreakpoints::is_supported(capabilities);
+        let supports_data_breakpoints = dap_command::SetDataBreakpoints::is_supported(capabilities);
         let configuration_sequence = cx.spawn({
             let this = self.clone();
             let worktree = self.worktree().clone();
@@ -465,12 +551,20 @@ impl LocalMode {
                 cx.update(|cx| {
                     this.send_exception_breakpoints(
                         exception_filters,
+                        &HashMap::default(),
                         supports_exception_filters,
                         cx,
                     )
                 })?
                 .await
                 .ok();
+                if supports_function_breakpoints {
+                    cx.update(|cx| this.send_function_breakpoints(false, cx))?
+                        .await;
+                }
+                if supports_data_breakpoints {
+                    cx.update(|cx| this.send_data_breakpoints(false, cx))?.await;
+                }
                 let ret = if configuration_done_supported {
                     this.request(ConfigurationDone {}, cx.background_executor().clone())
                 } else {
@@ -626,6 +720,7 @@ pub struct Session {
     is_session_terminated: bool,
     requests: HashMap<TypeId, HashMap<RequestSlot, Shared<Task<Option<()>>>>>,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
+    exception_breakpoint_conditions: HashMap<String, Arc<str>>,
     _background_tasks: Vec<Task<()>>,
 }
 
@@ -708,6 +803,8 @@ pub enum SessionEvent {
     StackTrace,
     Variables,
     Threads,
+    /// The debuggee's memory was changed, either by the adapter or through `write_memory`.
+    Memory,
 }
 
 pub(super) enum SessionStateEvent {
@@ -793,6 +890,7 @@ impl Session {
             locations: Default::default(),
             is_session_terminated: false,
             exception_breakpoints: Default::default(),
+            exception_breakpoint_conditions: Default::default(),
         }
     }
 
@@ -1132,7 +1230,10 @@ impl Session {
                 self.capabilities = self.capabilities.merge(event.capabilities);
                 cx.notify();
             }
-            Events::Memory(_) => {}
+            Events::Memory(_) => {
+                cx.emit(SessionEvent::Memory);
+                cx.notify();
+            }
             Events::Process(_) => {}
             Events::ProgressEnd(_) => {}
             Events::ProgressStart(_) => {}
@@ -1349,6 +1450,12 @@ impl Session {
         self.ignore_breakpoints = ignore;
 
         if let Some(local) = self.as_local() {
+            if dap_command::SetFunctionBreakpoints::is_supported(&self.capabilities) {
+                local.send_function_breakpoints(ignore, cx).detach();
+            }
+            if dap_command::SetDataBreakpoints::is_supported(&self.capabilities) {
+                local.send_data_breakpoints(ignore, cx).detach();
+            }
             local.send_source_breakpoints(ignore, cx)
         } else {
             // todo(debugger): We need to propagate this change to downstream sessions and send a message to upstream sessions
@@ -1369,6 +1476,34 @@ impl Session {
         }
     }
 
+    pub fn exception_breakpoint_condition(&self, id: &str) -> Option<&Arc<str>> {
+        self.exception_breakpoint_conditions.get(id)
+    }
+
+    /// Sets the condition under which the exception filter with the given id stops,
+    /// for adapters whose filters support conditions.
+    pub fn set_exception_breakpoint_condition(
+        &mut self,
+        id: &str,
+        condition: Option<Arc<str>>,
+        cx: &App,
+    ) {
+        if !self.exception_breakpoints.contains_key(id) {
+            return;
+        }
+
+        match condition.filter(|condition| !condition.trim().is_empty()) {
+            Some(condition) => {
+                self.exception_breakpoint_conditions
+                    .insert(id.to_string(), condition);
+            }
+            None => {
+                self.exception_breakpoint_conditions.remove(id);
+            }
+        }
+        self.send_exception_breakpoints(cx);
+    }
+
     fn send_exception_breakpoints(&mut self, cx: &App) {
         if let Some(local) = self.as_local() {
             let exception_filters = self
@@ -1382,7 +1517,12 @@ impl Session {
                 .supports_exception_filter_options
                 .unwrap_or_default();
             local
-                .send_exception_breakpoints(exception_filters, supports_exception_filters, cx)
+                .send_exception_breakpoints(
+                    exception_filters,
+                    &self.exception_breakpoint_conditions,
+                    supports_exception_filters,
+                    cx,
+                )
                 .detach_and_log_err(cx);
         } else {
             debug_assert!(false, "Not implemented");
@@ -1859,7 +1999,10 @@ impl Session {
         frame_id: Option<u64>,
         source: Option<Source>,
         cx: &mut Context<Self>,
-    ) {
+    ) -> Task<Option<dap::EvaluateResponse>> {
+        // Watch expressions are re-evaluated on every stop, so their results (and failures) are
+        // reported to the watch list instead of the console.
+        let is_watch = matches!(context, Some(EvaluateArgumentsContext::Watch));
         self.request(
             EvaluateCommand {
                 expression,
@@ -1867,7 +2010,11 @@ impl Session {
                 frame_id,
                 source,
             },
-            |this, response, cx| {
+            move |this, response, cx| {
+                if is_watch {
+                    return response.ok();
+                }
+
                 let response = response.log_err()?;
                 this.output_token.0 += 1;
                 this.output.push_back(dap::OutputEvent {
@@ -1888,7 +2035,6 @@ impl Session {
             },
             cx,
         )
-        .detach();
     }
 
     pub fn location(
@@ -1907,6 +2053,157 @@ impl Session {
         );
         self.locations.get(&reference).cloned()
     }
+
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions
+    /// away from the given memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<dap::DisassembledInstruction>>> {
+        let task = self.request(
+            DisassembleCommand {
+                memory_reference,
+                offset: None,
+                instruction_offset: Some(instruction_offset),
+                instruction_count,
+                resolve_symbols: Some(true),
+            },
+            |_, result, _| result.log_err(),
+            cx,
+        );
+
+        cx.background_executor().spawn(async move {
+            task.await
+                .ok_or_else(|| anyhow!("failed to disassemble instructions"))
+        })
+    }
+
+    pub fn read_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<MemoryChunk>> {
+        let task = self.request(
+            ReadMemoryCommand {
+                memory_reference,
+                offset: Some(offset),
+                count,
+            },
+            |_, result, _| result.log_err(),
+            cx,
+        );
+
+        cx.background_executor()
+            .spawn(async move { task.await.ok_or_else(|| anyhow!("failed to read memory")) })
+    }
+
+    /// Fetches the variables in all of the given stack frame's scopes that are cheap to
+    /// retrieve, such as its locals and arguments.
+    pub fn frame_variables(
+        &mut self,
+        stack_frame_id: StackFrameId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<dap::Variable>>> {
+        let scopes = self.request(
+            ScopesCommand { stack_frame_id },
+            |_, result, _| result.log_err(),
+            cx,
+        );
+
+        cx.spawn(async move |this, cx| {
+            let scopes = scopes
+                .await
+                .ok_or_else(|| anyhow!("failed to fetch scopes"))?;
+            let variables = this.update(cx, |this, cx| {
+                scopes
+                    .into_iter()
+                    .filter(|scope| !scope.expensive)
+                    .map(|scope| {
+                        this.request(
+                            VariablesCommand {
+                                variables_reference: scope.variables_reference,
+                                filter: None,
+                                start: None,
+                                count: None,
+                                format: None,
+                            },
+                            |_, result, _| result.log_err(),
+                            cx,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+
+            Ok(futures::future::join_all(variables)
+                .await
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect())
+        })
+    }
+
+    /// Asks the adapter whether a data breakpoint can be set on the named child of the given
+    /// variables container, and what the breakpoint's data id should be.
+    pub fn data_breakpoint_info(
+        &mut self,
+        variables_reference: VariableReference,
+        name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<dap::DataBreakpointInfoResponse>> {
+        let task = self.request(
+            DataBreakpointInfoCommand {
+                variables_reference: Some(variables_reference),
+                name,
+                frame_id: None,
+            },
+            |_, result, _| result.log_err(),
+            cx,
+        );
+
+        cx.background_executor().spawn(async move {
+            task.await
+                .ok_or_else(|| anyhow!("failed to get data breakpoint info"))
+        })
+    }
+
+    pub fn write_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        data: Vec<u8>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let task = self.request(
+            WriteMemoryCommand {
+                memory_reference,
+                offset: Some(offset),
+                data,
+                allow_partial: Some(false),
+            },
+            |this, response, cx| {
+                let response = response.log_err()?;
+                // Variables may be backed by the memory we just wrote to.
+                this.invalidate_command_type::<VariablesCommand>();
+                this.variables.clear();
+                cx.emit(SessionEvent::Memory);
+                cx.notify();
+                Some(response)
+            },
+            cx,
+        );
+
+        cx.background_executor().spawn(async move {
+            task.await
+                .map(|_| ())
+                .ok_or_else(|| anyhow!("failed to write memory"))
+        })
+    }
     pub fn disconnect_client(&mut self, cx: &mut Context<Self>) {
         let command = DisconnectCommand {
             restart: Some(false),
@@ -1990,6 +2287,26 @@ fn create_local_session(
                 local.unset_breakpoints_from_paths(paths, cx).detach();
             }
         }
+        BreakpointStoreEvent::FunctionBreakpointsUpdated => {
+            if dap_command::SetFunctionBreakpoints::is_supported(&this.capabilities) {
+                if let Some(local) = (!this.ignore_breakpoints)
+                    .then(|| this.as_local_mut())
+                    .flatten()
+                {
+                    local.send_function_breakpoints(false, cx).detach();
+                }
+            }
+        }
+        BreakpointStoreEvent::DataBreakpointsUpdated => {
+            if dap_command::SetDataBreakpoints::is_supported(&this.capabilities) {
+                if let Some(local) = (!this.ignore_breakpoints)
+                    .then(|| this.as_local_mut())
+                    .flatten()
+                {
+                    local.send_data_breakpoints(false, cx).detach();
+                }
+            }
+        }
         BreakpointStoreEvent::ActiveDebugLineChanged => {}
     })
     .detach();
@@ -2012,6 +2329,7 @@ fn create_local_session(
         stack_frames: IndexMap::default(),
         locations: Default::default(),
         exception_breakpoints: Default::default(),
+        exception_breakpoint_conditions: Default::default(),
         _background_tasks,
         is_session_terminated: false,
     }
@@ -1,10 +1,12 @@
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, DocumentHighlight, DocumentSymbol, Hover,
-    HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
-    InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, LspAction, MarkupContent,
-    PrepareRenameResponse, ProjectTransaction, ResolveState,
+    CallHierarchyCall, CallHierarchyItem, CodeAction, ColorPresentation, CompletionSource,
+    CoreCompletion, DocumentColor, DocumentHighlight, DocumentLink, DocumentSymbol, FoldingRange,
+    Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    InlayHintLabelPartTooltip, InlayHintTooltip, InlineValue, InlineValueKind, Location,
+    LocationLink, LspAction, MarkupContent, PrepareRenameResponse, ProjectTransaction,
+    ResolveState, TypeHierarchyItem,
     lsp_store::{LocalLspStore, LspStore},
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -256,6 +258,63 @@ pub(crate) struct LinkedEditingRange {
     pub position: Anchor,
 }
 
+#[derive(Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSupertypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSubtypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetFoldingRanges;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetInlineValues {
+    pub range: Range<Anchor>,
+    pub stack_frame_id: u64,
+    pub stopped_location: Range<Anchor>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
+#[derive(Debug)]
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<PointUtf16>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentColors;
+
+#[derive(Debug)]
+pub(crate) struct GetColorPresentations {
+    pub range: Range<PointUtf16>,
+    pub color: lsp::Color,
+}
+
 #[async_trait(?Send)]
 impl LspCommand for PrepareRename {
     type Response = PrepareRenameResponse;
@@ -3643,3 +3702,2039 @@ impl LspCommand for LinkedEditingRange {
         BufferId::new(message.buffer_id)
     }
 }
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match &capabilities.server_capabilities.call_hierarchy_provider {
+            Some(lsp::CallHierarchyServerCapability::Simple(has_support)) => *has_support,
+            Some(lsp::CallHierarchyServerCapability::Options(_)) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        let mut items = Vec::new();
+        for lsp_item in message.unwrap_or_default() {
+            items.push(
+                call_hierarchy_item_from_lsp(
+                    lsp_item,
+                    &lsp_store,
+                    &lsp_adapter,
+                    &language_server,
+                    &mut cx,
+                )
+                .await?,
+            );
+        }
+        Ok(items)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| call_hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(call_hierarchy_item_from_proto(item, &lsp_store, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn status(&self) -> Option<String> {
+        Some("Finding incoming calls...".to_owned())
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .call_hierarchy_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        let mut calls = Vec::new();
+        for incoming_call in message.unwrap_or_default() {
+            let item = call_hierarchy_item_from_lsp(
+                incoming_call.from,
+                &lsp_store,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?;
+            // The call sites of an incoming call are located in the caller.
+            let caller_buffer = item.location.buffer.clone();
+            let call_sites = caller_buffer.read_with(&cx, |caller, _| {
+                incoming_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: caller_buffer.clone(),
+                        range: anchor_range_from_lsp(caller, range),
+                    })
+                    .collect()
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing call hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: response
+                .into_iter()
+                .map(|call| call_hierarchy_call_to_proto(call, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.calls {
+            calls.push(call_hierarchy_call_from_proto(call, &lsp_store, &mut cx).await?);
+        }
+        Ok(calls)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn status(&self) -> Option<String> {
+        Some("Finding outgoing calls...".to_owned())
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .call_hierarchy_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let outgoing_calls = message.unwrap_or_default();
+        if outgoing_calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        // The call sites of an outgoing call are located in the item the calls were requested for.
+        let caller_buffer = lsp_store
+            .update(&mut cx, |lsp_store, cx| {
+                lsp_store.open_local_buffer_via_lsp(
+                    self.item.uri.clone(),
+                    language_server.server_id(),
+                    lsp_adapter.name.clone(),
+                    cx,
+                )
+            })?
+            .await?;
+
+        let mut calls = Vec::new();
+        for outgoing_call in outgoing_calls {
+            let item = call_hierarchy_item_from_lsp(
+                outgoing_call.to,
+                &lsp_store,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?;
+            let call_sites = caller_buffer.read_with(&cx, |caller, _| {
+                outgoing_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: caller_buffer.clone(),
+                        range: anchor_range_from_lsp(caller, range),
+                    })
+                    .collect()
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing call hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: response
+                .into_iter()
+                .map(|call| call_hierarchy_call_to_proto(call, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.calls {
+            calls.push(call_hierarchy_call_from_proto(call, &lsp_store, &mut cx).await?);
+        }
+        Ok(calls)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    // `lsp::ServerCapabilities` has no `typeHierarchyProvider` field, so the request is sent
+    // to the first server for the buffer, which replies with an error if it is unsupported.
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message, &lsp_store, &buffer, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn status(&self) -> Option<String> {
+        Some("Finding supertypes...".to_owned())
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message, &lsp_store, &buffer, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing type hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn status(&self) -> Option<String> {
+        Some("Finding subtypes...".to_owned())
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message, &lsp_store, &buffer, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing type hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    /// `None` if no language server of the buffer provides folding ranges.
+    type Response = Option<Vec<FoldingRange>>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn display_name(&self) -> &str {
+        "Folding ranges"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.folding_range_provider {
+            Some(lsp::FoldingRangeProviderCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::FoldingRangeParams> {
+        Ok(lsp::FoldingRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::FoldingRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<FoldingRange>>> {
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        let max_row = snapshot.max_point_utf16().row;
+        let mut ranges = message
+            .into_iter()
+            .filter(|range| range.start_line < range.end_line && range.end_line <= max_row)
+            .map(|range| {
+                // Columns that are omitted, or past the end of the line, are
+                // clipped to the end of the line.
+                let start =
+                    PointUtf16::new(range.start_line, range.start_character.unwrap_or(u32::MAX));
+                let end = PointUtf16::new(range.end_line, range.end_character.unwrap_or(u32::MAX));
+                let start = snapshot.clip_point_utf16(Unclipped(start), Bias::Left);
+                let end = snapshot.clip_point_utf16(Unclipped(end), Bias::Right);
+                FoldingRange {
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    kind: range.kind,
+                }
+            })
+            .collect::<Vec<_>>();
+        ranges.sort_by(|a, b| {
+            a.range
+                .start
+                .cmp(&b.range.start, &snapshot)
+                .then_with(|| b.range.end.cmp(&a.range.end, &snapshot))
+        });
+        Ok(Some(ranges))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetFoldingRanges {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetFoldingRanges,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Option<Vec<FoldingRange>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: response.map(|ranges| proto::FoldingRanges {
+                ranges: ranges
+                    .into_iter()
+                    .map(|range| proto::FoldingRange {
+                        start: Some(serialize_anchor(&range.range.start)),
+                        end: Some(serialize_anchor(&range.range.end)),
+                        kind: range.kind.map(|kind| {
+                            match kind {
+                                lsp::FoldingRangeKind::Comment => "comment",
+                                lsp::FoldingRangeKind::Imports => "imports",
+                                lsp::FoldingRangeKind::Region => "region",
+                            }
+                            .to_string()
+                        }),
+                    })
+                    .collect(),
+            }),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<FoldingRange>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let Some(ranges) = message.ranges else {
+            return Ok(None);
+        };
+        ranges
+            .ranges
+            .into_iter()
+            .map(|range| {
+                let start = range
+                    .start
+                    .and_then(deserialize_anchor)
+                    .context("invalid folding range start")?;
+                let end = range
+                    .end
+                    .and_then(deserialize_anchor)
+                    .context("invalid folding range end")?;
+                Ok(FoldingRange {
+                    range: start..end,
+                    kind: range.kind.map(|kind| match kind.as_str() {
+                        "comment" => lsp::FoldingRangeKind::Comment,
+                        "imports" => lsp::FoldingRangeKind::Imports,
+                        _ => lsp::FoldingRangeKind::Region,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetFoldingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetInlineValues {
+    /// `None` if no language server of the buffer provides inline values.
+    type Response = Option<Vec<InlineValue>>;
+    type LspRequest = lsp::InlineValueRequest;
+    type ProtoRequest = proto::GetInlineValues;
+
+    fn display_name(&self) -> &str {
+        "Inline values"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.inline_value_provider {
+            Some(lsp::OneOf::Left(enabled)) => enabled,
+            Some(lsp::OneOf::Right(_)) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::InlineValueParams> {
+        Ok(lsp::InlineValueParams {
+            text_document: make_text_document_identifier(path)?,
+            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            context: lsp::InlineValueContext {
+                frame_id: i32::try_from(self.stack_frame_id)
+                    .context("stack frame id out of range")?,
+                stopped_location: range_to_lsp(self.stopped_location.to_point_utf16(buffer))?,
+            },
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::InlineValue>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<InlineValue>>> {
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+        let values = message
+            .into_iter()
+            .map(|value| {
+                let (range, kind) = match value {
+                    lsp::InlineValue::Text(value) => {
+                        (value.range, InlineValueKind::Text(value.text))
+                    }
+                    lsp::InlineValue::VariableLookup(value) => (
+                        value.range,
+                        InlineValueKind::VariableLookup {
+                            variable_name: value.variable_name,
+                            case_sensitive: value.case_sensitive_lookup,
+                        },
+                    ),
+                    lsp::InlineValue::EvaluatableExpression(value) => (
+                        value.range,
+                        InlineValueKind::EvaluatableExpression(value.expression),
+                    ),
+                };
+                let start = snapshot.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                let end = snapshot.clip_point_utf16(point_from_lsp(range.end), Bias::Right);
+                InlineValue {
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    kind,
+                }
+            })
+            .collect();
+        Ok(Some(values))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetInlineValues {
+        proto::GetInlineValues {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            start: Some(serialize_anchor(&self.range.start)),
+            end: Some(serialize_anchor(&self.range.end)),
+            stack_frame_id: self.stack_frame_id,
+            stopped_location_start: Some(serialize_anchor(&self.stopped_location.start)),
+            stopped_location_end: Some(serialize_anchor(&self.stopped_location.end)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetInlineValues,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let anchor = |anchor: Option<proto::Anchor>| {
+            anchor
+                .and_then(deserialize_anchor)
+                .context("invalid inline values range")
+        };
+        let range = anchor(message.start)?..anchor(message.end)?;
+        let stopped_location =
+            anchor(message.stopped_location_start)?..anchor(message.stopped_location_end)?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            range,
+            stack_frame_id: message.stack_frame_id,
+            stopped_location,
+        })
+    }
+
+    fn response_to_proto(
+        response: Option<Vec<InlineValue>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetInlineValuesResponse {
+        proto::GetInlineValuesResponse {
+            values: response.map(|values| proto::InlineValues {
+                values: values
+                    .into_iter()
+                    .map(|value| proto::InlineValue {
+                        start: Some(serialize_anchor(&value.range.start)),
+                        end: Some(serialize_anchor(&value.range.end)),
+                        kind: Some(match value.kind {
+                            InlineValueKind::Text(text) => proto::inline_value::Kind::Text(text),
+                            InlineValueKind::VariableLookup {
+                                variable_name,
+                                case_sensitive,
+                            } => proto::inline_value::Kind::VariableLookup(
+                                proto::InlineValueVariableLookup {
+                                    variable_name,
+                                    case_sensitive,
+                                },
+                            ),
+                            InlineValueKind::EvaluatableExpression(expression) => {
+                                proto::inline_value::Kind::EvaluatableExpression(
+                                    proto::InlineValueEvaluatableExpression { expression },
+                                )
+                            }
+                        }),
+                    })
+                    .collect(),
+            }),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetInlineValuesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<InlineValue>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let Some(values) = message.values else {
+            return Ok(None);
+        };
+        values
+            .values
+            .into_iter()
+            .map(|value| {
+                let start = value
+                    .start
+                    .and_then(deserialize_anchor)
+                    .context("invalid inline value start")?;
+                let end = value
+                    .end
+                    .and_then(deserialize_anchor)
+                    .context("invalid inline value end")?;
+                let kind = match value.kind.context("missing inline value kind")? {
+                    proto::inline_value::Kind::Text(text) => InlineValueKind::Text(text),
+                    proto::inline_value::Kind::VariableLookup(lookup) => {
+                        InlineValueKind::VariableLookup {
+                            variable_name: lookup.variable_name,
+                            case_sensitive: lookup.case_sensitive,
+                        }
+                    }
+                    proto::inline_value::Kind::EvaluatableExpression(expression) => {
+                        InlineValueKind::EvaluatableExpression(expression.expression)
+                    }
+                };
+                Ok(InlineValue {
+                    range: start..end,
+                    kind,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetInlineValues) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        let (_, lsp_server) = language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        let can_resolve = Self::can_resolve_links(&lsp_server.capabilities());
+        buffer.update(&mut cx, |buffer, _| {
+            let mut links = message
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|link| link.target.is_some() || can_resolve)
+                .map(|link| {
+                    let resolve_state = if link.target.is_none() {
+                        ResolveState::CanResolve(server_id, link.data.clone())
+                    } else {
+                        ResolveState::Resolved
+                    };
+                    Self::lsp_to_project_link(link, buffer, resolve_state)
+                })
+                .collect::<Vec<_>>();
+            links.sort_by(|a, b| a.range.start.cmp(&b.range.start, buffer));
+            links
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentLink>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response
+                .into_iter()
+                .map(Self::project_to_proto_link)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(Self::proto_to_project_link)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl GetDocumentLinks {
+    pub fn can_resolve_links(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .document_link_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub fn lsp_to_project_link(
+        link: lsp::DocumentLink,
+        buffer: &Buffer,
+        resolve_state: ResolveState,
+    ) -> DocumentLink {
+        let start = buffer.clip_point_utf16(point_from_lsp(link.range.start), Bias::Left);
+        let end = buffer.clip_point_utf16(point_from_lsp(link.range.end), Bias::Left);
+        DocumentLink {
+            range: buffer.anchor_after(start)..buffer.anchor_before(end),
+            target: link.target,
+            tooltip: link.tooltip,
+            resolve_state,
+        }
+    }
+
+    pub fn project_to_lsp_link(link: DocumentLink, buffer: &Buffer) -> lsp::DocumentLink {
+        lsp::DocumentLink {
+            range: range_to_lsp(link.range.to_point_utf16(buffer)),
+            target: link.target,
+            tooltip: link.tooltip,
+            data: match link.resolve_state {
+                ResolveState::CanResolve(_, data) => data,
+                ResolveState::Resolving | ResolveState::Resolved => None,
+            },
+        }
+    }
+
+    pub fn project_to_proto_link(link: DocumentLink) -> proto::DocumentLink {
+        let (state, lsp_resolve_state) = match link.resolve_state {
+            ResolveState::Resolved => (proto::resolve_state::State::Resolved, None),
+            ResolveState::CanResolve(server_id, resolve_data) => (
+                proto::resolve_state::State::CanResolve,
+                Some(proto::resolve_state::LspResolveState {
+                    server_id: server_id.0 as u64,
+                    value: resolve_data.map(|json_data| {
+                        serde_json::to_string(&json_data)
+                            .expect("failed to serialize resolve json data")
+                    }),
+                }),
+            ),
+            ResolveState::Resolving => (proto::resolve_state::State::Resolving, None),
+        };
+        proto::DocumentLink {
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            target: link.target.map(|target| target.to_string()),
+            tooltip: link.tooltip,
+            resolve_state: Some(proto::ResolveState {
+                state: state.into(),
+                lsp_resolve_state,
+            }),
+        }
+    }
+
+    pub fn proto_to_project_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid document link start")?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid document link end")?;
+        let target = link
+            .target
+            .map(|target| lsp::Url::parse(&target))
+            .transpose()
+            .context("invalid document link target")?;
+        let resolve_state = link
+            .resolve_state
+            .context("missing document link resolve state")?;
+        let resolve_state = match proto::resolve_state::State::from_i32(resolve_state.state) {
+            Some(proto::resolve_state::State::Resolved) => ResolveState::Resolved,
+            Some(proto::resolve_state::State::CanResolve) => {
+                let lsp_resolve_state = resolve_state
+                    .lsp_resolve_state
+                    .context("missing document link resolve data")?;
+                let data = lsp_resolve_state
+                    .value
+                    .map(|value| serde_json::from_str::<lsp::LSPAny>(&value))
+                    .transpose()
+                    .context("invalid document link resolve data")?;
+                ResolveState::CanResolve(
+                    LanguageServerId(lsp_resolve_state.server_id as usize),
+                    data,
+                )
+            }
+            Some(proto::resolve_state::State::Resolving) => ResolveState::Resolving,
+            None => anyhow::bail!("invalid document link resolve state"),
+        };
+        Ok(DocumentLink {
+            range: start..end,
+            target,
+            tooltip: link.tooltip,
+            resolve_state,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    /// For each position, the ranges around it from the innermost to the
+    /// outermost. `None` if no language server of the buffer provides
+    /// selection ranges.
+    type Response = Option<Vec<Vec<Range<Anchor>>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn display_name(&self) -> &str {
+        "Selection ranges"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.selection_range_provider {
+            Some(lsp::SelectionRangeProviderCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SelectionRangeParams> {
+        Ok(lsp::SelectionRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(*position))
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::SelectionRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<Vec<Range<Anchor>>>>> {
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        if message.len() != self.positions.len() {
+            return Err(anyhow!(
+                "expected {} selection ranges, got {}",
+                self.positions.len(),
+                message.len()
+            ));
+        }
+        buffer
+            .update(&mut cx, |buffer, _| {
+                message
+                    .into_iter()
+                    .map(|selection_range| {
+                        let mut ranges = Vec::new();
+                        let mut next = Some(selection_range);
+                        while let Some(selection_range) = next {
+                            let start = buffer.clip_point_utf16(
+                                point_from_lsp(selection_range.range.start),
+                                Bias::Left,
+                            );
+                            let end = buffer.clip_point_utf16(
+                                point_from_lsp(selection_range.range.end),
+                                Bias::Right,
+                            );
+                            let range = buffer.anchor_before(start)..buffer.anchor_after(end);
+                            if ranges.last() != Some(&range) {
+                                ranges.push(range);
+                            }
+                            next = selection_range.parent.map(|parent| *parent);
+                        }
+                        ranges
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map(Some)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            positions: self
+                .positions
+                .iter()
+                .map(|position| serialize_anchor(&buffer.anchor_before(*position)))
+                .collect(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSelectionRanges,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).context("invalid position"))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            positions: buffer.update(&mut cx, |buffer, _| {
+                positions
+                    .iter()
+                    .map(|position| position.to_point_utf16(buffer))
+                    .collect()
+            })?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Option<Vec<Vec<Range<Anchor>>>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            ranges: response.map(|ranges| proto::SelectionRanges {
+                ranges: ranges
+                    .into_iter()
+                    .map(|ranges| proto::SelectionRange {
+                        ranges: ranges
+                            .into_iter()
+                            .map(|range| proto::AnchorRange {
+                                start: Some(serialize_anchor(&range.start)),
+                                end: Some(serialize_anchor(&range.end)),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSelectionRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Vec<Vec<Range<Anchor>>>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let Some(ranges) = message.ranges else {
+            return Ok(None);
+        };
+        ranges
+            .ranges
+            .into_iter()
+            .map(|ranges| {
+                ranges
+                    .ranges
+                    .into_iter()
+                    .map(|range| {
+                        let start = range
+                            .start
+                            .and_then(deserialize_anchor)
+                            .context("invalid selection range start")?;
+                        let end = range
+                            .end
+                            .and_then(deserialize_anchor)
+                            .context("invalid selection range end")?;
+                        Ok(start..end)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentColors {
+    type Response = Vec<DocumentColor>;
+    type LspRequest = lsp::request::DocumentColor;
+    type ProtoRequest = proto::GetDocumentColors;
+
+    fn display_name(&self) -> &str {
+        "Document colors"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.color_provider {
+            Some(lsp::ColorProviderCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentColorParams> {
+        Ok(lsp::DocumentColorParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Vec<lsp::ColorInformation>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentColor>> {
+        buffer.update(&mut cx, |buffer, _| {
+            let mut colors = message
+                .into_iter()
+                .map(|information| {
+                    let start = buffer
+                        .clip_point_utf16(point_from_lsp(information.range.start), Bias::Left);
+                    let end =
+                        buffer.clip_point_utf16(point_from_lsp(information.range.end), Bias::Left);
+                    DocumentColor {
+                        range: buffer.anchor_before(start)..buffer.anchor_after(end),
+                        color: information.color,
+                    }
+                })
+                .collect::<Vec<_>>();
+            colors.sort_by(|a, b| a.range.start.cmp(&b.range.start, buffer));
+            colors
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentColors {
+        proto::GetDocumentColors {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentColors,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentColor>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentColorsResponse {
+        proto::GetDocumentColorsResponse {
+            colors: response
+                .into_iter()
+                .map(|color| proto::DocumentColor {
+                    start: Some(serialize_anchor(&color.range.start)),
+                    end: Some(serialize_anchor(&color.range.end)),
+                    color: Some(serialize_lsp_color(color.color)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentColorsResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentColor>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .colors
+            .into_iter()
+            .map(|color| {
+                let start = color
+                    .start
+                    .and_then(deserialize_anchor)
+                    .context("invalid document color start")?;
+                let end = color
+                    .end
+                    .and_then(deserialize_anchor)
+                    .context("invalid document color end")?;
+                let color = color
+                    .color
+                    .map(deserialize_lsp_color)
+                    .context("missing document color")?;
+                Ok(DocumentColor {
+                    range: start..end,
+                    color,
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentColors) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetColorPresentations {
+    type Response = Vec<ColorPresentation>;
+    type LspRequest = lsp::request::ColorPresentationRequest;
+    type ProtoRequest = proto::GetColorPresentations;
+
+    fn display_name(&self) -> &str {
+        "Color presentations"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.color_provider {
+            Some(lsp::ColorProviderCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::ColorPresentationParams> {
+        Ok(lsp::ColorPresentationParams {
+            text_document: make_text_document_identifier(path)?,
+            color: self.color,
+            range: range_to_lsp(self.range.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Vec<lsp::ColorPresentation>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<ColorPresentation>> {
+        buffer.update(&mut cx, |buffer, _| {
+            message
+                .into_iter()
+                .map(|presentation| {
+                    // Without an edit, the label replaces the color's text.
+                    let text_edit = presentation.text_edit.unwrap_or_else(|| lsp::TextEdit {
+                        range: range_to_lsp(self.range.clone()),
+                        new_text: presentation.label.clone(),
+                    });
+                    let edits = std::iter::once(text_edit)
+                        .chain(presentation.additional_text_edits.into_iter().flatten())
+                        .map(|edit| (anchor_range_from_lsp(buffer, edit.range), edit.new_text))
+                        .collect();
+                    ColorPresentation {
+                        label: presentation.label,
+                        edits,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetColorPresentations {
+        proto::GetColorPresentations {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            start: Some(serialize_anchor(&buffer.anchor_before(self.range.start))),
+            end: Some(serialize_anchor(&buffer.anchor_after(self.range.end))),
+            color: Some(serialize_lsp_color(self.color)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetColorPresentations,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let start = message
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid color presentation start")?;
+        let end = message
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid color presentation end")?;
+        let color = message
+            .color
+            .map(deserialize_lsp_color)
+            .context("missing color presentation color")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            range: buffer.update(&mut cx, |buffer, _| (start..end).to_point_utf16(buffer))?,
+            color,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<ColorPresentation>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetColorPresentationsResponse {
+        proto::GetColorPresentationsResponse {
+            presentations: response
+                .into_iter()
+                .map(|presentation| proto::ColorPresentation {
+                    label: presentation.label,
+                    edits: presentation
+                        .edits
+                        .into_iter()
+                        .map(|(range, new_text)| proto::ColorPresentationEdit {
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                            new_text,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetColorPresentationsResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<ColorPresentation>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .presentations
+            .into_iter()
+            .map(|presentation| {
+                let edits = presentation
+                    .edits
+                    .into_iter()
+                    .map(|edit| {
+                        let start = edit
+                            .start
+                            .and_then(deserialize_anchor)
+                            .context("invalid color presentation edit start")?;
+                        let end = edit
+                            .end
+                            .and_then(deserialize_anchor)
+                            .context("invalid color presentation edit end")?;
+                        Ok((start..end, edit.new_text))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ColorPresentation {
+                    label: presentation.label,
+                    edits,
+                })
+            })
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetColorPresentations) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+fn serialize_lsp_color(color: lsp::Color) -> proto::LspColor {
+    proto::LspColor {
+        red: color.red,
+        green: color.green,
+        blue: color.blue,
+        alpha: color.alpha,
+    }
+}
+
+fn deserialize_lsp_color(color: proto::LspColor) -> lsp::Color {
+    lsp::Color {
+        red: color.red,
+        green: color.green,
+        blue: color.blue,
+        alpha: color.alpha,
+    }
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+async fn location_from_lsp(
+    uri: lsp::Url,
+    range: lsp::Range,
+    lsp_store: &Entity<LspStore>,
+    lsp_adapter: &Arc<CachedLspAdapter>,
+    language_server: &Arc<LanguageServer>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(
+                uri,
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    let range = buffer.read_with(cx, |buffer, _| anchor_range_from_lsp(buffer, range))?;
+    Ok(Location { buffer, range })
+}
+
+async fn call_hierarchy_item_from_lsp(
+    lsp_item: lsp::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    lsp_adapter: &Arc<CachedLspAdapter>,
+    language_server: &Arc<LanguageServer>,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let location = location_from_lsp(
+        lsp_item.uri.clone(),
+        lsp_item.selection_range,
+        lsp_store,
+        lsp_adapter,
+        language_server,
+        cx,
+    )
+    .await?;
+    Ok(CallHierarchyItem {
+        server_id: language_server.server_id(),
+        name: lsp_item.name.clone(),
+        kind: lsp_item.kind,
+        detail: lsp_item.detail.clone(),
+        location,
+        lsp_item,
+    })
+}
+
+fn location_to_proto(
+    location: Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location start"))?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location end"))?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn call_hierarchy_item_to_proto(
+    item: CallHierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::CallHierarchyItem {
+    proto::CallHierarchyItem {
+        server_id: item.server_id.to_proto(),
+        location: Some(location_to_proto(item.location, lsp_store, peer_id, cx)),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+    }
+}
+
+async fn call_hierarchy_item_from_proto(
+    item: proto::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let lsp_item: lsp::CallHierarchyItem =
+        serde_json::from_slice(&item.lsp_item).context("deserializing call hierarchy item")?;
+    let location = item
+        .location
+        .ok_or_else(|| anyhow!("missing call hierarchy item location"))?;
+    Ok(CallHierarchyItem {
+        server_id: LanguageServerId::from_proto(item.server_id),
+        name: lsp_item.name.clone(),
+        kind: lsp_item.kind,
+        detail: lsp_item.detail.clone(),
+        location: location_from_proto(location, lsp_store, cx).await?,
+        lsp_item,
+    })
+}
+
+fn call_hierarchy_call_to_proto(
+    call: CallHierarchyCall,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::CallHierarchyCall {
+    proto::CallHierarchyCall {
+        item: Some(call_hierarchy_item_to_proto(
+            call.item, lsp_store, peer_id, cx,
+        )),
+        call_sites: call
+            .call_sites
+            .into_iter()
+            .map(|location| location_to_proto(location, lsp_store, peer_id, cx))
+            .collect(),
+    }
+}
+
+async fn call_hierarchy_call_from_proto(
+    call: proto::CallHierarchyCall,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyCall> {
+    let item = call
+        .item
+        .ok_or_else(|| anyhow!("missing call hierarchy call item"))?;
+    let item = call_hierarchy_item_from_proto(item, lsp_store, cx).await?;
+    let mut call_sites = Vec::with_capacity(call.call_sites.len());
+    for location in call.call_sites {
+        call_sites.push(location_from_proto(location, lsp_store, cx).await?);
+    }
+    Ok(CallHierarchyCall { item, call_sites })
+}
+
+async fn type_hierarchy_items_from_lsp(
+    lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+    lsp_store: &Entity<LspStore>,
+    buffer: &Entity<Buffer>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let lsp_items = lsp_items.unwrap_or_default();
+    if lsp_items.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(lsp_store, buffer, server_id, cx)?;
+    let mut items = Vec::with_capacity(lsp_items.len());
+    for lsp_item in lsp_items {
+        let location = location_from_lsp(
+            lsp_item.uri.clone(),
+            lsp_item.selection_range,
+            lsp_store,
+            &lsp_adapter,
+            &language_server,
+            cx,
+        )
+        .await?;
+        items.push(TypeHierarchyItem {
+            server_id,
+            name: lsp_item.name.clone(),
+            kind: lsp_item.kind,
+            detail: lsp_item.detail.clone(),
+            location,
+            lsp_item,
+        });
+    }
+    Ok(items)
+}
+
+fn type_hierarchy_items_to_proto(
+    items: Vec<TypeHierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::TypeHierarchyItem> {
+    items
+        .into_iter()
+        .map(|item| proto::TypeHierarchyItem {
+            server_id: item.server_id.to_proto(),
+            location: Some(location_to_proto(item.location, lsp_store, peer_id, cx)),
+            lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+        })
+        .collect()
+}
+
+async fn type_hierarchy_items_from_proto(
+    items: Vec<proto::TypeHierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        let lsp_item: lsp::TypeHierarchyItem =
+            serde_json::from_slice(&item.lsp_item).context("deserializing type hierarchy item")?;
+        let location = item
+            .location
+            .ok_or_else(|| anyhow!("missing type hierarchy item location"))?;
+        result.push(TypeHierarchyItem {
+            server_id: LanguageServerId::from_proto(item.server_id),
+            name: lsp_item.name.clone(),
+            kind: lsp_item.kind,
+            detail: lsp_item.detail.clone(),
+            location: location_from_proto(location, lsp_store, cx).await?,
+            lsp_item,
+        });
+    }
+    Ok(result)
+}
@@ -1,10 +1,13 @@
 pub mod clangd_ext;
 pub mod lsp_ext_command;
+mod pull_diagnostics;
 pub mod rust_analyzer_ext;
+mod semantic_tokens;
 
 use crate::{
-    CodeAction, Completion, CompletionSource, CoreCompletion, Hover, InlayHint, LspAction,
-    ProjectItem, ProjectPath, ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    BufferSemanticTokens, CodeAction, Completion, CompletionSource, CoreCompletion, DocumentLink,
+    FoldingRange, Hover, InlayHint, InlineValue, LspAction, ProjectItem, ProjectPath,
+    ProjectTransaction, ResolveState, Symbol, ToolchainStore,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -20,6 +23,7 @@ use anyhow::{Context as _, Result, anyhow};
 use async_trait::async_trait;
 use client::{TypedEnvelope, proto};
 use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
+use dap::DapRegistry;
 use futures::{
     AsyncWriteExt, Future, FutureExt, StreamExt,
     future::{Shared, join_all},
@@ -100,6 +104,7 @@ pub use worktree::{
 };
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const DIAGNOSTIC_PULL_DEBOUNCE: Duration = Duration::from_millis(300);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -158,6 +163,12 @@ pub struct LocalLspStore {
         >,
     >,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    semantic_tokens: HashMap<BufferId, semantic_tokens::CachedSemanticTokens>,
+    /// The ID of the last diagnostics result each language server reported
+    /// for each document, for servers that diagnostics are pulled from.
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
+    document_diagnostic_pulls: HashMap<(BufferId, LanguageServerId), Task<()>>,
+    workspace_diagnostic_pulls: HashMap<LanguageServerId, Task<()>>,
     _subscription: gpui::Subscription,
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
@@ -846,6 +857,44 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -2452,6 +2501,7 @@ impl LocalLspStore {
     ) {
         buffer.update(cx, |buffer, cx| {
             let _ = self.buffer_snapshots.remove(&buffer.remote_id());
+            self.semantic_tokens.remove(&buffer.remote_id());
 
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server.unregister_buffer(file_url.clone());
@@ -3351,6 +3401,9 @@ pub struct LspStore {
     _maintain_buffer_languages: Task<()>,
     diagnostic_summaries:
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
+    /// The folding ranges last fetched for each buffer, with the buffer
+    /// version they were fetched for.
+    folding_ranges: HashMap<BufferId, (clock::Global, Option<Vec<FoldingRange>>)>,
 }
 
 pub enum LspStoreEvent {
@@ -3369,6 +3422,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -3423,9 +3477,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_get_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3443,6 +3500,18 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetInlineValues>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetColorPresentations>);
 
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::OpenDocs>);
@@ -3520,6 +3589,7 @@ impl LspStore {
         } else {
             log::info!("No extension events global found. Skipping JSON schema auto-reload setup");
         }
+        Self::reload_zed_json_schemas_on_debug_adapters_changed(cx);
         cx.observe_global::<SettingsStore>(Self::on_settings_changed)
             .detach();
 
@@ -3546,6 +3616,10 @@ impl LspStore {
                 language_server_watcher_registrations: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                document_diagnostic_pulls: Default::default(),
+                workspace_diagnostic_pulls: Default::default(),
                 prettier_store,
                 environment,
                 http_client,
@@ -3568,6 +3642,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().r#gen(),
             diagnostic_summaries: Default::default(),
+            folding_ranges: Default::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3624,6 +3699,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().r#gen(),
             diagnostic_summaries: Default::default(),
+            folding_ranges: Default::default(),
             active_entry: None,
             toolchain_store,
             _maintain_workspace_config,
@@ -3751,7 +3827,8 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited { .. } => {
-                self.on_buffer_edited(buffer, cx);
+                self.on_buffer_edited(buffer.clone(), cx);
+                self.pull_document_diagnostics(&buffer, None, true, cx);
             }
 
             language::BufferEvent::Saved => {
@@ -3771,6 +3848,10 @@ impl LspStore {
             this.on_buffer_event(buffer, event, cx);
         })
         .detach();
+        cx.observe_release(buffer, |this, buffer, _| {
+            this.folding_ranges.remove(&buffer.remote_id());
+        })
+        .detach();
 
         self.detect_language_for_buffer(buffer, cx);
         if let Some(local) = self.as_local_mut() {
@@ -3793,6 +3874,29 @@ impl LspStore {
         let extension::Event::ExtensionsInstalledChanged = evt else {
             return;
         };
+        self.reload_zed_json_schemas(cx);
+    }
+
+    /// Reloads the JSON schemas whenever debug adapters are registered or removed, as
+    /// the schema of `debug.json` includes the configurations of all adapters.
+    fn reload_zed_json_schemas_on_debug_adapters_changed(cx: &mut Context<Self>) {
+        let mut subscription = DapRegistry::global(cx).subscribe();
+        cx.spawn(async move |this, cx| {
+            // The first value is the registry's state at the time of subscribing.
+            subscription.next().await;
+            while let Some(()) = subscription.next().await {
+                if this
+                    .update(cx, |this, cx| this.reload_zed_json_schemas(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn reload_zed_json_schemas(&mut self, cx: &mut Context<Self>) {
         if self.as_local().is_none() {
             return;
         }
@@ -3895,7 +3999,7 @@ impl LspStore {
             let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
                 return handle;
             };
-            if !file.is_local() {
+            if !file.is_local() || buffer.read(cx).is_large_file() {
                 return handle;
             }
 
@@ -3904,7 +4008,8 @@ impl LspStore {
                 *refcount += 1;
             }
 
-            if ignore_refcounts || *refcount == 1 {
+            let register = ignore_refcounts || *refcount == 1;
+            if register {
                 local.register_buffer_with_language_servers(buffer, cx);
             }
             if !ignore_refcounts {
@@ -3918,6 +4023,9 @@ impl LspStore {
                     *refcount -= 1;
                     if *refcount == 0 {
                         local.registered_buffers.remove(&buffer_id);
+                        local
+                            .document_diagnostic_pulls
+                            .retain(|(pull_buffer_id, _), _| *pull_buffer_id != buffer_id);
                         if let Some(file) = File::from_dyn(buffer.read(cx).file()).cloned() {
                             local.unregister_old_buffer_from_language_servers(&buffer, &file, cx);
                         }
@@ -3925,6 +4033,9 @@ impl LspStore {
                 })
                 .detach();
             }
+            if register {
+                self.pull_document_diagnostics(buffer, None, false, cx);
+            }
         } else if let Some((upstream_client, upstream_project_id)) = self.upstream_client() {
             let buffer_id = buffer.read(cx).remote_id().to_proto();
             cx.background_spawn(async move {
@@ -4031,6 +4142,10 @@ impl LspStore {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
         let file = buffer.file()?;
+        // Parsing and language servers don't scale to large files, which are left as plain text.
+        if buffer.is_large_file() {
+            return None;
+        }
 
         let content = buffer.as_rope();
         let available_language = self.languages.language_for_file(file, Some(content), cx);
@@ -4654,6 +4769,61 @@ impl LspStore {
         }
     }
 
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<DocumentLink>> {
+        let ResolveState::CanResolve(server_id, _) = link.resolve_state else {
+            return Task::ready(Ok(link));
+        };
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                language_server_id: server_id.0 as u64,
+                link: Some(GetDocumentLinks::project_to_proto_link(link.clone())),
+            };
+            cx.spawn(async move |_, _| {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => GetDocumentLinks::proto_to_project_link(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&lang_server.capabilities()) {
+                return Task::ready(Ok(link));
+            }
+            let buffer = buffer.clone();
+            let lsp_link = GetDocumentLinks::project_to_lsp_link(link, buffer.read(cx));
+            cx.spawn(async move |_, cx| {
+                let resolved_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link)
+                    .await
+                    .context("document link resolve LSP request")?;
+                buffer.update(cx, |buffer, _| {
+                    GetDocumentLinks::lsp_to_project_link(
+                        resolved_link,
+                        buffer,
+                        ResolveState::Resolved,
+                    )
+                })
+            })
+        }
+    }
+
     pub(crate) fn linked_edit(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -5556,6 +5726,60 @@ impl LspStore {
         }
     }
 
+    /// Returns the folding ranges reported by the first language server of the
+    /// buffer that supports them, reusing the last response while the buffer
+    /// is unchanged. Returns `None` if no language server provides them.
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<FoldingRange>>>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let version = buffer.read(cx).version();
+        if let Some((cached_version, ranges)) = self.folding_ranges.get(&buffer_id) {
+            if *cached_version == version {
+                return Task::ready(Ok(ranges.clone()));
+            }
+        }
+
+        let request = self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetFoldingRanges,
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            let ranges = request.await?;
+            this.update(cx, |this, _| {
+                this.folding_ranges
+                    .insert(buffer_id, (version, ranges.clone()));
+            })?;
+            Ok(ranges)
+        })
+    }
+
+    /// Asks the language server which values to show in `range` while the debug
+    /// session is paused in the given stack frame, at `stopped_location`.
+    pub fn inline_values(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        range: Range<Anchor>,
+        stack_frame_id: u64,
+        stopped_location: Range<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<InlineValue>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetInlineValues {
+                range,
+                stack_frame_id,
+                stopped_location,
+            },
+            cx,
+        )
+    }
+
     pub fn inlay_hints(
         &mut self,
         buffer_handle: Entity<Buffer>,
@@ -5610,6 +5834,109 @@ impl LspStore {
         }
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<BufferSemanticTokens>>> {
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer_id.into(),
+                version: serialize_version(&buffer.version()),
+            };
+            return cx.spawn(async move |_, cx| {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                match response.tokens {
+                    Some(tokens) => semantic_tokens::tokens_from_proto(tokens, &buffer_handle, cx)
+                        .await
+                        .map(Some),
+                    None => Ok(None),
+                }
+            });
+        }
+
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let snapshot = buffer.snapshot();
+        let Some((language_server, support)) = buffer_handle.update(cx, |buffer, cx| {
+            self.language_servers_for_local_buffer(buffer, cx)
+                .find_map(|(_, server)| {
+                    let support =
+                        semantic_tokens::SemanticTokensSupport::new(&server.capabilities())?;
+                    Some((server.clone(), support))
+                })
+        }) else {
+            return Task::ready(Ok(None));
+        };
+        let uri = match lsp::Url::from_file_path(&abs_path) {
+            Ok(uri) => uri,
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path {abs_path:?}"))),
+        };
+
+        let server_id = language_server.server_id();
+        let previous_result_id = self.as_local().and_then(|local| {
+            let cached = local.semantic_tokens.get(&buffer_id)?;
+            if support.supports_delta && cached.server_id == server_id {
+                cached.result_id.clone()
+            } else {
+                None
+            }
+        });
+        cx.spawn(async move |this, cx| {
+            let Some((result_id, update)) = semantic_tokens::request_tokens(
+                &language_server,
+                lsp::TextDocumentIdentifier::new(uri),
+                previous_result_id.clone(),
+            )
+            .await?
+            else {
+                return Ok(None);
+            };
+
+            this.update(cx, |this, _| {
+                let local = this.as_local_mut().context("not a local lsp store")?;
+                let data = match update {
+                    semantic_tokens::TokensUpdate::Full(data) => data,
+                    semantic_tokens::TokensUpdate::Delta(edits) => {
+                        let cached = local
+                            .semantic_tokens
+                            .remove(&buffer_id)
+                            .filter(|cached| {
+                                cached.server_id == server_id
+                                    && cached.result_id == previous_result_id
+                            })
+                            .context("semantic tokens changed while a delta was requested")?;
+                        let mut data = cached.data;
+                        semantic_tokens::apply_edits(&mut data, edits);
+                        data
+                    }
+                };
+                let tokens =
+                    semantic_tokens::buffer_semantic_tokens(&support.legend, &data, &snapshot);
+                local.semantic_tokens.insert(
+                    buffer_id,
+                    semantic_tokens::CachedSemanticTokens {
+                        server_id,
+                        result_id,
+                        data,
+                    },
+                );
+                Ok(Some(tokens))
+            })?
+        })
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -6122,8 +6449,13 @@ impl LspStore {
         let language_servers = buffer.update(cx, |buffer, cx| {
             local.language_server_ids_for_buffer(buffer, cx)
         });
+        for language_server_id in &language_servers {
+            self.simulate_disk_based_diagnostics_events_if_needed(*language_server_id, cx);
+        }
+
+        self.pull_document_diagnostics(&buffer, None, false, cx);
         for language_server_id in language_servers {
-            self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
+            self.pull_workspace_diagnostics(language_server_id, cx);
         }
 
         None
@@ -7884,6 +8216,31 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = envelope
+            .payload
+            .link
+            .context("missing document link")
+            .and_then(GetDocumentLinks::proto_to_project_link)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let link = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(link, &buffer, cx)
+            })?
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(GetDocumentLinks::project_to_proto_link(link)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -7895,6 +8252,40 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_semantic_tokens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+        let tokens = this
+            .update(&mut cx, |this, cx| this.semantic_tokens(buffer, cx))?
+            .await?;
+        Ok(proto::GetSemanticTokensResponse {
+            tokens: tokens.map(semantic_tokens::tokens_to_proto),
+        })
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -8426,6 +8817,11 @@ impl LspStore {
             });
         }
         local.language_server_watched_paths.remove(&server_id);
+        local.diagnostic_result_ids.remove(&server_id);
+        local
+            .document_diagnostic_pulls
+            .retain(|(_, pull_server_id), _| *pull_server_id != server_id);
+        local.workspace_diagnostic_pulls.remove(&server_id);
         let server_state = local.language_servers.remove(&server_id);
         cx.notify();
         cx.emit(LspStoreEvent::LanguageServerRemoved(server_id));
@@ -8674,6 +9070,212 @@ impl LspStore {
         Ok(())
     }
 
+    /// Pulls the diagnostics of a buffer from the language servers that
+    /// support the pull model, or only from the given server.
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        for_server: Option<LanguageServerId>,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_local() {
+            return;
+        }
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(uri) = File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| file.as_local())
+            .and_then(|file| lsp::Url::from_file_path(file.abs_path(cx)).ok())
+        else {
+            return;
+        };
+        let servers = buffer.update(cx, |buffer, cx| {
+            self.language_servers_for_local_buffer(buffer, cx)
+                .filter(|(_, server)| {
+                    for_server.map_or(true, |server_id| server.server_id() == server_id)
+                })
+                .filter_map(|(adapter, server)| {
+                    let support =
+                        pull_diagnostics::DiagnosticPullSupport::new(&server.capabilities())?;
+                    Some((adapter.clone(), server.clone(), support))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        for (adapter, server, support) in servers {
+            let server_id = server.server_id();
+            let uri = uri.clone();
+            let task = cx.spawn(async move |this, cx| {
+                if debounce {
+                    cx.background_executor()
+                        .timer(DIAGNOSTIC_PULL_DEBOUNCE)
+                        .await;
+                }
+                let Some((version, previous_result_id)) = this
+                    .update(cx, |this, _| {
+                        let local = this.as_local()?;
+                        let version = local
+                            .buffer_snapshots
+                            .get(&buffer_id)?
+                            .get(&server_id)?
+                            .last()?
+                            .version;
+                        let previous_result_id = local
+                            .diagnostic_result_ids
+                            .get(&server_id)
+                            .and_then(|result_ids| result_ids.get(&uri))
+                            .cloned();
+                        Some((version, previous_result_id))
+                    })
+                    .ok()
+                    .flatten()
+                else {
+                    return;
+                };
+                let reports = pull_diagnostics::pull_document_diagnostics(
+                    &server,
+                    &support,
+                    lsp::TextDocumentIdentifier::new(uri),
+                    Some(version),
+                    previous_result_id,
+                )
+                .await;
+                this.update(cx, |this, cx| {
+                    if let Some(reports) = reports.log_err() {
+                        this.apply_pulled_diagnostics(server_id, &adapter, reports, cx);
+                    }
+                })
+                .ok();
+            });
+            local
+                .document_diagnostic_pulls
+                .insert((buffer_id, server_id), task);
+        }
+    }
+
+    /// Pulls the diagnostics of all documents in the workspace from a
+    /// language server, if it supports workspace diagnostics.
+    fn pull_workspace_diagnostics(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(LanguageServerState::Running {
+            adapter, server, ..
+        }) = local.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(support) = pull_diagnostics::DiagnosticPullSupport::new(&server.capabilities())
+            .filter(|support| support.workspace_diagnostics)
+        else {
+            return;
+        };
+        let adapter = adapter.clone();
+        let server = server.clone();
+        let previous_result_ids = local
+            .diagnostic_result_ids
+            .get(&server_id)
+            .cloned()
+            .unwrap_or_default();
+        let task = cx.spawn(async move |this, cx| {
+            let reports = pull_diagnostics::pull_workspace_diagnostics(
+                &server,
+                &support,
+                &previous_result_ids,
+            )
+            .await;
+            this.update(cx, |this, cx| {
+                if let Some(reports) = reports.log_err() {
+                    this.apply_pulled_diagnostics(server_id, &adapter, reports, cx);
+                }
+            })
+            .ok();
+        });
+        local.workspace_diagnostic_pulls.insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of all open buffers and of the workspace from a
+    /// language server again, after it asked for a refresh.
+    fn refresh_pulled_diagnostics(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| {
+                local
+                    .registered_buffers
+                    .contains_key(&buffer.read(cx).remote_id())
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(&buffer, Some(server_id), false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Replaces the diagnostics of the documents that a pull reported in full,
+    /// keeping the diagnostics of those reported as unchanged.
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &CachedLspAdapter,
+        reports: Vec<(lsp::Url, pull_diagnostics::DocumentReport)>,
+        cx: &mut Context<Self>,
+    ) {
+        for (uri, report) in reports {
+            let Some(local) = self.as_local_mut() else {
+                return;
+            };
+            // The server may have stopped while the pull was in flight.
+            if !local.language_servers.contains_key(&server_id) {
+                return;
+            }
+            let result_ids = local.diagnostic_result_ids.entry(server_id).or_default();
+            let mut params = match report {
+                pull_diagnostics::DocumentReport::Unchanged { result_id } => {
+                    result_ids.insert(uri, result_id);
+                    continue;
+                }
+                pull_diagnostics::DocumentReport::Full {
+                    result_id,
+                    version,
+                    diagnostics,
+                } => {
+                    match result_id {
+                        Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                        None => result_ids.remove(&uri),
+                    };
+                    lsp::PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version,
+                    }
+                }
+            };
+
+            let buffer = params
+                .uri
+                .to_file_path()
+                .map(|file_path| self.get_buffer(&file_path, cx))
+                .ok()
+                .flatten();
+            adapter.process_diagnostics(&mut params, server_id, buffer);
+            self.update_diagnostics(
+                server_id,
+                params,
+                &adapter.disk_based_diagnostic_sources,
+                cx,
+            )
+            .log_err();
+        }
+    }
+
     fn insert_newly_running_language_server(
         &mut self,
         adapter: Arc<CachedLspAdapter>,
@@ -8743,6 +9345,8 @@ impl LspStore {
             Some(key.0),
         ));
         cx.emit(LspStoreEvent::RefreshInlayHints);
+        cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        self.folding_ranges.clear();
 
         if let Some((downstream_client, project_id)) = self.downstream_client.as_ref() {
             downstream_client
@@ -8758,6 +9362,7 @@ impl LspStore {
         }
 
         // Tell the language server about every open buffer in the worktree that matches the language.
+        let mut opened_buffers = Vec::new();
         self.buffer_store.clone().update(cx, |buffer_store, cx| {
             for buffer_handle in buffer_store.buffers() {
                 let buffer = buffer_handle.read(cx);
@@ -8816,6 +9421,7 @@ impl LspStore {
                         version,
                         initial_snapshot.text(),
                     );
+                    opened_buffers.push(buffer_handle.clone());
                 }
                 buffer_handle.update(cx, |buffer, cx| {
                     buffer.set_completion_triggers(
@@ -8837,6 +9443,11 @@ impl LspStore {
             }
         });
 
+        for buffer in opened_buffers {
+            self.pull_document_diagnostics(&buffer, Some(server_id), false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+
         cx.notify();
     }
 
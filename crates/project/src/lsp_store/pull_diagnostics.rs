@@ -0,0 +1,160 @@
+use anyhow::Result;
+use collections::HashMap;
+use lsp::LanguageServer;
+
+/// What a language server advertised about its support for pulling
+/// diagnostics, as introduced in LSP 3.17.
+pub(super) struct DiagnosticPullSupport {
+    pub identifier: Option<String>,
+    pub workspace_diagnostics: bool,
+}
+
+impl DiagnosticPullSupport {
+    pub fn new(capabilities: &lsp::ServerCapabilities) -> Option<Self> {
+        let options = match capabilities.diagnostic_provider.as_ref()? {
+            lsp::DiagnosticServerCapabilities::Options(options) => options,
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                &options.diagnostic_options
+            }
+        };
+        Some(Self {
+            identifier: options.identifier.clone(),
+            workspace_diagnostics: options.workspace_diagnostics,
+        })
+    }
+}
+
+/// The diagnostics a language server reported for a single document.
+pub(super) enum DocumentReport {
+    Full {
+        result_id: Option<String>,
+        version: Option<i32>,
+        diagnostics: Vec<lsp::Diagnostic>,
+    },
+    /// The diagnostics haven't changed since the result with the given ID.
+    Unchanged { result_id: String },
+}
+
+/// Pulls the diagnostics of a document, along with those of the documents the
+/// server considers related to it.
+pub(super) async fn pull_document_diagnostics(
+    language_server: &LanguageServer,
+    support: &DiagnosticPullSupport,
+    text_document: lsp::TextDocumentIdentifier,
+    version: Option<i32>,
+    previous_result_id: Option<String>,
+) -> Result<Vec<(lsp::Url, DocumentReport)>> {
+    let uri = text_document.uri.clone();
+    let response = language_server
+        .request::<lsp::request::DocumentDiagnosticRequest>(lsp::DocumentDiagnosticParams {
+            text_document,
+            identifier: support.identifier.clone(),
+            previous_result_id,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await?;
+
+    let mut reports = Vec::new();
+    let related_documents = match response {
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+            report,
+        )) => {
+            let full_report = report.full_document_diagnostic_report;
+            reports.push((
+                uri,
+                DocumentReport::Full {
+                    result_id: full_report.result_id,
+                    version,
+                    diagnostics: full_report.items,
+                },
+            ));
+            report.related_documents
+        }
+        lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Unchanged(
+            report,
+        )) => {
+            reports.push((
+                uri,
+                DocumentReport::Unchanged {
+                    result_id: report.unchanged_document_diagnostic_report.result_id,
+                },
+            ));
+            report.related_documents
+        }
+        lsp::DocumentDiagnosticReportResult::Partial(partial) => partial.related_documents,
+    };
+    reports.extend(
+        related_documents
+            .into_iter()
+            .flatten()
+            .map(|(uri, report)| (uri, related_document_report(report))),
+    );
+    Ok(reports)
+}
+
+/// Pulls the diagnostics of all documents in the workspace, skipping those
+/// whose diagnostics haven't changed since the given results.
+pub(super) async fn pull_workspace_diagnostics(
+    language_server: &LanguageServer,
+    support: &DiagnosticPullSupport,
+    previous_result_ids: &HashMap<lsp::Url, String>,
+) -> Result<Vec<(lsp::Url, DocumentReport)>> {
+    let previous_result_ids = previous_result_ids
+        .iter()
+        .map(|(uri, result_id)| lsp::PreviousResultId {
+            uri: uri.clone(),
+            value: result_id.clone(),
+        })
+        .collect();
+    let response = language_server
+        .request::<lsp::request::WorkspaceDiagnosticRequest>(lsp::WorkspaceDiagnosticParams {
+            identifier: support.identifier.clone(),
+            previous_result_ids,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await?;
+
+    let items = match response {
+        lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+        lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| match item {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                let full_report = report.full_document_diagnostic_report;
+                (
+                    report.uri,
+                    DocumentReport::Full {
+                        result_id: full_report.result_id,
+                        version: report
+                            .version
+                            .and_then(|version| i32::try_from(version).ok()),
+                        diagnostics: full_report.items,
+                    },
+                )
+            }
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                report.uri,
+                DocumentReport::Unchanged {
+                    result_id: report.unchanged_document_diagnostic_report.result_id,
+                },
+            ),
+        })
+        .collect())
+}
+
+fn related_document_report(report: lsp::DocumentDiagnosticReportKind) -> DocumentReport {
+    match report {
+        lsp::DocumentDiagnosticReportKind::Full(report) => DocumentReport::Full {
+            result_id: report.result_id,
+            version: None,
+            diagnostics: report.items,
+        },
+        lsp::DocumentDiagnosticReportKind::Unchanged(report) => DocumentReport::Unchanged {
+            result_id: report.result_id,
+        },
+    }
+}
@@ -0,0 +1,280 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use client::proto;
+use gpui::{AsyncApp, Entity};
+use language::{
+    Bias, Buffer, BufferSnapshot, PointUtf16, Unclipped,
+    proto::{deserialize_anchor, serialize_anchor},
+};
+use lsp::{LanguageServer, LanguageServerId};
+
+use crate::{BufferSemanticTokens, SemanticToken};
+
+/// The number of integers used to encode each token in LSP's relative format.
+const TOKEN_LEN: usize = 5;
+
+/// The last semantic tokens a language server reported for a buffer, kept in
+/// their encoded form so that subsequent responses can be sent as deltas.
+pub(super) struct CachedSemanticTokens {
+    pub server_id: LanguageServerId,
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+/// What a language server advertised about its semantic tokens support.
+pub(super) struct SemanticTokensSupport {
+    pub legend: lsp::SemanticTokensLegend,
+    pub supports_delta: bool,
+}
+
+impl SemanticTokensSupport {
+    pub fn new(capabilities: &lsp::ServerCapabilities) -> Option<Self> {
+        let options = match capabilities.semantic_tokens_provider.as_ref()? {
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+            lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+                &options.semantic_tokens_options
+            }
+        };
+        let supports_delta = match options.full.as_ref()? {
+            lsp::SemanticTokensFullOptions::Bool(false) => return None,
+            lsp::SemanticTokensFullOptions::Bool(true) => false,
+            lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+        };
+        Some(Self {
+            legend: options.legend.clone(),
+            supports_delta,
+        })
+    }
+}
+
+pub(super) enum TokensUpdate {
+    Full(Vec<u32>),
+    Delta(Vec<lsp::SemanticTokensEdit>),
+}
+
+/// Requests the semantic tokens of a document, as a delta against the
+/// previous result if there is one.
+pub(super) async fn request_tokens(
+    language_server: &LanguageServer,
+    text_document: lsp::TextDocumentIdentifier,
+    previous_result_id: Option<String>,
+) -> Result<Option<(Option<String>, TokensUpdate)>> {
+    if let Some(previous_result_id) = previous_result_id {
+        let response = language_server
+            .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                lsp::SemanticTokensDeltaParams {
+                    text_document,
+                    previous_result_id,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .await?;
+        Ok(response.map(|response| match response {
+            lsp::SemanticTokensFullDeltaResult::Tokens(tokens) => (
+                tokens.result_id,
+                TokensUpdate::Full(encode_tokens(&tokens.data)),
+            ),
+            lsp::SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                (delta.result_id, TokensUpdate::Delta(delta.edits))
+            }
+            lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+                (None, TokensUpdate::Delta(edits))
+            }
+        }))
+    } else {
+        let response = language_server
+            .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                text_document,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            })
+            .await?;
+        Ok(response.map(|response| match response {
+            lsp::SemanticTokensResult::Tokens(tokens) => (
+                tokens.result_id,
+                TokensUpdate::Full(encode_tokens(&tokens.data)),
+            ),
+            lsp::SemanticTokensResult::Partial(partial) => {
+                (None, TokensUpdate::Full(encode_tokens(&partial.data)))
+            }
+        }))
+    }
+}
+
+pub(super) fn encode_tokens(tokens: &[lsp::SemanticToken]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(tokens.len() * TOKEN_LEN);
+    for token in tokens {
+        data.extend([
+            token.delta_line,
+            token.delta_start,
+            token.length,
+            token.token_type,
+            token.token_modifiers_bitset,
+        ]);
+    }
+    data
+}
+
+/// Applies the edits of a `semanticTokens/full/delta` response to the
+/// previously reported data. Edit offsets refer to the data before any edit
+/// was applied, so they are applied back to front.
+pub(super) fn apply_edits(data: &mut Vec<u32>, mut edits: Vec<lsp::SemanticTokensEdit>) {
+    edits.sort_unstable_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize).min(data.len());
+        let end = (start + edit.delete_count as usize).min(data.len());
+        let inserted = edit.data.as_deref().map(encode_tokens).unwrap_or_default();
+        data.splice(start..end, inserted);
+    }
+}
+
+/// Decodes tokens in LSP's relative format into anchored ranges in the given
+/// snapshot of the buffer.
+pub(super) fn decode_tokens(data: &[u32], snapshot: &BufferSnapshot) -> Vec<SemanticToken> {
+    let mut tokens = Vec::with_capacity(data.len() / TOKEN_LEN);
+    let mut line = 0;
+    let mut start = 0;
+    for token in data.chunks_exact(TOKEN_LEN) {
+        let [delta_line, delta_start, length, token_type, token_modifiers] = *token else {
+            continue;
+        };
+        if delta_line > 0 {
+            line += delta_line;
+            start = delta_start;
+        } else {
+            start += delta_start;
+        }
+
+        let range = Range {
+            start: snapshot.clip_point_utf16(Unclipped(PointUtf16::new(line, start)), Bias::Left),
+            end: snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, start + length)),
+                Bias::Right,
+            ),
+        };
+        if range.is_empty() {
+            continue;
+        }
+        tokens.push(SemanticToken {
+            range: snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end),
+            token_type,
+            token_modifiers,
+        });
+    }
+    tokens
+}
+
+pub(super) fn buffer_semantic_tokens(
+    legend: &lsp::SemanticTokensLegend,
+    data: &[u32],
+    snapshot: &BufferSnapshot,
+) -> BufferSemanticTokens {
+    BufferSemanticTokens {
+        token_types: legend
+            .token_types
+            .iter()
+            .map(|token_type| token_type.as_str().to_string())
+            .collect(),
+        token_modifiers: legend
+            .token_modifiers
+            .iter()
+            .map(|modifier| modifier.as_str().to_string())
+            .collect(),
+        tokens: Arc::from(decode_tokens(data, snapshot)),
+    }
+}
+
+pub(super) fn tokens_to_proto(tokens: BufferSemanticTokens) -> proto::SemanticTokens {
+    proto::SemanticTokens {
+        token_types: tokens.token_types.to_vec(),
+        token_modifiers: tokens.token_modifiers.to_vec(),
+        tokens: tokens
+            .tokens
+            .iter()
+            .map(|token| proto::SemanticToken {
+                start: Some(serialize_anchor(&token.range.start)),
+                end: Some(serialize_anchor(&token.range.end)),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers,
+            })
+            .collect(),
+    }
+}
+
+pub(super) async fn tokens_from_proto(
+    message: proto::SemanticTokens,
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<BufferSemanticTokens> {
+    let mut tokens = Vec::with_capacity(message.tokens.len());
+    for token in message.tokens {
+        let start = token
+            .start
+            .and_then(deserialize_anchor)
+            .context("missing semantic token start")?;
+        let end = token
+            .end
+            .and_then(deserialize_anchor)
+            .context("missing semantic token end")?;
+        tokens.push(SemanticToken {
+            range: start..end,
+            token_type: token.token_type,
+            token_modifiers: token.token_modifiers,
+        });
+    }
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors(
+                tokens
+                    .iter()
+                    .flat_map(|token| [token.range.start, token.range.end]),
+            )
+        })?
+        .await?;
+    Ok(BufferSemanticTokens {
+        token_types: message.token_types.into(),
+        token_modifiers: message.token_modifiers.into(),
+        tokens: tokens.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32, length: u32) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut data = encode_tokens(&[token(0, 0, 1), token(1, 2, 3), token(0, 4, 1)]);
+        apply_edits(
+            &mut data,
+            vec![
+                lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 5,
+                    data: None,
+                },
+                lsp::SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 0,
+                    data: Some(vec![token(0, 0, 2)]),
+                },
+            ],
+        );
+        assert_eq!(
+            data,
+            encode_tokens(&[token(0, 0, 2), token(0, 0, 1), token(1, 2, 3)])
+        );
+    }
+}
@@ -303,6 +303,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -688,6 +689,124 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A symbol that can be used as a node of a call hierarchy.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub server_id: LanguageServerId,
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range of the symbol's name, used when navigating to the item.
+    pub location: Location,
+    /// The item as it was sent by the language server, which needs to be
+    /// passed back verbatim when resolving incoming or outgoing calls.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges at which the calls appear.
+    pub call_sites: Vec<Location>,
+}
+
+/// A type that can be used as a node of a type hierarchy.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub server_id: LanguageServerId,
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range of the type's name, used when navigating to the item.
+    pub location: Location,
+    /// The item as it was sent by the language server, which needs to be
+    /// passed back verbatim when resolving supertypes or subtypes.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
+/// The semantic tokens of a buffer, as reported by a language server.
+#[derive(Clone, Debug, Default)]
+pub struct BufferSemanticTokens {
+    /// The token types from the language server's legend.
+    pub token_types: Arc<[String]>,
+    /// The token modifiers from the language server's legend.
+    pub token_modifiers: Arc<[String]>,
+    /// The tokens, ordered by their position in the buffer.
+    pub tokens: Arc<[SemanticToken]>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    /// An index into [`BufferSemanticTokens::token_types`].
+    pub token_type: u32,
+    /// A bit set of indices into [`BufferSemanticTokens::token_modifiers`].
+    pub token_modifiers: u32,
+}
+
+/// A region of a buffer that a language server reported as foldable.
+#[derive(Clone, Debug)]
+pub struct FoldingRange {
+    /// The folded text. When the server doesn't specify columns, the range
+    /// extends from the end of its first line to the end of its last line.
+    pub range: Range<language::Anchor>,
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
+
+/// A value a language server asks to show next to a buffer's text while a
+/// debug session is paused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineValue {
+    /// The text the value is about. It is shown at the end of the line the range starts on.
+    pub range: Range<language::Anchor>,
+    pub kind: InlineValueKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InlineValueKind {
+    /// Text that is shown as is.
+    Text(String),
+    /// The value of a variable of the paused stack frame. Without a name, the
+    /// variable is named by the text in the range.
+    VariableLookup {
+        variable_name: Option<String>,
+        case_sensitive: bool,
+    },
+    /// The result of evaluating an expression in the paused stack frame. Without
+    /// an expression, the text in the range is evaluated.
+    EvaluatableExpression(Option<String>),
+}
+
+/// A link in a buffer reported by a language server, such as a dependency's
+/// URL or an include path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    /// Where the link points to. Servers may leave this out until the link
+    /// is resolved.
+    pub target: Option<lsp::Url>,
+    pub tooltip: Option<String>,
+    pub resolve_state: ResolveState,
+}
+
+/// A color in a buffer reported by a language server, such as a CSS color
+/// literal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentColor {
+    pub range: Range<language::Anchor>,
+    pub color: lsp::Color,
+}
+
+/// One way of writing a color in a buffer, as offered by a language server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorPresentation {
+    pub label: String,
+    /// The edits that write the color this way, including the one replacing
+    /// the color's current text.
+    pub edits: Vec<(Range<language::Anchor>, String)>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -2734,6 +2853,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3165,6 +3285,17 @@ impl Project {
         })
     }
 
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.reload_buffer_with_encoding(buffer, encoding, cx)
+        })
+    }
+
     pub fn reload_images(
         &self,
         images: HashSet<Entity<ImageItem>>,
@@ -3326,6 +3457,195 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<FoldingRange>>>> {
+        self.lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.folding_ranges(buffer, cx))
+    }
+
+    pub fn inline_values(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        range: Range<language::Anchor>,
+        stack_frame_id: u64,
+        stopped_location: Range<language::Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<InlineValue>>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.inline_values(buffer, range, stack_frame_id, stopped_location, cx)
+        })
+    }
+
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer, cx)
+        })
+    }
+
+    pub fn document_colors(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentColor>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentColors,
+            cx,
+        )
+    }
+
+    /// Returns the ways the language server that reported a document color
+    /// could write the given color in its place.
+    pub fn color_presentations(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        range: Range<language::Anchor>,
+        color: lsp::Color,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ColorPresentation>>> {
+        let snapshot = buffer.read(cx);
+        let range = range.start.to_point_utf16(snapshot)..range.end.to_point_utf16(snapshot);
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetColorPresentations { range, color },
+            cx,
+        )
+    }
+
+    /// Returns, for each of the given positions, the ranges around it that the
+    /// first language server supporting them would select, from the innermost
+    /// to the outermost. Returns `None` if no language server provides them.
+    pub fn selection_ranges<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: impl IntoIterator<Item = T>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<Vec<Range<Anchor>>>>>> {
+        let positions = positions
+            .into_iter()
+            .map(|position| position.to_point_utf16(buffer.read(cx)))
+            .collect();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3558,6 +3878,15 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<BufferSemanticTokens>>> {
+        self.lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.semantic_tokens(buffer, cx))
+    }
+
     pub fn inlay_hints<T: ToOffset>(
         &mut self,
         buffer_handle: Entity<Buffer>,
@@ -4749,6 +5078,28 @@ impl Project {
         })
     }
 
+    pub fn any_language_server_supports_selection_ranges(
+        &self,
+        buffer: &Buffer,
+        cx: &mut App,
+    ) -> bool {
+        if !self.is_local() {
+            // Only the host knows its language servers, and it reports when
+            // none of them provides selection ranges.
+            return true;
+        }
+        self.lsp_store.update(cx, |this, cx| {
+            this.language_servers_for_local_buffer(buffer, cx)
+                .any(
+                    |(_, server)| match server.capabilities().selection_range_provider {
+                        Some(lsp::SelectionRangeProviderCapability::Simple(enabled)) => enabled,
+                        Some(_) => true,
+                        None => false,
+                    },
+                )
+        })
+    }
+
     pub fn language_server_id_for_name(
         &self,
         buffer: &Buffer,
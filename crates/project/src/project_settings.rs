@@ -69,6 +69,46 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for opening files that are too large for the full feature set
+    #[serde(default)]
+    pub large_files: LargeFileSettings,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LargeFileSettings {
+    /// Files larger than this many megabytes are opened in large-file mode, without
+    /// syntax highlighting, language servers or git diffs.
+    ///
+    /// Default: 64
+    #[serde(default = "default_large_file_threshold_mb")]
+    pub threshold_mb: u64,
+    /// Whether files opened in large-file mode are read-only until editing is
+    /// enabled with `editor: enable large file editing`.
+    ///
+    /// Default: true
+    #[serde(default = "true_value")]
+    pub read_only: bool,
+}
+
+impl LargeFileSettings {
+    /// Whether a file of `size` bytes on disk should be opened in large-file mode.
+    pub fn is_large_file(&self, size: u64) -> bool {
+        size > self.threshold_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+impl Default for LargeFileSettings {
+    fn default() -> Self {
+        Self {
+            threshold_mb: default_large_file_threshold_mb(),
+            read_only: true,
+        }
+    }
+}
+
+fn default_large_file_threshold_mb() -> u64 {
+    64
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
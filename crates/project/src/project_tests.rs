@@ -8,7 +8,7 @@ use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
     DiffHunkStatusKind, assert_hunks,
 };
-use fs::FakeFs;
+use fs::{Encoding, FakeFs};
 use futures::{StreamExt, future};
 use git::{
     repository::RepoPath,
@@ -35,7 +35,17 @@ use rand::{Rng as _, rngs::StdRng};
 use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
-use std::{env, mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
+use std::{
+    env, mem,
+    num::NonZeroU32,
+    ops::Range,
+    str::FromStr,
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+    task::Poll,
+};
 use task::{ResolvedTask, TaskContext};
 use unindent::Unindent as _;
 use util::{
@@ -1170,6 +1180,7 @@ async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppCon
         path!("/the-root/Cargo.lock").as_ref(),
         &"".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -1177,6 +1188,7 @@ async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppCon
         path!("/the-stdlib/LICENSE").as_ref(),
         &"".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -1184,6 +1196,7 @@ async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppCon
         path!("/the/stdlib/src/string.rs").as_ref(),
         &"".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -2948,14 +2961,14 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
-            "a.ts": "",
+            "a.rs": "fn a() { b() }\nfn b() {}",
         }),
     )
     .await;
@@ -2963,15 +2976,12 @@ async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp(
-        "TypeScript",
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![".".to_string()]),
-                    ..Default::default()
-                }),
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
                 ..Default::default()
             },
             ..Default::default()
@@ -2979,61 +2989,186 @@ async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     );
 
     let (buffer, _handle) = project
-        .update(cx, |p, cx| {
-            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
         })
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let uri = lsp::Url::from_file_path(path!("/dir/a.rs")).unwrap();
+    let item_b = lsp::CallHierarchyItem {
+        name: "b".to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 9)),
+        selection_range: lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 4)),
+        data: None,
+    };
+    let item_a = lsp::CallHierarchyItem {
+        name: "a".to_string(),
+        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+        selection_range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+        ..item_b.clone()
+    };
 
-    // When text_edit exists, it takes precedence over insert_text and label
-    let text = "let a = obj.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 3)
+                );
+                Ok(Some(vec![item_b]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let item_a = item_a.clone();
+        move |params, _| {
+            let item_a = item_a.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: item_a,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        }
     });
 
-    fake_server
-        .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "labelText".into(),
-                    insert_text: Some("insertText".into()),
-                    text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
-                        range: lsp::Range::new(
-                            lsp::Position::new(0, text.len() as u32 - 3),
-                            lsp::Position::new(0, text.len() as u32),
-                        ),
-                        new_text: "textEditText".into(),
-                    })),
-                    ..Default::default()
-                },
-            ])))
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(1, 3), cx)
         })
-        .next()
-        .await;
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+    cx.update(|cx| {
+        let location_buffer = items[0].location.buffer.read(cx);
+        assert_eq!(items[0].location.range.to_offset(location_buffer), 18..19);
+    });
 
-    let completions = completions.await.unwrap().unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name, "a");
+    cx.update(|cx| {
+        let call_site = &calls[0].call_sites[0];
+        assert_eq!(call_site.range.to_offset(call_site.buffer.read(cx)), 9..10);
+    });
+}
 
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "textEditText");
-    assert_eq!(
-        completions[0].replace_range.to_offset(&snapshot),
-        text.len() - 3..text.len()
-    );
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "class A {}\nclass B extends A {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp("TypeScript", FakeLspAdapter::default());
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let uri = lsp::Url::from_file_path(path!("/dir/a.ts")).unwrap();
+    let item_b = lsp::TypeHierarchyItem {
+        name: "B".to_string(),
+        kind: lsp::SymbolKind::CLASS,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 20)),
+        selection_range: lsp::Range::new(lsp::Position::new(1, 6), lsp::Position::new(1, 7)),
+        data: None,
+    };
+    let item_a = lsp::TypeHierarchyItem {
+        name: "A".to_string(),
+        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 10)),
+        selection_range: lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 7)),
+        ..item_b.clone()
+    };
+
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 6)
+                );
+                Ok(Some(vec![item_b]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>({
+        let item_a = item_a.clone();
+        move |params, _| {
+            let item_a = item_a.clone();
+            async move {
+                assert_eq!(params.item.name, "B");
+                Ok(Some(vec![item_a]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, Point::new(1, 6), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "B");
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    assert_eq!(supertypes[0].name, "A");
+    cx.update(|cx| {
+        let location = &supertypes[0].location;
+        assert_eq!(location.range.to_offset(location.buffer.read(cx)), 6..7);
+    });
 }
 
 #[gpui::test]
-async fn test_completions_with_edit_ranges(cx: &mut gpui::TestAppContext) {
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
-            "a.ts": "",
+            "a.ts": "let a = 1;\nlet b = a;",
         }),
     )
     .await;
@@ -3042,14 +3177,22 @@ async fn test_completions_with_edit_ranges(cx: &mut gpui::TestAppContext) {
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
     language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp(
+    let mut fake_servers = language_registry.register_fake_lsp(
         "TypeScript",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![".".to_string()]),
-                    ..Default::default()
-                }),
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::VARIABLE],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
                 ..Default::default()
             },
             ..Default::default()
@@ -3057,109 +3200,93 @@ async fn test_completions_with_edit_ranges(cx: &mut gpui::TestAppContext) {
     );
 
     let (buffer, _handle) = project
-        .update(cx, |p, cx| {
-            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
         })
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
-    let text = "let a = obj.fqn";
-
-    // Test 1: When text_edit is None but insert_text exists with default edit_range
-    {
-        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-        let completions = project.update(cx, |project, cx| {
-            project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
-        });
-
-        fake_server
-            .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
-                Ok(Some(lsp::CompletionResponse::List(lsp::CompletionList {
-                    is_incomplete: false,
-                    item_defaults: Some(lsp::CompletionListItemDefaults {
-                        edit_range: Some(lsp::CompletionListItemDefaultsEditRange::Range(
-                            lsp::Range::new(
-                                lsp::Position::new(0, text.len() as u32 - 3),
-                                lsp::Position::new(0, text.len() as u32),
-                            ),
-                        )),
-                        ..Default::default()
-                    }),
-                    items: vec![lsp::CompletionItem {
-                        label: "labelText".into(),
-                        insert_text: Some("insertText".into()),
-                        text_edit: None,
-                        ..Default::default()
-                    }],
-                })))
-            })
-            .next()
-            .await;
-
-        let completions = completions.await.unwrap().unwrap();
-        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-
-        assert_eq!(completions.len(), 1);
-        assert_eq!(completions[0].new_text, "insertText");
-        assert_eq!(
-            completions[0].replace_range.to_offset(&snapshot),
-            text.len() - 3..text.len()
-        );
-    }
-
-    // Test 2: When both text_edit and insert_text are None with default edit_range
-    {
-        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-        let completions = project.update(cx, |project, cx| {
-            project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
-        });
+    let fake_server = fake_servers.next().await.unwrap();
+    let token = |delta_line, delta_start, token_modifiers_bitset| lsp::SemanticToken {
+        delta_line,
+        delta_start,
+        length: 1,
+        token_type: 0,
+        token_modifiers_bitset,
+    };
 
-        fake_server
-            .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
-                Ok(Some(lsp::CompletionResponse::List(lsp::CompletionList {
-                    is_incomplete: false,
-                    item_defaults: Some(lsp::CompletionListItemDefaults {
-                        edit_range: Some(lsp::CompletionListItemDefaultsEditRange::Range(
-                            lsp::Range::new(
-                                lsp::Position::new(0, text.len() as u32 - 3),
-                                lsp::Position::new(0, text.len() as u32),
-                            ),
-                        )),
-                        ..Default::default()
-                    }),
-                    items: vec![lsp::CompletionItem {
-                        label: "labelText".into(),
-                        insert_text: None,
-                        text_edit: None,
-                        ..Default::default()
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".to_string()),
+                    data: vec![token(0, 4, 1), token(1, 4, 1)],
+                },
+            )))
+        },
+    );
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&*tokens.token_types, ["variable".to_string()]);
+    assert_eq!(&*tokens.token_modifiers, ["declaration".to_string()]);
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| (token.range.to_offset(buffer), token.token_modifiers))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [(4..5, 1), (15..16, 1)]);
+    });
+
+    // The second request only asks for the changes since the first one.
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".to_string()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: Some(vec![token(1, 8, 0)]),
                     }],
-                })))
-            })
-            .next()
-            .await;
-
-        let completions = completions.await.unwrap().unwrap();
-        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-
-        assert_eq!(completions.len(), 1);
-        assert_eq!(completions[0].new_text, "labelText");
-        assert_eq!(
-            completions[0].replace_range.to_offset(&snapshot),
-            text.len() - 3..text.len()
-        );
-    }
+                },
+            )))
+        },
+    );
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| (token.range.to_offset(buffer), token.token_modifiers))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [(4..5, 1), (19..20, 0)]);
+    });
 }
 
 #[gpui::test]
-async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
-            "a.ts": "",
+            "a.ts": "// one\n// two\nfunction a() {\n    return 1;\n}\n",
         }),
     )
     .await;
@@ -3168,14 +3295,11 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
     language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp(
+    let mut fake_servers = language_registry.register_fake_lsp(
         "TypeScript",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
                 ..Default::default()
             },
             ..Default::default()
@@ -3183,14 +3307,736 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     );
 
     let (buffer, _handle) = project
-        .update(cx, |p, cx| {
-            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
         })
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
-
+    let fake_server = fake_servers.next().await.unwrap();
+    let request_count = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::FoldingRangeRequest, _, _>({
+        let request_count = request_count.clone();
+        move |_, _| {
+            request_count.fetch_add(1, SeqCst);
+            async move {
+                Ok(Some(vec![
+                    lsp::FoldingRange {
+                        start_line: 0,
+                        end_line: 1,
+                        kind: Some(lsp::FoldingRangeKind::Comment),
+                        ..Default::default()
+                    },
+                    lsp::FoldingRange {
+                        start_line: 2,
+                        start_character: Some(14),
+                        end_line: 4,
+                        end_character: Some(0),
+                        ..Default::default()
+                    },
+                    // Ranges within a single line can't be folded.
+                    lsp::FoldingRange {
+                        start_line: 3,
+                        end_line: 3,
+                        ..Default::default()
+                    },
+                ]))
+            }
+        }
+    });
+
+    let ranges = project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let ranges = ranges
+            .iter()
+            .map(|range| (range.range.to_point(buffer), range.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [
+                (
+                    Point::new(0, 6)..Point::new(1, 6),
+                    Some(lsp::FoldingRangeKind::Comment)
+                ),
+                (Point::new(2, 14)..Point::new(4, 0), None),
+            ]
+        );
+    });
+
+    // Ranges are cached until the buffer changes.
+    project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(request_count.load(SeqCst), 1);
+
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(request_count.load(SeqCst), 2);
+}
+
+#[gpui::test]
+async fn test_document_links(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "import a from 'a';\nimport b from 'b';\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let range = |row| lsp::Range::new(lsp::Position::new(row, 15), lsp::Position::new(row, 16));
+    fake_server.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(vec![
+                // The server resolves the target of the second link lazily.
+                lsp::DocumentLink {
+                    range: range(1),
+                    target: None,
+                    tooltip: None,
+                    data: Some(json!({"module": "b"})),
+                },
+                lsp::DocumentLink {
+                    range: range(0),
+                    target: Some("https://example.com/a".parse().unwrap()),
+                    tooltip: Some("Open a".to_string()),
+                    data: None,
+                },
+            ]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+        move |link, _| async move {
+            assert_eq!(link.data, Some(json!({"module": "b"})));
+            Ok(lsp::DocumentLink {
+                target: Some("https://example.com/b".parse().unwrap()),
+                ..link
+            })
+        },
+    );
+
+    let links = project
+        .update(cx, |project, cx| project.document_links(&buffer, cx))
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let links = links
+            .iter()
+            .map(|link| {
+                (
+                    link.range.to_offset(buffer),
+                    link.target.as_ref().map(|target| target.to_string()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            links,
+            [
+                (15..16, Some("https://example.com/a".to_string())),
+                (34..35, None),
+            ]
+        );
+    });
+
+    let resolved_link = project
+        .update(cx, |project, cx| {
+            project.resolve_document_link(links[1].clone(), &buffer, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        resolved_link.target.map(|target| target.to_string()),
+        Some("https://example.com/b".to_string())
+    );
+    assert_eq!(resolved_link.resolve_state, ResolveState::Resolved);
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "let a: number = 'a';\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: Some("ts".to_string()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let uri = lsp::Url::from_file_path(path!("/dir/a.ts")).unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            assert_eq!(params.identifier.as_deref(), Some("ts"));
+            previous_result_ids
+                .lock()
+                .push(params.previous_result_id.clone());
+            async move {
+                let report = match params.previous_result_id {
+                    Some(result_id) => lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport { result_id },
+                        },
+                    ),
+                    None => lsp::DocumentDiagnosticReport::Full(
+                        lsp::RelatedFullDocumentDiagnosticReport {
+                            related_documents: None,
+                            full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                                result_id: Some("1".to_string()),
+                                items: vec![lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 16),
+                                        lsp::Position::new(0, 19),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                    message: "type mismatch".to_string(),
+                                    ..Default::default()
+                                }],
+                            },
+                        },
+                    ),
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+        let uri = uri.clone();
+        move |params, _| {
+            let uri = uri.clone();
+            async move {
+                // Report the documents pulled before as fixed.
+                let items = params
+                    .previous_result_ids
+                    .into_iter()
+                    .filter(|previous| previous.uri == uri)
+                    .map(|previous| {
+                        assert_eq!(previous.value, "1");
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(
+                            lsp::WorkspaceFullDocumentDiagnosticReport {
+                                uri: previous.uri,
+                                version: None,
+                                full_document_diagnostic_report:
+                                    lsp::FullDocumentDiagnosticReport {
+                                        result_id: Some("2".to_string()),
+                                        items: Vec::new(),
+                                    },
+                            },
+                        )
+                    })
+                    .collect();
+                Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                    lsp::WorkspaceDiagnosticReport { items },
+                ))
+            }
+        }
+    });
+
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+    let edit_and_wait = |cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, cx| {
+            let len = buffer.len();
+            buffer.edit([(len..len, "\n")], None, cx);
+        });
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.executor().run_until_parked();
+    };
+
+    // Editing the buffer pulls its diagnostics.
+    edit_and_wait(cx);
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 16)..Point::new(0, 19),
+            "type mismatch".to_string()
+        )]
+    );
+
+    // Subsequent pulls send the ID of the previous result, and keep the
+    // diagnostics the server reports as unchanged.
+    edit_and_wait(cx);
+    assert_eq!(
+        previous_result_ids
+            .lock()
+            .last()
+            .cloned()
+            .flatten()
+            .as_deref(),
+        Some("1")
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 16)..Point::new(0, 19),
+            "type mismatch".to_string()
+        )]
+    );
+
+    // Saving the buffer pulls the diagnostics of the whole workspace.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(diagnostics(cx), Vec::new());
+}
+
+#[gpui::test]
+async fn test_document_colors(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "const color = \"#ff0000\";\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let color_range = lsp::Range::new(lsp::Position::new(0, 15), lsp::Position::new(0, 22));
+    let red = lsp::Color {
+        red: 1.,
+        green: 0.,
+        blue: 0.,
+        alpha: 1.,
+    };
+    let green = lsp::Color {
+        red: 0.,
+        green: 1.,
+        blue: 0.,
+        alpha: 1.,
+    };
+    fake_server.set_request_handler::<lsp::request::DocumentColor, _, _>(move |_, _| async move {
+        Ok(vec![lsp::ColorInformation {
+            range: color_range,
+            color: red,
+        }])
+    });
+    fake_server.set_request_handler::<lsp::request::ColorPresentationRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.range, color_range);
+            assert_eq!(params.color, green);
+            Ok(vec![
+                // Without an edit, the label replaces the color.
+                lsp::ColorPresentation {
+                    label: "#00ff00".to_string(),
+                    text_edit: None,
+                    additional_text_edits: None,
+                },
+                lsp::ColorPresentation {
+                    label: "rgb(0, 255, 0)".to_string(),
+                    text_edit: Some(lsp::TextEdit {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 14),
+                            lsp::Position::new(0, 23),
+                        ),
+                        new_text: "rgb(0, 255, 0)".to_string(),
+                    }),
+                    additional_text_edits: Some(vec![lsp::TextEdit {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 24),
+                            lsp::Position::new(0, 24),
+                        ),
+                        new_text: " // green".to_string(),
+                    }]),
+                },
+            ])
+        },
+    );
+
+    let colors = project
+        .update(cx, |project, cx| project.document_colors(&buffer, cx))
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let colors = colors
+            .iter()
+            .map(|color| (color.range.to_offset(buffer), color.color))
+            .collect::<Vec<_>>();
+        assert_eq!(colors, [(15..22, red)]);
+    });
+
+    let presentations = project
+        .update(cx, |project, cx| {
+            project.color_presentations(&buffer, colors[0].range.clone(), green, cx)
+        })
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let presentations = presentations
+            .iter()
+            .map(|presentation| {
+                let edits = presentation
+                    .edits
+                    .iter()
+                    .map(|(range, new_text)| (range.to_offset(buffer), new_text.as_str()))
+                    .collect::<Vec<_>>();
+                (presentation.label.as_str(), edits)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            presentations,
+            [
+                ("#00ff00", vec![(15..22, "#00ff00")]),
+                (
+                    "rgb(0, 255, 0)",
+                    vec![(14..23, "rgb(0, 255, 0)"), (24..24, " // green")]
+                ),
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    // When text_edit exists, it takes precedence over insert_text and label
+    let text = "let a = obj.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    });
+
+    fake_server
+        .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "labelText".into(),
+                    insert_text: Some("insertText".into()),
+                    text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, text.len() as u32 - 3),
+                            lsp::Position::new(0, text.len() as u32),
+                        ),
+                        new_text: "textEditText".into(),
+                    })),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+
+    let completions = completions.await.unwrap().unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "textEditText");
+    assert_eq!(
+        completions[0].replace_range.to_offset(&snapshot),
+        text.len() - 3..text.len()
+    );
+}
+
+#[gpui::test]
+async fn test_completions_with_edit_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+    let text = "let a = obj.fqn";
+
+    // Test 1: When text_edit is None but insert_text exists with default edit_range
+    {
+        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+        let completions = project.update(cx, |project, cx| {
+            project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+        });
+
+        fake_server
+            .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
+                Ok(Some(lsp::CompletionResponse::List(lsp::CompletionList {
+                    is_incomplete: false,
+                    item_defaults: Some(lsp::CompletionListItemDefaults {
+                        edit_range: Some(lsp::CompletionListItemDefaultsEditRange::Range(
+                            lsp::Range::new(
+                                lsp::Position::new(0, text.len() as u32 - 3),
+                                lsp::Position::new(0, text.len() as u32),
+                            ),
+                        )),
+                        ..Default::default()
+                    }),
+                    items: vec![lsp::CompletionItem {
+                        label: "labelText".into(),
+                        insert_text: Some("insertText".into()),
+                        text_edit: None,
+                        ..Default::default()
+                    }],
+                })))
+            })
+            .next()
+            .await;
+
+        let completions = completions.await.unwrap().unwrap();
+        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].new_text, "insertText");
+        assert_eq!(
+            completions[0].replace_range.to_offset(&snapshot),
+            text.len() - 3..text.len()
+        );
+    }
+
+    // Test 2: When both text_edit and insert_text are None with default edit_range
+    {
+        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+        let completions = project.update(cx, |project, cx| {
+            project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+        });
+
+        fake_server
+            .set_request_handler::<lsp::request::Completion, _, _>(|_, _| async {
+                Ok(Some(lsp::CompletionResponse::List(lsp::CompletionList {
+                    is_incomplete: false,
+                    item_defaults: Some(lsp::CompletionListItemDefaults {
+                        edit_range: Some(lsp::CompletionListItemDefaultsEditRange::Range(
+                            lsp::Range::new(
+                                lsp::Position::new(0, text.len() as u32 - 3),
+                                lsp::Position::new(0, text.len() as u32),
+                            ),
+                        )),
+                        ..Default::default()
+                    }),
+                    items: vec![lsp::CompletionItem {
+                        label: "labelText".into(),
+                        insert_text: None,
+                        text_edit: None,
+                        ..Default::default()
+                    }],
+                })))
+            })
+            .next()
+            .await;
+
+        let completions = completions.await.unwrap().unwrap();
+        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].new_text, "labelText");
+        assert_eq!(
+            completions[0].replace_range.to_offset(&snapshot),
+            text.len() - 3..text.len()
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
     // Test 1: When text_edit is None but insert_text exists (no edit_range in defaults)
     let text = "let a = b.fqn";
     buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
@@ -3487,6 +4333,157 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_file_encodings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    fs.insert_file(path!("/dir/file.txt"), b"caf\xE9\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/file.txt"), cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "café\n");
+        assert_eq!(buffer.encoding().name(), "windows-1252");
+        buffer.edit([(5..5, " crème")], None, cx);
+    });
+
+    // Saving keeps the file's encoding.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/file.txt")))
+            .await
+            .unwrap(),
+        b"caf\xE9 cr\xE8me\n"
+    );
+
+    // Reopening with another encoding decodes the file again.
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffer_with_encoding(buffer.clone(), Encoding::default(), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "caf\u{FFFD} cr\u{FFFD}me\n");
+        assert!(!buffer.is_dirty());
+    });
+    let windows_1252 = Encoding::from_name("windows-1252", false).unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffer_with_encoding(buffer.clone(), windows_1252, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(buffer.update(cx, |buffer, _| buffer.text()), "café crème\n");
+
+    // Saving with another encoding converts the file.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_encoding(Encoding::from_name("UTF-16LE", true).unwrap(), cx)
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    let bytes = fs
+        .load_bytes(Path::new(path!("/dir/file.txt")))
+        .await
+        .unwrap();
+    assert_eq!(&bytes[..4], b"\xFF\xFEc\0");
+    assert_eq!(
+        Encoding::detect(&bytes).unwrap().decode(&bytes),
+        "café crème\n"
+    );
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<project_settings::ProjectSettings>(cx, |settings| {
+                settings.large_files.threshold_mb = 1;
+            });
+        });
+    });
+
+    let large_text = "2024-01-01 INFO request handled\n".repeat(64 * 1024);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "small.rs": "fn main() {}\n",
+            "large.rs": large_text.clone(),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (large_buffer, _large_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/large.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), large_text);
+        assert!(buffer.is_large_file());
+        assert!(buffer.read_only());
+        assert!(buffer.language().is_none());
+    });
+    assert!(fake_servers.try_next().is_err());
+
+    // Small files keep the full feature set.
+    let (small_buffer, _small_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/small.rs"), cx)
+        })
+        .await
+        .unwrap();
+    small_buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(!buffer.read_only());
+        assert_eq!(buffer.language().unwrap().name(), "Rust".into());
+    });
+    fake_servers.next().await.unwrap();
+
+    // Text appended on disk is picked up without diffing the whole file.
+    let appended_text = format!("{large_text}2024-01-02 WARN disk almost full\n");
+    fs.save(
+        path!("/dir/large.rs").as_ref(),
+        &appended_text.as_str().into(),
+        LineEnding::Unix,
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), appended_text);
+        assert!(!buffer.is_dirty());
+    });
+
+    let git_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(large_buffer.clone(), cx)
+        })
+        .await;
+    assert!(git_diff.is_err());
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3517,6 +4514,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         path!("/dir/file1").as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3528,6 +4526,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         path!("/dir/file1").as_ref(),
         &"the second contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3572,6 +4571,7 @@ async fn test_edit_buffer_while_it_reloads(cx: &mut gpui::TestAppContext) {
         path!("/dir/file1").as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -4177,6 +5177,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         path!("/dir/the-file").as_ref(),
         &new_contents.as_str().into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -4209,6 +5210,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         path!("/dir/the-file").as_ref(),
         &"\n\n\nAAAA\naaa\nBB\nbbbbb\n".into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -4261,6 +5263,7 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
         path!("/dir/file1").as_ref(),
         &"aaa\nb\nc\n".into(),
         LineEnding::Windows,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -246,6 +246,8 @@ messages!(
     (SyncExtensionsResponse, Background),
     (BreakpointsForFile, Background),
     (ToggleBreakpoint, Foreground),
+    (FunctionAndDataBreakpoints, Background),
+    (SetFunctionAndDataBreakpoints, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
     (SynchronizeContexts, Foreground),
@@ -306,6 +308,62 @@ messages!(
     (DebugAdapterBinary, Background),
     (RunDebugLocator, Background),
     (DebugTaskDefinition, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetInlineValues, Background),
+    (GetInlineValuesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
+    (GetDocumentColors, Background),
+    (GetDocumentColorsResponse, Background),
+    (GetColorPresentations, Background),
+    (GetColorPresentationsResponse, Background),
+    (GitStashPush, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashShow, Background),
+    (GitStashApply, Background),
+    (GitStashPop, Background),
+    (GitStashDrop, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitRebaseInteractive, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitContinueOperation, Background),
+    (GitAbortOperation, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
+    (GitCheckoutDetached, Background),
+    (GitListWorktrees, Background),
+    (GitWorktreesResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitListSubmodules, Background),
+    (GitSubmodulesResponse, Background),
+    (GitSyncSubmodules, Background),
 );
 
 request_messages!(
@@ -383,6 +441,7 @@ request_messages!(
     (CountLanguageModelTokens, CountLanguageModelTokensResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -464,8 +523,45 @@ request_messages!(
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
+    (SetFunctionAndDataBreakpoints, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocator, DebugTaskDefinition),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetInlineValues, GetInlineValuesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
+    (GetDocumentColors, GetDocumentColorsResponse),
+    (GetColorPresentations, GetColorPresentationsResponse),
+    (GitStashPush, Ack),
+    (GitStashList, GitStashListResponse),
+    (GitStashShow, LoadCommitDiffResponse),
+    (GitStashApply, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
+    (GitLog, GitLogResponse),
+    (GitRebaseInteractive, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitContinueOperation, Ack),
+    (GitAbortOperation, Ack),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
+    (GitCheckoutDetached, Ack),
+    (GitListWorktrees, GitWorktreesResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitListSubmodules, GitSubmodulesResponse),
+    (GitSyncSubmodules, RemoteMessageResponse),
 );
 
 entity_messages!(
@@ -585,8 +681,47 @@ entity_messages!(
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
+    FunctionAndDataBreakpoints,
+    SetFunctionAndDataBreakpoints,
     RunDebugLocator,
     GetDebugAdapterBinary,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetSemanticTokens,
+    RefreshSemanticTokens,
+    GetFoldingRanges,
+    GetInlineValues,
+    GetSelectionRanges,
+    GetDocumentLinks,
+    ResolveDocumentLink,
+    GetDocumentColors,
+    GetColorPresentations,
+    GitStashPush,
+    GitStashList,
+    GitStashShow,
+    GitStashApply,
+    GitStashPop,
+    GitStashDrop,
+    GitLog,
+    GitRebaseInteractive,
+    GitCherryPick,
+    GitRevert,
+    GitContinueOperation,
+    GitAbortOperation,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
+    GitCheckoutDetached,
+    GitListWorktrees,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitListSubmodules,
+    GitSyncSubmodules,
 );
 
 entity_messages!(
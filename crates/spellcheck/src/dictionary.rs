@@ -0,0 +1,592 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+
+/// The most suggestions offered for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The characters tried when the affix file doesn't have a `TRY` directive.
+const DEFAULT_TRY_CHARS: &str = "esianrtolcdugmphbyfvkwzqxj'";
+
+type Flag = u32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FlagFormat {
+    /// Each character is a flag.
+    #[default]
+    Short,
+    /// Each pair of characters is a flag.
+    Long,
+    /// Flags are decimal numbers separated by commas.
+    Numeric,
+}
+
+impl FlagFormat {
+    fn parse(&self, flags: &str) -> Result<Vec<Flag>> {
+        match self {
+            FlagFormat::Short => Ok(flags.chars().map(|c| c as Flag).collect()),
+            FlagFormat::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+                if chars.len() % 2 != 0 {
+                    return Err(anyhow!("odd number of characters in long flags {flags:?}"));
+                }
+                Ok(chars
+                    .chunks(2)
+                    .map(|pair| ((pair[0] as Flag) << 16) | pair[1] as Flag)
+                    .collect())
+            }
+            FlagFormat::Numeric => flags
+                .split(',')
+                .map(|flag| {
+                    flag.trim()
+                        .parse()
+                        .with_context(|| format!("invalid numeric flag {flag:?}"))
+                })
+                .collect(),
+        }
+    }
+
+    fn parse_one(&self, flag: &str) -> Result<Flag> {
+        match self.parse(flag)?.as_slice() {
+            [flag] => Ok(*flag),
+            _ => Err(anyhow!("expected a single flag, got {flag:?}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ConditionChar {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl ConditionChar {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionChar::Any => true,
+            ConditionChar::Char(expected) => c == *expected,
+            ConditionChar::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// The condition an affix places on the stem it is attached to, a simplified regular expression
+/// made of characters, `.` and bracketed character sets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Condition(Vec<ConditionChar>);
+
+impl Condition {
+    fn parse(condition: &str) -> Result<Self> {
+        if condition == "." {
+            return Ok(Self::default());
+        }
+        let mut result = Vec::new();
+        let mut chars = condition.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => result.push(ConditionChar::Any),
+                '[' => {
+                    let mut set = Vec::new();
+                    let mut negated = false;
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some('^') if set.is_empty() && !negated => negated = true,
+                            Some(c) => set.push(c),
+                            None => return Err(anyhow!("unterminated set in {condition:?}")),
+                        }
+                    }
+                    result.push(ConditionChar::Set {
+                        chars: set,
+                        negated,
+                    });
+                }
+                c => result.push(ConditionChar::Char(c)),
+            }
+        }
+        Ok(Self(result))
+    }
+
+    fn matches_start(&self, stem: &str) -> bool {
+        let mut chars = stem.chars();
+        self.0
+            .iter()
+            .all(|condition| chars.next().is_some_and(|c| condition.matches(c)))
+    }
+
+    fn matches_end(&self, stem: &str) -> bool {
+        let mut chars = stem.chars().rev();
+        self.0
+            .iter()
+            .rev()
+            .all(|condition| chars.next().is_some_and(|c| condition.matches(c)))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Condition,
+}
+
+/// A Hunspell dictionary, made of an affix file (`.aff`) and a word list (`.dic`).
+///
+/// Words are checked the way Hunspell does: by looking them up directly, or by removing a prefix
+/// and/or a suffix and looking up a stem that allows them. Compounding, continuation classes on
+/// affixes and morphological data are not supported.
+pub struct Dictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    forbidden_word: Option<Flag>,
+    need_affix: Option<Flag>,
+    only_in_compound: Option<Flag>,
+    no_suggest: Option<Flag>,
+}
+
+impl Dictionary {
+    /// Returns the character encoding declared by an affix file's `SET` directive.
+    pub fn declared_encoding(aff: &[u8]) -> Option<&str> {
+        aff.split(|byte| *byte == b'\n').find_map(|line| {
+            let line = std::str::from_utf8(line).ok()?;
+            let mut parts = line.split_whitespace();
+            (parts.next()? == "SET").then(|| parts.next())?
+        })
+    }
+
+    pub fn new(aff: &str, dic: &str) -> Result<Self> {
+        let mut dictionary = Self {
+            words: HashMap::default(),
+            prefixes: Vec::new(),
+            suffixes: Vec::new(),
+            try_chars: DEFAULT_TRY_CHARS.chars().collect(),
+            replacements: Vec::new(),
+            forbidden_word: None,
+            need_affix: None,
+            only_in_compound: None,
+            no_suggest: None,
+        };
+        let flag_format = dictionary.parse_aff(aff).context("parsing affix file")?;
+        dictionary
+            .parse_dic(dic, flag_format)
+            .context("parsing word list")?;
+        Ok(dictionary)
+    }
+
+    fn parse_aff(&mut self, aff: &str) -> Result<FlagFormat> {
+        let mut flag_format = FlagFormat::default();
+        let mut lines = aff.lines().enumerate();
+        while let Some((row, line)) = lines.next() {
+            let mut parts = line.split_whitespace();
+            let Some(directive) = parts.next() else {
+                continue;
+            };
+            let mut argument = || {
+                parts
+                    .next()
+                    .with_context(|| format!("missing argument on line {}", row + 1))
+            };
+            match directive {
+                "FLAG" => {
+                    flag_format = match argument()? {
+                        "long" => FlagFormat::Long,
+                        "num" => FlagFormat::Numeric,
+                        _ => FlagFormat::Short,
+                    }
+                }
+                "TRY" => self.try_chars = argument()?.chars().collect(),
+                "FORBIDDENWORD" => self.forbidden_word = Some(flag_format.parse_one(argument()?)?),
+                "NEEDAFFIX" => self.need_affix = Some(flag_format.parse_one(argument()?)?),
+                "ONLYINCOMPOUND" => {
+                    self.only_in_compound = Some(flag_format.parse_one(argument()?)?)
+                }
+                "NOSUGGEST" => self.no_suggest = Some(flag_format.parse_one(argument()?)?),
+                "REP" => {
+                    let count = argument()?.parse::<usize>().unwrap_or(0);
+                    for (_, line) in lines.by_ref().take(count) {
+                        let mut parts = line.split_whitespace().skip(1);
+                        if let Some((from, to)) = parts.next().zip(parts.next()) {
+                            self.replacements
+                                .push((from.replace('_', " "), to.replace('_', " ")));
+                        }
+                    }
+                }
+                "PFX" | "SFX" => {
+                    let flag = flag_format.parse_one(argument()?)?;
+                    let cross_product = argument()? == "Y";
+                    let count = argument()?
+                        .parse::<usize>()
+                        .with_context(|| format!("invalid affix count on line {}", row + 1))?;
+                    for (row, line) in lines.by_ref().take(count) {
+                        let mut parts = line.split_whitespace().skip(2);
+                        let (Some(strip), Some(add)) = (parts.next(), parts.next()) else {
+                            return Err(anyhow!("invalid affix on line {}", row + 1));
+                        };
+                        // Continuation classes after the affix are not supported.
+                        let add = add.split('/').next().unwrap_or_default();
+                        let affix = Affix {
+                            flag,
+                            cross_product,
+                            strip: if strip == "0" { "" } else { strip }.to_string(),
+                            add: if add == "0" { "" } else { add }.to_string(),
+                            condition: Condition::parse(parts.next().unwrap_or("."))?,
+                        };
+                        if directive == "PFX" {
+                            self.prefixes.push(affix);
+                        } else {
+                            self.suffixes.push(affix);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(flag_format)
+    }
+
+    fn parse_dic(&mut self, dic: &str, flag_format: FlagFormat) -> Result<()> {
+        // The first line is the approximate number of words.
+        for line in dic.lines().skip(1) {
+            let line = line.trim_end();
+            // Morphological fields are separated from the word by a tab or a space.
+            let entry = line
+                .split(|c| c == '\t' || c == ' ')
+                .next()
+                .unwrap_or_default();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, flags) = split_entry(entry);
+            let flags = match flags {
+                Some(flags) => flag_format.parse(flags)?,
+                None => Vec::new(),
+            };
+            self.words
+                .entry(word.replace("\\/", "/"))
+                .or_default()
+                .push(flags);
+        }
+        Ok(())
+    }
+
+    /// Returns whether the word is spelled correctly, accepting capitalized and upper case
+    /// variants of lower case words.
+    pub fn check(&self, word: &str) -> bool {
+        case_variants(word)
+            .iter()
+            .any(|variant| self.check_exact(variant))
+    }
+
+    fn check_exact(&self, word: &str) -> bool {
+        if let Some(homonyms) = self.words.get(word) {
+            if homonyms.iter().any(|flags| self.is_valid_root(flags)) {
+                return true;
+            }
+            if homonyms
+                .iter()
+                .any(|flags| self.has_flag(flags, self.forbidden_word))
+            {
+                return false;
+            }
+        }
+
+        for suffix in &self.suffixes {
+            let Some(stem) = strip_suffix(word, suffix) else {
+                continue;
+            };
+            if self.stem_has_flags(&stem, &[suffix.flag]) {
+                return true;
+            }
+            if !suffix.cross_product {
+                continue;
+            }
+            for prefix in self.prefixes.iter().filter(|prefix| prefix.cross_product) {
+                if let Some(stem) = strip_prefix(&stem, prefix) {
+                    if self.stem_has_flags(&stem, &[prefix.flag, suffix.flag]) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        self.prefixes.iter().any(|prefix| {
+            strip_prefix(word, prefix)
+                .is_some_and(|stem| self.stem_has_flags(&stem, &[prefix.flag]))
+        })
+    }
+
+    fn is_valid_root(&self, flags: &[Flag]) -> bool {
+        !self.has_flag(flags, self.forbidden_word)
+            && !self.has_flag(flags, self.need_affix)
+            && !self.has_flag(flags, self.only_in_compound)
+    }
+
+    fn stem_has_flags(&self, stem: &str, required: &[Flag]) -> bool {
+        self.words.get(stem).is_some_and(|homonyms| {
+            homonyms.iter().any(|flags| {
+                required.iter().all(|flag| flags.contains(flag))
+                    && !self.has_flag(flags, self.forbidden_word)
+                    && !self.has_flag(flags, self.only_in_compound)
+            })
+        })
+    }
+
+    fn has_flag(&self, flags: &[Flag], flag: Option<Flag>) -> bool {
+        flag.is_some_and(|flag| flags.contains(&flag))
+    }
+
+    fn is_suggestible(&self, word: &str) -> bool {
+        if !self.check(word) {
+            return false;
+        }
+        match self.words.get(word) {
+            Some(homonyms) => !homonyms
+                .iter()
+                .all(|flags| self.has_flag(flags, self.no_suggest)),
+            None => true,
+        }
+    }
+
+    /// Returns correctly spelled words that are one edit away from the given word, using the
+    /// affix file's replacement table and `TRY` characters. Suggestions keep the word's case.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let casing = Casing::of(word);
+        let lower = word.to_lowercase();
+        let chars = lower.chars().collect::<Vec<_>>();
+
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (ix, _) in lower.match_indices(from.as_str()) {
+                candidates.push(format!("{}{to}{}", &lower[..ix], &lower[ix + from.len()..]));
+            }
+        }
+        for ix in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(ix, ix + 1);
+            candidates.push(swapped.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            for &c in &self.try_chars {
+                if c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = c;
+                    candidates.push(replaced.into_iter().collect());
+                }
+            }
+        }
+        for ix in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(ix);
+            candidates.push(deleted.into_iter().collect());
+        }
+        for ix in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, c);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+
+        let mut seen = HashSet::default();
+        let mut suggestions = candidates
+            .into_iter()
+            .filter(|candidate| candidate != &lower && seen.insert(candidate.clone()))
+            .filter(|candidate| {
+                if candidate.contains(' ') {
+                    candidate.split(' ').all(|word| self.check(word))
+                } else {
+                    self.is_suggestible(candidate)
+                }
+            })
+            .take(MAX_SUGGESTIONS)
+            .collect::<Vec<_>>();
+
+        // Splitting the word in two is the last resort.
+        for ix in 1..chars.len() {
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+            let first = chars[..ix].iter().collect::<String>();
+            let second = chars[ix..].iter().collect::<String>();
+            if first.chars().count() > 1 && self.check(&first) && self.check(&second) {
+                suggestions.push(format!("{first} {second}"));
+            }
+        }
+
+        suggestions
+            .into_iter()
+            .map(|suggestion| casing.apply(&suggestion))
+            .collect()
+    }
+}
+
+/// Splits a `.dic` entry into the word and its flags, ignoring escaped slashes.
+fn split_entry(entry: &str) -> (&str, Option<&str>) {
+    let mut escaped = false;
+    for (ix, c) in entry.char_indices() {
+        match c {
+            '\\' => escaped = !escaped,
+            '/' if !escaped && ix > 0 => return (&entry[..ix], Some(&entry[ix + 1..])),
+            _ => escaped = false,
+        }
+    }
+    (entry, None)
+}
+
+fn strip_suffix(word: &str, suffix: &Affix) -> Option<String> {
+    let rest = word.strip_suffix(suffix.add.as_str())?;
+    if rest.is_empty() {
+        return None;
+    }
+    let stem = format!("{rest}{}", suffix.strip);
+    suffix.condition.matches_end(&stem).then_some(stem)
+}
+
+fn strip_prefix(word: &str, prefix: &Affix) -> Option<String> {
+    let rest = word.strip_prefix(prefix.add.as_str())?;
+    if rest.is_empty() {
+        return None;
+    }
+    let stem = format!("{}{rest}", prefix.strip);
+    prefix.condition.matches_start(&stem).then_some(stem)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Casing::Lower;
+        };
+        let rest = chars.collect::<Vec<_>>();
+        if first.is_lowercase() {
+            if rest.iter().all(|c| c.is_lowercase()) {
+                Casing::Lower
+            } else {
+                Casing::Mixed
+            }
+        } else if rest.iter().all(|c| c.is_lowercase()) {
+            Casing::Capitalized
+        } else if rest.iter().all(|c| c.is_uppercase()) {
+            Casing::Upper
+        } else {
+            Casing::Mixed
+        }
+    }
+
+    fn apply(&self, word: &str) -> String {
+        match self {
+            Casing::Capitalized => capitalize(word),
+            Casing::Upper => word.to_uppercase(),
+            Casing::Lower | Casing::Mixed => word.to_string(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The spellings under which a word may appear in the dictionary.
+fn case_variants(word: &str) -> Vec<String> {
+    match Casing::of(word) {
+        Casing::Lower | Casing::Mixed => vec![word.to_string()],
+        Casing::Capitalized => vec![word.to_string(), word.to_lowercase()],
+        Casing::Upper => {
+            let lower = word.to_lowercase();
+            vec![word.to_string(), capitalize(&lower), lower]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+REP 1
+REP f ph
+
+PFX A Y 1
+PFX A   0     re         .
+
+SFX B Y 3
+SFX B   0     s          [^sxy]
+SFX B   y     ies        [^aeiou]y
+SFX B   0     es         [sx]
+
+SFX C N 1
+SFX C   e     ing        e
+
+FORBIDDENWORD X
+";
+
+    const DIC: &str = "8
+make/ABC
+box/B
+city/B
+write/ABC
+phone/B
+hello
+Paris
+teh/X
+";
+
+    #[test]
+    fn test_check() {
+        let dictionary = Dictionary::new(AFF, DIC).unwrap();
+
+        for word in [
+            "make", "makes", "remake", "remakes", "making", "boxes", "cities", "rewrite", "Hello",
+            "HELLO", "Paris", "PARIS", "phones",
+        ] {
+            assert!(dictionary.check(word), "{word} should be correct");
+        }
+        for word in [
+            "maek", "boxs", "citys", "remaking", "paris", "hELLO", "teh", "s",
+        ] {
+            assert!(!dictionary.check(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = Dictionary::new(AFF, DIC).unwrap();
+
+        assert_eq!(dictionary.suggest("maek"), ["make"]);
+        assert_eq!(dictionary.suggest("Helo"), ["Hello"]);
+        assert_eq!(dictionary.suggest("BOXS"), ["BOX", "BOXES"]);
+        assert_eq!(dictionary.suggest("fones"), ["phones"]);
+        assert_eq!(dictionary.suggest("hellobox"), ["hello box"]);
+        assert!(dictionary.suggest("xyzzy").is_empty());
+    }
+
+    #[test]
+    fn test_long_flags_and_escapes() {
+        let aff = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dic = "2\nword/Aa\nand\\/or\n";
+        let dictionary = Dictionary::new(aff, dic).unwrap();
+        assert!(dictionary.check("words"));
+        assert!(dictionary.check("and/or"));
+        assert_eq!(Dictionary::declared_encoding(AFF.as_bytes()), Some("UTF-8"));
+        assert_eq!(Dictionary::declared_encoding(aff.as_bytes()), None);
+    }
+}
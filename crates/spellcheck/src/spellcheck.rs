@@ -0,0 +1,639 @@
+mod dictionary;
+
+pub use dictionary::Dictionary;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+use editor::{CodeActionProvider, Editor, ExcerptId};
+use fs::{Encoding, Fs};
+use gpui::{App, AppContext as _, Context, Entity, Global, Subscription, Task, WeakEntity, Window};
+use language::{
+    Buffer, BufferEvent, BufferSnapshot, Diagnostic, DiagnosticEntry, DiagnosticSet,
+    DiagnosticSeverity, LanguageServerId, PointUtf16,
+};
+use project::{CodeAction, LspAction, Project, ProjectTransaction};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsLocation, SettingsSources, SettingsStore};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+use text::BufferId;
+use util::ResultExt;
+
+/// The source of the diagnostics reported for misspelled words.
+pub const SPELLCHECK_DIAGNOSTIC_SOURCE: &str = "spellcheck";
+
+/// Misspellings are stored alongside language server diagnostics, under an id that no language
+/// server is ever assigned. Every collaborator checks its own replica of a buffer with its own
+/// dictionary, so these diagnostics are never sent to other replicas.
+pub const SPELLCHECK_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+const CODE_ACTION_PROVIDER_ID: &str = "spellcheck";
+
+/// How long to wait after an edit before checking a buffer again.
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+
+pub fn init(cx: &mut App) {
+    SpellcheckSettings::register(cx);
+
+    let spellchecker = cx.new(Spellchecker::new);
+    cx.set_global(GlobalSpellchecker(spellchecker.clone()));
+
+    cx.observe_new(move |editor: &mut Editor, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        if !editor.mode().is_full() {
+            return;
+        }
+        for buffer in editor.buffer().read(cx).all_buffers() {
+            spellchecker.update(cx, |spellchecker, cx| {
+                spellchecker.register_buffer(buffer, cx)
+            });
+        }
+        editor.add_code_action_provider(
+            Rc::new(SpellcheckCodeActionProvider {
+                spellchecker: spellchecker.downgrade(),
+                project: editor.project.as_ref().map(Entity::downgrade),
+            }),
+            window,
+            cx,
+        );
+    })
+    .detach();
+}
+
+/// Settings for spell checking.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpellcheckSettings {
+    pub enabled: bool,
+    pub language: String,
+    pub dictionary_paths: Vec<String>,
+    /// The words from the user settings and from every project settings file that applies.
+    pub words: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SpellcheckSettingsContent {
+    /// Whether to check the spelling of comments, strings and prose files.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The name of the Hunspell dictionary to use, such as `en_US` for `en_US.aff` and `en_US.dic`.
+    ///
+    /// Default: "en_US"
+    pub language: Option<String>,
+    /// Additional directories to look for Hunspell dictionaries in, before the
+    /// `dictionaries` directory in Zed's config directory and the system's dictionaries.
+    ///
+    /// Default: []
+    pub dictionary_paths: Option<Vec<String>>,
+    /// Words that are always considered correctly spelled. Words from the user settings
+    /// and from project settings are combined rather than replaced.
+    ///
+    /// Default: []
+    pub words: Option<Vec<String>>,
+}
+
+impl Settings for SpellcheckSettings {
+    const KEY: Option<&'static str> = Some("spellcheck");
+
+    type FileContent = SpellcheckSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        let content: SpellcheckSettingsContent = sources.json_merge()?;
+        let mut words = Vec::new();
+        for content in sources.defaults_and_customizations() {
+            for word in content.words.iter().flatten() {
+                if !words.contains(word) {
+                    words.push(word.clone());
+                }
+            }
+        }
+        Ok(Self {
+            enabled: content.enabled.unwrap_or_default(),
+            language: content.language.unwrap_or_else(|| "en_US".to_string()),
+            dictionary_paths: content.dictionary_paths.unwrap_or_default(),
+            words,
+        })
+    }
+}
+
+struct GlobalSpellchecker(Entity<Spellchecker>);
+
+impl Global for GlobalSpellchecker {}
+
+/// Checks the spelling of the buffers open in editors, reporting misspelled words as
+/// diagnostics.
+pub struct Spellchecker {
+    dictionary: Option<Arc<Dictionary>>,
+    /// The language and dictionary paths the dictionary was loaded for.
+    dictionary_source: Option<(String, Vec<String>)>,
+    load_dictionary: Task<()>,
+    buffers: HashMap<BufferId, SpellcheckedBuffer>,
+    _settings_subscription: Subscription,
+}
+
+struct SpellcheckedBuffer {
+    buffer: WeakEntity<Buffer>,
+    check: Task<()>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl Spellchecker {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            dictionary: None,
+            dictionary_source: None,
+            load_dictionary: Task::ready(()),
+            buffers: HashMap::default(),
+            _settings_subscription: cx.observe_global::<SettingsStore>(Self::settings_changed),
+        };
+        this.settings_changed(cx);
+        this
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSpellchecker>()
+            .map(|spellchecker| spellchecker.0.clone())
+    }
+
+    pub fn dictionary(&self) -> Option<&Arc<Dictionary>> {
+        self.dictionary.as_ref()
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_dictionary(&mut self, dictionary: Arc<Dictionary>, cx: &mut Context<Self>) {
+        self.dictionary = Some(dictionary);
+        self.check_all_buffers(cx);
+    }
+
+    fn settings_changed(&mut self, cx: &mut Context<Self>) {
+        let settings = SpellcheckSettings::get_global(cx);
+        if !settings.enabled {
+            self.dictionary = None;
+            self.dictionary_source = None;
+            self.load_dictionary = Task::ready(());
+            self.check_all_buffers(cx);
+            return;
+        }
+
+        let source = (settings.language.clone(), settings.dictionary_paths.clone());
+        if self.dictionary_source.as_ref() == Some(&source) {
+            self.check_all_buffers(cx);
+            return;
+        }
+        self.dictionary_source = Some(source.clone());
+        let (language, dictionary_paths) = source;
+        self.load_dictionary = cx.spawn(async move |this, cx| {
+            let dictionary = cx
+                .background_spawn(async move { load_dictionary(&language, &dictionary_paths) })
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.dictionary = dictionary.map(Arc::new);
+                this.check_all_buffers(cx);
+            })
+            .ok();
+        });
+    }
+
+    pub fn register_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let subscriptions = [
+            cx.subscribe(&buffer, move |this, buffer, event, cx| match event {
+                BufferEvent::Edited
+                | BufferEvent::Reparsed
+                | BufferEvent::LanguageChanged
+                | BufferEvent::Reloaded
+                | BufferEvent::FileHandleChanged => this.check_buffer(buffer, CHECK_DEBOUNCE, cx),
+                _ => {}
+            }),
+            cx.observe_release(&buffer, move |this, _, _| {
+                this.buffers.remove(&buffer_id);
+            }),
+        ];
+        self.buffers.insert(
+            buffer_id,
+            SpellcheckedBuffer {
+                buffer: buffer.downgrade(),
+                check: Task::ready(()),
+                _subscriptions: subscriptions,
+            },
+        );
+        self.check_buffer(buffer, Duration::ZERO, cx);
+    }
+
+    fn check_all_buffers(&mut self, cx: &mut Context<Self>) {
+        let buffers = self
+            .buffers
+            .values()
+            .filter_map(|state| state.buffer.upgrade())
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.check_buffer(buffer, Duration::ZERO, cx);
+        }
+    }
+
+    fn check_buffer(&mut self, buffer: Entity<Buffer>, delay: Duration, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let dictionary = self.dictionary.clone();
+        state.check = cx.spawn(async move |_, cx| {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            let Some((snapshot, words)) = buffer
+                .update(cx, |buffer, cx| {
+                    let location = buffer.file().map(|file| SettingsLocation {
+                        worktree_id: file.worktree_id(cx),
+                        path: file.path().as_ref(),
+                    });
+                    let words = SpellcheckSettings::get(location, cx)
+                        .words
+                        .iter()
+                        .map(|word| word.to_lowercase())
+                        .collect::<HashSet<_>>();
+                    (buffer.snapshot(), words)
+                })
+                .ok()
+            else {
+                return;
+            };
+
+            let diagnostics = cx
+                .background_spawn(async move {
+                    let entries = match dictionary {
+                        Some(dictionary) => misspellings(&snapshot, &dictionary, &words),
+                        None => Vec::new(),
+                    };
+                    DiagnosticSet::new(entries, &snapshot)
+                })
+                .await;
+            buffer
+                .update(cx, |buffer, cx| {
+                    buffer.update_local_diagnostics(SPELLCHECK_SERVER_ID, diagnostics, cx)
+                })
+                .ok();
+        });
+    }
+}
+
+/// Finds a dictionary for the given language, looking in the configured directories first,
+/// then in Zed's `dictionaries` directory and finally in the system's dictionary directories.
+fn load_dictionary(language: &str, dictionary_paths: &[String]) -> Result<Dictionary> {
+    let directories = dictionary_paths
+        .iter()
+        .map(|path| PathBuf::from(shellexpand::tilde(path).as_ref()))
+        .chain([paths::dictionaries_dir().clone()])
+        .chain(system_dictionary_dirs());
+    for directory in directories {
+        let aff_path = directory.join(format!("{language}.aff"));
+        let dic_path = directory.join(format!("{language}.dic"));
+        if aff_path.is_file() && dic_path.is_file() {
+            return read_dictionary(&aff_path, &dic_path)
+                .with_context(|| format!("loading dictionary {}", aff_path.display()));
+        }
+    }
+    Err(anyhow!("no dictionary found for {language}"))
+}
+
+fn read_dictionary(aff_path: &Path, dic_path: &Path) -> Result<Dictionary> {
+    let aff = std::fs::read(aff_path)?;
+    let dic = std::fs::read(dic_path)?;
+    let encoding = Dictionary::declared_encoding(&aff)
+        .and_then(|name| Encoding::from_name(name, false))
+        .unwrap_or_default();
+    Dictionary::new(&encoding.decode(&aff), &encoding.decode(&dic))
+}
+
+fn system_dictionary_dirs() -> Vec<PathBuf> {
+    if cfg!(target_os = "macos") {
+        vec![
+            paths::home_dir().join("Library/Spelling"),
+            PathBuf::from("/Library/Spelling"),
+        ]
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        vec![
+            PathBuf::from("/usr/share/hunspell"),
+            PathBuf::from("/usr/local/share/hunspell"),
+            PathBuf::from("/usr/share/myspell"),
+            PathBuf::from("/usr/share/myspell/dicts"),
+        ]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Returns a diagnostic for each misspelled word in the buffer's prose.
+fn misspellings(
+    snapshot: &BufferSnapshot,
+    dictionary: &Dictionary,
+    words: &HashSet<String>,
+) -> Vec<DiagnosticEntry<PointUtf16>> {
+    let mut entries = Vec::new();
+    for range in snapshot.spellcheck_ranges(0..snapshot.len()) {
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        for word_range in misspelled_words(&text, dictionary, words) {
+            let start = range.start + word_range.start;
+            let end = range.start + word_range.end;
+            entries.push(DiagnosticEntry {
+                range: snapshot.offset_to_point_utf16(start)..snapshot.offset_to_point_utf16(end),
+                diagnostic: Diagnostic {
+                    source: Some(SPELLCHECK_DIAGNOSTIC_SOURCE.to_string()),
+                    severity: DiagnosticSeverity::INFORMATION,
+                    message: format!("Unknown word: {}", &text[word_range]),
+                    group_id: entries.len(),
+                    is_primary: true,
+                    ..Default::default()
+                },
+            });
+        }
+    }
+    entries
+}
+
+/// Returns the ranges of the words in the text that are neither in the dictionary nor in the
+/// given list of lower case words.
+///
+/// Tokens that look like code rather than prose, such as paths, URLs, `snake_case`,
+/// `camelCase` and upper case identifiers or anything containing digits, are skipped.
+fn misspelled_words(
+    text: &str,
+    dictionary: &Dictionary,
+    words: &HashSet<String>,
+) -> Vec<Range<usize>> {
+    let mut misspelled = Vec::new();
+    let mut offset = 0;
+    for token in text.split_inclusive(char::is_whitespace) {
+        let token_start = offset;
+        offset += token.len();
+
+        let trimmed = token.trim_start_matches(|c: char| !c.is_alphanumeric());
+        let start = token_start + token.len() - trimmed.len();
+        let trimmed = trimmed.trim_end_matches(|c: char| !c.is_alphanumeric());
+        if trimmed.is_empty()
+            || !trimmed
+                .chars()
+                .all(|c| c.is_alphabetic() || c == '\'' || c == '’' || c == '-')
+        {
+            continue;
+        }
+
+        let mut part_start = start;
+        for part in trimmed.split('-') {
+            let part_range = part_start..part_start + part.len();
+            part_start += part.len() + 1;
+            if part.chars().count() < 2
+                || !is_prose_case(part)
+                || words.contains(&part.to_lowercase())
+            {
+                continue;
+            }
+            let normalized = part.replace('’', "'");
+            if !dictionary.check(&normalized) {
+                misspelled.push(part_range);
+            }
+        }
+    }
+    misspelled
+}
+
+/// Whether the word is lower case or capitalized, as opposed to an acronym or an identifier.
+fn is_prose_case(word: &str) -> bool {
+    word.chars().skip(1).all(|c| !c.is_uppercase())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellcheckAction {
+    Replace { text: String },
+    AddToUserDictionary { word: String },
+    AddToProjectDictionary { word: String },
+}
+
+struct SpellcheckCodeActionProvider {
+    spellchecker: WeakEntity<Spellchecker>,
+    project: Option<WeakEntity<Project>>,
+}
+
+impl CodeActionProvider for SpellcheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(dictionary) = self
+            .spellchecker
+            .upgrade()
+            .and_then(|spellchecker| spellchecker.read(cx).dictionary().cloned())
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let can_add_to_project = self.project.as_ref().is_some_and(|project| {
+            project
+                .upgrade()
+                .is_some_and(|project| project.read(cx).is_local())
+        }) && buffer.read(cx).file().is_some();
+
+        let snapshot = buffer.read(cx).snapshot();
+        let misspellings = snapshot
+            .diagnostics_in_range::<_, usize>(range, false)
+            .filter(|entry| {
+                entry.diagnostic.source.as_deref() == Some(SPELLCHECK_DIAGNOSTIC_SOURCE)
+            })
+            .map(|entry| {
+                let word = snapshot
+                    .text_for_range(entry.range.clone())
+                    .collect::<String>();
+                let range = snapshot.anchor_before(entry.range.start)
+                    ..snapshot.anchor_after(entry.range.end);
+                (word, range)
+            })
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            let mut actions = Vec::new();
+            for (word, range) in misspellings {
+                let action = |title: String, action: SpellcheckAction| CodeAction {
+                    server_id: SPELLCHECK_SERVER_ID,
+                    range: range.clone(),
+                    lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                        title,
+                        kind: Some(lsp::CodeActionKind::QUICKFIX),
+                        data: serde_json::to_value(action).log_err(),
+                        ..Default::default()
+                    })),
+                    resolved: true,
+                };
+                for suggestion in dictionary.suggest(&word) {
+                    actions.push(action(
+                        format!("Change to \"{suggestion}\""),
+                        SpellcheckAction::Replace { text: suggestion },
+                    ));
+                }
+                actions.push(action(
+                    format!("Add \"{word}\" to user dictionary"),
+                    SpellcheckAction::AddToUserDictionary { word: word.clone() },
+                ));
+                if can_add_to_project {
+                    actions.push(action(
+                        format!("Add \"{word}\" to project dictionary"),
+                        SpellcheckAction::AddToProjectDictionary { word },
+                    ));
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        _push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let LspAction::Action(lsp_action) = &action.lsp_action else {
+            return Task::ready(Err(anyhow!("unexpected spellcheck action")));
+        };
+        let spellcheck_action = match lsp_action
+            .data
+            .clone()
+            .map(serde_json::from_value::<SpellcheckAction>)
+        {
+            Some(Ok(spellcheck_action)) => spellcheck_action,
+            _ => return Task::ready(Err(anyhow!("invalid spellcheck action"))),
+        };
+
+        match spellcheck_action {
+            SpellcheckAction::Replace { text } => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range.clone(), text)], None, cx);
+                    buffer.end_transaction(cx);
+                    buffer.finalize_last_transaction().cloned()
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellcheckAction::AddToUserDictionary { word } => {
+                let Some(project) = self.project.as_ref().and_then(WeakEntity::upgrade) else {
+                    return Task::ready(Err(anyhow!("no project to update settings for")));
+                };
+                let fs = project.read(cx).fs().clone();
+                settings::update_settings_file::<SpellcheckSettings>(fs, cx, move |content, _| {
+                    add_word(content, word);
+                });
+                Task::ready(Ok(ProjectTransaction::default()))
+            }
+            SpellcheckAction::AddToProjectDictionary { word } => {
+                let Some(project) = self.project.as_ref().and_then(WeakEntity::upgrade) else {
+                    return Task::ready(Err(anyhow!("no project to update settings for")));
+                };
+                let settings_path = buffer
+                    .read(cx)
+                    .file()
+                    .and_then(|file| project.read(cx).worktree_for_id(file.worktree_id(cx), cx))
+                    .map(|worktree| {
+                        worktree
+                            .read(cx)
+                            .abs_path()
+                            .join(paths::local_settings_file_relative_path())
+                    });
+                let Some(settings_path) = settings_path else {
+                    return Task::ready(Err(anyhow!("file is not part of a worktree")));
+                };
+                let fs = project.read(cx).fs().clone();
+                cx.spawn(async move |cx| {
+                    add_word_to_settings_file(fs, settings_path, word, cx).await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+        }
+    }
+}
+
+fn add_word(content: &mut SpellcheckSettingsContent, word: String) {
+    let words = content.words.get_or_insert_default();
+    if !words.contains(&word) {
+        words.push(word);
+    }
+}
+
+async fn add_word_to_settings_file(
+    fs: Arc<dyn Fs>,
+    settings_path: PathBuf,
+    word: String,
+    cx: &mut gpui::AsyncApp,
+) -> Result<()> {
+    let old_text = if fs.is_file(&settings_path).await {
+        fs.load(&settings_path).await?
+    } else {
+        String::new()
+    };
+    let new_text = cx.read_global(|store: &SettingsStore, _| {
+        store.new_text_for_update::<SpellcheckSettings>(old_text, |content| add_word(content, word))
+    })?;
+    if let Some(parent) = settings_path.parent() {
+        fs.create_dir(parent).await?;
+    }
+    fs.atomic_write(settings_path, new_text).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misspelled_words() {
+        let dictionary = Dictionary::new(
+            "SFX S Y 1\nSFX S 0 s .\n",
+            "6\nthe/S\nquick\nbrown\nfox\njump/S\ndog/S\n",
+        )
+        .unwrap();
+        let words = HashSet::from_iter(["zed".to_string()]);
+        let misspelled = |text: &str| {
+            misspelled_words(text, &dictionary, &words)
+                .into_iter()
+                .map(|range| text[range].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            misspelled("The quikc brown fox jumps over the lazy dog."),
+            ["quikc", "over", "lazy"]
+        );
+        // Words from the word list are accepted in any case.
+        assert_eq!(misspelled("Zed, ZED and zed"), ["and"]);
+        // Code-like tokens and acronyms are skipped.
+        assert_eq!(
+            misspelled("see snake_case, camelCase, HTTP, path/to/file.rs and v2"),
+            ["see", "and"]
+        );
+        // Hyphenated words are checked part by part.
+        assert_eq!(misspelled("brown-foxx"), ["foxx"]);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    CollapseSelectedEntry, ExpandSelectedEntry, ToggleDirection,
+    hierarchy_tree::{
+        Children, HierarchyTree, active_editor_position, location_label, open_location,
+    },
+};
+use editor::actions::ShowCallHierarchy;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, SharedString,
+    WeakEntity, Window, uniform_list,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{CallHierarchyCall, CallHierarchyItem, Project};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    SplitDirection, Workspace,
+    item::{Item, ItemEvent},
+};
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(show_call_hierarchy);
+    })
+    .detach();
+}
+
+fn show_call_hierarchy(
+    workspace: &mut Workspace,
+    _: &ShowCallHierarchy,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some((buffer, position)) = active_editor_position(workspace, cx) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let prepare = project.update(cx, |project, cx| {
+        project.prepare_call_hierarchy(&buffer, position, cx)
+    });
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(root) = prepare.await?.into_iter().next() else {
+            return Ok(());
+        };
+        workspace.update_in(cx, |workspace, window, cx| {
+            if let Some(existing) = workspace.item_of_type::<CallHierarchyView>(cx) {
+                existing.update(cx, |view, cx| view.set_root(root, cx));
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let workspace_handle = workspace.weak_handle();
+                let view = cx
+                    .new(|cx| CallHierarchyView::new(root, project, workspace_handle, window, cx));
+                workspace.split_item(SplitDirection::Right, Box::new(view), window, cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Incoming,
+    Outgoing,
+}
+
+/// A tree of the callers or callees of a symbol, lazily expanded by querying
+/// the language server for each node as it is opened.
+pub struct CallHierarchyView {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    direction: Direction,
+    tree: HierarchyTree<CallHierarchyCall>,
+    focus_handle: FocusHandle,
+}
+
+fn root_call(item: CallHierarchyItem) -> CallHierarchyCall {
+    CallHierarchyCall {
+        item,
+        call_sites: Vec::new(),
+    }
+}
+
+impl CallHierarchyView {
+    pub fn new(
+        root: CallHierarchyItem,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        focus_handle.focus(window);
+        let mut this = Self {
+            project,
+            workspace,
+            direction: Direction::Incoming,
+            tree: HierarchyTree::new(root_call(root)),
+            focus_handle,
+        };
+        this.expand_entry(0, cx);
+        this
+    }
+
+    pub fn set_root(&mut self, root: CallHierarchyItem, cx: &mut Context<Self>) {
+        self.tree.reset(root_call(root));
+        self.expand_entry(0, cx);
+    }
+
+    fn root(&self) -> &CallHierarchyItem {
+        &self.tree.root().item
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        let direction = match self.direction {
+            Direction::Incoming => Direction::Outgoing,
+            Direction::Outgoing => Direction::Incoming,
+        };
+        self.set_direction(direction, cx);
+    }
+
+    fn set_direction(&mut self, direction: Direction, cx: &mut Context<Self>) {
+        if self.direction == direction {
+            return;
+        }
+        self.direction = direction;
+        let root = self.root().clone();
+        self.set_root(root, cx);
+    }
+
+    fn expand_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        let generation = self.tree.generation();
+        if let Some(call) = self.tree.expand(entry_ix) {
+            let calls = self.project.update(cx, |project, cx| match self.direction {
+                Direction::Incoming => project.incoming_calls(&call.item, cx),
+                Direction::Outgoing => project.outgoing_calls(&call.item, cx),
+            });
+            let task = cx.spawn(async move |this, cx| {
+                let calls = calls.await.log_err().unwrap_or_default();
+                this.update(cx, |this, cx| {
+                    if this.tree.generation() == generation {
+                        this.tree.insert_children(entry_ix, calls);
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+            self.tree.set_loading(entry_ix, task);
+        }
+        cx.notify();
+    }
+
+    fn toggle_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.collapse(entry_ix);
+            cx.notify();
+        } else {
+            self.expand_entry(entry_ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_first();
+        cx.notify();
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_last();
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_next();
+        cx.notify();
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_previous();
+        cx.notify();
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.tree.selected_entry() else {
+            return;
+        };
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.select_next();
+            cx.notify();
+        } else {
+            self.expand_entry(entry_ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.tree.selected_entry() else {
+            return;
+        };
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.collapse(entry_ix);
+        } else {
+            self.tree.select_previous();
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry_ix) = self.tree.selected_entry() {
+            self.open_entry(entry_ix, window, cx);
+        }
+    }
+
+    /// Opens the first call site of the entry, falling back to the
+    /// declaration of the symbol for the root of the hierarchy.
+    fn open_entry(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let call = &self.tree.entry(entry_ix).node;
+        let location = call
+            .call_sites
+            .first()
+            .unwrap_or(&call.item.location)
+            .clone();
+        open_location(&self.workspace, location, window, cx);
+    }
+
+    fn render_entry(&self, ix: usize, entry_ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = self.tree.entry(entry_ix);
+        let item = &entry.node.item;
+        let toggle = match &entry.children {
+            Children::Loaded(children) if children.is_empty() => None,
+            _ => Some(entry.is_expanded),
+        };
+        let location = location_label(&item.location, cx);
+        let call_count = entry.node.call_sites.len();
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(entry.depth)
+            .indent_step_size(px(12.))
+            .toggle(toggle)
+            .toggle_state(self.tree.selected_ix() == Some(ix))
+            .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_entry(entry_ix, cx)))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.tree.select(ix);
+                this.open_entry(entry_ix, window, cx);
+                cx.notify();
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(item.name.clone()))
+                    .when_some(item.detail.clone(), |row, detail| {
+                        row.child(Label::new(detail).color(Color::Muted).truncate())
+                    })
+                    .when_some(location, |row, location| {
+                        row.child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_slot::<Label>(if let Children::Loading(_) = entry.children {
+                Some(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            } else if call_count > 1 {
+                Some(
+                    Label::new(format!("{call_count} calls"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            } else {
+                None
+            })
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(self.root().name.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("incoming-calls", "Incoming Calls")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.direction == Direction::Incoming)
+                    .on_click(
+                        cx.listener(|this, _, _, cx| this.set_direction(Direction::Incoming, cx)),
+                    ),
+            )
+            .child(
+                Button::new("outgoing-calls", "Outgoing Calls")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.direction == Direction::Outgoing)
+                    .on_click(
+                        cx.listener(|this, _, _, cx| this.set_direction(Direction::Outgoing, cx)),
+                    ),
+            )
+    }
+}
+
+impl Render for CallHierarchyView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SymbolHierarchy")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .on_action(cx.listener(Self::confirm))
+            .child(self.render_header(cx))
+            .child(
+                uniform_list(
+                    cx.entity().clone(),
+                    "call-hierarchy",
+                    self.tree.visible_entries().len(),
+                    |this, range, _, cx| {
+                        range
+                            .map(|ix| this.render_entry(ix, this.tree.visible_entries()[ix], cx))
+                            .collect()
+                    },
+                )
+                .track_scroll(self.tree.scroll_handle.clone())
+                .size_full(),
+            )
+    }
+}
+
+impl EventEmitter<()> for CallHierarchyView {}
+
+impl Focusable for CallHierarchyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CallHierarchyView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(ItemEvent)) {}
+
+    fn tab_content_text(&self, _: &Window, _: &App) -> Option<SharedString> {
+        Some(format!("Call Hierarchy: {}", self.root().name).into())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Call Hierarchy Opened")
+    }
+}
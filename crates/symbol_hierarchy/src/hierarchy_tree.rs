@@ -0,0 +1,217 @@
+use editor::{Editor, scroll::Autoscroll};
+use gpui::{App, Entity, ScrollStrategy, Task, UniformListScrollHandle, WeakEntity, Window};
+use language::{Anchor, Buffer, Location, Point, ToPoint as _};
+use std::ops::Range;
+use workspace::Workspace;
+
+pub(crate) enum Children {
+    NotLoaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+pub(crate) struct HierarchyEntry<T> {
+    pub node: T,
+    pub depth: usize,
+    pub is_expanded: bool,
+    pub children: Children,
+}
+
+/// The state of a lazily expanded hierarchy, flattened into the rows that are
+/// currently displayed.
+pub(crate) struct HierarchyTree<T> {
+    /// Incremented whenever the tree is reset, so that responses for entries
+    /// of a previous tree can be discarded.
+    generation: usize,
+    /// All entries fetched so far, with the hierarchy's root at index 0.
+    entries: Vec<HierarchyEntry<T>>,
+    /// Indices into `entries` of the rows that are currently displayed.
+    visible_entries: Vec<usize>,
+    selected_ix: Option<usize>,
+    pub scroll_handle: UniformListScrollHandle,
+}
+
+impl<T> HierarchyTree<T> {
+    pub fn new(root: T) -> Self {
+        let mut this = Self {
+            generation: 0,
+            entries: Vec::new(),
+            visible_entries: Vec::new(),
+            selected_ix: None,
+            scroll_handle: UniformListScrollHandle::new(),
+        };
+        this.reset(root);
+        this
+    }
+
+    pub fn reset(&mut self, root: T) {
+        self.generation += 1;
+        self.entries = vec![HierarchyEntry {
+            node: root,
+            depth: 0,
+            is_expanded: false,
+            children: Children::NotLoaded,
+        }];
+        self.selected_ix = Some(0);
+        self.update_visible_entries();
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn root(&self) -> &T {
+        &self.entries[0].node
+    }
+
+    pub fn entry(&self, entry_ix: usize) -> &HierarchyEntry<T> {
+        &self.entries[entry_ix]
+    }
+
+    pub fn visible_entries(&self) -> &[usize] {
+        &self.visible_entries
+    }
+
+    pub fn selected_ix(&self) -> Option<usize> {
+        self.selected_ix
+    }
+
+    pub fn selected_entry(&self) -> Option<usize> {
+        self.visible_entries.get(self.selected_ix?).copied()
+    }
+
+    /// Marks the entry as expanded, returning its node if its children still
+    /// need to be fetched.
+    pub fn expand(&mut self, entry_ix: usize) -> Option<&T> {
+        let entry = self.entries.get_mut(entry_ix)?;
+        entry.is_expanded = true;
+        let needs_children = matches!(entry.children, Children::NotLoaded);
+        self.update_visible_entries();
+        needs_children.then(|| &self.entries[entry_ix].node)
+    }
+
+    pub fn collapse(&mut self, entry_ix: usize) {
+        if let Some(entry) = self.entries.get_mut(entry_ix) {
+            entry.is_expanded = false;
+            self.update_visible_entries();
+        }
+    }
+
+    pub fn set_loading(&mut self, entry_ix: usize, task: Task<()>) {
+        self.entries[entry_ix].children = Children::Loading(task);
+    }
+
+    pub fn insert_children(&mut self, parent_ix: usize, nodes: Vec<T>) {
+        let depth = self.entries[parent_ix].depth + 1;
+        let mut children = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            children.push(self.entries.len());
+            self.entries.push(HierarchyEntry {
+                node,
+                depth,
+                is_expanded: false,
+                children: Children::NotLoaded,
+            });
+        }
+        self.entries[parent_ix].children = Children::Loaded(children);
+        self.update_visible_entries();
+    }
+
+    pub fn select(&mut self, ix: usize) {
+        self.selected_ix = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+    }
+
+    pub fn select_first(&mut self) {
+        self.select(0);
+    }
+
+    pub fn select_last(&mut self) {
+        self.select(self.visible_entries.len().saturating_sub(1));
+    }
+
+    pub fn select_next(&mut self) {
+        let ix = match self.selected_ix {
+            Some(ix) if ix + 1 < self.visible_entries.len() => ix + 1,
+            _ => 0,
+        };
+        self.select(ix);
+    }
+
+    pub fn select_previous(&mut self) {
+        let ix = match self.selected_ix {
+            Some(ix) if ix > 0 => ix - 1,
+            _ => self.visible_entries.len().saturating_sub(1),
+        };
+        self.select(ix);
+    }
+
+    fn update_visible_entries(&mut self) {
+        let selected_entry = self.selected_entry();
+
+        self.visible_entries.clear();
+        let mut stack = vec![0];
+        while let Some(entry_ix) = stack.pop() {
+            self.visible_entries.push(entry_ix);
+            let entry = &self.entries[entry_ix];
+            if let (true, Children::Loaded(children)) = (entry.is_expanded, &entry.children) {
+                stack.extend(children.iter().rev());
+            }
+        }
+
+        self.selected_ix = selected_entry
+            .and_then(|selected_entry| {
+                self.visible_entries
+                    .iter()
+                    .position(|entry_ix| *entry_ix == selected_entry)
+            })
+            .or(Some(0));
+    }
+}
+
+/// Returns the buffer and position of the newest selection in the active editor.
+pub(crate) fn active_editor_position(
+    workspace: &Workspace,
+    cx: &mut App,
+) -> Option<(Entity<Buffer>, Anchor)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    })
+}
+
+/// Formats the location as `file_name:row`, for display next to an entry.
+pub(crate) fn location_label(location: &Location, cx: &App) -> Option<String> {
+    let buffer = location.buffer.read(cx);
+    let file = buffer.file()?;
+    let row = location.range.start.to_point(buffer).row;
+    Some(format!(
+        "{}:{}",
+        file.file_name(cx).to_string_lossy(),
+        row + 1
+    ))
+}
+
+pub(crate) fn open_location(
+    workspace: &WeakEntity<Workspace>,
+    location: Location,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(workspace) = workspace.upgrade() else {
+        return;
+    };
+    let range: Range<Point> = location.range.to_point(location.buffer.read(cx));
+    workspace.update(cx, |workspace, cx| {
+        let pane = workspace.adjacent_pane(window, cx);
+        let editor =
+            workspace.open_project_item::<Editor>(pane, location.buffer, true, true, window, cx);
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                s.select_ranges([range])
+            });
+        });
+    });
+}
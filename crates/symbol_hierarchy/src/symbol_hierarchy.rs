@@ -0,0 +1,18 @@
+mod call_hierarchy_view;
+mod hierarchy_tree;
+mod type_hierarchy_view;
+
+use gpui::{App, actions};
+
+pub use call_hierarchy_view::CallHierarchyView;
+pub use type_hierarchy_view::TypeHierarchyView;
+
+actions!(
+    symbol_hierarchy,
+    [ToggleDirection, ExpandSelectedEntry, CollapseSelectedEntry]
+);
+
+pub fn init(cx: &mut App) {
+    call_hierarchy_view::init(cx);
+    type_hierarchy_view::init(cx);
+}
@@ -0,0 +1,352 @@
+use crate::{
+    CollapseSelectedEntry, ExpandSelectedEntry, ToggleDirection,
+    hierarchy_tree::{
+        Children, HierarchyTree, active_editor_position, location_label, open_location,
+    },
+};
+use editor::actions::ShowTypeHierarchy;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, SharedString,
+    WeakEntity, Window, uniform_list,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{Project, TypeHierarchyItem};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    SplitDirection, Workspace,
+    item::{Item, ItemEvent},
+};
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(show_type_hierarchy);
+    })
+    .detach();
+}
+
+fn show_type_hierarchy(
+    workspace: &mut Workspace,
+    _: &ShowTypeHierarchy,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some((buffer, position)) = active_editor_position(workspace, cx) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let prepare = project.update(cx, |project, cx| {
+        project.prepare_type_hierarchy(&buffer, position, cx)
+    });
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(root) = prepare.await?.into_iter().next() else {
+            return Ok(());
+        };
+        workspace.update_in(cx, |workspace, window, cx| {
+            if let Some(existing) = workspace.item_of_type::<TypeHierarchyView>(cx) {
+                existing.update(cx, |view, cx| view.set_root(root, cx));
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let workspace_handle = workspace.weak_handle();
+                let view = cx
+                    .new(|cx| TypeHierarchyView::new(root, project, workspace_handle, window, cx));
+                workspace.split_item(SplitDirection::Right, Box::new(view), window, cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Supertypes,
+    Subtypes,
+}
+
+/// A tree of the supertypes or subtypes of a type, lazily expanded by querying
+/// the language server for each node as it is opened.
+pub struct TypeHierarchyView {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    direction: Direction,
+    tree: HierarchyTree<TypeHierarchyItem>,
+    focus_handle: FocusHandle,
+}
+
+impl TypeHierarchyView {
+    pub fn new(
+        root: TypeHierarchyItem,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        focus_handle.focus(window);
+        let mut this = Self {
+            project,
+            workspace,
+            direction: Direction::Supertypes,
+            tree: HierarchyTree::new(root),
+            focus_handle,
+        };
+        this.expand_entry(0, cx);
+        this
+    }
+
+    pub fn set_root(&mut self, root: TypeHierarchyItem, cx: &mut Context<Self>) {
+        self.tree.reset(root);
+        self.expand_entry(0, cx);
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        let direction = match self.direction {
+            Direction::Supertypes => Direction::Subtypes,
+            Direction::Subtypes => Direction::Supertypes,
+        };
+        self.set_direction(direction, cx);
+    }
+
+    fn set_direction(&mut self, direction: Direction, cx: &mut Context<Self>) {
+        if self.direction == direction {
+            return;
+        }
+        self.direction = direction;
+        let root = self.tree.root().clone();
+        self.set_root(root, cx);
+    }
+
+    fn expand_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        let generation = self.tree.generation();
+        if let Some(item) = self.tree.expand(entry_ix) {
+            let items = self.project.update(cx, |project, cx| match self.direction {
+                Direction::Supertypes => project.supertypes(item, cx),
+                Direction::Subtypes => project.subtypes(item, cx),
+            });
+            let task = cx.spawn(async move |this, cx| {
+                let items = items.await.log_err().unwrap_or_default();
+                this.update(cx, |this, cx| {
+                    if this.tree.generation() == generation {
+                        this.tree.insert_children(entry_ix, items);
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+            self.tree.set_loading(entry_ix, task);
+        }
+        cx.notify();
+    }
+
+    fn toggle_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.collapse(entry_ix);
+            cx.notify();
+        } else {
+            self.expand_entry(entry_ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_first();
+        cx.notify();
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_last();
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_next();
+        cx.notify();
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.tree.select_previous();
+        cx.notify();
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.tree.selected_entry() else {
+            return;
+        };
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.select_next();
+            cx.notify();
+        } else {
+            self.expand_entry(entry_ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.tree.selected_entry() else {
+            return;
+        };
+        if self.tree.entry(entry_ix).is_expanded {
+            self.tree.collapse(entry_ix);
+        } else {
+            self.tree.select_previous();
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry_ix) = self.tree.selected_entry() {
+            self.open_entry(entry_ix, window, cx);
+        }
+    }
+
+    fn open_entry(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let location = self.tree.entry(entry_ix).node.location.clone();
+        open_location(&self.workspace, location, window, cx);
+    }
+
+    fn render_entry(&self, ix: usize, entry_ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = self.tree.entry(entry_ix);
+        let item = &entry.node;
+        let toggle = match &entry.children {
+            Children::Loaded(children) if children.is_empty() => None,
+            _ => Some(entry.is_expanded),
+        };
+        let location = location_label(&item.location, cx);
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(entry.depth)
+            .indent_step_size(px(12.))
+            .toggle(toggle)
+            .toggle_state(self.tree.selected_ix() == Some(ix))
+            .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_entry(entry_ix, cx)))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.tree.select(ix);
+                this.open_entry(entry_ix, window, cx);
+                cx.notify();
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(item.name.clone()))
+                    .when_some(item.detail.clone(), |row, detail| {
+                        row.child(Label::new(detail).color(Color::Muted).truncate())
+                    })
+                    .when_some(location, |row, location| {
+                        row.child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_slot::<Label>(if let Children::Loading(_) = entry.children {
+                Some(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            } else {
+                None
+            })
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(self.tree.root().name.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("supertypes", "Supertypes")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.direction == Direction::Supertypes)
+                    .on_click(
+                        cx.listener(|this, _, _, cx| this.set_direction(Direction::Supertypes, cx)),
+                    ),
+            )
+            .child(
+                Button::new("subtypes", "Subtypes")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.direction == Direction::Subtypes)
+                    .on_click(
+                        cx.listener(|this, _, _, cx| this.set_direction(Direction::Subtypes, cx)),
+                    ),
+            )
+    }
+}
+
+impl Render for TypeHierarchyView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SymbolHierarchy")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .on_action(cx.listener(Self::confirm))
+            .child(self.render_header(cx))
+            .child(
+                uniform_list(
+                    cx.entity().clone(),
+                    "type-hierarchy",
+                    self.tree.visible_entries().len(),
+                    |this, range, _, cx| {
+                        range
+                            .map(|ix| this.render_entry(ix, this.tree.visible_entries()[ix], cx))
+                            .collect()
+                    },
+                )
+                .track_scroll(self.tree.scroll_handle.clone())
+                .size_full(),
+            )
+    }
+}
+
+impl EventEmitter<()> for TypeHierarchyView {}
+
+impl Focusable for TypeHierarchyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for TypeHierarchyView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(ItemEvent)) {}
+
+    fn tab_content_text(&self, _: &Window, _: &App) -> Option<SharedString> {
+        Some(format!("Type Hierarchy: {}", self.tree.root().name).into())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Type Hierarchy Opened")
+    }
+}
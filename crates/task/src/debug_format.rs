@@ -272,11 +272,41 @@ impl DebugTaskFile {
 
         serde_json_lenient::to_value(schema).unwrap()
     }
+
+    /// Generates JSON schema of Debug Tasks JSON format, validating the
+    /// `initialize_args` of tasks using each of the given adapters against
+    /// that adapter's schema.
+    pub fn generate_json_schema_with_adapter_schemas(
+        adapter_schemas: &[(String, serde_json::Value)],
+    ) -> serde_json_lenient::Value {
+        let mut schema = Self::generate_json_schema();
+        if adapter_schemas.is_empty() {
+            return schema;
+        }
+
+        let mut item_schemas = vec![schema["items"].take()];
+        item_schemas.extend(adapter_schemas.iter().map(|(adapter, adapter_schema)| {
+            serde_json_lenient::json!({
+                "if": {
+                    "properties": { "adapter": { "const": adapter } },
+                    "required": ["adapter"],
+                },
+                "then": {
+                    "properties": {
+                        "initialize_args": serde_json_lenient::to_value(adapter_schema)
+                            .unwrap_or_default(),
+                    },
+                },
+            })
+        }));
+        schema["items"] = serde_json_lenient::json!({ "allOf": item_schemas });
+        schema
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{DebugRequest, LaunchRequest};
+    use crate::{DebugRequest, DebugTaskFile, LaunchRequest};
 
     #[test]
     fn test_can_deserialize_non_attach_task() {
@@ -290,4 +320,31 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_adapter_schemas_are_applied_to_initialize_args() {
+        let adapter_schema = serde_json::json!({
+            "properties": { "mode": { "enum": ["debug", "test"] } },
+        });
+        let schema = DebugTaskFile::generate_json_schema_with_adapter_schemas(&[(
+            "Ruby".to_owned(),
+            adapter_schema.clone(),
+        )]);
+
+        let item_schemas = schema["items"]["allOf"].as_array().unwrap();
+        assert_eq!(item_schemas.len(), 2);
+        assert_eq!(
+            item_schemas[1]["if"]["properties"]["adapter"]["const"],
+            serde_json_lenient::json!("Ruby")
+        );
+        assert_eq!(
+            item_schemas[1]["then"]["properties"]["initialize_args"],
+            serde_json_lenient::to_value(&adapter_schema).unwrap()
+        );
+
+        assert_eq!(
+            DebugTaskFile::generate_json_schema_with_adapter_schemas(&[]),
+            DebugTaskFile::generate_json_schema()
+        );
+    }
 }
@@ -13,7 +13,9 @@ use client::DevServerProjectId;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{Axis, Bounds, WindowBounds, WindowId, point, size};
 use itertools::Itertools;
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::debugger::breakpoint_store::{
+    BreakpointState, DataBreakpoint, DataBreakpointAccessType, FunctionBreakpoint, SourceBreakpoint,
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -541,6 +543,30 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE function_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER DEFAULT(0) NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE data_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            data_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            access_type INTEGER,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER DEFAULT(0) NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
     ];
 }
 
@@ -629,6 +655,8 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            function_breakpoints: self.function_breakpoints(workspace_id),
+            data_breakpoints: self.data_breakpoints(workspace_id),
             window_id,
         })
     }
@@ -683,6 +711,8 @@ impl WorkspaceDb {
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
             breakpoints: self.breakpoints(workspace_id),
+            function_breakpoints: self.function_breakpoints(workspace_id),
+            data_breakpoints: self.data_breakpoints(workspace_id),
             display,
             docks,
             session_id: None,
@@ -736,6 +766,84 @@ impl WorkspaceDb {
         }
     }
 
+    fn function_breakpoints(&self, workspace_id: WorkspaceId) -> Vec<FunctionBreakpoint> {
+        let breakpoints: Result<
+            Vec<(
+                Arc<str>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT name, condition, hit_condition, state
+                FROM function_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match breakpoints {
+            Ok(breakpoints) => breakpoints
+                .into_iter()
+                .map(
+                    |(name, condition, hit_condition, state)| FunctionBreakpoint {
+                        name,
+                        condition,
+                        hit_condition,
+                        state: state.0.into_owned(),
+                    },
+                )
+                .collect(),
+            Err(msg) => {
+                log::error!("Function breakpoints query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
+    fn data_breakpoints(&self, workspace_id: WorkspaceId) -> Vec<DataBreakpoint> {
+        let breakpoints: Result<
+            Vec<(
+                Arc<str>,
+                Arc<str>,
+                Option<i32>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT data_id, description, access_type, condition, hit_condition, state
+                FROM data_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match breakpoints {
+            Ok(breakpoints) => breakpoints
+                .into_iter()
+                .map(
+                    |(data_id, description, access_type, condition, hit_condition, state)| {
+                        DataBreakpoint {
+                            data_id,
+                            description,
+                            access_type: access_type.and_then(DataBreakpointAccessType::from_int),
+                            condition,
+                            hit_condition,
+                            state: state.0.into_owned(),
+                            // Only data breakpoints that outlive their session are stored.
+                            can_persist: true,
+                        }
+                    },
+                )
+                .collect(),
+            Err(msg) => {
+                log::error!("Data breakpoints query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -777,6 +885,45 @@ impl WorkspaceDb {
 
                 }
 
+                conn.exec_bound(sql!(
+                    DELETE FROM function_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM data_breakpoints WHERE workspace_id = ?1;))?(workspace.id)
+                    .context("Clearing old function and data breakpoints")?;
+
+                for bp in workspace.function_breakpoints {
+                    conn.exec_bound(sql!(
+                        INSERT INTO function_breakpoints (workspace_id, name, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?
+                    ((
+                        workspace.id,
+                        bp.name,
+                        bp.condition,
+                        bp.hit_condition,
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .log_err();
+                }
+
+                for bp in workspace.data_breakpoints {
+                    // The adapter's data ids are usually only valid for the session they came from.
+                    if !bp.can_persist {
+                        continue;
+                    }
+                    conn.exec_bound(sql!(
+                        INSERT INTO data_breakpoints (workspace_id, data_id, description, access_type, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);))?
+                    ((
+                        workspace.id,
+                        bp.data_id,
+                        bp.description,
+                        bp.access_type.map(|access_type| access_type.to_int()),
+                        bp.condition,
+                        bp.hit_condition,
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .log_err();
+                }
+
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
@@ -1530,6 +1677,8 @@ mod tests {
                 );
                 map
             },
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1649,6 +1798,8 @@ mod tests {
                 );
                 map
             },
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1681,6 +1832,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1696,6 +1849,64 @@ mod tests {
         assert!(empty_breakpoints.is_none());
     }
 
+    #[gpui::test]
+    async fn test_function_and_data_breakpoints() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_function_and_data_breakpoints").await);
+        let id = db.next_id().await.unwrap();
+
+        let function_breakpoint = FunctionBreakpoint {
+            name: "main".into(),
+            condition: Some("argc > 1".into()),
+            hit_condition: None,
+            state: BreakpointState::Disabled,
+        };
+        let persistent_data_breakpoint = DataBreakpoint {
+            data_id: "0x1000".into(),
+            description: "counter".into(),
+            access_type: Some(DataBreakpointAccessType::Write),
+            condition: None,
+            hit_condition: Some(">= 2".into()),
+            state: BreakpointState::Enabled,
+            can_persist: true,
+        };
+        let session_data_breakpoint = DataBreakpoint {
+            data_id: "1000.counter".into(),
+            description: "counter".into(),
+            access_type: None,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+            can_persist: false,
+        };
+
+        let workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            function_breakpoints: vec![function_breakpoint.clone()],
+            data_breakpoints: vec![persistent_data_breakpoint.clone(), session_data_breakpoint],
+            session_id: None,
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.function_breakpoints, vec![function_breakpoint]);
+        assert_eq!(
+            loaded.data_breakpoints,
+            vec![persistent_data_breakpoint],
+            "Data breakpoints that can't outlive their session shouldn't be stored"
+        );
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         env_logger::try_init().ok();
@@ -1775,6 +1986,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1788,6 +2001,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1893,6 +2108,8 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1928,6 +2145,8 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1944,6 +2163,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: Some(2),
         };
@@ -1984,6 +2205,8 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2024,6 +2247,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
         };
@@ -2037,6 +2262,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
         };
@@ -2050,6 +2277,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
         };
@@ -2063,6 +2292,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2081,6 +2312,8 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
         };
@@ -2094,6 +2327,8 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2152,6 +2387,8 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -2200,6 +2437,8 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -2292,6 +2531,8 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
+            data_breakpoints: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
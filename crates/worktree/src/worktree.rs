@@ -7,7 +7,7 @@ use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
-use fs::{Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
+use fs::{Encoding, Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
 use futures::{
     FutureExt as _, Stream, StreamExt,
     channel::{
@@ -52,6 +52,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io,
     mem::{self},
     ops::{Deref, DerefMut},
     path::{Component, Path, PathBuf},
@@ -100,6 +101,18 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    /// The encoding the file was decoded from.
+    pub encoding: Encoding,
+}
+
+/// How many bytes of a large file are read at a time.
+const LARGE_FILE_CHUNK_LEN: usize = 16 * 1024 * 1024;
+
+/// A large UTF-8 file, read into a rope a chunk at a time.
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub text: Rope,
+    pub line_ending: LineEnding,
 }
 
 pub struct LoadedBinaryFile {
@@ -832,6 +845,22 @@ impl Worktree {
         }
     }
 
+    /// Loads a large file without holding its whole contents in a single string, building its
+    /// rope and line index one chunk at a time. Returns `None` when the file isn't UTF-8, in
+    /// which case it has to be loaded with [`Worktree::load_file`].
+    pub fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &Path,
@@ -850,10 +879,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1479,6 +1509,50 @@ impl LocalWorktree {
         self.settings.clone()
     }
 
+    fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let entry = self.refresh_entry(path.clone(), None, cx);
+        let is_private = self.is_path_private(path.as_ref());
+
+        let worktree = cx.weak_entity();
+        cx.background_spawn(async move {
+            let abs_path = abs_path?;
+            // Unlike `load_file`, this doesn't refuse files above a size limit: the text is
+            // read straight into the rope, so memory use stays close to the file's size.
+            let reader = fs.open_sync(&abs_path).await?;
+            let Some((text, line_ending)) = read_utf8_rope(reader, LARGE_FILE_CHUNK_LEN)
+                .with_context(|| format!("reading {abs_path:?}"))?
+            else {
+                return Ok(None);
+            };
+
+            let worktree = worktree
+                .upgrade()
+                .ok_or_else(|| anyhow!("worktree was dropped"))?;
+            let file = loaded_file(
+                worktree,
+                entry.await?,
+                fs.as_ref(),
+                &abs_path,
+                path,
+                is_private,
+            )
+            .await?;
+
+            Ok(Some(LoadedLargeFile {
+                file,
+                text,
+                line_ending,
+            }))
+        })
+    }
+
     fn load_binary_file(
         &self,
         path: &Path,
@@ -1498,30 +1572,15 @@ impl LocalWorktree {
             let worktree = worktree
                 .upgrade()
                 .ok_or_else(|| anyhow!("worktree was dropped"))?;
-            let file = match entry.await? {
-                Some(entry) => File::for_entry(entry, worktree),
-                None => {
-                    let metadata = fs
-                        .metadata(&abs_path)
-                        .await
-                        .with_context(|| {
-                            format!("Loading metadata for excluded file {abs_path:?}")
-                        })?
-                        .with_context(|| {
-                            format!("Excluded file {abs_path:?} got removed during loading")
-                        })?;
-                    Arc::new(File {
-                        entry_id: None,
-                        worktree,
-                        path,
-                        disk_state: DiskState::Present {
-                            mtime: metadata.mtime,
-                        },
-                        is_local: true,
-                        is_private,
-                    })
-                }
-            };
+            let file = loaded_file(
+                worktree,
+                entry.await?,
+                fs.as_ref(),
+                &abs_path,
+                path,
+                is_private,
+            )
+            .await?;
 
             Ok(LoadedBinaryFile { file, content })
         })
@@ -1550,37 +1609,26 @@ impl LocalWorktree {
                     }
                 }
             }
-            let text = fs.load(&abs_path).await?;
+            let (text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
 
             let worktree = this
                 .upgrade()
                 .ok_or_else(|| anyhow!("worktree was dropped"))?;
-            let file = match entry.await? {
-                Some(entry) => File::for_entry(entry, worktree),
-                None => {
-                    let metadata = fs
-                        .metadata(&abs_path)
-                        .await
-                        .with_context(|| {
-                            format!("Loading metadata for excluded file {abs_path:?}")
-                        })?
-                        .with_context(|| {
-                            format!("Excluded file {abs_path:?} got removed during loading")
-                        })?;
-                    Arc::new(File {
-                        entry_id: None,
-                        worktree,
-                        path,
-                        disk_state: DiskState::Present {
-                            mtime: metadata.mtime,
-                        },
-                        is_local: true,
-                        is_private,
-                    })
-                }
-            };
+            let file = loaded_file(
+                worktree,
+                entry.await?,
+                fs.as_ref(),
+                &abs_path,
+                path,
+                is_private,
+            )
+            .await?;
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                encoding,
+            })
         })
     }
 
@@ -1667,6 +1715,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1679,7 +1728,7 @@ impl LocalWorktree {
         let write = cx.background_spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move { fs.save(&abs_path, &text, line_ending, encoding).await }
         });
 
         cx.spawn(async move |this, cx| {
@@ -3239,6 +3288,95 @@ async fn is_git_dir(path: &Path, fs: &dyn Fs) -> bool {
     matches!(config_metadata, Ok(Some(_)))
 }
 
+/// Returns the file for a path that was just loaded, which has no entry when the path is
+/// excluded from the worktree.
+async fn loaded_file(
+    worktree: Entity<Worktree>,
+    entry: Option<Entry>,
+    fs: &dyn Fs,
+    abs_path: &Path,
+    path: Arc<Path>,
+    is_private: bool,
+) -> Result<Arc<File>> {
+    Ok(match entry {
+        Some(entry) => File::for_entry(entry, worktree),
+        None => {
+            let metadata = fs
+                .metadata(abs_path)
+                .await
+                .with_context(|| format!("Loading metadata for excluded file {abs_path:?}"))?
+                .with_context(|| {
+                    format!("Excluded file {abs_path:?} got removed during loading")
+                })?;
+            Arc::new(File {
+                entry_id: None,
+                worktree,
+                path,
+                disk_state: DiskState::Present {
+                    mtime: metadata.mtime,
+                },
+                is_local: true,
+                is_private,
+            })
+        }
+    })
+}
+
+/// Reads UTF-8 text into a rope `chunk_len` bytes at a time, normalizing its line endings.
+/// Returns `None` if the text is in another encoding or starts with a byte order mark.
+fn read_utf8_rope(
+    mut reader: impl io::Read,
+    chunk_len: usize,
+) -> Result<Option<(Rope, LineEnding)>> {
+    let mut rope = Rope::new();
+    let mut line_ending = None;
+    let mut buffer = vec![0; chunk_len];
+    // Bytes at the end of the previous chunk that couldn't be pushed yet: the start of a
+    // multi-byte character, or a `\r` that may be followed by a `\n`.
+    let mut carried_len = 0;
+    let mut is_first = true;
+    loop {
+        let mut len = carried_len;
+        while len < buffer.len() {
+            match reader.read(&mut buffer[len..])? {
+                0 => break,
+                read_len => len += read_len,
+            }
+        }
+        let is_last = len < buffer.len();
+
+        let text = match std::str::from_utf8(&buffer[..len]) {
+            Ok(text) => text,
+            Err(error) if error.error_len().is_none() && !is_last => {
+                std::str::from_utf8(&buffer[..error.valid_up_to()])?
+            }
+            Err(_) => return Ok(None),
+        };
+        if is_first {
+            if !Encoding::detect(text.as_bytes()).is_some_and(|encoding| encoding.is_utf8()) {
+                return Ok(None);
+            }
+            is_first = false;
+        }
+        if line_ending.is_none() && text.contains('\n') {
+            line_ending = Some(LineEnding::detect(text));
+        }
+        let text = match text.strip_suffix('\r') {
+            Some(text) if !is_last => text,
+            _ => text,
+        };
+        rope.push(&LineEnding::normalize_cow(text.into()));
+
+        let pushed_len = text.len();
+        buffer.copy_within(pushed_len..len, 0);
+        carried_len = len - pushed_len;
+        if is_last {
+            break;
+        }
+    }
+    Ok(Some((rope, line_ending.unwrap_or_default())))
+}
+
 async fn build_gitignore(abs_path: &Path, fs: &dyn Fs) -> Result<Gitignore> {
     let contents = fs.load(abs_path).await?;
     let parent = abs_path.parent().unwrap_or_else(|| Path::new("/"));
@@ -20,6 +20,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use text::LineEnding;
 use util::{ResultExt, path, test::TempTree};
 
 #[gpui::test]
@@ -642,9 +643,14 @@ async fn test_dirs_no_longer_ignored(cx: &mut TestAppContext) {
 
     // Update the gitignore so that node_modules is no longer ignored,
     // but a subdirectory is ignored
-    fs.save("/root/.gitignore".as_ref(), &"e".into(), Default::default())
-        .await
-        .unwrap();
+    fs.save(
+        "/root/.gitignore".as_ref(),
+        &"e".into(),
+        Default::default(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
     cx.executor().run_until_parked();
 
     // All of the directories that are no longer ignored are now loaded.
@@ -714,6 +720,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
                 Path::new("tracked-dir/file.txt"),
                 "hello".into(),
                 Default::default(),
+                Default::default(),
                 cx,
             )
         })
@@ -725,6 +732,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
                 Path::new("ignored-dir/file.txt"),
                 "world".into(),
                 Default::default(),
+                Default::default(),
                 cx,
             )
         })
@@ -1746,8 +1754,13 @@ fn randomly_mutate_worktree(
                 })
             } else {
                 log::info!("overwriting file {:?} ({})", entry.path, entry.id.0);
-                let task =
-                    worktree.write_file(entry.path.clone(), "".into(), Default::default(), cx);
+                let task = worktree.write_file(
+                    entry.path.clone(),
+                    "".into(),
+                    Default::default(),
+                    Default::default(),
+                    cx,
+                );
                 cx.background_spawn(async move {
                     task.await?;
                     Ok(())
@@ -1838,6 +1851,7 @@ async fn randomly_mutate_fs(
             &ignore_path,
             &ignore_contents.as_str().into(),
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
@@ -2090,6 +2104,34 @@ fn check_worktree_entries(
     }
 }
 
+#[test]
+fn test_read_utf8_rope() {
+    let read = |bytes: &[u8]| {
+        crate::read_utf8_rope(bytes, 4)
+            .unwrap()
+            .map(|(rope, line_ending)| (rope.to_string(), line_ending))
+    };
+
+    // Characters and line endings that straddle two chunks are kept whole.
+    assert_eq!(
+        read("aé€\r\nb\r\n".as_bytes()),
+        Some(("aé€\nb\n".to_string(), LineEnding::Windows))
+    );
+    assert_eq!(
+        read(b"abc\r\r\nd"),
+        Some(("abc\n\nd".to_string(), LineEnding::Windows))
+    );
+    assert_eq!(
+        read(b"line\nline"),
+        Some(("line\nline".to_string(), LineEnding::Unix))
+    );
+    assert_eq!(read(b""), Some((String::new(), LineEnding::default())));
+    // Other encodings are left to the regular loading path.
+    assert_eq!(read(b"caf\xE9\n"), None);
+    assert_eq!(read(b"\xEF\xBB\xBFhello"), None);
+    assert_eq!(read(b"\xFF\xFEh\0i\0"), None);
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
         env_logger::try_init().ok();
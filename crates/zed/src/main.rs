@@ -460,6 +460,7 @@ fn main() {
 
         auto_update::init(client.http_client(), cx);
         dap_adapters::init(cx);
+        debug_adapter_extension::init(extension_host_proxy.clone(), cx);
         auto_update_ui::init(cx);
         reliability::init(
             client.http_client(),
@@ -544,6 +545,7 @@ fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        symbol_hierarchy::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -551,6 +553,8 @@ fn main() {
         vim::init(cx);
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
+        encoding_selector::init(cx);
+        spellcheck::init(cx);
         language_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
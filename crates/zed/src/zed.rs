@@ -233,6 +233,7 @@ pub fn initialize_workspace(
         );
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding = cx.new(|_| encoding_selector::ActiveBufferEncoding::default());
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
@@ -244,6 +245,7 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
             status_bar.add_right_item(inline_completion_button, window, cx);
+            status_bar.add_right_item(active_buffer_encoding, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
@@ -3898,6 +3900,7 @@ mod tests {
                 "/settings.json".as_ref(),
                 &r#"{"base_keymap": "Atom"}"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3908,6 +3911,7 @@ mod tests {
                 "/keymap.json".as_ref(),
                 &r#"[{"bindings": {"backspace": "test1::A"}}]"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3951,6 +3955,7 @@ mod tests {
                 "/keymap.json".as_ref(),
                 &r#"[{"bindings": {"backspace": "test1::B"}}]"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3971,6 +3976,7 @@ mod tests {
                 "/settings.json".as_ref(),
                 &r#"{"base_keymap": "JetBrains"}"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -4012,6 +4018,7 @@ mod tests {
                 "/settings.json".as_ref(),
                 &r#"{"base_keymap": "Atom"}"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -4021,6 +4028,7 @@ mod tests {
                 "/keymap.json".as_ref(),
                 &r#"[{"bindings": {"backspace": "test2::A"}}]"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -4059,6 +4067,7 @@ mod tests {
                 "/keymap.json".as_ref(),
                 &r#"[{"bindings": {"backspace": null}}]"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -4079,6 +4088,7 @@ mod tests {
                 "/settings.json".as_ref(),
                 &r#"{"base_keymap": "JetBrains"}"#.into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();